@@ -1,5 +1,15 @@
-use crate::db::BrowserType;
-use crate::utils::browser_detector;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, State};
+use crate::core::access::{self, AppLock};
+use crate::core::browser_launcher::BrowserLauncher;
+use crate::core::{displays, tabs};
+use crate::db::{BrowserLaunchTest, BrowserProfile, BrowserType, Database, DisplayInfo, OpenTab, SessionSnapshot};
+use crate::utils::{browser_detector, profile_detector};
+use crate::utils::validation::{validate_browser_profile, validate_url};
+
+/// Page opened by a test launch when no URL is given
+const TEST_LAUNCH_URL: &str = "about:blank";
 
 #[tauri::command]
 pub fn get_installed_browsers() -> Vec<BrowserType> {
@@ -10,3 +20,86 @@ pub fn get_installed_browsers() -> Vec<BrowserType> {
 pub fn get_default_browser() -> Option<BrowserType> {
     browser_detector::get_default_browser()
 }
+
+/// Profiles of `browser` on this machine, for picking a task's profile.
+#[tauri::command]
+pub async fn get_browser_profiles(browser: BrowserType) -> Result<Vec<BrowserProfile>, String> {
+    tauri::async_runtime::spawn_blocking(move || profile_detector::get_browser_profiles(&browser))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+/// Opens a test page in a browser so users can verify a browser and profile before saving a task.
+#[tauri::command]
+pub async fn test_browser_launch(
+    browser: BrowserType,
+    profile: Option<String>,
+    url: Option<String>,
+    close_after_secs: Option<u64>,
+) -> Result<BrowserLaunchTest, String> {
+    if let Some(ref profile) = profile {
+        validate_browser_profile(profile).map_err(|e| e.to_string())?;
+    }
+    if let Some(ref url) = url {
+        validate_url(url).map_err(|e| e.to_string())?;
+    }
+
+    BrowserLauncher::new()
+        .test_launch(
+            &browser,
+            profile.as_deref(),
+            url.as_deref().unwrap_or(TEST_LAUNCH_URL),
+            close_after_secs.map(Duration::from_secs),
+        )
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Connected monitors a task can target with `target_display`.
+#[tauri::command]
+pub fn get_displays(app_handle: AppHandle) -> Result<Vec<DisplayInfo>, String> {
+    displays::list_displays(&app_handle).map_err(|e| e.to_string())
+}
+
+/// Tabs currently open in `browser`, or in every readable browser, so they can be turned into
+/// scheduled tasks.
+#[tauri::command]
+pub async fn get_open_tabs(
+    browser: Option<BrowserType>,
+    db: State<'_, Arc<Database>>,
+) -> Result<Vec<OpenTab>, String> {
+    let devtools_port = db
+        .get_settings()
+        .await
+        .map(|s| s.devtools_port)
+        .map_err(|e| e.to_string())?;
+
+    tauri::async_runtime::spawn_blocking(move || tabs::list_open_tabs(browser.as_ref(), devtools_port))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+/// Session snapshots saved by save_session tasks.
+#[tauri::command]
+pub async fn get_session_snapshots(db: State<'_, Arc<Database>>) -> Result<Vec<SessionSnapshot>, String> {
+    db.get_session_snapshots()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_session_snapshot(
+    name: String,
+    db: State<'_, Arc<Database>>,
+    lock: State<'_, AppLock>,
+) -> Result<(), String> {
+    access::ensure_can_modify(&db, &lock, "Deleting sessions")
+        .await
+        .map_err(|e| e.to_string())?;
+
+    db.delete_session_snapshot(&name)
+        .await
+        .map_err(|e| e.to_string())
+}
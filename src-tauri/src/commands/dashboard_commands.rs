@@ -0,0 +1,31 @@
+use std::sync::Arc;
+use chrono::{DateTime, Duration, Local, TimeZone, Utc};
+use tauri::State;
+use crate::db::{Dashboard, Database, TaskUsage};
+
+#[tauri::command]
+pub async fn get_dashboard(db: State<'_, Arc<Database>>) -> Result<Dashboard, String> {
+    // "Today" follows the user's local calendar day
+    let midnight = Local::now()
+        .date_naive()
+        .and_hms_opt(0, 0, 0)
+        .and_then(|t| Local.from_local_datetime(&t).earliest())
+        .ok_or("Failed to determine start of day")?;
+    let day_start = midnight.with_timezone(&Utc);
+    let day_end = day_start + Duration::days(1);
+
+    db.get_dashboard(day_start, day_end)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_usage_stats(
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    db: State<'_, Arc<Database>>,
+) -> Result<Vec<TaskUsage>, String> {
+    db.get_usage_stats(from, to)
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,17 @@
+use std::sync::Arc;
+use tauri::{AppHandle, State};
+use crate::core::health::{self, HealthReport};
+use crate::core::updates::{self, UpdateInfo};
+use crate::db::Database;
+
+#[tauri::command]
+pub async fn health_check(app: AppHandle) -> Result<HealthReport, String> {
+    Ok(health::check(&app).await)
+}
+
+#[tauri::command]
+pub async fn check_for_updates(db: State<'_, Arc<Database>>) -> Result<UpdateInfo, String> {
+    updates::check_for_updates(&db)
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,59 @@
+use std::sync::Arc;
+use serde::Serialize;
+use tauri::State;
+use crate::core::access::{self, AppLock};
+use crate::db::Database;
+
+#[derive(Debug, Serialize)]
+pub struct LockStatus {
+    /// Whether a PIN is set
+    pub enabled: bool,
+    /// Whether guarded operations are currently allowed
+    pub unlocked: bool,
+}
+
+#[tauri::command]
+pub async fn get_lock_status(
+    db: State<'_, Arc<Database>>,
+    lock: State<'_, AppLock>,
+) -> Result<LockStatus, String> {
+    let enabled = access::is_lock_enabled(&db).await;
+
+    Ok(LockStatus {
+        enabled,
+        unlocked: !enabled || lock.is_unlocked(),
+    })
+}
+
+#[tauri::command]
+pub async fn unlock(
+    pin: String,
+    db: State<'_, Arc<Database>>,
+    lock: State<'_, AppLock>,
+) -> Result<(), String> {
+    lock.unlock(&db, &pin)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn lock(lock: State<'_, AppLock>) {
+    lock.lock();
+}
+
+/// Sets, changes or removes (`new_pin` of `None`) the app lock PIN.
+#[tauri::command]
+pub async fn set_pin(
+    current_pin: Option<String>,
+    new_pin: Option<String>,
+    db: State<'_, Arc<Database>>,
+    lock: State<'_, AppLock>,
+) -> Result<(), String> {
+    access::ensure_writable(&db, "Changing the PIN")
+        .await
+        .map_err(|e| e.to_string())?;
+
+    lock.set_pin(&db, current_pin.as_deref(), new_pin.as_deref())
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -3,3 +3,9 @@ pub mod scheduler_commands;
 pub mod browser_commands;
 pub mod settings_commands;
 pub mod window_commands;
+pub mod notification_commands;
+pub mod dashboard_commands;
+pub mod report_commands;
+pub mod health_commands;
+pub mod workspace_commands;
+pub mod lock_commands;
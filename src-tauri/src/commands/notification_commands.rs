@@ -0,0 +1,83 @@
+use std::sync::Arc;
+use tauri::State;
+use crate::core::access::{self, AppLock};
+use crate::db::{AppNotification, Database};
+use crate::notifications::{channels, email};
+use crate::utils::keyring;
+
+#[tauri::command]
+pub async fn get_notifications(
+    unread_only: Option<bool>,
+    db: State<'_, Arc<Database>>,
+) -> Result<Vec<AppNotification>, String> {
+    db.get_notifications(unread_only.unwrap_or(false))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn mark_notification_read(id: i64, db: State<'_, Arc<Database>>) -> Result<(), String> {
+    db.mark_notification_read(id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn mark_all_notifications_read(db: State<'_, Arc<Database>>) -> Result<(), String> {
+    db.mark_all_notifications_read()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn clear_notifications(db: State<'_, Arc<Database>>) -> Result<(), String> {
+    db.clear_notifications()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Stores the SMTP password in the OS keyring, or removes it when `password` is empty.
+#[tauri::command]
+pub async fn set_smtp_password(
+    password: Option<String>,
+    db: State<'_, Arc<Database>>,
+    lock: State<'_, AppLock>,
+) -> Result<(), String> {
+    access::ensure_can_modify(&db, &lock, "Changing the SMTP password")
+        .await
+        .map_err(|e| e.to_string())?;
+
+    match password.filter(|p| !p.is_empty()) {
+        Some(password) => keyring::set_secret(email::PASSWORD_KEY, &password),
+        None => keyring::delete_secret(email::PASSWORD_KEY),
+    }
+    .map_err(|e| e.to_string())
+}
+
+/// Stores the Telegram bot token in the OS keyring, or removes it when `token` is empty.
+#[tauri::command]
+pub async fn set_telegram_token(
+    token: Option<String>,
+    db: State<'_, Arc<Database>>,
+    lock: State<'_, AppLock>,
+) -> Result<(), String> {
+    access::ensure_can_modify(&db, &lock, "Changing the Telegram token")
+        .await
+        .map_err(|e| e.to_string())?;
+
+    match token.filter(|t| !t.is_empty()) {
+        Some(token) => keyring::set_secret(channels::TELEGRAM_TOKEN_KEY, &token),
+        None => keyring::delete_secret(channels::TELEGRAM_TOKEN_KEY),
+    }
+    .map_err(|e| e.to_string())
+}
+
+/// Sends a test notification through one channel (`email`, `slack`, `discord`, `telegram`)
+/// with the saved settings.
+#[tauri::command]
+pub async fn send_test_notification(channel: String, db: State<'_, Arc<Database>>) -> Result<(), String> {
+    let settings = db.get_settings().await.map_err(|e| e.to_string())?;
+    channels::send_test(&settings, &channel)
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,151 @@
+use std::sync::Arc;
+use chrono::Utc;
+use chrono_tz::Tz;
+use tauri::{AppHandle, State};
+use crate::core::access::{self, AppLock};
+use crate::core::history::EditHistory;
+use crate::core::recurrence;
+use crate::db::{AuditSource, BrowserType, Database, NotificationKind, SkippedEvent, Task};
+use crate::notifications;
+use crate::report::{self, WeeklyReport};
+use crate::utils::{ical, locale};
+
+/// Most EXDATEs exported per task for occurrences on excluded dates
+const MAX_EXCLUDED_EXPORT: usize = 500;
+
+#[derive(serde::Serialize)]
+pub struct WeeklyReportResult {
+    pub report: WeeklyReport,
+    pub html: String,
+    pub saved_path: Option<String>,
+}
+
+#[derive(serde::Serialize)]
+pub struct IcalExport {
+    pub ics: String,
+    pub saved_path: Option<String>,
+}
+
+#[derive(serde::Serialize)]
+pub struct IcalImport {
+    pub created: Vec<Task>,
+    pub skipped: Vec<SkippedEvent>,
+}
+
+#[tauri::command]
+pub async fn generate_weekly_report(
+    save: Option<bool>,
+    db: State<'_, Arc<Database>>,
+    app: AppHandle,
+) -> Result<WeeklyReportResult, String> {
+    let report = report::build_weekly_report(&db, Utc::now())
+        .await
+        .map_err(|e| e.to_string())?;
+    let locale = db.get_locale_config().await.unwrap_or_default();
+
+    let saved_path = if save.unwrap_or(false) {
+        let path = report::save_report(&report, &locale).map_err(|e| e.to_string())?;
+        let path = path.display().to_string();
+
+        let _ = notifications::record(
+            &app,
+            &db,
+            NotificationKind::Info,
+            None,
+            "Weekly report ready",
+            &format!("Saved to {}", path),
+        )
+        .await;
+
+        Some(path)
+    } else {
+        None
+    };
+
+    Ok(WeeklyReportResult {
+        html: report::render_html(&report, &locale),
+        report,
+        saved_path,
+    })
+}
+
+/// The schedule as an iCalendar file. With `save` it is also written to `schedule.ics` in the
+/// data directory, a fixed path a calendar app can subscribe to.
+#[tauri::command]
+pub async fn export_ical(save: Option<bool>, db: State<'_, Arc<Database>>) -> Result<IcalExport, String> {
+    let tasks = db.get_all_tasks().await.map_err(|e| e.to_string())?;
+    let mut exceptions = db.get_skipped_occurrences().await.map_err(|e| e.to_string())?;
+
+    // Occurrences on excluded dates never run, so they are left out of the calendar too
+    for task in &tasks {
+        let Some(task_id) = task.id else { continue };
+        let excluded = recurrence::excluded_occurrences(task, MAX_EXCLUDED_EXPORT).map_err(|e| e.to_string())?;
+        if !excluded.is_empty() {
+            let skipped = exceptions.entry(task_id).or_default();
+            skipped.extend(excluded);
+            skipped.sort();
+            skipped.dedup();
+        }
+    }
+
+    let ics = ical::render_calendar(&tasks, &exceptions, Utc::now());
+
+    let saved_path = if save.unwrap_or(false) {
+        let path = Database::data_dir().map_err(|e| e.to_string())?.join("schedule.ics");
+        std::fs::write(&path, &ics).map_err(|e| e.to_string())?;
+        Some(path.display().to_string())
+    } else {
+        None
+    };
+
+    Ok(IcalExport { ics, saved_path })
+}
+
+/// Creates a task opening `default_browser` for each event in the calendar file or feed at
+/// `path_or_url`. Repeating events that started in the past join at their next occurrence.
+#[tauri::command]
+pub async fn import_ical(
+    path_or_url: String,
+    default_browser: BrowserType,
+    db: State<'_, Arc<Database>>,
+    lock: State<'_, AppLock>,
+    history: State<'_, EditHistory>,
+) -> Result<IcalImport, String> {
+    access::ensure_can_modify(&db, &lock, "Importing tasks")
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let ics = tauri::async_runtime::spawn_blocking(move || ical::read_calendar(&path_or_url))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())?;
+
+    let now = Utc::now();
+    let default_tz: Tz = locale::system_timezone().parse().unwrap_or(Tz::UTC);
+    let (tasks, mut skipped) =
+        ical::parse_calendar(&ics, &default_browser, default_tz, now).map_err(|e| e.to_string())?;
+
+    let mut created = Vec::new();
+    for mut task in tasks {
+        if task.repeat_config.is_some() && task.start_time < now {
+            if let Ok(next) = recurrence::next_occurrence_after(&task, now) {
+                task.next_open_execution = Some(next);
+                task.next_close_execution = task.close_time.map(|close| next + (close - task.start_time));
+            }
+        }
+
+        let summary = task.name.clone();
+        match db.create_task(task, AuditSource::Ui).await {
+            Ok(task) => created.push(task),
+            Err(e) => skipped.push(SkippedEvent { summary, reason: e.to_string() }),
+        }
+    }
+
+    // One undo step for the whole import
+    let ids: Vec<i64> = created.iter().filter_map(|task| task.id).collect();
+    if !ids.is_empty() {
+        let _ = history.record(&db, &ids).await;
+    }
+
+    Ok(IcalImport { created, skipped })
+}
@@ -1,10 +1,21 @@
 use std::sync::Arc;
+use chrono::{DateTime, Utc};
 use tauri::State;
-use crate::core::TaskScheduler;
+use crate::core::events::SchedulerErrorPayload;
+use crate::core::{simulation, TaskScheduler};
+use crate::db::{BackfillReport, Database, SchedulerState, SimulatedAction, Task};
 
 #[derive(serde::Serialize)]
 pub struct SchedulerStatus {
     pub running: bool,
+    pub recent_error_count: usize,
+    pub last_error: Option<SchedulerErrorPayload>,
+    /// Persisted loop heartbeat; survives restarts of the app
+    pub state: SchedulerState,
+    /// End of a pause set with `pause_scheduler_until`; scheduled opens are skipped until then
+    pub paused_until: Option<DateTime<Utc>>,
+    /// End of the quiet hours in effect; scheduled actions wait until then
+    pub quiet_until: Option<DateTime<Utc>>,
 }
 
 #[tauri::command]
@@ -24,8 +35,105 @@ pub async fn stop_scheduler(scheduler: State<'_, Arc<TaskScheduler>>) -> Result<
 }
 
 #[tauri::command]
-pub async fn get_scheduler_status(scheduler: State<'_, Arc<TaskScheduler>>) -> Result<SchedulerStatus, String> {
+pub async fn get_scheduler_status(
+    scheduler: State<'_, Arc<TaskScheduler>>,
+    db: State<'_, Arc<Database>>,
+) -> Result<SchedulerStatus, String> {
+    let state = db.get_scheduler_state().await.map_err(|e| e.to_string())?;
+
     Ok(SchedulerStatus {
         running: scheduler.is_running().await,
+        recent_error_count: scheduler.recent_error_count().await,
+        last_error: scheduler.last_error().await,
+        state,
+        paused_until: scheduler.paused_until().await,
+        quiet_until: scheduler.quiet_until().await,
     })
 }
+
+/// Skips scheduled opens until `until` and then resumes on its own, instead of stopping the
+/// scheduler and having to remember to start it again.
+#[tauri::command]
+pub async fn pause_scheduler_until(
+    until: DateTime<Utc>,
+    scheduler: State<'_, Arc<TaskScheduler>>,
+) -> Result<(), String> {
+    scheduler
+        .pause_until(until)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Ends a pause set with `pause_scheduler_until` early.
+#[tauri::command]
+pub async fn resume_scheduler(scheduler: State<'_, Arc<TaskScheduler>>) -> Result<(), String> {
+    scheduler
+        .resume_from_pause()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_backfill_report(
+    scheduler: State<'_, Arc<TaskScheduler>>,
+) -> Result<Option<BackfillReport>, String> {
+    Ok(scheduler.backfill_report().await)
+}
+
+/// Panic button: immediately closes everything the scheduler opened and has not closed yet.
+/// Returns how many tasks were closed.
+#[tauri::command]
+pub async fn close_all_scheduled(scheduler: State<'_, Arc<TaskScheduler>>) -> Result<usize, String> {
+    Ok(scheduler.close_all_opened().await)
+}
+
+#[tauri::command]
+pub async fn run_task_now(id: i64, scheduler: State<'_, Arc<TaskScheduler>>) -> Result<(), String> {
+    scheduler
+        .run_task_now(id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Pushes a task's next open back by `minutes`.
+#[tauri::command]
+pub async fn snooze_task(
+    id: i64,
+    minutes: i64,
+    scheduler: State<'_, Arc<TaskScheduler>>,
+) -> Result<Task, String> {
+    scheduler
+        .snooze_task(id, minutes)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Skips a task's next occurrence.
+#[tauri::command]
+pub async fn skip_next(id: i64, scheduler: State<'_, Arc<TaskScheduler>>) -> Result<Task, String> {
+    scheduler
+        .skip_next(id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Timeline of everything the scheduler would do between `from` and `to`, without running it.
+#[tauri::command]
+pub async fn simulate_schedule(
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    db: State<'_, Arc<Database>>,
+) -> Result<Vec<SimulatedAction>, String> {
+    if to <= from {
+        return Err("The end of the range must be after its start".to_string());
+    }
+
+    let mut tasks = db.get_all_tasks().await.map_err(|e| e.to_string())?;
+
+    // Tasks of inactive workspaces are skipped by the executor
+    if let Some(active_id) = db.get_active_workspace().await.map_err(|e| e.to_string())? {
+        tasks.retain(|task| task.workspace_id.map_or(true, |id| id == active_id));
+    }
+
+    simulation::simulate_schedule(&tasks, from, to).map_err(|e| e.to_string())
+}
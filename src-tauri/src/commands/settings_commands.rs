@@ -1,6 +1,11 @@
 use std::sync::Arc;
-use tauri::State;
-use crate::db::{Database, AppSettings};
+use chrono::{DateTime, Duration, Utc};
+use tauri::{AppHandle, State};
+use crate::core::access::{self, AppLock};
+use crate::core::home_assistant;
+use crate::db::{Database, AppSettings, LocaleConfig};
+use crate::tray;
+use crate::utils::{i18n, locale};
 
 #[tauri::command]
 pub async fn get_settings(db: State<'_, Arc<Database>>) -> Result<AppSettings, String> {
@@ -13,10 +18,116 @@ pub async fn get_settings(db: State<'_, Arc<Database>>) -> Result<AppSettings, S
 pub async fn update_settings(
     settings: AppSettings,
     db: State<'_, Arc<Database>>,
+    lock: State<'_, AppLock>,
 ) -> Result<AppSettings, String> {
+    access::ensure_can_modify(&db, &lock, "Changing settings")
+        .await
+        .map_err(|e| e.to_string())?;
+
     db.update_settings(settings.clone())
         .await
         .map_err(|e| e.to_string())?;
 
     Ok(settings)
 }
+
+/// Turns quiet hours on or off and sets their window, e.g. "22:00" to "07:00" local time.
+#[tauri::command]
+pub async fn set_quiet_hours(
+    enabled: bool,
+    start: String,
+    end: String,
+    app: AppHandle,
+    db: State<'_, Arc<Database>>,
+    lock: State<'_, AppLock>,
+) -> Result<AppSettings, String> {
+    access::ensure_can_modify(&db, &lock, "Changing quiet hours")
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut settings = db.get_settings().await.map_err(|e| e.to_string())?;
+    settings.quiet_hours_enabled = enabled;
+    settings.quiet_hours_start = start.trim().to_string();
+    settings.quiet_hours_end = end.trim().to_string();
+
+    db.update_settings(settings.clone())
+        .await
+        .map_err(|e| e.to_string())?;
+    tray::refresh_tooltip(&app, &db).await;
+
+    Ok(settings)
+}
+
+/// Directory currently holding the database.
+#[tauri::command]
+pub fn get_data_dir() -> Result<String, String> {
+    Database::data_dir()
+        .map(|dir| dir.to_string_lossy().to_string())
+        .map_err(|e| e.to_string())
+}
+
+/// Copies the database into `new_path`, verifies it and switches to it; the old file is kept.
+#[tauri::command]
+pub async fn move_database(
+    new_path: String,
+    db: State<'_, Arc<Database>>,
+    lock: State<'_, AppLock>,
+) -> Result<String, String> {
+    access::ensure_can_modify(&db, &lock, "Moving the database")
+        .await
+        .map_err(|e| e.to_string())?;
+
+    db.move_to(std::path::Path::new(&new_path))
+        .await
+        .map(|path| path.to_string_lossy().to_string())
+        .map_err(|e| e.to_string())
+}
+
+/// Date and time conventions from the settings, for formatting on the frontend.
+#[tauri::command]
+pub async fn get_locale_config(db: State<'_, Arc<Database>>) -> Result<LocaleConfig, String> {
+    db.get_locale_config()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Length of `secs` seconds for people, e.g. "2 h 15 min".
+#[tauri::command]
+pub async fn humanize_duration(secs: i64) -> Result<String, String> {
+    Ok(locale::humanize_duration(Duration::seconds(secs)))
+}
+
+/// `at` relative to now, e.g. "in 2 h 15 min" or "3 days ago".
+#[tauri::command]
+pub async fn humanize_until(at: DateTime<Utc>, db: State<'_, Arc<Database>>) -> Result<String, String> {
+    let locale_config = db.get_locale_config().await.map_err(|e| e.to_string())?;
+    Ok(locale::humanize_until(at, &locale_config))
+}
+
+/// A task's schedule in words in the configured language, e.g. "Every Monday at 09:30".
+#[tauri::command]
+pub async fn describe_schedule(id: i64, db: State<'_, Arc<Database>>) -> Result<String, String> {
+    let task = db.get_task(id).await.map_err(|e| e.to_string())?;
+    let locale_config = db.get_locale_config().await.map_err(|e| e.to_string())?;
+    Ok(i18n::describe_schedule(&task, &locale_config))
+}
+
+/// Replaces the Home Assistant token with a new one and returns it, for pasting into the Home
+/// Assistant configuration.
+#[tauri::command]
+pub async fn generate_home_assistant_token(
+    db: State<'_, Arc<Database>>,
+    lock: State<'_, AppLock>,
+) -> Result<String, String> {
+    access::ensure_can_modify(&db, &lock, "Generating a Home Assistant token")
+        .await
+        .map_err(|e| e.to_string())?;
+
+    home_assistant::generate_token().map_err(|e| e.to_string())
+}
+
+/// Whether task and settings changes are rejected; see `core::access::is_read_only`.
+#[tauri::command]
+pub async fn is_read_only(db: State<'_, Arc<Database>>) -> Result<bool, String> {
+    Ok(access::is_read_only(&db).await)
+}
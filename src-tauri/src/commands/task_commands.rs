@@ -1,10 +1,35 @@
 use std::sync::Arc;
-use tauri::State;
-use crate::db::{Database, Task};
+use tauri::{AppHandle, State};
+use crate::core::access::{self, AppLock};
+use crate::core::history::EditHistory;
+use crate::core::events::{emit_task_updated, TaskUpdateCause};
+use crate::db::{AuditEntry, AuditSource, Database, DeletedTask, ExecutionPage, TagCount, Task, TaskExecution, TaskKind, TaskPage, TaskRevision, TaskSort, TaskStatus, ValidationIssue};
+use crate::utils::{browser_detector, share, task_validation};
 
+/// Most results `search_tasks` returns
+const SEARCH_LIMIT: i64 = 50;
+
+/// Executions returned per call when no `limit` is given
+const EXECUTION_PAGE_SIZE: i64 = 50;
+
+/// A page of tasks and the total count; without a `limit` all tasks are returned.
+#[tauri::command]
+pub async fn get_all_tasks(
+    limit: Option<i64>,
+    offset: Option<i64>,
+    sort: Option<TaskSort>,
+    descending: Option<bool>,
+    db: State<'_, Arc<Database>>,
+) -> Result<TaskPage, String> {
+    db.get_tasks_page(limit, offset.unwrap_or(0), sort.unwrap_or_default(), descending.unwrap_or(false))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Tasks whose name, URL or description match `query`, best matches first.
 #[tauri::command]
-pub async fn get_all_tasks(db: State<'_, Arc<Database>>) -> Result<Vec<Task>, String> {
-    db.get_all_tasks()
+pub async fn search_tasks(query: String, db: State<'_, Arc<Database>>) -> Result<Vec<Task>, String> {
+    db.search_tasks(&query, SEARCH_LIMIT)
         .await
         .map_err(|e| e.to_string())
 }
@@ -16,33 +41,409 @@ pub async fn get_task(id: i64, db: State<'_, Arc<Database>>) -> Result<Task, Str
         .map_err(|e| e.to_string())
 }
 
+/// A `do-in-time://import-task` link that gives a teammate the same schedule.
+#[tauri::command]
+pub async fn share_task(id: i64, db: State<'_, Arc<Database>>) -> Result<String, String> {
+    let task = db.get_task(id).await.map_err(|e| e.to_string())?;
+    share::share_link(&task).map_err(|e| e.to_string())
+}
+
+/// The unsaved task in a shared link or bare share code, for the task form. Deep links only
+/// reach the app on macOS, so elsewhere links are pasted in.
+#[tauri::command]
+pub async fn read_task_link(link: String) -> Result<Task, String> {
+    let code = match link.trim().split_once("data=") {
+        Some((_, code)) => code.split('&').next().unwrap_or_default(),
+        None => link.trim(),
+    };
+    share::import(code).map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn create_task(
     task: Task,
     db: State<'_, Arc<Database>>,
+    lock: State<'_, AppLock>,
+    history: State<'_, EditHistory>,
 ) -> Result<Task, String> {
-    db.create_task(task)
+    access::ensure_can_modify(&db, &lock, "Creating tasks")
         .await
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+
+    let task = db
+        .create_task(task, AuditSource::Ui)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if let Some(id) = task.id {
+        let _ = history.record(&db, &[id]).await;
+    }
+    Ok(task)
+}
+
+/// Checks a task without saving it, for live validation in the task form.
+#[tauri::command]
+pub async fn validate_task(task: Task, db: State<'_, Arc<Database>>) -> Result<Vec<ValidationIssue>, String> {
+    let settings = db.get_settings().await.map_err(|e| e.to_string())?;
+    let mut issues = task_validation::check_task(&task, &settings.extra_url_schemes());
+
+    if task.kind == TaskKind::Browser && !browser_detector::get_installed_browsers().contains(&task.browser) {
+        issues.push(ValidationIssue::warning(
+            "browser",
+            format!("{} does not appear to be installed", task.browser),
+        ));
+    }
+
+    Ok(issues)
 }
 
 #[tauri::command]
 pub async fn update_task(
     id: i64,
     task: Task,
+    force: Option<bool>,
     db: State<'_, Arc<Database>>,
+    lock: State<'_, AppLock>,
+    history: State<'_, EditHistory>,
+    app: AppHandle,
 ) -> Result<Task, String> {
-    db.update_task(id, task)
+    access::ensure_can_modify(&db, &lock, "Editing tasks")
         .await
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+
+    let task = db
+        .update_task(id, task, force.unwrap_or(false), AuditSource::Ui)
+        .await
+        .map_err(|e| e.to_string())?;
+    let _ = history.record(&db, &[id]).await;
+
+    emit_task_updated(&app, &task, TaskUpdateCause::Edited);
+    Ok(task)
 }
 
 #[tauri::command]
 pub async fn delete_task(
     id: i64,
     db: State<'_, Arc<Database>>,
+    lock: State<'_, AppLock>,
+    history: State<'_, EditHistory>,
 ) -> Result<(), String> {
-    db.delete_task(id)
+    access::ensure_can_modify(&db, &lock, "Deleting tasks")
+        .await
+        .map_err(|e| e.to_string())?;
+
+    db.delete_task(id, AuditSource::Ui)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let _ = history.record(&db, &[id]).await;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn pause_task(
+    id: i64,
+    db: State<'_, Arc<Database>>,
+    history: State<'_, EditHistory>,
+    app: AppHandle,
+) -> Result<Task, String> {
+    let task = db
+        .set_task_status(id, TaskStatus::Paused, AuditSource::Ui)
+        .await
+        .map_err(|e| e.to_string())?;
+    let _ = history.record(&db, &[id]).await;
+
+    emit_task_updated(&app, &task, TaskUpdateCause::Edited);
+    Ok(task)
+}
+
+#[tauri::command]
+pub async fn resume_task(
+    id: i64,
+    db: State<'_, Arc<Database>>,
+    history: State<'_, EditHistory>,
+    app: AppHandle,
+) -> Result<Task, String> {
+    let task = db
+        .set_task_status(id, TaskStatus::Active, AuditSource::Ui)
+        .await
+        .map_err(|e| e.to_string())?;
+    let _ = history.record(&db, &[id]).await;
+
+    emit_task_updated(&app, &task, TaskUpdateCause::Edited);
+    Ok(task)
+}
+
+/// Sets the status of several tasks at once, e.g. to pause a selection. Returns the updated
+/// tasks; the change is a single undo step.
+#[tauri::command]
+pub async fn bulk_update_status(
+    ids: Vec<i64>,
+    status: TaskStatus,
+    db: State<'_, Arc<Database>>,
+    history: State<'_, EditHistory>,
+    app: AppHandle,
+) -> Result<Vec<Task>, String> {
+    let tasks = db
+        .bulk_update_status(&ids, status, AuditSource::Ui)
+        .await
+        .map_err(|e| e.to_string())?;
+    record_bulk_change(&db, &history, &app, &tasks).await;
+    Ok(tasks)
+}
+
+/// Moves several tasks into the recycle bin at once. Returns the ids deleted.
+#[tauri::command]
+pub async fn bulk_delete(
+    ids: Vec<i64>,
+    db: State<'_, Arc<Database>>,
+    lock: State<'_, AppLock>,
+    history: State<'_, EditHistory>,
+) -> Result<Vec<i64>, String> {
+    access::ensure_can_modify(&db, &lock, "Deleting tasks")
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let deleted = db
+        .bulk_delete(&ids, AuditSource::Ui)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if !deleted.is_empty() {
+        let _ = history.record(&db, &deleted).await;
+    }
+    Ok(deleted)
+}
+
+/// Moves the schedule of several tasks by `minutes`, earlier when negative. Returns the
+/// updated tasks.
+#[tauri::command]
+pub async fn bulk_shift_time(
+    ids: Vec<i64>,
+    minutes: i64,
+    db: State<'_, Arc<Database>>,
+    lock: State<'_, AppLock>,
+    history: State<'_, EditHistory>,
+    app: AppHandle,
+) -> Result<Vec<Task>, String> {
+    access::ensure_can_modify(&db, &lock, "Editing tasks")
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let tasks = db
+        .bulk_shift_time(&ids, minutes, AuditSource::Ui)
+        .await
+        .map_err(|e| e.to_string())?;
+    record_bulk_change(&db, &history, &app, &tasks).await;
+    Ok(tasks)
+}
+
+/// Every tag in use, with the number of tasks carrying it.
+#[tauri::command]
+pub async fn get_tags(db: State<'_, Arc<Database>>) -> Result<Vec<TagCount>, String> {
+    db.get_tags()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_tasks_by_tag(tag: String, db: State<'_, Arc<Database>>) -> Result<Vec<Task>, String> {
+    db.get_tasks_by_tag(&tag)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Sets the status of every task carrying `tag`, e.g. to pause all "work" tasks for a
+/// holiday. Returns the updated tasks.
+#[tauri::command]
+pub async fn set_tag_status(
+    tag: String,
+    status: TaskStatus,
+    db: State<'_, Arc<Database>>,
+    history: State<'_, EditHistory>,
+    app: AppHandle,
+) -> Result<Vec<Task>, String> {
+    let ids: Vec<i64> = db
+        .get_tasks_by_tag(&tag)
+        .await
+        .map_err(|e| e.to_string())?
+        .iter()
+        .filter_map(|task| task.id)
+        .collect();
+
+    let tasks = db
+        .bulk_update_status(&ids, status, AuditSource::Ui)
+        .await
+        .map_err(|e| e.to_string())?;
+    record_bulk_change(&db, &history, &app, &tasks).await;
+    Ok(tasks)
+}
+
+/// Records a bulk change as one undo step and emits `task-updated` for each task.
+async fn record_bulk_change(db: &Database, history: &EditHistory, app: &AppHandle, tasks: &[Task]) {
+    let ids: Vec<i64> = tasks.iter().filter_map(|task| task.id).collect();
+    if !ids.is_empty() {
+        let _ = history.record(db, &ids).await;
+    }
+    for task in tasks {
+        emit_task_updated(app, task, TaskUpdateCause::Edited);
+    }
+}
+
+/// Tasks in the recycle bin, most recently deleted first.
+#[tauri::command]
+pub async fn get_deleted_tasks(db: State<'_, Arc<Database>>) -> Result<Vec<DeletedTask>, String> {
+    db.get_deleted_tasks()
         .await
         .map_err(|e| e.to_string())
 }
+
+#[tauri::command]
+pub async fn restore_deleted_task(
+    id: i64,
+    db: State<'_, Arc<Database>>,
+    lock: State<'_, AppLock>,
+    history: State<'_, EditHistory>,
+    app: AppHandle,
+) -> Result<Task, String> {
+    access::ensure_can_modify(&db, &lock, "Restoring tasks")
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let task = db
+        .restore_deleted_task(id, AuditSource::Ui)
+        .await
+        .map_err(|e| e.to_string())?;
+    let _ = history.record(&db, &[id]).await;
+
+    emit_task_updated(&app, &task, TaskUpdateCause::Edited);
+    Ok(task)
+}
+
+/// Empties the recycle bin. Returns the number of tasks purged.
+#[tauri::command]
+pub async fn purge_deleted(
+    db: State<'_, Arc<Database>>,
+    lock: State<'_, AppLock>,
+) -> Result<u64, String> {
+    access::ensure_can_modify(&db, &lock, "Purging deleted tasks")
+        .await
+        .map_err(|e| e.to_string())?;
+
+    db.purge_deleted_tasks(None)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Recorded task mutations, newest first, optionally for a single task.
+#[tauri::command]
+pub async fn get_audit_log(
+    task_id: Option<i64>,
+    limit: Option<i64>,
+    db: State<'_, Arc<Database>>,
+) -> Result<Vec<AuditEntry>, String> {
+    db.get_audit_log(task_id, limit.unwrap_or(100))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// A page of a task's execution history, newest first.
+#[tauri::command]
+pub async fn get_task_executions(
+    task_id: i64,
+    limit: Option<i64>,
+    offset: Option<i64>,
+    db: State<'_, Arc<Database>>,
+) -> Result<ExecutionPage, String> {
+    db.get_task_executions(task_id, limit.unwrap_or(EXECUTION_PAGE_SIZE), offset.unwrap_or(0))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// The latest executions across all tasks, newest first.
+#[tauri::command]
+pub async fn get_recent_executions(
+    limit: Option<i64>,
+    db: State<'_, Arc<Database>>,
+) -> Result<Vec<TaskExecution>, String> {
+    db.get_recent_executions(limit.unwrap_or(EXECUTION_PAGE_SIZE))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Saved versions of a task, newest first.
+#[tauri::command]
+pub async fn get_task_revisions(id: i64, db: State<'_, Arc<Database>>) -> Result<Vec<TaskRevision>, String> {
+    db.get_task_revisions(id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Puts a task's configuration back to a saved revision.
+#[tauri::command]
+pub async fn restore_task_revision(
+    id: i64,
+    rev: i64,
+    db: State<'_, Arc<Database>>,
+    lock: State<'_, AppLock>,
+    history: State<'_, EditHistory>,
+    app: AppHandle,
+) -> Result<Task, String> {
+    access::ensure_can_modify(&db, &lock, "Restoring task revisions")
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let task = db
+        .restore_task_revision(id, rev, AuditSource::Ui)
+        .await
+        .map_err(|e| e.to_string())?;
+    let _ = history.record(&db, &[id]).await;
+
+    emit_task_updated(&app, &task, TaskUpdateCause::Edited);
+    Ok(task)
+}
+
+/// Reverts the last task change made from the UI in this session. Returns the ids of the
+/// affected tasks, empty if there was nothing to undo.
+#[tauri::command]
+pub async fn undo_last_change(
+    db: State<'_, Arc<Database>>,
+    lock: State<'_, AppLock>,
+    history: State<'_, EditHistory>,
+    app: AppHandle,
+) -> Result<Vec<i64>, String> {
+    access::ensure_can_modify(&db, &lock, "Undoing changes")
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let task_ids = history.undo(&db).await.map_err(|e| e.to_string())?;
+    emit_changed_tasks(&db, &app, &task_ids).await;
+    Ok(task_ids)
+}
+
+/// Re-applies the last undone change. Returns the ids of the affected tasks, empty if there was
+/// nothing to redo.
+#[tauri::command]
+pub async fn redo(
+    db: State<'_, Arc<Database>>,
+    lock: State<'_, AppLock>,
+    history: State<'_, EditHistory>,
+    app: AppHandle,
+) -> Result<Vec<i64>, String> {
+    access::ensure_can_modify(&db, &lock, "Redoing changes")
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let task_ids = history.redo(&db).await.map_err(|e| e.to_string())?;
+    emit_changed_tasks(&db, &app, &task_ids).await;
+    Ok(task_ids)
+}
+
+/// Emits `task-updated` for every task in `task_ids` that still exists.
+async fn emit_changed_tasks(db: &Database, app: &AppHandle, task_ids: &[i64]) {
+    for &id in task_ids {
+        if let Ok(task) = db.get_task(id).await {
+            emit_task_updated(app, &task, TaskUpdateCause::Edited);
+        }
+    }
+}
@@ -1,5 +1,9 @@
-use tauri::{AppHandle, Manager};
+use std::sync::Arc;
+use tauri::{AppHandle, Manager, State, WebviewUrl, WebviewWindowBuilder};
 use tauri_plugin_autostart::ManagerExt;
+use crate::core::TaskScheduler;
+use crate::db::{LinkDraft, Task};
+use crate::utils::links;
 
 #[tauri::command]
 pub async fn toggle_window_visibility(app: AppHandle) -> Result<(), String> {
@@ -32,3 +36,100 @@ pub async fn apply_auto_start(enabled: bool, app: AppHandle) -> Result<(), Strin
 
     Ok(())
 }
+
+/// Quits the app, first closing the browsers the scheduler opened if `close_opened` is set.
+#[tauri::command]
+pub async fn quit_app(
+    close_opened: bool,
+    app: AppHandle,
+    scheduler: State<'_, Arc<TaskScheduler>>,
+) -> Result<(), String> {
+    if close_opened {
+        let closed = scheduler.close_all_opened().await;
+        println!("Closed {} scheduler-opened browser(s) before quitting", closed);
+    }
+
+    app.exit(0);
+    Ok(())
+}
+
+/// The links on the clipboard, to start a new task from a copied link.
+#[tauri::command]
+pub async fn paste_links() -> Result<Vec<LinkDraft>, String> {
+    let text = tokio::task::spawn_blocking(links::clipboard_text)
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())?;
+    Ok(links::extract_links(&text))
+}
+
+/// Label of the countdown window shown before `task_id` closes
+fn countdown_label(task_id: i64) -> String {
+    format!("close-countdown-{}", task_id)
+}
+
+/// Shows the small always-on-top countdown before a task's scheduled close, without taking
+/// focus from whatever the user is doing.
+pub fn show_close_countdown(app: &AppHandle, task_id: i64, task_name: &str) -> tauri::Result<()> {
+    let label = countdown_label(task_id);
+    if let Some(window) = app.get_webview_window(&label) {
+        return window.show();
+    }
+
+    WebviewWindowBuilder::new(app, label, WebviewUrl::App(format!("index.html?countdown={}", task_id).into()))
+        .title(format!("Closing {}", task_name))
+        .inner_size(320.0, 110.0)
+        .resizable(false)
+        .decorations(false)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .focused(false)
+        .build()
+        .map(|_| ())
+}
+
+/// Closes a task's countdown window if it is showing.
+pub fn hide_close_countdown(app: &AppHandle, task_id: i64) {
+    if let Some(window) = app.get_webview_window(&countdown_label(task_id)) {
+        if let Err(e) = window.close() {
+            eprintln!("Failed to close the countdown window of task {}: {}", task_id, e);
+        }
+    }
+}
+
+/// Extend button of the close countdown: keeps the task open `minutes` longer.
+#[tauri::command]
+pub async fn extend_task_close(
+    id: i64,
+    minutes: i64,
+    app: AppHandle,
+    scheduler: State<'_, Arc<TaskScheduler>>,
+) -> Result<Task, String> {
+    let task = scheduler
+        .extend_close(id, minutes)
+        .await
+        .map_err(|e| e.to_string())?;
+    hide_close_countdown(&app, id);
+    Ok(task)
+}
+
+/// Close-now button of the close countdown: runs the pending close right away.
+#[tauri::command]
+pub async fn close_task_now(
+    id: i64,
+    app: AppHandle,
+    scheduler: State<'_, Arc<TaskScheduler>>,
+) -> Result<(), String> {
+    hide_close_countdown(&app, id);
+    scheduler
+        .close_now(id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Closes the countdown window once its close has run.
+#[tauri::command]
+pub async fn dismiss_close_countdown(id: i64, app: AppHandle) -> Result<(), String> {
+    hide_close_countdown(&app, id);
+    Ok(())
+}
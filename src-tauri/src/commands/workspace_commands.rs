@@ -0,0 +1,66 @@
+use std::sync::Arc;
+use tauri::State;
+use crate::core::access::{self, AppLock};
+use crate::db::{Database, Workspace};
+
+#[tauri::command]
+pub async fn get_workspaces(db: State<'_, Arc<Database>>) -> Result<Vec<Workspace>, String> {
+    db.get_workspaces()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn create_workspace(
+    name: String,
+    db: State<'_, Arc<Database>>,
+    lock: State<'_, AppLock>,
+) -> Result<Workspace, String> {
+    access::ensure_can_modify(&db, &lock, "Creating workspaces")
+        .await
+        .map_err(|e| e.to_string())?;
+
+    db.create_workspace(&name)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_workspace(
+    id: i64,
+    db: State<'_, Arc<Database>>,
+    lock: State<'_, AppLock>,
+) -> Result<(), String> {
+    access::ensure_can_modify(&db, &lock, "Deleting workspaces")
+        .await
+        .map_err(|e| e.to_string())?;
+
+    db.delete_workspace(id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Id of the workspace the scheduler runs, or `None` when all workspaces are active.
+#[tauri::command]
+pub async fn get_active_workspace(db: State<'_, Arc<Database>>) -> Result<Option<i64>, String> {
+    db.get_active_workspace()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Makes `workspace_id` the only workspace whose tasks run, or all of them when `None`.
+/// Tasks without a workspace always run.
+#[tauri::command]
+pub async fn switch_workspace(
+    workspace_id: Option<i64>,
+    db: State<'_, Arc<Database>>,
+    lock: State<'_, AppLock>,
+) -> Result<(), String> {
+    access::ensure_can_modify(&db, &lock, "Switching workspaces")
+        .await
+        .map_err(|e| e.to_string())?;
+
+    db.set_active_workspace(workspace_id)
+        .await
+        .map_err(|e| e.to_string())
+}
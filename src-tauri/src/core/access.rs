@@ -0,0 +1,232 @@
+use chrono::{DateTime, Duration, Utc};
+use sha2::{Digest, Sha256};
+use std::sync::Mutex;
+use crate::db::Database;
+use crate::error::{AppError, Result};
+use crate::utils::{keyring, locale};
+
+/// Command-line flag that starts the app in read-only mode
+const READ_ONLY_FLAG: &str = "--read-only";
+
+/// Environment variable that enables read-only mode when set to `1` or `true`
+const READ_ONLY_ENV: &str = "DO_IN_TIME_READ_ONLY";
+
+/// Whether the app runs in read-only mode, for shared signage machines whose tasks are
+/// administered centrally. Tasks can still be run and paused, but not created, edited or deleted.
+///
+/// Enabled by the `--read-only` flag, the `DO_IN_TIME_READ_ONLY` environment variable, or the
+/// `read_only` setting, which the app itself never writes.
+pub async fn is_read_only(db: &Database) -> bool {
+    if std::env::args().any(|arg| arg == READ_ONLY_FLAG)
+        || std::env::var(READ_ONLY_ENV).is_ok_and(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+    {
+        return true;
+    }
+
+    db.get_setting_value("read_only")
+        .await
+        .ok()
+        .flatten()
+        .is_some_and(|v| v == "true")
+}
+
+/// Rejects an operation that changes tasks or settings while in read-only mode.
+pub async fn ensure_writable(db: &Database, operation: &str) -> Result<()> {
+    if is_read_only(db).await {
+        return Err(AppError::PermissionDenied(format!(
+            "{} is not allowed in read-only mode",
+            operation
+        )));
+    }
+    Ok(())
+}
+
+/// Keyring entry holding the salted hash of the app lock PIN
+const PIN_KEY: &str = "app-lock-pin";
+
+/// Hash iterations; makes guessing a short PIN from a leaked hash slow
+const PIN_HASH_ROUNDS: u32 = 100_000;
+
+const MIN_PIN_LENGTH: usize = 4;
+const MAX_PIN_LENGTH: usize = 64;
+
+/// An unlocked session locks itself again after this long
+const UNLOCK_TIMEOUT_MINUTES: i64 = 15;
+
+/// Wrong PINs in a row after which unlocking is refused for `LOCKOUT_SECONDS`
+const MAX_FAILED_ATTEMPTS: u32 = 5;
+const LOCKOUT_SECONDS: i64 = 60;
+
+#[derive(Debug, Default)]
+struct LockState {
+    unlocked_until: Option<DateTime<Utc>>,
+    failed_attempts: u32,
+    locked_out_until: Option<DateTime<Utc>>,
+}
+
+/// Optional PIN lock guarding task and settings changes on shared computers. The PIN hash lives
+/// in the OS keyring; the `pin_lock_enabled` setting only records that one is set.
+#[derive(Debug, Default)]
+pub struct AppLock {
+    state: Mutex<LockState>,
+}
+
+impl AppLock {
+    fn state(&self) -> std::sync::MutexGuard<'_, LockState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn is_unlocked(&self) -> bool {
+        self.state().unlocked_until.is_some_and(|until| Utc::now() < until)
+    }
+
+    pub fn lock(&self) {
+        self.state().unlocked_until = None;
+    }
+
+    /// Checks `pin` and starts an unlocked session if it matches.
+    pub async fn unlock(&self, db: &Database, pin: &str) -> Result<()> {
+        if !is_lock_enabled(db).await {
+            return Ok(());
+        }
+
+        self.check_pin(db, pin).await?;
+        self.state().unlocked_until = Some(Utc::now() + Duration::minutes(UNLOCK_TIMEOUT_MINUTES));
+        Ok(())
+    }
+
+    /// Checks `pin` against the stored one, counting wrong PINs towards the lockout, so that
+    /// every way of entering a PIN is rate-limited alike.
+    async fn check_pin(&self, db: &Database, pin: &str) -> Result<()> {
+        let locked_out_until = self.state().locked_out_until.filter(|until| Utc::now() < *until);
+        if let Some(until) = locked_out_until {
+            let locale_config = db.get_locale_config().await.unwrap_or_default();
+            return Err(AppError::PermissionDenied(format!(
+                "Too many wrong PINs; try again after {}",
+                locale::format_time(until, &locale_config)
+            )));
+        }
+
+        let pin = pin.to_string();
+        if blocking(move || verify_pin(&pin)).await? {
+            let mut state = self.state();
+            state.failed_attempts = 0;
+            state.locked_out_until = None;
+            return Ok(());
+        }
+
+        let mut state = self.state();
+        state.failed_attempts += 1;
+        if state.failed_attempts >= MAX_FAILED_ATTEMPTS {
+            state.failed_attempts = 0;
+            state.locked_out_until = Some(Utc::now() + Duration::seconds(LOCKOUT_SECONDS));
+        }
+        Err(AppError::PermissionDenied("Wrong PIN".to_string()))
+    }
+
+    /// Sets, changes or (with `new_pin` of `None`) removes the PIN. Changing or removing an
+    /// existing PIN requires it.
+    pub async fn set_pin(&self, db: &Database, current_pin: Option<&str>, new_pin: Option<&str>) -> Result<()> {
+        if is_lock_enabled(db).await {
+            self.check_pin(db, current_pin.unwrap_or_default()).await?;
+        }
+
+        match new_pin {
+            Some(pin) => {
+                let length = pin.chars().count();
+                if !(MIN_PIN_LENGTH..=MAX_PIN_LENGTH).contains(&length) {
+                    return Err(AppError::InvalidPin(format!(
+                        "must be between {} and {} characters",
+                        MIN_PIN_LENGTH, MAX_PIN_LENGTH
+                    )));
+                }
+
+                let pin = pin.to_string();
+                blocking(move || {
+                    let salt: [u8; 16] = rand::random();
+                    keyring::set_secret(PIN_KEY, &format!("{}${}", hex::encode(salt), hash_pin(&salt, &pin)))
+                })
+                .await?;
+                db.update_setting("pin_lock_enabled", true).await?;
+            }
+            None => {
+                blocking(|| keyring::delete_secret(PIN_KEY)).await?;
+                db.update_setting("pin_lock_enabled", false).await?;
+            }
+        }
+
+        self.lock();
+        Ok(())
+    }
+}
+
+pub async fn is_lock_enabled(db: &Database) -> bool {
+    db.get_setting_value("pin_lock_enabled")
+        .await
+        .ok()
+        .flatten()
+        .is_some_and(|v| v == "true")
+}
+
+/// Rejects a destructive operation while a PIN is set and the session is locked.
+pub async fn ensure_unlocked(db: &Database, lock: &AppLock, operation: &str) -> Result<()> {
+    if is_lock_enabled(db).await && !lock.is_unlocked() {
+        return Err(AppError::PermissionDenied(format!(
+            "{} requires unlocking the app first",
+            operation
+        )));
+    }
+    Ok(())
+}
+
+/// Guard for commands that change tasks or settings: checks read-only mode and the app lock.
+pub async fn ensure_can_modify(db: &Database, lock: &AppLock, operation: &str) -> Result<()> {
+    ensure_writable(db, operation).await?;
+    ensure_unlocked(db, lock, operation).await
+}
+
+/// Runs PIN hashing and keyring access off the async runtime; both can take a while.
+async fn blocking<T: Send + 'static>(work: impl FnOnce() -> Result<T> + Send + 'static) -> Result<T> {
+    tokio::task::spawn_blocking(work)
+        .await
+        .map_err(|e| AppError::Keyring(format!("PIN check failed: {}", e)))?
+}
+
+fn hash_pin(salt: &[u8], pin: &str) -> String {
+    let mut digest = Sha256::new().chain_update(salt).chain_update(pin.as_bytes()).finalize();
+    for _ in 1..PIN_HASH_ROUNDS {
+        digest = Sha256::new().chain_update(salt).chain_update(digest).finalize();
+    }
+    hex::encode(digest)
+}
+
+fn verify_pin(pin: &str) -> Result<bool> {
+    let Some(stored) = keyring::get_secret(PIN_KEY)? else {
+        return Ok(false);
+    };
+    let Some((salt, expected)) = stored.split_once('$') else {
+        return Ok(false);
+    };
+    let Ok(salt) = hex::decode(salt) else {
+        return Ok(false);
+    };
+
+    // Compare without short-circuiting so timing does not reveal matching prefixes
+    let actual = hash_pin(&salt, pin);
+    Ok(actual.len() == expected.len()
+        && actual.bytes().zip(expected.bytes()).fold(0u8, |diff, (a, b)| diff | (a ^ b)) == 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash_pin_depends_on_salt_and_pin() {
+        let hash = hash_pin(b"salt", "1234");
+        assert_eq!(hash, hash_pin(b"salt", "1234"));
+        assert_ne!(hash, hash_pin(b"other", "1234"));
+        assert_ne!(hash, hash_pin(b"salt", "1235"));
+        assert_eq!(hash.len(), 64);
+    }
+}
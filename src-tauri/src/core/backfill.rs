@@ -0,0 +1,189 @@
+use chrono::{DateTime, Duration, Utc};
+use tauri::AppHandle;
+use crate::core::events::{emit_task_updated, TaskUpdateCause};
+use crate::core::recurrence;
+use crate::db::{
+    AuditSource, BackfillReport, Database, ExecutionAction, ExecutionStatus, LocaleConfig, MissedPolicy, MissedTask,
+    NotificationKind, Task, TaskStatus,
+};
+use crate::error::Result;
+use crate::notifications;
+use crate::utils::locale;
+
+/// Missed occurrences listed (and recorded as skipped) per task; older ones are only counted
+const MAX_LISTED_MISSED: usize = 100;
+
+/// Upper bound on missed occurrences counted per task, e.g. for a daily task after years offline
+const MAX_COUNTED_MISSED: usize = 10_000;
+
+/// Minutes the notification asking to run a missed task stays up when the task has no close
+const ASK_WAIT_MINUTES: i64 = 30;
+
+/// Finds open occurrences that passed while the scheduler was not running them and applies
+/// each task's missed policy, or `policy` for tasks without one. `reason` tells when they were
+/// missed, e.g. "while the app was not running".
+///
+/// Skipped occurrences are recorded in `task_executions`, which also keeps the scheduler from
+/// firing them later. With `MissedPolicy::RunLatest` the task's next open is set to its latest
+/// missed occurrence, so the scheduler runs it once right away. With `MissedPolicy::Ask` a
+/// notification offers to open it instead.
+pub async fn backfill_missed(
+    db: &Database,
+    app_handle: &AppHandle,
+    policy: MissedPolicy,
+    reason: &str,
+) -> Result<BackfillReport> {
+    let now = Utc::now();
+    let mut missed_tasks = Vec::new();
+
+    for mut task in db.get_all_tasks().await? {
+        // Interrupted pomodoro sessions and step sequences simply resume late
+        if task.status != TaskStatus::Active || task.pomodoro_cycle > 0 || task.current_step > 0 {
+            continue;
+        }
+        let Some(task_id) = task.id else { continue };
+
+        let missed = missed_occurrences(&task, now)?;
+        let Some(&latest) = missed.last() else { continue };
+
+        let task_policy = task.missed_policy.clone().unwrap_or_else(|| policy.clone());
+        let window = task.open_length();
+        let latest_open = window.map_or(true, |window| latest + window > now);
+        let runs_latest = task_policy == MissedPolicy::RunLatest && latest_open;
+        let asked = task_policy == MissedPolicy::Ask && latest_open;
+
+        let skipped = if runs_latest { &missed[..missed.len() - 1] } else { &missed[..] };
+        for &occurrence in skipped.iter().rev().take(MAX_LISTED_MISSED) {
+            let message = if asked && occurrence == latest {
+                format!("Missed {}; asked whether to run it now", reason)
+            } else {
+                format!("Missed {}", reason)
+            };
+            if let Some(execution_id) = db.begin_execution(task_id, &ExecutionAction::Open, Some(occurrence)).await? {
+                db.finish_execution(execution_id, ExecutionStatus::Skipped, Some(message)).await?;
+            }
+        }
+
+        if runs_latest {
+            task.next_open_execution = Some(latest);
+            task.next_close_execution = window.map(|window| latest + window);
+        } else {
+            skip_to_next_occurrence(&mut task, now)?;
+        }
+
+        let task = db.update_task(task_id, task, false, AuditSource::Scheduler).await?;
+        emit_task_updated(app_handle, &task, TaskUpdateCause::Executed);
+
+        if asked {
+            let wait = window.map_or(Duration::minutes(ASK_WAIT_MINUTES), |window| latest + window - now);
+            ask_to_run(db, app_handle, &task, latest, reason, wait).await;
+        }
+
+        let missed_count = missed.len();
+        missed_tasks.push(MissedTask {
+            task_id,
+            task_name: task.name.clone(),
+            missed: missed.into_iter().rev().take(MAX_LISTED_MISSED).rev().collect(),
+            missed_count,
+            runs_latest,
+            asked,
+        });
+    }
+
+    if !missed_tasks.is_empty() {
+        let names: Vec<&str> = missed_tasks.iter().map(|t| t.task_name.as_str()).collect();
+        let _ = notifications::record(
+            app_handle,
+            db,
+            NotificationKind::Missed,
+            None,
+            &format!("{} task(s) missed {}", missed_tasks.len(), reason),
+            &names.join(", "),
+        )
+        .await;
+    }
+
+    Ok(BackfillReport {
+        generated_at: now,
+        policy,
+        tasks: missed_tasks,
+    })
+}
+
+/// Asks with a notification whether to open a task whose occurrence at `missed_at` was missed.
+/// The Open now button runs it as a manual run; the schedule has already moved on.
+async fn ask_to_run(
+    db: &Database,
+    app_handle: &AppHandle,
+    task: &Task,
+    missed_at: DateTime<Utc>,
+    reason: &str,
+    wait: Duration,
+) {
+    let Some(task_id) = task.id else { return };
+    let settings = db.get_settings().await.ok();
+    let locale_config = settings.as_ref().map(LocaleConfig::from).unwrap_or_default();
+
+    let title = format!("Run {} now?", task.name);
+    let body = format!(
+        "{} was due at {} but was missed {}.",
+        task.name,
+        locale::format_datetime(missed_at, &locale_config),
+        reason
+    );
+
+    let _ = notifications::record(app_handle, db, NotificationKind::Missed, Some(task_id), &title, &body).await;
+
+    let sound = settings.map(|s| s.action_sound).unwrap_or_default();
+    notifications::actions::notify_confirm_open(app_handle, task_id, &title, &body, &sound, wait);
+}
+
+/// Open occurrences of a task between its next scheduled open and `now`, oldest first.
+fn missed_occurrences(task: &Task, now: DateTime<Utc>) -> Result<Vec<DateTime<Utc>>> {
+    let mut missed = Vec::new();
+    let Some(mut occurrence) = task.next_open_execution else {
+        return Ok(missed);
+    };
+
+    while occurrence <= now && missed.len() < MAX_COUNTED_MISSED {
+        missed.push(occurrence);
+
+        let Some(ref repeat_config) = task.repeat_config else { break };
+        let next = recurrence::next_execution(task, occurrence)?;
+        if !recurrence::should_continue_repeating(task, next, repeat_config) {
+            break;
+        }
+        occurrence = next;
+    }
+
+    Ok(missed)
+}
+
+/// Moves a task past every missed occurrence to its first occurrence after `now`, completing
+/// it if there is none.
+pub(crate) fn skip_to_next_occurrence(task: &mut Task, now: DateTime<Utc>) -> Result<()> {
+    let next = match task.repeat_config {
+        Some(ref repeat_config) => {
+            let next = recurrence::next_occurrence_after(task, now)?;
+            recurrence::should_continue_repeating(task, next, repeat_config).then_some(next)
+        }
+        None => None,
+    };
+
+    match next {
+        Some(next) => {
+            let next = task.jittered(next);
+            task.next_open_execution = Some(next);
+            task.next_close_execution = task
+                .close_time
+                .map(|close| next + close.signed_duration_since(task.start_time));
+        }
+        None => {
+            task.next_open_execution = None;
+            task.next_close_execution = None;
+            task.status = TaskStatus::Completed;
+        }
+    }
+
+    Ok(())
+}
@@ -1,12 +1,263 @@
-use crate::db::models::BrowserType;
+use crate::db::models::{BrowserLaunchTest, BrowserType, WindowState};
 use crate::error::{AppError, Result};
-use crate::utils::validation::validate_browser_profile;
+use crate::utils::validation::{validate_browser_profile, validate_locale, validate_user_agent};
 #[cfg(target_os = "macos")]
 use crate::utils::validation::escape_applescript_string;
-use std::process::{Child, Command};
+use crate::core::reaper::{self, ProcessExit};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+#[cfg(not(target_os = "macos"))]
+use std::process::Stdio;
+use tokio::sync::oneshot;
+
+/// Seconds a test launch waits for the browser process to appear
+const LAUNCH_CHECK_ATTEMPTS: u64 = 5;
+
+/// Prints the ids of the chrome.exe processes whose executable lies in one of the `|`-separated
+/// directories in DIT_DIRS, passed in the environment so no path reaches the script text
+#[cfg(target_os = "windows")]
+const CHROMIUM_PIDS_SCRIPT: &str = r#"
+$dirs = $env:DIT_DIRS -split '\|'
+Get-CimInstance Win32_Process -Filter "Name = 'chrome.exe'" | Where-Object {
+    $path = $_.ExecutablePath
+    $path -and @($dirs | Where-Object { $path.StartsWith($_, [System.StringComparison]::OrdinalIgnoreCase) }).Count -gt 0
+} | ForEach-Object { $_.ProcessId }
+"#;
+
+/// Stops the processes whose command line matches the `-like` pattern in DIT_PATTERN, passed in
+/// the environment so no URL reaches the script text, and prints how many there were
+#[cfg(target_os = "windows")]
+const STOP_MATCHING_SCRIPT: &str = r#"
+$pattern = "*$($env:DIT_PATTERN)*"
+$found = @(Get-CimInstance Win32_Process | Where-Object { $_.ProcessId -ne $PID -and $_.CommandLine -like $pattern })
+$found | ForEach-Object { Stop-Process -Id $_.ProcessId -Force -ErrorAction SilentlyContinue }
+$found.Count
+"#;
 
 pub struct BrowserLauncher;
 
+/// Starts the browser in its own session (Unix) or process group (Windows), so that neither a
+/// signal sent to the app's group, e.g. Ctrl+C or a closed terminal, nor the app quitting
+/// takes the user's browser down with it. The output pipes close when the app quits; browsers
+/// ignore SIGPIPE, so their later writes just fail.
+#[cfg(not(target_os = "macos"))]
+fn detach(cmd: &mut Command) {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+
+        extern "C" {
+            fn setsid() -> i32;
+        }
+
+        // SAFETY: setsid is async-signal-safe and touches no memory of the forked process
+        unsafe {
+            cmd.pre_exec(|| {
+                if setsid() == -1 {
+                    return Err(std::io::Error::last_os_error());
+                }
+                Ok(())
+            });
+        }
+    }
+
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+
+        const DETACHED_PROCESS: u32 = 0x0000_0008;
+        const CREATE_NEW_PROCESS_GROUP: u32 = 0x0000_0200;
+        cmd.creation_flags(DETACHED_PROCESS | CREATE_NEW_PROCESS_GROUP);
+    }
+}
+
+/// The error for a browser that could not be started, naming the executable that was tried.
+fn launch_error(browser: &BrowserType, command: &str, error: std::io::Error) -> AppError {
+    AppError::Scheduler(format!("Failed to launch {} ({}): {}", browser, command, error))
+}
+
+/// A browser process started by `open_browser`.
+#[derive(Debug)]
+pub struct LaunchedBrowser {
+    pub pid: Option<u32>,
+    /// Resolves when the process exits. `None` where the launch goes through a helper such as
+    /// `open` on macOS, whose exit says nothing about the browser.
+    pub exit: Option<oneshot::Receiver<ProcessExit>>,
+}
+
+/// Per-launch window settings a task adds on top of the browser and profile.
+#[derive(Debug, Clone, Default)]
+pub struct LaunchOptions {
+    /// Top-left corner of the new window, e.g. the origin of the task's target display
+    pub window_position: Option<(i32, i32)>,
+    /// Width and height of the new window
+    pub window_size: Option<(u32, u32)>,
+    /// Start the browser fullscreen without any of its own UI
+    pub kiosk: bool,
+    /// Open the URL as an app, in its own window without tabs or toolbars
+    pub app_mode: bool,
+    pub window_state: WindowState,
+    pub zoom: Option<f64>,
+    pub user_agent: Option<String>,
+    pub lang: Option<String>,
+    /// Throwaway profile directory the browser runs on instead of the user's profile
+    pub user_data_dir: Option<PathBuf>,
+}
+
+/// Temporary profile directory of a task's fresh-session opens
+pub fn fresh_session_dir(task_id: i64) -> PathBuf {
+    std::env::temp_dir().join(format!("do-in-time-fresh-{}", task_id))
+}
+
+/// Time a launched browser gets to show its window before it is minimized
+const MINIMIZE_DELAY: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// Command-line switches for `options`. Chromium-family browsers take window switches and
+/// Firefox-based ones take a window size; for the others the options are ignored.
+pub fn launch_args(browser: &BrowserType, options: &LaunchOptions) -> Vec<String> {
+    let mut args = Vec::new();
+    if browser.is_firefox() {
+        if options.kiosk {
+            args.push("-kiosk".to_string());
+        }
+        // Sizes the window a new instance opens; Firefox has no switch for its position
+        if let Some((width, height)) = options.window_size {
+            args.extend(["-width".to_string(), width.to_string(), "-height".to_string(), height.to_string()]);
+        }
+        return args;
+    }
+    if !browser.is_chromium() {
+        return args;
+    }
+
+    // A tab added to a running browser's window would keep that window's position and state
+    if options.window_position.is_some()
+        || options.window_size.is_some()
+        || options.window_state != WindowState::Normal
+        || options.kiosk
+    {
+        args.push("--new-window".to_string());
+    }
+    if options.kiosk {
+        args.push("--kiosk".to_string());
+    }
+    if let Some((x, y)) = options.window_position {
+        args.push(format!("--window-position={},{}", x, y));
+    }
+    if let Some((width, height)) = options.window_size {
+        args.push(format!("--window-size={},{}", width, height));
+    }
+    if let Some(zoom) = options.zoom {
+        // Scales the whole browser process, which only picks it up when it starts
+        args.push(format!("--force-device-scale-factor={}", zoom));
+    }
+    if let Some(ref user_agent) = options.user_agent {
+        args.push(format!("--user-agent={}", user_agent.trim()));
+    }
+    if let Some(ref lang) = options.lang {
+        args.push(format!("--lang={}", lang));
+    }
+    if let Some(ref dir) = options.user_data_dir {
+        // A separate profile directory runs as its own browser instance
+        args.push(format!("--user-data-dir={}", dir.display()));
+        args.push("--no-first-run".to_string());
+        args.push("--no-default-browser-check".to_string());
+    }
+    match options.window_state {
+        WindowState::Maximized => args.push("--start-maximized".to_string()),
+        WindowState::Fullscreen => args.push("--start-fullscreen".to_string()),
+        // Chromium has no switch for it; minimizing happens after the launch
+        WindowState::Normal | WindowState::Minimized => {}
+    }
+
+    args
+}
+
+/// The URL argument of a launch: `--app=<url>` for an app window of a Chromium-family browser,
+/// otherwise the URL itself
+fn app_url_arg(browser: &BrowserType, url: &str, app_mode: bool) -> String {
+    if app_mode && browser.is_chromium() {
+        format!("--app={}", url)
+    } else {
+        url.to_string()
+    }
+}
+
+/// Stops every process whose command line contains `fragment`, returning whether there was any
+fn stop_processes_matching(fragment: &str) -> Result<bool> {
+    #[cfg(target_os = "windows")]
+    {
+        // `-like` treats brackets, `*` and `?` as wildcards
+        let pattern: String = fragment
+            .chars()
+            .flat_map(|c| {
+                let escape = "[]*?`".contains(c);
+                escape.then_some('`').into_iter().chain(std::iter::once(c))
+            })
+            .collect();
+        let output = Command::new("powershell")
+            .args(["-NoProfile", "-NonInteractive", "-Command", STOP_MATCHING_SCRIPT])
+            .env("DIT_PATTERN", pattern)
+            .output()
+            .map_err(|e| AppError::Scheduler(format!("Failed to stop the browser process: {}", e)))?;
+        Ok(String::from_utf8_lossy(&output.stdout).trim().parse::<usize>().is_ok_and(|count| count > 0))
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        // pkill matches a regular expression against the full command line
+        let pattern: String = fragment
+            .chars()
+            .flat_map(|c| {
+                let escape = ".^$*+?()[]{}|\\".contains(c);
+                escape.then_some('\\').into_iter().chain(std::iter::once(c))
+            })
+            .collect();
+        let output = Command::new(if cfg!(target_os = "macos") { "/usr/bin/pkill" } else { "pkill" })
+            .args(["-f", "--", &pattern])
+            .output()
+            .map_err(|e| AppError::Scheduler(format!("Failed to stop the browser process: {}", e)))?;
+        // pkill exits with 1 when no process matched
+        Ok(output.status.success())
+    }
+}
+
+/// Where Chromium installs on Windows. Its executable is chrome.exe, the same as Google
+/// Chrome's, so its processes are told apart by the directory they run from.
+#[cfg(target_os = "windows")]
+fn chromium_install_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    if let Some(local) = std::env::var_os("LOCALAPPDATA") {
+        dirs.push(PathBuf::from(local).join("Chromium").join("Application"));
+    }
+    dirs.push(PathBuf::from("C:\\Program Files\\Chromium\\Application"));
+    dirs
+}
+
+/// Ids of the running Chromium processes, leaving out Google Chrome's.
+#[cfg(target_os = "windows")]
+fn chromium_pids() -> Result<Vec<u32>> {
+    let dirs: Vec<String> = chromium_install_dirs()
+        .iter()
+        .map(|dir| format!("{}\\", dir.display()))
+        .collect();
+    let output = Command::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command", CHROMIUM_PIDS_SCRIPT])
+        .env("DIT_DIRS", dirs.join("|"))
+        .output()
+        .map_err(|e| AppError::Scheduler(format!("Failed to list Chromium processes: {}", e)))?;
+    if !output.status.success() {
+        return Err(AppError::Scheduler(format!(
+            "Failed to list Chromium processes: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .split_whitespace()
+        .filter_map(|pid| pid.parse().ok())
+        .collect())
+}
+
 impl BrowserLauncher {
     pub fn new() -> Self {
         Self
@@ -18,27 +269,138 @@ impl BrowserLauncher {
         browser: &BrowserType,
         url: Option<&str>,
         profile: Option<&str>,
-    ) -> Result<Option<u32>> {
+        options: &LaunchOptions,
+    ) -> Result<LaunchedBrowser> {
+        // Validate the user agent for security, as it is passed on the command line
+        if let Some(ref user_agent) = options.user_agent {
+            validate_user_agent(user_agent)?;
+        }
+        if let Some(ref lang) = options.lang {
+            validate_locale(lang)?;
+        }
+
+        // A throwaway profile replaces the configured one
+        let profile = if options.user_data_dir.is_some() && browser.is_chromium() { None } else { profile };
         let (command, mut args) = self.get_browser_command(browser, profile)?;
+        args.extend(launch_args(browser, options));
 
         // Add URL if provided
         if let Some(u) = url {
-            args.push(u.to_string());
+            args.push(app_url_arg(browser, u, options.app_mode));
         }
 
-        let child = self.spawn_browser(&command, &args, browser)?;
-        let pid = child.map(|c| c.id());
+        let launched = self.spawn_browser(&command, &args, browser)?;
+
+        if options.window_state == WindowState::Minimized {
+            tokio::time::sleep(MINIMIZE_DELAY).await;
+            if let Err(e) = self.minimize_front_window(browser) {
+                println!("⚠ Could not minimize the new {} window: {}", browser, e);
+            }
+        }
 
         if let Some(u) = url {
-            println!("Opening {} with URL: {}", browser, u);
+            // The query string may hold a secret resolved from the keyring
+            let logged = u.split(['?', '#']).next().unwrap_or(u);
+            println!("Opening {} with URL: {}", browser, logged);
         } else {
             println!("Opening {}", browser);
         }
 
-        Ok(pid)
+        Ok(launched)
+    }
+
+    /// Opens a test page to check a browser and profile configuration.
+    ///
+    /// Waits for the browser process to show up and, if `close_after` is given, closes the test
+    /// page again after that delay. Where tabs cannot be closed individually, the browser is only
+    /// closed if the test started it, so an existing session is never killed.
+    pub async fn test_launch(
+        &self,
+        browser: &BrowserType,
+        profile: Option<&str>,
+        url: &str,
+        close_after: Option<std::time::Duration>,
+    ) -> Result<BrowserLaunchTest> {
+        let was_running = self.is_running(browser);
+        let pid = self.open_browser(browser, Some(url), profile, &LaunchOptions::default()).await?.pid;
+
+        let mut launched = false;
+        for _ in 0..LAUNCH_CHECK_ATTEMPTS {
+            tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+            if self.is_running(browser) {
+                launched = true;
+                break;
+            }
+        }
+
+        let mut closed = false;
+        if let Some(delay) = close_after.filter(|_| launched) {
+            tokio::time::sleep(delay).await;
+
+            if cfg!(target_os = "macos") {
+                self.close_browser_by_url(browser, url, false).await?;
+                closed = true;
+            } else if !was_running {
+                self.close_browser(browser).await?;
+                closed = true;
+            }
+        }
+
+        let message = match (launched, closed) {
+            (false, _) => format!("{} did not start within {} seconds", browser, LAUNCH_CHECK_ATTEMPTS),
+            (true, false) if close_after.is_some() => {
+                format!("{} opened; close the test page manually since the browser was already running", browser)
+            }
+            (true, false) => format!("{} opened the test page", browser),
+            (true, true) => format!("{} opened and closed the test page", browser),
+        };
+
+        Ok(BrowserLaunchTest {
+            browser: browser.clone(),
+            launched,
+            pid,
+            closed,
+            message,
+        })
     }
 
-    fn spawn_browser(&self, command: &str, args: &[String], browser: &BrowserType) -> Result<Option<Child>> {
+    /// Whether any process of the browser is currently running
+    fn is_running(&self, browser: &BrowserType) -> bool {
+        let process_name = self.get_process_name(browser);
+
+        #[cfg(target_os = "windows")]
+        {
+            if *browser == BrowserType::Chromium {
+                return chromium_pids().is_ok_and(|pids| !pids.is_empty());
+            }
+            Command::new(Self::windows_system32_exe("tasklist.exe"))
+                .args(["/FI", &format!("IMAGENAME eq {}", process_name), "/NH"])
+                .output()
+                .map(|o| String::from_utf8_lossy(&o.stdout).to_lowercase().contains(&process_name.to_lowercase()))
+                .unwrap_or(false)
+        }
+
+        #[cfg(target_os = "macos")]
+        {
+            Command::new("/usr/bin/pgrep")
+                .arg("-x")
+                .arg(&process_name)
+                .output()
+                .map(|o| o.status.success())
+                .unwrap_or(false)
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            Command::new("pgrep")
+                .arg(&process_name)
+                .output()
+                .map(|o| o.status.success())
+                .unwrap_or(false)
+        }
+    }
+
+    fn spawn_browser(&self, command: &str, args: &[String], browser: &BrowserType) -> Result<LaunchedBrowser> {
         #[cfg(target_os = "windows")]
         {
             // On Windows, launch directly to get PID
@@ -46,18 +408,23 @@ impl BrowserLauncher {
             for arg in args {
                 cmd.arg(arg);
             }
+            // Piped so a browser that quits right away can say why
+            cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+            detach(&mut cmd);
 
-            let child = cmd
-                .spawn()
-                .map_err(|e| AppError::Scheduler(format!("Failed to launch {}: {}", browser, e)))?;
+            let child = cmd.spawn().map_err(|e| launch_error(browser, command, e))?;
 
-            Ok(Some(child))
+            Ok(LaunchedBrowser { pid: Some(child.id()), exit: Some(reaper::reap(child)) })
         }
 
         #[cfg(target_os = "macos")]
         {
             // On macOS, use open command but can't easily track PID
             let mut cmd = Command::new("/usr/bin/open");
+            // A new instance is needed for a separate profile; a running one ignores --args
+            if args.iter().any(|arg| arg.starts_with("--user-data-dir=")) {
+                cmd.arg("-n");
+            }
             cmd.arg("-a").arg(command);
 
             if !args.is_empty() {
@@ -67,11 +434,10 @@ impl BrowserLauncher {
                 }
             }
 
-            cmd.spawn()
-                .map_err(|e| AppError::Scheduler(format!("Failed to launch {}: {}", browser, e)))?;
+            reaper::spawn_detached(&mut cmd).map_err(|e| launch_error(browser, "/usr/bin/open", e))?;
 
             // Can't reliably get PID on macOS with open command
-            Ok(None)
+            Ok(LaunchedBrowser { pid: None, exit: None })
         }
 
         #[cfg(target_os = "linux")]
@@ -79,13 +445,18 @@ impl BrowserLauncher {
             let mut cmd = Command::new(command);
             for arg in args {
                 cmd.arg(arg);
+                // Chromium ignores --lang on Linux and reads the locale from the environment
+                if let Some(lang) = arg.strip_prefix("--lang=") {
+                    cmd.env("LANGUAGE", lang.replace('-', "_"));
+                }
             }
+            // Piped so a browser that quits right away can say why, e.g. no display to open on
+            cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+            detach(&mut cmd);
 
-            let child = cmd
-                .spawn()
-                .map_err(|e| AppError::Scheduler(format!("Failed to launch {}: {}", browser, e)))?;
+            let child = cmd.spawn().map_err(|e| launch_error(browser, command, e))?;
 
-            Ok(Some(child))
+            Ok(LaunchedBrowser { pid: Some(child.id()), exit: Some(reaper::reap(child)) })
         }
     }
 
@@ -93,7 +464,8 @@ impl BrowserLauncher {
     ///
     /// Platform-specific implementations:
     /// - macOS: Uses AppleScript to close tabs matching URL (automatic)
-    /// - Windows: Manual close required (no native tab-level control available)
+    /// - Windows: Manual close required, unless `ui_automation` is set: then Chromium tabs are
+    ///   closed through UI Automation, best effort (see `core::ui_automation`)
     /// - Linux: Closes all browser instances (fallback)
     ///
     /// ## Windows Limitation
@@ -111,10 +483,31 @@ impl BrowserLauncher {
     /// - **Browser extensions**: Requires pre-installation and browser-specific implementations
     /// - **Native messaging**: Requires separate browser extension for each browser
     ///
-    /// For now, Windows users must manually close tabs after they're opened by the scheduler.
-    pub async fn close_browser_by_url(&self, browser: &BrowserType, url: &str) -> Result<()> {
+    /// Without the opt-in UI Automation backend, Windows users must manually close tabs after
+    /// they're opened by the scheduler.
+    #[cfg_attr(not(target_os = "windows"), allow(unused_variables))]
+    pub async fn close_browser_by_url(&self, browser: &BrowserType, url: &str, ui_automation: bool) -> Result<()> {
         #[cfg(target_os = "windows")]
         {
+            if ui_automation && browser.is_chromium() {
+                // Only Chromium's own windows, not those of Google Chrome sharing its process name
+                let pids = match browser {
+                    BrowserType::Chromium => Some(chromium_pids()?),
+                    _ => None,
+                };
+                let (process, target) = (self.get_process_name(browser), url.to_string());
+                let closed = tokio::task::spawn_blocking(move || {
+                    super::ui_automation::close_tabs(&process, pids.as_deref(), &target)
+                })
+                .await
+                    .map_err(|e| AppError::Scheduler(format!("UI Automation task failed: {}", e)))??;
+                if closed > 0 {
+                    println!("Closed {} {} tab(s) with URL {} through UI Automation", closed, browser, url);
+                    return Ok(());
+                }
+                println!("UI Automation found no {} tab with URL {}", browser, url);
+            }
+
             // Windows: Manual close required
             // See function documentation above for detailed explanation of Windows limitations
             println!(
@@ -128,14 +521,7 @@ impl BrowserLauncher {
         #[cfg(target_os = "macos")]
         {
             // Use AppleScript like the original Deno implementation
-            let app_name = match browser {
-                BrowserType::Chrome => "Google Chrome",
-                BrowserType::Edge => "Microsoft Edge",
-                BrowserType::Firefox => "Firefox",
-                BrowserType::Safari => "Safari",
-                BrowserType::Brave => "Brave Browser",
-                BrowserType::Opera => "Opera",
-            };
+            let app_name = Self::macos_app_name(browser);
 
             // Sanitize URL to prevent AppleScript injection
             let escaped_url = escape_applescript_string(url);
@@ -170,33 +556,177 @@ impl BrowserLauncher {
         }
     }
 
+    /// Closes the browser instance running on the throwaway profile `user_data_dir` and
+    /// deletes the profile. Other windows of the browser are left alone.
+    pub async fn close_fresh_session(&self, user_data_dir: &Path) -> Result<()> {
+        self.close_profile_instance(user_data_dir)?;
+
+        // The browser may still be writing to the profile while it shuts down
+        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+        remove_profile_dir(user_data_dir)
+    }
+
+    /// Closes the browser instance running on the profile directory `user_data_dir`, leaving
+    /// other windows of the browser alone.
+    pub fn close_profile_instance(&self, user_data_dir: &Path) -> Result<()> {
+        stop_processes_matching(&format!("--user-data-dir={}", user_data_dir.display()))?;
+        Ok(())
+    }
+
+    /// Closes the app window `browser` opened on `url` in app mode. A browser started for the
+    /// app window carries `--app=<url>` on its command line and is stopped as a whole; when the
+    /// window went to a browser that was already running, it is closed like a tab instead.
+    pub async fn close_app_window(&self, browser: &BrowserType, url: &str, ui_automation: bool) -> Result<()> {
+        if browser.is_chromium() && stop_processes_matching(&format!("--app={}", url))? {
+            println!("Closed the {} app window with URL: {}", browser, url);
+            return Ok(());
+        }
+        self.close_browser_by_url(browser, url, ui_automation).await
+    }
+
+    /// Minimizes or restores every window of a browser, used to enforce breaks
+    ///
+    /// Platform-specific implementations:
+    /// - macOS: AppleScript window properties (Firefox and Zen are not scriptable and are skipped)
+    /// - Linux: `xdotool`, when installed
+    /// - Windows: not supported
+    pub async fn set_windows_minimized(&self, browser: &BrowserType, minimized: bool) -> Result<()> {
+        #[cfg(target_os = "windows")]
+        {
+            let _ = minimized;
+            println!("⚠ Windows: minimizing {} windows is not supported", browser);
+            Ok(())
+        }
+
+        #[cfg(target_os = "macos")]
+        {
+            if browser.is_firefox() {
+                println!("macOS: {} windows cannot be minimized via AppleScript", browser);
+                return Ok(());
+            }
+
+            // Safari uses the standard AppleScript property, Chromium browsers their own
+            let property = if *browser == BrowserType::Safari { "miniaturized" } else { "minimized" };
+            let script = format!(
+                r#"tell application "{}" to set {} of every window to {}"#,
+                Self::macos_app_name(browser),
+                property,
+                minimized
+            );
+
+            let output = Command::new("/usr/bin/osascript")
+                .arg("-e")
+                .arg(&script)
+                .output()
+                .map_err(|e| AppError::Scheduler(format!("Failed to execute AppleScript: {}", e)))?;
+
+            if output.status.success() {
+                Ok(())
+            } else {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                Err(AppError::Scheduler(format!("AppleScript error: {}", stderr)))
+            }
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            let window_command = if minimized { "windowminimize" } else { "windowmap" };
+
+            Command::new("xdotool")
+                .args(["search", "--class", &self.get_process_name(browser), window_command, "%@"])
+                .output()
+                .map_err(|e| AppError::Scheduler(format!("Failed to run xdotool: {}", e)))?;
+
+            Ok(())
+        }
+    }
+
+    /// Minimizes the browser's frontmost window, the one a launch just opened
+    ///
+    /// Platform-specific implementations:
+    /// - macOS: AppleScript (Firefox and Zen are not scriptable and are skipped)
+    /// - Linux: `xdotool` on the active window, when installed
+    /// - Windows: not supported
+    fn minimize_front_window(&self, browser: &BrowserType) -> Result<()> {
+        #[cfg(target_os = "windows")]
+        {
+            println!("⚠ Windows: minimizing the new {} window is not supported", browser);
+            Ok(())
+        }
+
+        #[cfg(target_os = "macos")]
+        {
+            if browser.is_firefox() {
+                println!("macOS: {} windows cannot be minimized via AppleScript", browser);
+                return Ok(());
+            }
+
+            let property = if *browser == BrowserType::Safari { "miniaturized" } else { "minimized" };
+            let script = format!(
+                r#"tell application "{}" to set {} of front window to true"#,
+                Self::macos_app_name(browser),
+                property
+            );
+
+            let output = Command::new("/usr/bin/osascript")
+                .arg("-e")
+                .arg(&script)
+                .output()
+                .map_err(|e| AppError::Scheduler(format!("Failed to execute AppleScript: {}", e)))?;
+
+            if output.status.success() {
+                Ok(())
+            } else {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                Err(AppError::Scheduler(format!("AppleScript error: {}", stderr)))
+            }
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            let _ = browser;
+            // The new window takes the focus, so it is the active one
+            Command::new("xdotool")
+                .args(["getactivewindow", "windowminimize"])
+                .output()
+                .map_err(|e| AppError::Scheduler(format!("Failed to run xdotool: {}", e)))?;
+
+            Ok(())
+        }
+    }
+
     pub async fn close_browser(&self, browser: &BrowserType) -> Result<()> {
         let process_name = self.get_process_name(browser);
 
         #[cfg(target_os = "windows")]
         {
-            Command::new(Self::windows_system32_exe("taskkill.exe"))
-                .arg("/F")
-                .arg("/IM")
-                .arg(&process_name)
-                .spawn()
+            let mut taskkill = Command::new(Self::windows_system32_exe("taskkill.exe"));
+            taskkill.arg("/F");
+            if *browser == BrowserType::Chromium {
+                // By process id, as /IM chrome.exe would take Google Chrome down too
+                let pids = chromium_pids()?;
+                if pids.is_empty() {
+                    return Ok(());
+                }
+                for pid in pids {
+                    taskkill.arg("/PID").arg(pid.to_string());
+                }
+            } else {
+                taskkill.arg("/IM").arg(&process_name);
+            }
+            reaper::spawn_detached(&mut taskkill)
                 .map_err(|e| AppError::Scheduler(format!("Failed to close {}: {}", browser, e)))?;
         }
 
         #[cfg(target_os = "macos")]
         {
-            Command::new("/usr/bin/pkill")
-                .arg("-x")
-                .arg(&process_name)
-                .spawn()
+            reaper::spawn_detached(Command::new("/usr/bin/pkill").arg("-x").arg(&process_name))
                 .map_err(|e| AppError::Scheduler(format!("Failed to close {}: {}", browser, e)))?;
         }
 
         #[cfg(target_os = "linux")]
         {
-            Command::new("pkill")
-                .arg(&process_name)
-                .spawn()
+            reaper::spawn_detached(Command::new("pkill").arg(&process_name))
                 .map_err(|e| AppError::Scheduler(format!("Failed to close {}: {}", browser, e)))?;
         }
 
@@ -368,6 +898,110 @@ impl BrowserLauncher {
                     .unwrap_or_else(|| "opera".to_string())
                 }
             }
+            BrowserType::Vivaldi => {
+                if let Some(prof) = profile {
+                    args.push(format!("--profile-directory={}", prof));
+                }
+
+                #[cfg(target_os = "windows")]
+                {
+                    self.find_browser_path_windows("vivaldi.exe", &[
+                        "C:\\Program Files\\Vivaldi\\Application\\vivaldi.exe",
+                        "C:\\Program Files (x86)\\Vivaldi\\Application\\vivaldi.exe",
+                    ])
+                    .ok_or_else(|| AppError::BrowserNotFound("Vivaldi executable not found".to_string()))?
+                }
+
+                #[cfg(target_os = "macos")]
+                {
+                    "Vivaldi".to_string()
+                }
+
+                #[cfg(target_os = "linux")]
+                {
+                    self.find_browser_path(&[
+                        "/usr/bin/vivaldi",
+                        "/usr/bin/vivaldi-stable",
+                        "/opt/vivaldi/vivaldi",
+                    ])
+                    .unwrap_or_else(|| "vivaldi".to_string())
+                }
+            }
+            BrowserType::Chromium => {
+                if let Some(prof) = profile {
+                    args.push(format!("--profile-directory={}", prof));
+                }
+
+                #[cfg(target_os = "windows")]
+                {
+                    // Chromium's executable is chrome.exe too, so its App Paths entry may well
+                    // be Google Chrome's; only its own install locations are checked
+                    chromium_install_dirs()
+                        .into_iter()
+                        .map(|dir| dir.join("chrome.exe"))
+                        .find(|path| path.exists())
+                        .map(|path| path.display().to_string())
+                        .ok_or_else(|| AppError::BrowserNotFound("Chromium executable not found".to_string()))?
+                }
+
+                #[cfg(target_os = "macos")]
+                {
+                    "Chromium".to_string()
+                }
+
+                #[cfg(target_os = "linux")]
+                {
+                    self.find_browser_path(&[
+                        "/usr/bin/chromium",
+                        "/usr/bin/chromium-browser",
+                        "/snap/bin/chromium",
+                    ])
+                    .unwrap_or_else(|| "chromium".to_string())
+                }
+            }
+            BrowserType::Arc => {
+                #[cfg(target_os = "macos")]
+                {
+                    "Arc".to_string()
+                }
+
+                #[cfg(not(target_os = "macos"))]
+                {
+                    return Err(AppError::BrowserNotFound(
+                        "Arc is only supported on macOS".to_string(),
+                    ));
+                }
+            }
+            BrowserType::Zen => {
+                if let Some(prof) = profile {
+                    args.push("-P".to_string());
+                    args.push(prof.to_string());
+                }
+
+                #[cfg(target_os = "windows")]
+                {
+                    self.find_browser_path_windows("zen.exe", &[
+                        "C:\\Program Files\\Zen Browser\\zen.exe",
+                        "C:\\Program Files (x86)\\Zen Browser\\zen.exe",
+                    ])
+                    .ok_or_else(|| AppError::BrowserNotFound("Zen Browser executable not found".to_string()))?
+                }
+
+                #[cfg(target_os = "macos")]
+                {
+                    "Zen".to_string()
+                }
+
+                #[cfg(target_os = "linux")]
+                {
+                    self.find_browser_path(&[
+                        "/usr/bin/zen-browser",
+                        "/opt/zen-browser/zen",
+                        "/opt/zen/zen",
+                    ])
+                    .unwrap_or_else(|| "zen-browser".to_string())
+                }
+            }
         };
 
         Ok((command, args))
@@ -448,10 +1082,73 @@ impl BrowserLauncher {
                     "opera".to_string()
                 }
             }
+            BrowserType::Vivaldi => {
+                #[cfg(target_os = "windows")]
+                {
+                    "vivaldi.exe".to_string()
+                }
+                #[cfg(target_os = "macos")]
+                {
+                    "Vivaldi".to_string()
+                }
+                #[cfg(target_os = "linux")]
+                {
+                    "vivaldi".to_string()
+                }
+            }
+            BrowserType::Chromium => {
+                #[cfg(target_os = "windows")]
+                {
+                    // Shared with Google Chrome; Chromium's processes are found by their path
+                    // through chromium_pids wherever the two must not be mixed up
+                    "chrome.exe".to_string()
+                }
+                #[cfg(target_os = "macos")]
+                {
+                    "Chromium".to_string()
+                }
+                #[cfg(target_os = "linux")]
+                {
+                    "chromium".to_string()
+                }
+            }
+            BrowserType::Arc => {
+                "Arc".to_string()
+            }
+            BrowserType::Zen => {
+                #[cfg(target_os = "windows")]
+                {
+                    "zen.exe".to_string()
+                }
+                #[cfg(target_os = "macos")]
+                {
+                    "Zen".to_string()
+                }
+                #[cfg(target_os = "linux")]
+                {
+                    // Anchored, as a bare `zen` would also match processes like `zenity`
+                    "^zen(-bin)?$".to_string()
+                }
+            }
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    pub(crate) fn macos_app_name(browser: &BrowserType) -> &'static str {
+        match browser {
+            BrowserType::Chrome => "Google Chrome",
+            BrowserType::Edge => "Microsoft Edge",
+            BrowserType::Firefox => "Firefox",
+            BrowserType::Safari => "Safari",
+            BrowserType::Brave => "Brave Browser",
+            BrowserType::Opera => "Opera",
+            BrowserType::Vivaldi => "Vivaldi",
+            BrowserType::Chromium => "Chromium",
+            BrowserType::Arc => "Arc",
+            BrowserType::Zen => "Zen",
         }
     }
 
-    #[cfg(any(target_os = "macos", target_os = "linux"))]
     fn find_browser_path(&self, paths: &[&str]) -> Option<String> {
         for path in paths {
             if std::path::Path::new(path).exists() {
@@ -543,3 +1240,112 @@ impl Default for BrowserLauncher {
         Self::new()
     }
 }
+
+/// Deletes a throwaway profile directory; a missing directory is fine.
+pub fn remove_profile_dir(dir: &Path) -> Result<()> {
+    match std::fs::remove_dir_all(dir) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_launch_args_window_position() {
+        let options = LaunchOptions { window_position: Some((1920, 0)), ..Default::default() };
+        assert_eq!(
+            launch_args(&BrowserType::Chrome, &options),
+            vec!["--new-window".to_string(), "--window-position=1920,0".to_string()]
+        );
+        assert!(launch_args(&BrowserType::Firefox, &options).is_empty());
+        assert!(launch_args(&BrowserType::Edge, &LaunchOptions::default()).is_empty());
+    }
+
+    #[test]
+    fn test_launch_args_window_size() {
+        let options = LaunchOptions {
+            window_position: Some((1920, 0)),
+            window_size: Some((1920, 1080)),
+            ..Default::default()
+        };
+        assert_eq!(
+            launch_args(&BrowserType::Edge, &options),
+            vec![
+                "--new-window".to_string(),
+                "--window-position=1920,0".to_string(),
+                "--window-size=1920,1080".to_string(),
+            ]
+        );
+        assert_eq!(launch_args(&BrowserType::Firefox, &options), vec!["-width", "1920", "-height", "1080"]);
+        assert!(launch_args(&BrowserType::Safari, &options).is_empty());
+    }
+
+    #[test]
+    fn test_app_url_arg() {
+        let url = "https://example.com/board";
+        assert_eq!(app_url_arg(&BrowserType::Edge, url, true), "--app=https://example.com/board");
+        assert_eq!(app_url_arg(&BrowserType::Edge, url, false), url);
+        assert_eq!(app_url_arg(&BrowserType::Firefox, url, true), url);
+    }
+
+    #[test]
+    fn test_launch_args_window_state() {
+        let options = LaunchOptions { window_state: WindowState::Fullscreen, ..Default::default() };
+        assert_eq!(
+            launch_args(&BrowserType::Brave, &options),
+            vec!["--new-window".to_string(), "--start-fullscreen".to_string()]
+        );
+
+        let options = LaunchOptions { window_state: WindowState::Minimized, ..Default::default() };
+        assert_eq!(launch_args(&BrowserType::Chrome, &options), vec!["--new-window".to_string()]);
+    }
+
+    #[test]
+    fn test_launch_args_kiosk() {
+        let options = LaunchOptions { kiosk: true, ..Default::default() };
+        assert_eq!(
+            launch_args(&BrowserType::Chrome, &options),
+            vec!["--new-window".to_string(), "--kiosk".to_string()]
+        );
+        assert_eq!(launch_args(&BrowserType::Firefox, &options), vec!["-kiosk"]);
+        assert_eq!(launch_args(&BrowserType::Zen, &options), vec!["-kiosk"]);
+        assert!(launch_args(&BrowserType::Safari, &options).is_empty());
+    }
+
+    #[test]
+    fn test_launch_args_zoom() {
+        let options = LaunchOptions { zoom: Some(1.5), ..Default::default() };
+        assert_eq!(
+            launch_args(&BrowserType::Chrome, &options),
+            vec!["--force-device-scale-factor=1.5".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_launch_args_user_agent() {
+        let options = LaunchOptions { user_agent: Some("Mobile Agent/1.0".to_string()), ..Default::default() };
+        assert_eq!(
+            launch_args(&BrowserType::Edge, &options),
+            vec!["--user-agent=Mobile Agent/1.0".to_string()]
+        );
+        assert!(launch_args(&BrowserType::Safari, &options).is_empty());
+    }
+
+    #[test]
+    fn test_launch_args_lang() {
+        let options = LaunchOptions { lang: Some("de".to_string()), ..Default::default() };
+        assert_eq!(launch_args(&BrowserType::Chrome, &options), vec!["--lang=de".to_string()]);
+    }
+
+    #[test]
+    fn test_launch_args_fresh_session() {
+        let dir = fresh_session_dir(7);
+        let options = LaunchOptions { user_data_dir: Some(dir.clone()), ..Default::default() };
+        let args = launch_args(&BrowserType::Chrome, &options);
+        assert_eq!(args[0], format!("--user-data-dir={}", dir.display()));
+        assert!(args.contains(&"--no-first-run".to_string()));
+    }
+}
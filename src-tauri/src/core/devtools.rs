@@ -0,0 +1,152 @@
+//! Minimal client for the HTTP endpoints of the Chrome DevTools Protocol, exposed by Chromium
+//! browsers started with `--remote-debugging-port`. Only the loopback interface is contacted.
+
+use std::io::{Read, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpStream};
+use std::time::Duration;
+use serde::Deserialize;
+use crate::db::BrowserType;
+use crate::error::{AppError, Result};
+
+const TIMEOUT: Duration = Duration::from_secs(2);
+
+/// A tab or other target listed by `/json/list`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Target {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Version {
+    #[serde(rename = "Browser", default)]
+    browser: String,
+    #[serde(rename = "User-Agent", default)]
+    user_agent: String,
+}
+
+/// Sends a GET request to the DevTools endpoint on `port` and returns the response body.
+pub fn get(port: u16, path: &str) -> Result<String> {
+    request(port, "GET", path)
+}
+
+/// Sends a `method` request to the DevTools endpoint on `port` and returns the response body.
+fn request(port: u16, method: &str, path: &str) -> Result<String> {
+    let address = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+    let mut stream = TcpStream::connect_timeout(&address, TIMEOUT)
+        .map_err(|e| AppError::Scheduler(format!("DevTools port {} is not reachable: {}", port, e)))?;
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    stream.set_write_timeout(Some(TIMEOUT))?;
+
+    write!(stream, "{} {} HTTP/1.0\r\nHost: 127.0.0.1:{}\r\nContent-Length: 0\r\n\r\n", method, path, port)?;
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response)?;
+
+    let (head, body) = response
+        .split_once("\r\n\r\n")
+        .ok_or_else(|| AppError::Scheduler("Malformed DevTools response".to_string()))?;
+    if !head.lines().next().is_some_and(|status| status.contains(" 200 ")) {
+        return Err(AppError::Scheduler(format!(
+            "DevTools request {} failed: {}",
+            path,
+            head.lines().next().unwrap_or_default()
+        )));
+    }
+
+    Ok(body.to_string())
+}
+
+/// Open page targets, i.e. the browser's tabs.
+pub fn list_pages(port: u16) -> Result<Vec<Target>> {
+    let targets: Vec<Target> = serde_json::from_str(&get(port, "/json/list")?)
+        .map_err(|e| AppError::Scheduler(format!("Unexpected DevTools target list: {}", e)))?;
+
+    Ok(targets.into_iter().filter(|t| t.kind == "page").collect())
+}
+
+/// Opens `url` in a new tab of the browser listening on `port`.
+pub fn open_page(port: u16, url: &str) -> Result<Target> {
+    // Chrome 111 and later refuse GET here; the URL is the whole query string, encoded so its
+    // own query and fragment survive and nothing can end the request line early
+    let body = request(port, "PUT", &format!("/json/new?{}", percent_encode(url)))?;
+    serde_json::from_str(&body).map_err(|e| AppError::Scheduler(format!("Unexpected DevTools target: {}", e)))
+}
+
+/// Percent-encodes everything in `value` but unreserved characters.
+fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (byte as char).to_string(),
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+/// Closes the tab `target_id`.
+pub fn close_page(port: u16, target_id: &str) -> Result<()> {
+    get(port, &format!("/json/close/{}", target_id)).map(|_| ())
+}
+
+/// Which browser is listening on `port`, judged from its version strings.
+pub fn browser(port: u16) -> Result<BrowserType> {
+    let version: Version = serde_json::from_str(&get(port, "/json/version")?)
+        .map_err(|e| AppError::Scheduler(format!("Unexpected DevTools version info: {}", e)))?;
+
+    Ok(browser_from_version(&version.browser, &version.user_agent))
+}
+
+fn browser_from_version(browser: &str, user_agent: &str) -> BrowserType {
+    if user_agent.contains("Edg/") || browser.contains("Edg") {
+        BrowserType::Edge
+    } else if user_agent.contains("OPR/") {
+        BrowserType::Opera
+    } else if user_agent.contains("Brave") {
+        BrowserType::Brave
+    } else if user_agent.contains("Vivaldi") {
+        BrowserType::Vivaldi
+    } else {
+        // Brave, Vivaldi and Chromium usually report themselves as Chrome
+        BrowserType::Chrome
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detects_browser_from_version_strings() {
+        assert_eq!(
+            browser_from_version("Chrome/120.0.6099.71", "Mozilla/5.0 Chrome/120.0.0.0 Safari/537.36"),
+            BrowserType::Chrome
+        );
+        assert_eq!(
+            browser_from_version("Chrome/120.0.6099.71", "Mozilla/5.0 Chrome/120.0.0.0 Safari/537.36 Edg/120.0.0.0"),
+            BrowserType::Edge
+        );
+        assert_eq!(
+            browser_from_version("Chrome/120.0.6099.71", "Mozilla/5.0 Chrome/120.0.0.0 Safari/537.36 OPR/106.0.0.0"),
+            BrowserType::Opera
+        );
+        assert_eq!(
+            browser_from_version("Chrome/120.0.6099.71", "Mozilla/5.0 Chrome/120.0.0.0 Safari/537.36 Vivaldi/6.5"),
+            BrowserType::Vivaldi
+        );
+    }
+
+    #[test]
+    fn test_percent_encodes_page_urls() {
+        assert_eq!(
+            percent_encode("https://example.com/a b?x=1&y=ü#top"),
+            "https%3A%2F%2Fexample.com%2Fa%20b%3Fx%3D1%26y%3D%C3%BC%23top"
+        );
+        assert_eq!(percent_encode("a\r\nHost: evil"), "a%0D%0AHost%3A%20evil");
+    }
+}
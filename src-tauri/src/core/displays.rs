@@ -0,0 +1,37 @@
+use crate::db::DisplayInfo;
+use crate::error::{AppError, Result};
+use tauri::AppHandle;
+
+/// Monitors connected right now, in the order the platform reports them.
+pub fn list_displays(app_handle: &AppHandle) -> Result<Vec<DisplayInfo>> {
+    let monitors = app_handle
+        .available_monitors()
+        .map_err(|e| AppError::Scheduler(format!("Failed to list displays: {}", e)))?;
+    let primary = app_handle.primary_monitor().ok().flatten();
+
+    Ok(monitors
+        .iter()
+        .enumerate()
+        .map(|(index, monitor)| DisplayInfo {
+            index: index as u32,
+            name: monitor.name().cloned(),
+            x: monitor.position().x,
+            y: monitor.position().y,
+            width: monitor.size().width,
+            height: monitor.size().height,
+            primary: primary.as_ref().is_some_and(|p| {
+                p.name() == monitor.name() && p.position() == monitor.position()
+            }),
+        })
+        .collect())
+}
+
+/// Top-left corner of display `index`, or None when that display is not connected, so a
+/// window falls back to wherever the browser puts it.
+pub fn display_origin(app_handle: &AppHandle, index: u32) -> Option<(i32, i32)> {
+    list_displays(app_handle)
+        .ok()?
+        .into_iter()
+        .find(|display| display.index == index)
+        .map(|display| (display.x, display.y))
+}
@@ -0,0 +1,100 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use crate::db::{BackfillReport, ExecutionAction, LinkDraft, Task, TaskExecution};
+use crate::error::AppError;
+
+pub const TASK_UPDATED: &str = "task-updated";
+pub const TASK_EXECUTED: &str = "task-executed";
+pub const TASK_FAILED: &str = "task-failed";
+pub const SCHEDULER_ERROR: &str = "scheduler-error";
+pub const MISSED_EXECUTIONS: &str = "missed-executions";
+pub const QUICK_ADD: &str = "quick-add";
+pub const IMPORT_TASK: &str = "import-task";
+
+/// Why a `task-updated` event was emitted.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TaskUpdateCause {
+    Executed,
+    Edited,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskUpdatedPayload {
+    pub task: Task,
+    pub cause: TaskUpdateCause,
+}
+
+/// Emits the full task so the frontend can update a single row without refetching.
+pub fn emit_task_updated(app_handle: &AppHandle, task: &Task, cause: TaskUpdateCause) {
+    let payload = TaskUpdatedPayload {
+        task: task.clone(),
+        cause,
+    };
+    let _ = app_handle.emit(TASK_UPDATED, payload);
+}
+
+/// Emits the execution record written for a finished action.
+pub fn emit_task_executed(app_handle: &AppHandle, execution: &TaskExecution) {
+    let _ = app_handle.emit(TASK_EXECUTED, execution.clone());
+}
+
+/// Details of a failed open or close, so the frontend can show what went wrong.
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskFailedPayload {
+    pub task_id: i64,
+    pub task_name: String,
+    pub action: ExecutionAction,
+    pub code: String,
+    pub message: String,
+    /// Failed attempt of this occurrence, counting from 1
+    pub attempt: i32,
+    /// When the action is tried again, if it has attempts left
+    pub retry_at: Option<DateTime<Utc>>,
+    pub failed_at: DateTime<Utc>,
+}
+
+/// Emits a failed action; emitted for every attempt, including ones that will be retried.
+pub fn emit_task_failed(app_handle: &AppHandle, payload: &TaskFailedPayload) {
+    let _ = app_handle.emit(TASK_FAILED, payload.clone());
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SchedulerErrorPayload {
+    pub code: String,
+    pub message: String,
+    pub task_id: Option<i64>,
+    pub occurred_at: DateTime<Utc>,
+}
+
+impl SchedulerErrorPayload {
+    pub fn new(error: &AppError, task_id: Option<i64>) -> Self {
+        Self {
+            code: error.code().to_string(),
+            message: error.to_string(),
+            task_id,
+            occurred_at: Utc::now(),
+        }
+    }
+}
+
+pub fn emit_scheduler_error(app_handle: &AppHandle, payload: &SchedulerErrorPayload) {
+    let _ = app_handle.emit(SCHEDULER_ERROR, payload.clone());
+}
+
+/// Emits the startup report of occurrences missed while the app was not running.
+pub fn emit_missed_executions(app_handle: &AppHandle, report: &BackfillReport) {
+    let _ = app_handle.emit(MISSED_EXECUTIONS, report.clone());
+}
+
+/// Asks the frontend to start a new task from links dropped on the window.
+pub fn emit_quick_add(app_handle: &AppHandle, links: &[LinkDraft]) {
+    let _ = app_handle.emit(QUICK_ADD, links.to_vec());
+}
+
+/// Asks the frontend to show a shared task in the task form, ready to save.
+pub fn emit_import_task(app_handle: &AppHandle, task: &Task) {
+    let _ = app_handle.emit(IMPORT_TASK, task.clone());
+}
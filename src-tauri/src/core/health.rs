@@ -0,0 +1,168 @@
+use std::sync::Arc;
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use tauri::tray::TrayIcon;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::{NotificationExt, PermissionState};
+use crate::core::TaskScheduler;
+use crate::db::Database;
+use crate::utils::locale;
+
+/// A running loop ticks at least once a minute; allow some slack before calling it stalled
+const MAX_TICK_AGE_SECS: i64 = 180;
+
+/// Free space below which writing the database or backups may start failing
+const LOW_DISK_SPACE_BYTES: u64 = 100 * 1024 * 1024;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthStatus {
+    Ok,
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ComponentHealth {
+    pub component: &'static str,
+    pub status: HealthStatus,
+    pub detail: String,
+}
+
+impl ComponentHealth {
+    fn new(component: &'static str, status: HealthStatus, detail: impl Into<String>) -> Self {
+        Self {
+            component,
+            status,
+            detail: detail.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthReport {
+    pub checked_at: DateTime<Utc>,
+    /// Whether no component reported an error
+    pub healthy: bool,
+    pub components: Vec<ComponentHealth>,
+}
+
+/// Checks every component the app depends on.
+pub async fn check(app: &AppHandle) -> HealthReport {
+    let components = vec![
+        check_database(app).await,
+        check_scheduler(app).await,
+        check_tray(app),
+        check_notifications(app),
+        check_disk_space(),
+    ];
+
+    HealthReport {
+        checked_at: Utc::now(),
+        healthy: components.iter().all(|c| c.status != HealthStatus::Error),
+        components,
+    }
+}
+
+async fn check_database(app: &AppHandle) -> ComponentHealth {
+    let Some(db) = app.try_state::<Arc<Database>>() else {
+        return ComponentHealth::new("database", HealthStatus::Error, "Database was not initialized");
+    };
+
+    match sqlx::query("SELECT 1").execute(&db.pool()).await {
+        Ok(_) => ComponentHealth::new("database", HealthStatus::Ok, "Reachable"),
+        Err(e) => ComponentHealth::new("database", HealthStatus::Error, e.to_string()),
+    }
+}
+
+async fn check_scheduler(app: &AppHandle) -> ComponentHealth {
+    let (Some(scheduler), Some(db)) = (app.try_state::<Arc<TaskScheduler>>(), app.try_state::<Arc<Database>>()) else {
+        return ComponentHealth::new("scheduler", HealthStatus::Error, "Scheduler was not initialized");
+    };
+
+    if !scheduler.is_running().await {
+        return ComponentHealth::new("scheduler", HealthStatus::Warning, "Stopped");
+    }
+
+    let last_tick = db.get_scheduler_state().await.ok().and_then(|s| s.last_tick_at);
+    let locale_config = db.get_locale_config().await.unwrap_or_default();
+    match last_tick {
+        Some(at) if Utc::now() - at <= Duration::seconds(MAX_TICK_AGE_SECS) => {
+            ComponentHealth::new("scheduler", HealthStatus::Ok, format!("Running, last tick at {}", locale::format_datetime(at, &locale_config)))
+        }
+        Some(at) => ComponentHealth::new(
+            "scheduler",
+            HealthStatus::Error,
+            format!("Loop looks stalled, last tick at {}", locale::format_datetime(at, &locale_config)),
+        ),
+        None => ComponentHealth::new("scheduler", HealthStatus::Warning, "Running, but has not ticked yet"),
+    }
+}
+
+fn check_tray(app: &AppHandle) -> ComponentHealth {
+    if app.try_state::<TrayIcon<tauri::Wry>>().is_some() {
+        ComponentHealth::new("tray", HealthStatus::Ok, "Created")
+    } else {
+        ComponentHealth::new("tray", HealthStatus::Warning, "Tray icon was not created")
+    }
+}
+
+fn check_notifications(app: &AppHandle) -> ComponentHealth {
+    match app.notification().permission_state() {
+        Ok(PermissionState::Granted) => ComponentHealth::new("notifications", HealthStatus::Ok, "Permission granted"),
+        Ok(state) => ComponentHealth::new(
+            "notifications",
+            HealthStatus::Warning,
+            format!("Permission not granted ({:?})", state),
+        ),
+        Err(e) => ComponentHealth::new("notifications", HealthStatus::Warning, e.to_string()),
+    }
+}
+
+fn check_disk_space() -> ComponentHealth {
+    let available = Database::data_dir().map_err(|e| e.to_string()).and_then(|dir| {
+        available_space(&dir).ok_or_else(|| "Could not determine free disk space".to_string())
+    });
+
+    match available {
+        Ok(bytes) if bytes < LOW_DISK_SPACE_BYTES => ComponentHealth::new(
+            "disk_space",
+            HealthStatus::Warning,
+            format!("Only {} MB free in the data directory", bytes / (1024 * 1024)),
+        ),
+        Ok(bytes) => ComponentHealth::new(
+            "disk_space",
+            HealthStatus::Ok,
+            format!("{} MB free in the data directory", bytes / (1024 * 1024)),
+        ),
+        Err(e) => ComponentHealth::new("disk_space", HealthStatus::Warning, e),
+    }
+}
+
+/// Free bytes on the volume holding `dir`, queried from the platform's own tools.
+fn available_space(dir: &std::path::Path) -> Option<u64> {
+    #[cfg(target_os = "windows")]
+    {
+        let drive = dir.to_string_lossy().chars().next().filter(|c| c.is_ascii_alphabetic())?;
+        let output = std::process::Command::new("powershell")
+            .args(["-NoProfile", "-Command", &format!("(Get-PSDrive -Name {}).Free", drive)])
+            .output()
+            .ok()?;
+
+        String::from_utf8_lossy(&output.stdout).trim().parse().ok()
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        // POSIX output: Filesystem 1024-blocks Used Available Capacity Mounted-on
+        let output = std::process::Command::new("df")
+            .arg("-Pk")
+            .arg(dir)
+            .output()
+            .ok()?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let available_kb: u64 = stdout.lines().nth(1)?.split_whitespace().nth(3)?.parse().ok()?;
+        Some(available_kb * 1024)
+    }
+}
@@ -0,0 +1,108 @@
+use std::sync::Mutex;
+use crate::db::{AuditEntry, AuditSource, Database, Task};
+use crate::error::Result;
+
+/// Changes kept for undo in one session
+const MAX_HISTORY: usize = 50;
+
+#[derive(Debug, Default)]
+struct Stacks {
+    /// Groups of audit entry ids; a bulk operation is undone as one group
+    undo: Vec<Vec<i64>>,
+    redo: Vec<Vec<i64>>,
+}
+
+/// Per-session undo/redo of task changes made from the UI, backed by the audit log snapshots.
+#[derive(Debug, Default)]
+pub struct EditHistory {
+    stacks: Mutex<Stacks>,
+}
+
+impl EditHistory {
+    fn stacks(&self) -> std::sync::MutexGuard<'_, Stacks> {
+        self.stacks.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Records the latest UI change of each of `task_ids` as one undoable step.
+    pub async fn record(&self, db: &Database, task_ids: &[i64]) -> Result<()> {
+        let mut group = Vec::with_capacity(task_ids.len());
+        for &task_id in task_ids {
+            if let Some(audit_id) = db.latest_ui_audit_id(task_id).await? {
+                group.push(audit_id);
+            }
+        }
+        if group.is_empty() {
+            return Ok(());
+        }
+
+        let mut stacks = self.stacks();
+        stacks.undo.push(group);
+        if stacks.undo.len() > MAX_HISTORY {
+            stacks.undo.remove(0);
+        }
+        stacks.redo.clear();
+        Ok(())
+    }
+
+    /// Reverts the last recorded step. Returns the ids of the affected tasks, empty if there
+    /// was nothing to undo.
+    pub async fn undo(&self, db: &Database) -> Result<Vec<i64>> {
+        let Some(group) = self.stacks().undo.pop() else {
+            return Ok(Vec::new());
+        };
+
+        let mut task_ids = Vec::new();
+        for &audit_id in group.iter().rev() {
+            let entry = db.get_audit_entry(audit_id).await?;
+            task_ids.push(entry.task_id);
+            apply(db, &entry, entry.before.clone()).await?;
+        }
+
+        self.stacks().redo.push(group);
+        Ok(task_ids)
+    }
+
+    /// Re-applies the last undone step. Returns the ids of the affected tasks, empty if there
+    /// was nothing to redo.
+    pub async fn redo(&self, db: &Database) -> Result<Vec<i64>> {
+        let Some(group) = self.stacks().redo.pop() else {
+            return Ok(Vec::new());
+        };
+
+        let mut task_ids = Vec::new();
+        for &audit_id in &group {
+            let entry = db.get_audit_entry(audit_id).await?;
+            task_ids.push(entry.task_id);
+            apply(db, &entry, entry.after.clone()).await?;
+        }
+
+        self.stacks().undo.push(group);
+        Ok(task_ids)
+    }
+}
+
+/// Brings a task to `target`, one of the snapshots of `entry`: `None` means it should not exist.
+async fn apply(db: &Database, entry: &AuditEntry, target: Option<Task>) -> Result<()> {
+    let current = db.get_task(entry.task_id).await.ok();
+
+    match (current, target) {
+        (Some(_), None) => db.delete_task(entry.task_id, AuditSource::Ui).await,
+        (None, Some(_)) if db.is_task_deleted(entry.task_id).await? => {
+            db.restore_deleted_task(entry.task_id, AuditSource::Ui).await.map(|_| ())
+        }
+        (None, Some(mut target)) => {
+            target.id = Some(entry.task_id);
+            db.restore_task(target, AuditSource::Ui).await.map(|_| ())
+        }
+        (Some(current), Some(mut target)) => {
+            // Only what the user edited is reverted; progress the scheduler made since is kept
+            target.execution_count = current.execution_count;
+            target.consecutive_failures = current.consecutive_failures;
+            target.last_success_at = current.last_success_at;
+            target.next_open_execution = current.next_open_execution;
+            target.next_close_execution = current.next_close_execution;
+            db.update_task(entry.task_id, target, true, AuditSource::Ui).await.map(|_| ())
+        }
+        (None, None) => Ok(()),
+    }
+}
@@ -0,0 +1,284 @@
+//! A small REST endpoint for Home Assistant: scheduler status for a `rest` sensor, and run, pause
+//! and resume calls for `rest_command`s, so browser schedules can join automations.
+//!
+//! Every request needs the bearer token generated in the settings:
+//!
+//! ```yaml
+//! sensor:
+//!   - platform: rest
+//!     name: Do In Time
+//!     resource: http://192.168.1.20:8765/api/status
+//!     headers:
+//!       Authorization: Bearer <token>
+//!     value_template: "{{ 'running' if value_json.running else 'stopped' }}"
+//!     json_attributes: [opened, next_task, next_action, next_at]
+//!
+//! rest_command:
+//!   open_morning_tabs:
+//!     url: http://192.168.1.20:8765/api/tasks/3/run
+//!     method: POST
+//!     headers:
+//!       Authorization: Bearer <token>
+//! ```
+
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Manager};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::Semaphore;
+use crate::core::events::{emit_task_updated, TaskUpdateCause};
+use crate::core::TaskScheduler;
+use crate::db::{AuditSource, Database, ExecutionAction, TaskStatus};
+use crate::error::{AppError, Result};
+use crate::utils::keyring;
+
+/// Keyring entry holding the bearer token Home Assistant authenticates with
+pub const TOKEN_KEY: &str = "home-assistant-token";
+
+/// Requests are a request line and a few headers; anything larger is rejected
+const MAX_REQUEST_BYTES: usize = 8 * 1024;
+
+/// Time a client gets to send its request, so an idle connection cannot be held open
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Connections served at once; further ones wait in the listen backlog
+const MAX_CONNECTIONS: usize = 8;
+
+#[derive(Debug, PartialEq)]
+enum Route {
+    Status,
+    Run(i64),
+    Pause(i64),
+    Resume(i64),
+}
+
+#[derive(Debug, PartialEq)]
+struct Request {
+    method: String,
+    path: String,
+    token: Option<String>,
+}
+
+/// Request line and bearer token of a raw HTTP request head
+fn parse_request(head: &str) -> Option<Request> {
+    let mut lines = head.lines();
+    let mut request_line = lines.next()?.split_whitespace();
+    let method = request_line.next()?.to_string();
+    let path = request_line.next()?.to_string();
+
+    let token = lines
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("authorization"))
+        .and_then(|(_, value)| value.trim().strip_prefix("Bearer ").map(|t| t.trim().to_string()));
+
+    Some(Request { method, path, token })
+}
+
+fn route(method: &str, path: &str) -> Option<Route> {
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    match (method, segments.as_slice()) {
+        ("GET", ["api", "status"]) => Some(Route::Status),
+        ("POST", ["api", "tasks", id, action]) => {
+            let id = id.parse().ok()?;
+            match *action {
+                "run" => Some(Route::Run(id)),
+                "pause" => Some(Route::Pause(id)),
+                "resume" => Some(Route::Resume(id)),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Compares digests so the comparison takes the same time wherever the tokens differ
+fn token_matches(given: &str, expected: &str) -> bool {
+    Sha256::digest(given.as_bytes()) == Sha256::digest(expected.as_bytes())
+}
+
+/// Generates a new token, replacing the old one, and returns it for pasting into Home Assistant.
+pub fn generate_token() -> Result<String> {
+    let token = hex::encode(rand::random::<[u8; 24]>());
+    keyring::set_secret(TOKEN_KEY, &token)?;
+    Ok(token)
+}
+
+async fn status(app: &AppHandle, db: &Database) -> Result<Value> {
+    let scheduler = app
+        .try_state::<Arc<TaskScheduler>>()
+        .ok_or_else(|| AppError::Scheduler("Scheduler was not initialized".to_string()))?;
+
+    let next = db.get_next_action().await?.and_then(|(task, action)| {
+        let at = match action {
+            ExecutionAction::Open => task.next_open_execution,
+            ExecutionAction::Close => task.next_close_execution,
+        }?;
+        Some((task.name, action, at))
+    });
+
+    Ok(json!({
+        "running": scheduler.is_running().await,
+        "paused_until": scheduler.paused_until().await.map(|until| until.to_rfc3339()),
+        "opened": scheduler.opened_count(),
+        "recent_errors": scheduler.recent_error_count().await,
+        "next_task": next.as_ref().map(|(name, _, _)| name),
+        "next_action": next.as_ref().map(|(_, action, _)| action.to_string()),
+        "next_at": next.as_ref().map(|(_, _, at)| at.to_rfc3339()),
+    }))
+}
+
+async fn handle(app: &AppHandle, db: &Database, route: Route) -> Result<Value> {
+    match route {
+        Route::Status => status(app, db).await,
+        Route::Run(id) => {
+            let scheduler = app
+                .try_state::<Arc<TaskScheduler>>()
+                .ok_or_else(|| AppError::Scheduler("Scheduler was not initialized".to_string()))?;
+            scheduler.run_task_now(id).await?;
+            Ok(json!({ "ok": true }))
+        }
+        Route::Pause(id) | Route::Resume(id) => {
+            let status = if matches!(route, Route::Pause(_)) { TaskStatus::Paused } else { TaskStatus::Active };
+            let task = db.set_task_status(id, status, AuditSource::Integration).await?;
+            emit_task_updated(app, &task, TaskUpdateCause::Edited);
+            Ok(json!({ "ok": true, "status": task.status.to_string() }))
+        }
+    }
+}
+
+async fn respond(stream: &mut TcpStream, status: &str, body: &Value) {
+    let body = body.to_string();
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    let _ = stream.write_all(response.as_bytes()).await;
+}
+
+/// Reads up to the end of the request head. `None` once the client hung up, or after
+/// answering a request over `MAX_REQUEST_BYTES`.
+async fn read_head(stream: &mut TcpStream) -> Option<Vec<u8>> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 1024];
+    while !buffer.windows(4).any(|w| w == b"\r\n\r\n") {
+        match stream.read(&mut chunk).await {
+            Ok(0) | Err(_) => return None,
+            Ok(n) => buffer.extend_from_slice(&chunk[..n]),
+        }
+        if buffer.len() > MAX_REQUEST_BYTES {
+            respond(stream, "413 Payload Too Large", &json!({ "error": "Request too large" })).await;
+            return None;
+        }
+    }
+    Some(buffer)
+}
+
+async fn serve_connection(app: &AppHandle, db: &Database, mut stream: TcpStream) {
+    let buffer = match tokio::time::timeout(REQUEST_TIMEOUT, read_head(&mut stream)).await {
+        Ok(Some(buffer)) => buffer,
+        Ok(None) => return,
+        Err(_) => {
+            respond(&mut stream, "408 Request Timeout", &json!({ "error": "Request not received in time" })).await;
+            return;
+        }
+    };
+
+    let Some(request) = parse_request(&String::from_utf8_lossy(&buffer)) else {
+        respond(&mut stream, "400 Bad Request", &json!({ "error": "Malformed request" })).await;
+        return;
+    };
+
+    let expected = tokio::task::spawn_blocking(|| keyring::get_secret(TOKEN_KEY))
+        .await
+        .ok()
+        .and_then(|result| result.ok())
+        .flatten();
+    let authorized = match (request.token.as_deref(), expected.as_deref()) {
+        (Some(given), Some(expected)) => token_matches(given, expected),
+        _ => false,
+    };
+    if !authorized {
+        respond(&mut stream, "401 Unauthorized", &json!({ "error": "Missing or wrong token" })).await;
+        return;
+    }
+
+    let Some(route) = route(&request.method, &request.path) else {
+        respond(&mut stream, "404 Not Found", &json!({ "error": "Unknown endpoint" })).await;
+        return;
+    };
+
+    match handle(app, db, route).await {
+        Ok(body) => respond(&mut stream, "200 OK", &body).await,
+        Err(e @ AppError::TaskNotFound(_)) => {
+            respond(&mut stream, "404 Not Found", &json!({ "error": e.to_string(), "code": e.code() })).await
+        }
+        Err(e) => {
+            respond(&mut stream, "500 Internal Server Error", &json!({ "error": e.to_string(), "code": e.code() })).await
+        }
+    }
+}
+
+/// Starts the endpoint in the background when `home_assistant_enabled` is set.
+pub fn spawn_server(app: AppHandle, db: Arc<Database>) {
+    tauri::async_runtime::spawn(async move {
+        let Ok(settings) = db.get_settings().await else { return };
+        if !settings.home_assistant_enabled {
+            return;
+        }
+
+        let ip = if settings.home_assistant_listen_all { Ipv4Addr::UNSPECIFIED } else { Ipv4Addr::LOCALHOST };
+        let listener = match TcpListener::bind(SocketAddr::from((ip, settings.home_assistant_port))).await {
+            Ok(listener) => listener,
+            Err(e) => {
+                eprintln!("Failed to start the Home Assistant endpoint: {}", e);
+                return;
+            }
+        };
+        println!("Home Assistant endpoint listening on {}", SocketAddr::from((ip, settings.home_assistant_port)));
+
+        let connections = Arc::new(Semaphore::new(MAX_CONNECTIONS));
+        loop {
+            let Ok(permit) = Arc::clone(&connections).acquire_owned().await else { return };
+            let Ok((stream, _)) = listener.accept().await else { continue };
+            let app = app.clone();
+            let db = Arc::clone(&db);
+            tauri::async_runtime::spawn(async move {
+                serve_connection(&app, &db, stream).await;
+                drop(permit);
+            });
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_request() {
+        let head = "POST /api/tasks/3/run HTTP/1.1\r\nHost: desk:8765\r\nauthorization: Bearer abc123\r\n\r\n";
+        assert_eq!(
+            parse_request(head),
+            Some(Request {
+                method: "POST".to_string(),
+                path: "/api/tasks/3/run".to_string(),
+                token: Some("abc123".to_string()),
+            })
+        );
+        assert_eq!(parse_request("GET /api/status HTTP/1.1\r\n\r\n").unwrap().token, None);
+    }
+
+    #[test]
+    fn test_route() {
+        assert_eq!(route("GET", "/api/status"), Some(Route::Status));
+        assert_eq!(route("POST", "/api/tasks/3/pause"), Some(Route::Pause(3)));
+        assert_eq!(route("GET", "/api/tasks/3/run"), None);
+        assert_eq!(route("POST", "/api/tasks/abc/run"), None);
+    }
+}
@@ -0,0 +1,55 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// A browser the scheduler opened for a task.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Launch {
+    /// Process started for the open, where the platform reports it
+    pub pid: Option<u32>,
+    /// Set once the process has exited and been reaped; `None` inside for a signal
+    pub exit_code: Option<Option<i32>>,
+}
+
+/// Browsers the scheduler opened and has yet to close, keyed by task.
+///
+/// Only opens with a scheduled close are registered: those are the windows the scheduler owns.
+/// Entries are dropped when the close runs or, through `retain`, when the task no longer has a
+/// close pending.
+#[derive(Debug, Default)]
+pub struct LaunchRegistry {
+    open: Mutex<HashMap<i64, Launch>>,
+}
+
+impl LaunchRegistry {
+    fn open(&self) -> std::sync::MutexGuard<'_, HashMap<i64, Launch>> {
+        self.open.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn register(&self, task_id: i64, pid: Option<u32>) {
+        self.open().insert(task_id, Launch { pid, exit_code: None });
+    }
+
+    /// Notes that the process `pid` of a task's open has exited.
+    pub fn record_exit(&self, task_id: i64, pid: u32, code: Option<i32>) {
+        if let Some(launch) = self.open().get_mut(&task_id).filter(|launch| launch.pid == Some(pid)) {
+            launch.exit_code = Some(code);
+        }
+    }
+
+    pub fn release(&self, task_id: i64) -> Option<Launch> {
+        self.open().remove(&task_id)
+    }
+
+    pub fn task_ids(&self) -> Vec<i64> {
+        self.open().keys().copied().collect()
+    }
+
+    /// Keeps only the entries of `task_ids`.
+    pub fn retain(&self, task_ids: &[i64]) {
+        self.open().retain(|task_id, _| task_ids.contains(task_id));
+    }
+
+    pub fn count(&self) -> usize {
+        self.open().len()
+    }
+}
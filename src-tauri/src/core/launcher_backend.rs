@@ -0,0 +1,313 @@
+//! Backends that open and close browsers for the executor. The native backend is the
+//! `BrowserLauncher` itself; the DevTools backend drives a Chromium browser through its
+//! `--remote-debugging-port` endpoint and falls back to another backend while the port is
+//! unreachable. Tests swap in `MockBackend`, which records calls instead of spawning anything.
+
+use std::future::Future;
+use std::path::Path;
+use std::pin::Pin;
+use std::sync::Arc;
+use crate::core::browser_launcher::{BrowserLauncher, LaunchOptions, LaunchedBrowser};
+use crate::core::devtools;
+use crate::db::BrowserType;
+use crate::error::{AppError, Result};
+
+pub type BackendFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T>> + Send + 'a>>;
+
+/// Opens and closes browser windows and tabs.
+pub trait LauncherBackend: Send + Sync {
+    /// Opens `browser`, on `url` if given.
+    fn open<'a>(
+        &'a self,
+        browser: &'a BrowserType,
+        url: Option<&'a str>,
+        profile: Option<&'a str>,
+        options: &'a LaunchOptions,
+    ) -> BackendFuture<'a, LaunchedBrowser>;
+
+    /// Closes the tabs of `browser` showing `url`. `ui_automation` allows the opt-in Windows
+    /// UI Automation path.
+    fn close_url<'a>(&'a self, browser: &'a BrowserType, url: &'a str, ui_automation: bool) -> BackendFuture<'a, ()>;
+
+    /// Closes the app window of `browser` opened on `url` in app mode.
+    fn close_app<'a>(&'a self, browser: &'a BrowserType, url: &'a str, ui_automation: bool) -> BackendFuture<'a, ()>;
+
+    /// Closes every window of `browser`.
+    fn close_all<'a>(&'a self, browser: &'a BrowserType) -> BackendFuture<'a, ()>;
+
+    /// Closes the browser instance running on the profile directory `user_data_dir`.
+    fn close_profile<'a>(&'a self, user_data_dir: &'a Path) -> BackendFuture<'a, ()>;
+
+    /// Closes the instance on the throwaway profile `user_data_dir` and deletes the profile.
+    fn close_fresh_session<'a>(&'a self, user_data_dir: &'a Path) -> BackendFuture<'a, ()>;
+
+    /// Minimizes or restores every window of `browser`.
+    fn set_minimized<'a>(&'a self, browser: &'a BrowserType, minimized: bool) -> BackendFuture<'a, ()>;
+}
+
+impl LauncherBackend for BrowserLauncher {
+    fn open<'a>(
+        &'a self,
+        browser: &'a BrowserType,
+        url: Option<&'a str>,
+        profile: Option<&'a str>,
+        options: &'a LaunchOptions,
+    ) -> BackendFuture<'a, LaunchedBrowser> {
+        Box::pin(self.open_browser(browser, url, profile, options))
+    }
+
+    fn close_url<'a>(&'a self, browser: &'a BrowserType, url: &'a str, ui_automation: bool) -> BackendFuture<'a, ()> {
+        Box::pin(self.close_browser_by_url(browser, url, ui_automation))
+    }
+
+    fn close_app<'a>(&'a self, browser: &'a BrowserType, url: &'a str, ui_automation: bool) -> BackendFuture<'a, ()> {
+        Box::pin(self.close_app_window(browser, url, ui_automation))
+    }
+
+    fn close_all<'a>(&'a self, browser: &'a BrowserType) -> BackendFuture<'a, ()> {
+        Box::pin(self.close_browser(browser))
+    }
+
+    fn close_profile<'a>(&'a self, user_data_dir: &'a Path) -> BackendFuture<'a, ()> {
+        Box::pin(async move { self.close_profile_instance(user_data_dir) })
+    }
+
+    fn close_fresh_session<'a>(&'a self, user_data_dir: &'a Path) -> BackendFuture<'a, ()> {
+        Box::pin(BrowserLauncher::close_fresh_session(self, user_data_dir))
+    }
+
+    fn set_minimized<'a>(&'a self, browser: &'a BrowserType, minimized: bool) -> BackendFuture<'a, ()> {
+        Box::pin(self.set_windows_minimized(browser, minimized))
+    }
+}
+
+/// Runs a blocking DevTools request off the async runtime.
+async fn blocking<T: Send + 'static>(request: impl FnOnce() -> Result<T> + Send + 'static) -> Result<T> {
+    tokio::task::spawn_blocking(request)
+        .await
+        .map_err(|e| AppError::Scheduler(format!("DevTools request failed: {}", e)))?
+}
+
+/// Whether the tab at `tab_url` shows `url`; like the AppleScript close, any tab whose URL
+/// contains it matches.
+fn url_matches(tab_url: &str, url: &str) -> bool {
+    tab_url.contains(url.trim_end_matches('/'))
+}
+
+/// Opens and closes tabs through the DevTools endpoint on `port`, when the browser listening
+/// there is the one asked for. Everything else goes to `fallback`.
+pub struct DevtoolsBackend {
+    port: u16,
+    fallback: Arc<dyn LauncherBackend>,
+}
+
+impl DevtoolsBackend {
+    pub fn new(port: u16, fallback: Arc<dyn LauncherBackend>) -> Self {
+        Self { port, fallback }
+    }
+
+    /// Fails unless `browser` is listening on the port.
+    async fn check_browser(&self, browser: &BrowserType) -> Result<()> {
+        let port = self.port;
+        let listening = blocking(move || devtools::browser(port)).await?;
+        if listening != *browser {
+            return Err(AppError::BrowserNotFound(format!(
+                "DevTools port {} belongs to {}, not {}",
+                port, listening, browser
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl LauncherBackend for DevtoolsBackend {
+    fn open<'a>(
+        &'a self,
+        browser: &'a BrowserType,
+        url: Option<&'a str>,
+        profile: Option<&'a str>,
+        options: &'a LaunchOptions,
+    ) -> BackendFuture<'a, LaunchedBrowser> {
+        Box::pin(async move {
+            // A separate profile needs its own instance and an app window its own window, neither
+            // of which the endpoint can start
+            if let Some(url) = url.filter(|_| profile.is_none() && options.user_data_dir.is_none() && !options.app_mode) {
+                let (port, target) = (self.port, url.to_string());
+                let opened = match self.check_browser(browser).await {
+                    Ok(()) => blocking(move || devtools::open_page(port, &target)).await,
+                    Err(e) => Err(e),
+                };
+                match opened {
+                    Ok(_) => {
+                        // The query string may hold a secret resolved from the keyring
+                        let logged = url.split(['?', '#']).next().unwrap_or(url);
+                        println!("Opened {} through DevTools with URL: {}", browser, logged);
+                        return Ok(LaunchedBrowser { pid: None, exit: None });
+                    }
+                    Err(e) => println!("⚠ {}; launching {} natively", e, browser),
+                }
+            }
+            self.fallback.open(browser, url, profile, options).await
+        })
+    }
+
+    fn close_url<'a>(&'a self, browser: &'a BrowserType, url: &'a str, ui_automation: bool) -> BackendFuture<'a, ()> {
+        Box::pin(async move {
+            let (port, target) = (self.port, url.to_string());
+            let closed = match self.check_browser(browser).await {
+                Ok(()) => {
+                    blocking(move || {
+                        let pages = devtools::list_pages(port)?;
+                        let matching: Vec<_> = pages.iter().filter(|page| url_matches(&page.url, &target)).collect();
+                        for page in &matching {
+                            devtools::close_page(port, &page.id)?;
+                        }
+                        Ok(matching.len())
+                    })
+                    .await
+                }
+                Err(e) => Err(e),
+            };
+            match closed {
+                Ok(count) => {
+                    println!("Closed {} {} tab(s) with URL {} through DevTools", count, browser, url);
+                    Ok(())
+                }
+                Err(e) => {
+                    println!("⚠ {}; closing {} natively", e, browser);
+                    self.fallback.close_url(browser, url, ui_automation).await
+                }
+            }
+        })
+    }
+
+    fn close_app<'a>(&'a self, browser: &'a BrowserType, url: &'a str, ui_automation: bool) -> BackendFuture<'a, ()> {
+        // App windows are always launched natively
+        self.fallback.close_app(browser, url, ui_automation)
+    }
+
+    fn close_all<'a>(&'a self, browser: &'a BrowserType) -> BackendFuture<'a, ()> {
+        self.fallback.close_all(browser)
+    }
+
+    fn close_profile<'a>(&'a self, user_data_dir: &'a Path) -> BackendFuture<'a, ()> {
+        self.fallback.close_profile(user_data_dir)
+    }
+
+    fn close_fresh_session<'a>(&'a self, user_data_dir: &'a Path) -> BackendFuture<'a, ()> {
+        self.fallback.close_fresh_session(user_data_dir)
+    }
+
+    fn set_minimized<'a>(&'a self, browser: &'a BrowserType, minimized: bool) -> BackendFuture<'a, ()> {
+        self.fallback.set_minimized(browser, minimized)
+    }
+}
+
+/// A call made on a `MockBackend`.
+#[cfg(test)]
+#[derive(Debug, Clone, PartialEq)]
+pub enum BackendCall {
+    Open { browser: BrowserType, url: Option<String> },
+    CloseUrl { browser: BrowserType, url: String },
+    CloseApp { browser: BrowserType, url: String },
+    CloseAll(BrowserType),
+    CloseProfile(std::path::PathBuf),
+    CloseFreshSession(std::path::PathBuf),
+    SetMinimized { browser: BrowserType, minimized: bool },
+}
+
+/// Records the calls made on it instead of touching any browser. Opens fail with `fail_opens`.
+#[cfg(test)]
+#[derive(Debug, Default)]
+pub struct MockBackend {
+    calls: std::sync::Mutex<Vec<BackendCall>>,
+    pub fail_opens: Option<String>,
+}
+
+#[cfg(test)]
+impl MockBackend {
+    pub fn calls(&self) -> Vec<BackendCall> {
+        self.calls.lock().unwrap().clone()
+    }
+
+    fn record(&self, call: BackendCall) -> BackendFuture<'_, ()> {
+        self.calls.lock().unwrap().push(call);
+        Box::pin(async { Ok(()) })
+    }
+}
+
+#[cfg(test)]
+impl LauncherBackend for MockBackend {
+    fn open<'a>(
+        &'a self,
+        browser: &'a BrowserType,
+        url: Option<&'a str>,
+        _profile: Option<&'a str>,
+        _options: &'a LaunchOptions,
+    ) -> BackendFuture<'a, LaunchedBrowser> {
+        self.calls.lock().unwrap().push(BackendCall::Open { browser: browser.clone(), url: url.map(str::to_string) });
+        let failure = self.fail_opens.clone();
+        Box::pin(async move {
+            match failure {
+                Some(message) => Err(AppError::Scheduler(message)),
+                None => Ok(LaunchedBrowser { pid: None, exit: None }),
+            }
+        })
+    }
+
+    fn close_url<'a>(&'a self, browser: &'a BrowserType, url: &'a str, _ui_automation: bool) -> BackendFuture<'a, ()> {
+        self.record(BackendCall::CloseUrl { browser: browser.clone(), url: url.to_string() })
+    }
+
+    fn close_app<'a>(&'a self, browser: &'a BrowserType, url: &'a str, _ui_automation: bool) -> BackendFuture<'a, ()> {
+        self.record(BackendCall::CloseApp { browser: browser.clone(), url: url.to_string() })
+    }
+
+    fn close_all<'a>(&'a self, browser: &'a BrowserType) -> BackendFuture<'a, ()> {
+        self.record(BackendCall::CloseAll(browser.clone()))
+    }
+
+    fn close_profile<'a>(&'a self, user_data_dir: &'a Path) -> BackendFuture<'a, ()> {
+        self.record(BackendCall::CloseProfile(user_data_dir.to_path_buf()))
+    }
+
+    fn close_fresh_session<'a>(&'a self, user_data_dir: &'a Path) -> BackendFuture<'a, ()> {
+        self.record(BackendCall::CloseFreshSession(user_data_dir.to_path_buf()))
+    }
+
+    fn set_minimized<'a>(&'a self, browser: &'a BrowserType, minimized: bool) -> BackendFuture<'a, ()> {
+        self.record(BackendCall::SetMinimized { browser: browser.clone(), minimized })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_url_matches() {
+        assert!(url_matches("https://example.com/dashboard?tab=1", "https://example.com/dashboard/"));
+        assert!(!url_matches("https://example.org/", "https://example.com"));
+    }
+
+    #[tokio::test]
+    async fn test_devtools_backend_falls_back_when_port_is_closed() {
+        let mock = Arc::new(MockBackend::default());
+        // Port 9 (discard) is not a DevTools endpoint, so every request fails
+        let backend = DevtoolsBackend::new(9, mock.clone());
+
+        backend
+            .open(&BrowserType::Chrome, Some("https://example.com"), None, &LaunchOptions::default())
+            .await
+            .unwrap();
+        backend.close_url(&BrowserType::Chrome, "https://example.com", false).await.unwrap();
+
+        assert_eq!(
+            mock.calls(),
+            vec![
+                BackendCall::Open { browser: BrowserType::Chrome, url: Some("https://example.com".to_string()) },
+                BackendCall::CloseUrl { browser: BrowserType::Chrome, url: "https://example.com".to_string() },
+            ]
+        );
+    }
+}
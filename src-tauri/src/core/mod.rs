@@ -1,5 +1,24 @@
+pub mod access;
+pub mod backfill;
 pub mod browser_launcher;
+pub mod devtools;
+pub mod displays;
+pub mod events;
+pub mod health;
+pub mod history;
+pub mod home_assistant;
+pub mod launch_registry;
+pub mod launcher_backend;
+pub mod power_monitor;
+pub mod quiet_hours;
+pub mod reaper;
+pub mod recurrence;
 pub mod scheduler;
+pub mod simulation;
+pub mod tabs;
 pub mod task_executor;
+#[cfg(target_os = "windows")]
+pub mod ui_automation;
+pub mod updates;
 
 pub use scheduler::TaskScheduler;
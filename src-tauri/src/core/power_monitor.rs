@@ -0,0 +1,155 @@
+//! Notices the system resuming from sleep and tells the scheduler, so it re-checks the schedule
+//! right away rather than finishing a wait that started before the sleep.
+//!
+//! Each platform's power events are followed through a long-running helper process, as the
+//! rest of the app talks to the system through its own tools: logind's `PrepareForSleep`
+//! signal through `gdbus monitor` on Linux, `NSWorkspaceDidWakeNotification` through a JXA
+//! script on macOS, and on Windows the `Win32_PowerManagementEvent` WMI class, which carries
+//! the resume events of `WM_POWERBROADCAST`. Without a helper, the scheduler still notices the
+//! sleep from the gap between two of its iterations, only later.
+
+use std::process::Stdio;
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::Command;
+use crate::core::TaskScheduler;
+
+/// Starts following the system's power events for as long as the app runs.
+pub fn spawn(scheduler: Arc<TaskScheduler>) {
+    tauri::async_runtime::spawn(async move {
+        let Some(mut command) = watcher() else { return };
+        let mut child = match command.stdout(Stdio::piped()).stderr(Stdio::null()).kill_on_drop(true).spawn() {
+            Ok(child) => child,
+            Err(e) => {
+                eprintln!("Failed to watch for the system resuming from sleep: {}", e);
+                return;
+            }
+        };
+        let Some(stdout) = child.stdout.take() else { return };
+
+        let mut lines = BufReader::new(stdout).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            if is_resume(&line) {
+                println!("System resumed from sleep: checking the schedule");
+                scheduler.notify_resumed();
+            }
+        }
+        eprintln!("Stopped watching for the system resuming from sleep");
+    });
+}
+
+#[cfg(target_os = "linux")]
+fn watcher() -> Option<Command> {
+    let mut command = Command::new("gdbus");
+    command.args([
+        "monitor",
+        "--system",
+        "--dest",
+        "org.freedesktop.login1",
+        "--object-path",
+        "/org/freedesktop/login1",
+    ]);
+    Some(command)
+}
+
+#[cfg(target_os = "linux")]
+fn is_resume(line: &str) -> bool {
+    logind_resumed(line)
+}
+
+#[cfg(target_os = "macos")]
+fn watcher() -> Option<Command> {
+    const SCRIPT: &str = r#"
+ObjC.import('AppKit');
+ObjC.registerSubclass({
+    name: 'DoInTimeWakeObserver',
+    methods: {
+        'didWake:': {
+            types: ['void', ['id']],
+            implementation: function () {
+                $.NSFileHandle.fileHandleWithStandardOutput.writeData($('wake\n').dataUsingEncoding($.NSUTF8StringEncoding));
+            }
+        }
+    }
+});
+const observer = $.DoInTimeWakeObserver.alloc.init;
+$.NSWorkspace.sharedWorkspace.notificationCenter.addObserverSelectorNameObject(observer, 'didWake:', $.NSWorkspaceDidWakeNotification, null);
+$.NSRunLoop.currentRunLoop.run;
+"#;
+    let mut command = Command::new("/usr/bin/osascript");
+    command.args(["-l", "JavaScript", "-e", SCRIPT]);
+    Some(command)
+}
+
+#[cfg(target_os = "macos")]
+fn is_resume(line: &str) -> bool {
+    line.trim() == "wake"
+}
+
+#[cfg(target_os = "windows")]
+fn watcher() -> Option<Command> {
+    const SCRIPT: &str = "Register-WmiEvent -Class Win32_PowerManagementEvent -SourceIdentifier power | Out-Null; \
+        while ($true) { \
+            $event = Wait-Event -SourceIdentifier power; \
+            [Console]::Out.WriteLine($event.SourceEventArgs.NewEvent.EventType); \
+            [Console]::Out.Flush(); \
+            Remove-Event -EventIdentifier $event.EventIdentifier \
+        }";
+    // Keeps PowerShell from flashing a console window
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+    let mut command = Command::new("powershell");
+    command.args(["-NoProfile", "-NonInteractive", "-Command", SCRIPT]).creation_flags(CREATE_NO_WINDOW);
+    Some(command)
+}
+
+#[cfg(target_os = "windows")]
+fn is_resume(line: &str) -> bool {
+    wmi_resumed(line)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn watcher() -> Option<Command> {
+    None
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn is_resume(_line: &str) -> bool {
+    false
+}
+
+/// Whether a `gdbus monitor` line is logind announcing the end of a sleep, e.g.
+/// `/org/freedesktop/login1: org.freedesktop.login1.Manager.PrepareForSleep (false,)`
+#[cfg(any(target_os = "linux", test))]
+fn logind_resumed(line: &str) -> bool {
+    line.split_once("PrepareForSleep")
+        .is_some_and(|(_, args)| args.trim().starts_with("(false"))
+}
+
+/// Whether a `Win32_PowerManagementEvent` type is a resume: 7 after a user-initiated resume,
+/// 18 after any resume, as PBT_APMRESUMESUSPEND and PBT_APMRESUMEAUTOMATIC of WM_POWERBROADCAST
+#[cfg(any(target_os = "windows", test))]
+fn wmi_resumed(line: &str) -> bool {
+    matches!(line.trim(), "7" | "18")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_logind_resumed() {
+        let prefix = "/org/freedesktop/login1: org.freedesktop.login1.Manager.";
+        assert!(logind_resumed(&format!("{}PrepareForSleep (false,)", prefix)));
+        assert!(!logind_resumed(&format!("{}PrepareForSleep (true,)", prefix)));
+        assert!(!logind_resumed(&format!("{}PrepareForShutdown (false,)", prefix)));
+        assert!(!logind_resumed("Monitoring signals from all objects owned by org.freedesktop.login1"));
+    }
+
+    #[test]
+    fn test_wmi_resumed() {
+        assert!(wmi_resumed("7"));
+        assert!(wmi_resumed("18\r"));
+        assert!(!wmi_resumed("4"));
+        assert!(!wmi_resumed("10"));
+    }
+}
@@ -0,0 +1,105 @@
+//! Quiet hours: a daily window of local time, e.g. 22:00–07:00, in which the scheduler runs
+//! nothing. What comes due meanwhile is handled by the missed policy once the window ends.
+
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use crate::db::AppSettings;
+use crate::utils::locale;
+use crate::utils::validation::parse_time_of_day;
+
+/// End of the quiet hours in effect right now, in the system's timezone
+pub fn active_now(settings: &AppSettings) -> Option<DateTime<Utc>> {
+    let tz = locale::system_timezone().parse().unwrap_or(Tz::UTC);
+    active_until(settings, Utc::now(), tz)
+}
+
+/// End of the quiet hours `now` falls into, or `None` outside of them or when they are off.
+/// Start and end are local times in `tz`; a start after the end spans midnight, and equal
+/// times make an empty window.
+pub fn active_until(settings: &AppSettings, now: DateTime<Utc>, tz: Tz) -> Option<DateTime<Utc>> {
+    if !settings.quiet_hours_enabled {
+        return None;
+    }
+    let start = parse_time_of_day(&settings.quiet_hours_start).ok()?;
+    let end = parse_time_of_day(&settings.quiet_hours_end).ok()?;
+
+    let local = now.with_timezone(&tz);
+    let today = local.date_naive();
+    let time = local.time();
+
+    let end_date = if start < end {
+        (start <= time && time < end).then_some(today)?
+    } else if start > end {
+        if time >= start {
+            today.succ_opt()?
+        } else if time < end {
+            today
+        } else {
+            return None;
+        }
+    } else {
+        return None;
+    };
+
+    Some(local_to_utc(end_date, end, tz))
+}
+
+/// `date` at `time` in `tz`. A time skipped by a DST change resolves to the first valid time
+/// after it, a repeated one to its first occurrence.
+fn local_to_utc(date: NaiveDate, time: NaiveTime, tz: Tz) -> DateTime<Utc> {
+    let naive = date.and_time(time);
+    (0..=4)
+        .find_map(|quarters| tz.from_local_datetime(&(naive + Duration::minutes(15 * quarters))).earliest())
+        .unwrap_or_else(|| Utc.from_utc_datetime(&naive).with_timezone(&tz))
+        .with_timezone(&Utc)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(start: &str, end: &str) -> AppSettings {
+        AppSettings {
+            quiet_hours_enabled: true,
+            quiet_hours_start: start.to_string(),
+            quiet_hours_end: end.to_string(),
+            ..AppSettings::default()
+        }
+    }
+
+    fn at(tz: Tz, value: &str) -> DateTime<Utc> {
+        let naive = chrono::NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M").unwrap();
+        tz.from_local_datetime(&naive).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_active_until_over_midnight() {
+        let tz: Tz = "Europe/Berlin".parse().unwrap();
+        let quiet = settings("22:00", "07:00");
+
+        assert_eq!(active_until(&quiet, at(tz, "2024-05-10 21:59"), tz), None);
+        assert_eq!(active_until(&quiet, at(tz, "2024-05-10 22:00"), tz), Some(at(tz, "2024-05-11 07:00")));
+        assert_eq!(active_until(&quiet, at(tz, "2024-05-11 03:15"), tz), Some(at(tz, "2024-05-11 07:00")));
+        assert_eq!(active_until(&quiet, at(tz, "2024-05-11 07:00"), tz), None);
+
+        let off = AppSettings { quiet_hours_enabled: false, ..quiet.clone() };
+        assert_eq!(active_until(&off, at(tz, "2024-05-11 03:15"), tz), None);
+        assert_eq!(active_until(&settings("07:00", "07:00"), at(tz, "2024-05-11 07:00"), tz), None);
+        assert_eq!(active_until(&settings("nonsense", "07:00"), at(tz, "2024-05-11 03:15"), tz), None);
+    }
+
+    #[test]
+    fn test_active_until_same_day_and_dst() {
+        let tz: Tz = "Europe/Berlin".parse().unwrap();
+        let lunch = settings("12:00", "13:30");
+        assert_eq!(active_until(&lunch, at(tz, "2024-05-10 12:45"), tz), Some(at(tz, "2024-05-10 13:30")));
+        assert_eq!(active_until(&lunch, at(tz, "2024-05-10 14:00"), tz), None);
+
+        // 02:30 does not exist on the night clocks go forward; the window ends at 03:00 instead
+        let night = settings("01:00", "02:30");
+        assert_eq!(
+            active_until(&night, at(tz, "2024-03-31 01:30"), tz),
+            Some(at(tz, "2024-03-31 03:00"))
+        );
+    }
+}
@@ -0,0 +1,144 @@
+//! Waits on spawned processes so they never linger as zombies on Linux, and reports how they
+//! exited to whoever launched them.
+
+use std::collections::VecDeque;
+use std::io::Read;
+use std::process::{Child, Command};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::oneshot;
+
+/// Most bytes of a process's output kept; earlier output is dropped
+const MAX_OUTPUT_BYTES: usize = 4 * 1024;
+
+/// Time the output readers get to catch up with what an exited process wrote last
+const OUTPUT_SETTLE_MS: u64 = 100;
+
+/// How a spawned process ended. `code` is `None` when it was killed by a signal or could not
+/// be waited on.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProcessExit {
+    pub pid: u32,
+    pub code: Option<i32>,
+    /// The end of what the process wrote to its piped stdout and stderr, empty when nothing
+    /// was piped
+    pub output: String,
+}
+
+type OutputTail = Arc<Mutex<VecDeque<u8>>>;
+
+/// Appends `data` to `tail`, dropping the oldest bytes beyond `max`.
+fn push_bounded(tail: &mut VecDeque<u8>, data: &[u8], max: usize) {
+    tail.extend(data.iter().skip(data.len().saturating_sub(max)));
+    let excess = tail.len().saturating_sub(max);
+    tail.drain(..excess);
+}
+
+/// Drains `stream` into `tail` on its own thread until the stream closes. The pipe must be
+/// read even when nobody looks at the output, or a chatty browser blocks once it fills up.
+fn collect_output(stream: impl Read + Send + 'static, tail: OutputTail, pid: u32) {
+    let spawned = std::thread::Builder::new()
+        .name(format!("output-{}", pid))
+        .spawn(move || {
+            let mut stream = stream;
+            let mut buffer = [0u8; 1024];
+            while let Ok(read) = stream.read(&mut buffer) {
+                if read == 0 {
+                    break;
+                }
+                push_bounded(&mut tail.lock().unwrap_or_else(|e| e.into_inner()), &buffer[..read], MAX_OUTPUT_BYTES);
+            }
+        });
+    if let Err(e) = spawned {
+        eprintln!("Failed to read the output of process {}: {}", pid, e);
+    }
+}
+
+/// Waits for `child` on a background thread and sends its exit on the returned channel,
+/// together with the tail of any output piped from it. Dropping the receiver is fine; the
+/// process is still reaped.
+pub fn reap(mut child: Child) -> oneshot::Receiver<ProcessExit> {
+    let pid = child.id();
+    let (sender, receiver) = oneshot::channel();
+
+    // stdout and stderr share one tail so the output stays in the order it was read
+    let tail = OutputTail::default();
+    let piped = child.stdout.is_some() || child.stderr.is_some();
+    if let Some(stdout) = child.stdout.take() {
+        collect_output(stdout, Arc::clone(&tail), pid);
+    }
+    if let Some(stderr) = child.stderr.take() {
+        collect_output(stderr, Arc::clone(&tail), pid);
+    }
+
+    // Browsers can run for hours, so a blocking wait on its own thread beats polling
+    let spawned = std::thread::Builder::new()
+        .name(format!("reaper-{}", pid))
+        .spawn(move || {
+            let code = child.wait().ok().and_then(|status| status.code());
+            // Helpers the browser started may hold the pipes open long after it exits, so the
+            // readers are not joined
+            if piped {
+                std::thread::sleep(Duration::from_millis(OUTPUT_SETTLE_MS));
+            }
+            let output = {
+                let tail = tail.lock().unwrap_or_else(|e| e.into_inner());
+                String::from_utf8_lossy(&tail.iter().copied().collect::<Vec<u8>>()).trim().to_string()
+            };
+            let _ = sender.send(ProcessExit { pid, code, output });
+        });
+    if let Err(e) = spawned {
+        eprintln!("Failed to start a reaper for process {}: {}", pid, e);
+    }
+
+    receiver
+}
+
+/// Spawns a helper whose outcome nobody waits for, e.g. `pkill` or `open`, and reaps it in the
+/// background. Returns its pid.
+pub fn spawn_detached(command: &mut Command) -> std::io::Result<u32> {
+    let child = command.spawn()?;
+    let pid = child.id();
+    drop(reap(child));
+    Ok(pid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Stdio;
+
+    #[test]
+    fn test_push_bounded_keeps_the_tail() {
+        let mut tail = VecDeque::new();
+        push_bounded(&mut tail, b"abcdef", 4);
+        assert_eq!(tail.iter().copied().collect::<Vec<u8>>(), b"cdef");
+        push_bounded(&mut tail, b"gh", 4);
+        assert_eq!(tail.iter().copied().collect::<Vec<u8>>(), b"efgh");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_reap_reports_exit_code() {
+        let child = Command::new("sh").args(["-c", "exit 3"]).spawn().unwrap();
+        let pid = child.id();
+
+        let exit = reap(child).await.unwrap();
+        assert_eq!(exit, ProcessExit { pid, code: Some(3), output: String::new() });
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_reap_captures_stderr() {
+        let child = Command::new("sh")
+            .args(["-c", "echo 'cannot open display' >&2; exit 1"])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+
+        let exit = reap(child).await.unwrap();
+        assert_eq!(exit.code, Some(1));
+        assert_eq!(exit.output, "cannot open display");
+    }
+}
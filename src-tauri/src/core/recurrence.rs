@@ -0,0 +1,354 @@
+use chrono::{DateTime, Datelike, Duration, LocalResult, NaiveDate, NaiveDateTime, Offset, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
+use crate::db::{RepeatInterval, Task, LAST_WEEK_OF_MONTH};
+use crate::error::{AppError, Result};
+
+/// Most occurrences in a row skipped for falling on excluded dates before giving up, e.g. for
+/// an every-5-minutes task excluded for a year
+const MAX_EXCLUDED_OCCURRENCES: u32 = 200_000;
+
+/// First occurrence of a recurring task strictly after `after`.
+pub fn next_occurrence_after(task: &Task, after: DateTime<Utc>) -> Result<DateTime<Utc>> {
+    let mut next = task.start_time;
+
+    // Sub-daily repeats would take many steps to catch up; they are a fixed length, so jump
+    // close to `after` first
+    if let Some(step) = task.repeat_config.as_ref().and_then(|r| sub_daily_step(&r.interval)) {
+        if after > next {
+            let steps = (after - next).num_seconds() / step.num_seconds();
+            next += Duration::seconds(steps * step.num_seconds());
+        }
+    }
+
+    while next <= after {
+        next = next_execution(task, next)?;
+    }
+    // The first occurrence itself may be excluded
+    if is_excluded(task, next) {
+        next = next_execution(task, next)?;
+    }
+    Ok(next)
+}
+
+/// Whether an occurrence at `at` falls on one of the task's excluded dates, in its timezone.
+pub fn is_excluded(task: &Task, at: DateTime<Utc>) -> bool {
+    if task.exclusions.is_empty() {
+        return false;
+    }
+    let date = match task.timezone.parse::<Tz>() {
+        Ok(tz) => at.with_timezone(&tz).date_naive(),
+        Err(_) => at.date_naive(),
+    };
+    task.exclusions.iter().any(|exclusion| exclusion.contains(date))
+}
+
+/// Occurrences of a recurring task that fall on its excluded dates, oldest first and at most
+/// `limit`, e.g. for the EXDATEs of a calendar export.
+pub fn excluded_occurrences(task: &Task, limit: usize) -> Result<Vec<DateTime<Utc>>> {
+    let Some(last_day) = task.exclusions.iter().map(|e| e.end.unwrap_or(e.start)).max() else {
+        return Ok(Vec::new());
+    };
+    if task.repeat_config.is_none() {
+        return Ok(Vec::new());
+    }
+    let tz: Tz = task.timezone.parse().unwrap_or(Tz::UTC);
+
+    let mut excluded = Vec::new();
+    let mut at = task.start_time;
+    for _ in 0..MAX_EXCLUDED_OCCURRENCES {
+        if excluded.len() >= limit || at.with_timezone(&tz).date_naive() > last_day {
+            break;
+        }
+        if is_excluded(task, at) {
+            excluded.push(at);
+        }
+        at = step(task, at)?;
+    }
+    Ok(excluded)
+}
+
+/// Length of an hourly or every-N-minutes repeat
+fn sub_daily_step(interval: &RepeatInterval) -> Option<Duration> {
+    match interval {
+        RepeatInterval::Hourly => Some(Duration::hours(1)),
+        RepeatInterval::EveryNMinutes(n) if *n > 0 => Some(Duration::minutes(*n as i64)),
+        _ => None,
+    }
+}
+
+pub fn should_continue_repeating(
+    task: &Task,
+    next: DateTime<Utc>,
+    repeat_config: &crate::db::RepeatConfig,
+) -> bool {
+    match (&repeat_config.end_after, &repeat_config.end_date) {
+        (Some(count), _) => task.execution_count < *count,
+        (None, Some(end_date)) => next < *end_date,
+        (None, None) => true,
+    }
+}
+
+/// Occurrence following `base_time` according to the task's repeat interval, passing over
+/// occurrences on excluded dates.
+pub fn next_execution(task: &Task, base_time: DateTime<Utc>) -> Result<DateTime<Utc>> {
+    let mut next = step(task, base_time)?;
+    for _ in 0..MAX_EXCLUDED_OCCURRENCES {
+        if !is_excluded(task, next) {
+            return Ok(next);
+        }
+        next = step(task, next)?;
+    }
+    Err(AppError::InvalidTask("Too many occurrences in a row fall on excluded dates".to_string()))
+}
+
+/// Occurrence directly following `base_time`, excluded or not.
+///
+/// Hourly and every-N-minutes repeats add elapsed time. Longer ones keep the wall-clock time of
+/// the task's start in its timezone, so a 09:00 task stays at 09:00 when clocks change.
+fn step(task: &Task, base_time: DateTime<Utc>) -> Result<DateTime<Utc>> {
+    let repeat_config = task
+        .repeat_config
+        .as_ref()
+        .expect("Task must have repeat config");
+
+    // Parse timezone
+    let tz: Tz = task
+        .timezone
+        .parse()
+        .map_err(|_| crate::error::AppError::TimeParse(format!("Invalid timezone: {}", task.timezone)))?;
+
+    // Convert base time to task's timezone
+    let local_time = base_time.with_timezone(&tz);
+    let base_date = local_time.date_naive();
+    let wall_clock = task.start_time.with_timezone(&tz).time();
+
+    // Calculate next occurrence based on interval
+    let next_local = match repeat_config.interval {
+        RepeatInterval::EveryNMinutes(0) | RepeatInterval::EveryNDays(0) => {
+            return Err(crate::error::AppError::InvalidTask("Repeat interval must be at least 1".to_string()));
+        }
+        RepeatInterval::Hourly => local_time + Duration::hours(1),
+        RepeatInterval::EveryNMinutes(n) => local_time + Duration::minutes(n as i64),
+        RepeatInterval::EveryNDays(n) => from_wall_clock(tz, (base_date + Duration::days(n as i64)).and_time(wall_clock)),
+        RepeatInterval::Daily => from_wall_clock(tz, (base_date + Duration::days(1)).and_time(wall_clock)),
+        RepeatInterval::Weekly => from_wall_clock(tz, (base_date + Duration::weeks(1)).and_time(wall_clock)),
+        RepeatInterval::Monthly => {
+            let month = local_time.month();
+            let year = local_time.year();
+
+            let (next_month, next_year) = if month == 12 {
+                (1, year + 1)
+            } else {
+                (month + 1, year)
+            };
+
+            let last_day_of_month = chrono::NaiveDate::from_ymd_opt(
+                next_year,
+                next_month + 1,
+                1,
+            )
+            .unwrap_or_else(|| chrono::NaiveDate::from_ymd_opt(next_year + 1, 1, 1).unwrap())
+            .pred_opt()
+            .unwrap()
+            .day();
+
+            let day = local_time.day().min(last_day_of_month);
+
+            let next_date = chrono::NaiveDate::from_ymd_opt(next_year, next_month, day)
+                .ok_or_else(|| crate::error::AppError::TimeParse("Failed to calculate next month".to_string()))?;
+
+            from_wall_clock(tz, next_date.and_time(wall_clock))
+        }
+        RepeatInterval::MonthlyByWeekday { week, weekday } => {
+            // The first matching day after the base day, in this month or the next
+            let this_month = nth_weekday_of_month(base_date.year(), base_date.month(), week, weekday)?;
+            let next_date = if this_month > base_date {
+                this_month
+            } else if base_date.month() == 12 {
+                nth_weekday_of_month(base_date.year() + 1, 1, week, weekday)?
+            } else {
+                nth_weekday_of_month(base_date.year(), base_date.month() + 1, week, weekday)?
+            };
+
+            from_wall_clock(tz, next_date.and_time(wall_clock))
+        }
+    };
+
+    Ok(next_local.with_timezone(&Utc))
+}
+
+/// `naive` as a local time in `tz`. A time that happens twice when clocks go back resolves to
+/// the first; one skipped when they go forward is read with the offset from before the change,
+/// as RFC 5545 does, so 02:30 on the night Berlin moves to summer time becomes 03:30.
+fn from_wall_clock(tz: Tz, naive: NaiveDateTime) -> DateTime<Tz> {
+    match tz.from_local_datetime(&naive) {
+        LocalResult::Single(at) => at,
+        LocalResult::Ambiguous(first, _) => first,
+        LocalResult::None => {
+            let offset_before = tz
+                .offset_from_local_datetime(&(naive - Duration::days(1)))
+                .earliest()
+                .map_or(0, |offset| offset.fix().local_minus_utc());
+            tz.from_utc_datetime(&(naive - Duration::seconds(offset_before as i64)))
+        }
+    }
+}
+
+/// The `week`th `weekday` of a month, e.g. its second Tuesday; `LAST_WEEK_OF_MONTH` is the last.
+fn nth_weekday_of_month(year: i32, month: u32, week: u8, weekday: Weekday) -> Result<NaiveDate> {
+    let date = if week == LAST_WEEK_OF_MONTH {
+        let first_of_next = if month == 12 {
+            NaiveDate::from_ymd_opt(year + 1, 1, 1)
+        } else {
+            NaiveDate::from_ymd_opt(year, month + 1, 1)
+        };
+        first_of_next.and_then(|first| first.pred_opt()).map(|last| {
+            let back = (7 + last.weekday().num_days_from_monday() - weekday.num_days_from_monday()) % 7;
+            last - Duration::days(back as i64)
+        })
+    } else {
+        NaiveDate::from_weekday_of_month_opt(year, month, weekday, week)
+    };
+
+    date.ok_or_else(|| AppError::InvalidTask(format!("Week {} of the month does not exist", week)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{BrowserType, DateExclusion, RepeatConfig};
+
+    fn daily() -> Task {
+        // 09:00 in Berlin, from Monday 23 December 2024
+        let start = Utc.with_ymd_and_hms(2024, 12, 23, 8, 0, 0).unwrap();
+        let mut task = Task::new("Standup".to_string(), BrowserType::Chrome, start, "Europe/Berlin".to_string());
+        task.repeat_config = Some(RepeatConfig { interval: RepeatInterval::Daily, end_after: None, end_date: None });
+        task
+    }
+
+    fn day(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 12, d).unwrap()
+    }
+
+    /// A task repeating at `interval` from `start`, given as Berlin local time
+    fn berlin(start: &str, interval: RepeatInterval) -> Task {
+        let mut task = daily();
+        task.start_time = local(start);
+        task.repeat_config = Some(RepeatConfig { interval, end_after: None, end_date: None });
+        task
+    }
+
+    /// A Berlin local time; the first one where clocks go back
+    fn local(value: &str) -> DateTime<Utc> {
+        let naive = NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M").unwrap();
+        chrono_tz::Europe::Berlin.from_local_datetime(&naive).earliest().unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_daily_keeps_wall_clock_across_dst() {
+        let task = berlin("2024-03-30 09:00", RepeatInterval::Daily);
+
+        // Clocks go forward on 31 March and back on 27 October; 09:00 stays 09:00
+        assert_eq!(next_execution(&task, local("2024-03-30 09:00")).unwrap(), local("2024-03-31 09:00"));
+        assert_eq!(next_execution(&task, local("2024-10-26 09:00")).unwrap(), local("2024-10-27 09:00"));
+        assert_eq!(
+            next_occurrence_after(&task, local("2024-10-27 12:00")).unwrap(),
+            local("2024-10-28 09:00")
+        );
+
+        let weekly = berlin("2024-03-25 09:00", RepeatInterval::Weekly);
+        assert_eq!(next_execution(&weekly, local("2024-03-25 09:00")).unwrap(), local("2024-04-01 09:00"));
+
+        let monthly = berlin("2024-03-15 09:00", RepeatInterval::Monthly);
+        assert_eq!(next_execution(&monthly, local("2024-03-15 09:00")).unwrap(), local("2024-04-15 09:00"));
+
+        // Short repeats count elapsed time instead: an hour after 01:30 is 03:30 that night
+        let hourly = berlin("2024-03-31 00:30", RepeatInterval::Hourly);
+        assert_eq!(next_execution(&hourly, local("2024-03-31 01:30")).unwrap(), local("2024-03-31 03:30"));
+    }
+
+    #[test]
+    fn test_wall_clock_in_dst_gap_and_overlap() {
+        // 02:30 is skipped on 31 March: that run is at 03:30, and the next one back at 02:30
+        let task = berlin("2024-03-30 02:30", RepeatInterval::Daily);
+        let skipped = next_execution(&task, local("2024-03-30 02:30")).unwrap();
+        assert_eq!(skipped, Utc.with_ymd_and_hms(2024, 3, 31, 1, 30, 0).unwrap());
+        assert_eq!(skipped, local("2024-03-31 03:30"));
+        assert_eq!(next_execution(&task, skipped).unwrap(), local("2024-04-01 02:30"));
+
+        // 02:30 happens twice on 27 October: the run is at the first, then 02:30 again
+        let task = berlin("2024-10-26 02:30", RepeatInterval::Daily);
+        let repeated = next_execution(&task, local("2024-10-26 02:30")).unwrap();
+        assert_eq!(repeated, Utc.with_ymd_and_hms(2024, 10, 27, 0, 30, 0).unwrap());
+        assert_eq!(next_execution(&task, repeated).unwrap(), local("2024-10-28 02:30"));
+        assert_eq!(
+            next_execution(&task, local("2024-10-28 02:30")).unwrap(),
+            Utc.with_ymd_and_hms(2024, 10, 29, 1, 30, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_next_execution_skips_excluded_dates() {
+        let mut task = daily();
+        task.exclusions = vec![
+            DateExclusion { start: day(25), end: None },
+            DateExclusion { start: day(27), end: Some(day(29)) },
+        ];
+
+        let at = |d: u32| Utc.with_ymd_and_hms(2024, 12, d, 8, 0, 0).unwrap();
+        assert_eq!(next_execution(&task, at(24)).unwrap(), at(26));
+        assert_eq!(next_execution(&task, at(26)).unwrap(), at(30));
+        assert_eq!(next_occurrence_after(&task, at(24)).unwrap(), at(26));
+
+        // The first occurrence itself is excluded
+        task.exclusions.push(DateExclusion { start: day(23), end: None });
+        assert_eq!(next_occurrence_after(&task, at(22)).unwrap(), at(24));
+
+        assert!(is_excluded(&task, at(28)));
+        assert!(!is_excluded(&task, at(30)));
+    }
+
+    #[test]
+    fn test_excluded_dates_use_task_timezone() {
+        let mut task = daily();
+        task.exclusions = vec![DateExclusion { start: day(25), end: None }];
+
+        // 23:30 UTC on the 24th is already the 25th in Berlin
+        assert!(is_excluded(&task, Utc.with_ymd_and_hms(2024, 12, 24, 23, 30, 0).unwrap()));
+        assert!(!is_excluded(&task, Utc.with_ymd_and_hms(2024, 12, 24, 22, 30, 0).unwrap()));
+    }
+
+    #[test]
+    fn test_monthly_by_weekday() {
+        let mut task = daily();
+        let second_tuesday = RepeatInterval::MonthlyByWeekday { week: 2, weekday: Weekday::Tue };
+        task.repeat_config = Some(RepeatConfig { interval: second_tuesday.clone(), end_after: None, end_date: None });
+
+        // From a start that is not a second Tuesday, the next one in the same month comes first
+        let at = |y: i32, m: u32, d: u32| Utc.with_ymd_and_hms(y, m, d, 8, 0, 0).unwrap();
+        assert_eq!(next_execution(&task, at(2024, 12, 3)).unwrap(), at(2024, 12, 10));
+        assert_eq!(next_execution(&task, at(2024, 12, 10)).unwrap(), at(2025, 1, 14));
+        assert_eq!(next_execution(&task, at(2025, 1, 14)).unwrap(), at(2025, 2, 11));
+
+        let last_friday = RepeatInterval::MonthlyByWeekday { week: LAST_WEEK_OF_MONTH, weekday: Weekday::Fri };
+        task.repeat_config = Some(RepeatConfig { interval: last_friday.clone(), end_after: None, end_date: None });
+        assert_eq!(next_execution(&task, at(2024, 12, 10)).unwrap(), at(2024, 12, 27));
+        assert_eq!(next_execution(&task, at(2024, 12, 27)).unwrap(), at(2025, 1, 31));
+
+        // Stored as text and read back
+        assert_eq!(second_tuesday.to_string(), "monthly_by_weekday:2:tue");
+        assert_eq!("monthly_by_weekday:5:fri".parse::<RepeatInterval>(), Ok(last_friday));
+        assert!("monthly_by_weekday:2".parse::<RepeatInterval>().is_err());
+    }
+
+    #[test]
+    fn test_excluded_occurrences() {
+        let mut task = daily();
+        assert!(excluded_occurrences(&task, 10).unwrap().is_empty());
+
+        task.exclusions = vec![DateExclusion { start: day(25), end: Some(day(27)) }];
+        let at = |d: u32| Utc.with_ymd_and_hms(2024, 12, d, 8, 0, 0).unwrap();
+        assert_eq!(excluded_occurrences(&task, 10).unwrap(), vec![at(25), at(26), at(27)]);
+        assert_eq!(excluded_occurrences(&task, 2).unwrap(), vec![at(25), at(26)]);
+    }
+}
@@ -1,25 +1,203 @@
+use std::collections::VecDeque;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{Mutex, Notify, RwLock};
 use tokio::time::{sleep, Duration};
+use crate::core::{backfill, quiet_hours};
+use crate::core::events::{
+    emit_missed_executions, emit_scheduler_error, emit_task_updated, SchedulerErrorPayload, TaskUpdateCause,
+};
 use crate::core::task_executor::TaskExecutor;
-use crate::db::Database;
+use crate::db::{AuditSource, BackfillReport, Database, ExecutionAction, Task, MAX_LAUNCH_STAGGER_SECS};
 use crate::error::{AppError, Result};
-use chrono::Utc;
+use crate::tray;
+use crate::utils::session_lock;
+use chrono::{DateTime, Utc};
 use tauri::AppHandle;
 
+/// Errors older than this no longer count towards the scheduler's error count
+const ERROR_WINDOW_MINUTES: i64 = 15;
+
+/// How often a locked screen is checked for being unlocked again
+const LOCK_POLL_SECS: u64 = 15;
+
+/// Longest the loop sleeps during quiet hours before checking the settings again
+const QUIET_POLL_SECS: i64 = 60;
+
+/// Pause after an action failed, so a failure that repeats cannot spin the loop
+const FAILED_BACKOFF_SECS: u64 = 5;
+
+/// Gap between two loop iterations taken to mean the computer was asleep; the loop itself
+/// never waits this long
+const WAKE_GAP_SECS: i64 = 180;
+
+/// Waits for `duration`, or less if the system reports having resumed from sleep meanwhile.
+async fn sleep_until_resumed(resumed: &Notify, duration: Duration) {
+    tokio::select! {
+        _ = sleep(duration) => {}
+        _ = resumed.notified() => {}
+    }
+}
+
+/// Rolling record of errors raised inside the scheduler loop.
+#[derive(Default)]
+struct ErrorLog {
+    recent: VecDeque<SchedulerErrorPayload>,
+}
+
+impl ErrorLog {
+    fn record(&mut self, error: SchedulerErrorPayload) {
+        self.recent.push_back(error);
+        self.prune();
+    }
+
+    fn prune(&mut self) {
+        let cutoff = Utc::now() - chrono::Duration::minutes(ERROR_WINDOW_MINUTES);
+        while self.recent.front().is_some_and(|e| e.occurred_at < cutoff) {
+            self.recent.pop_front();
+        }
+    }
+}
+
+/// Records an error from the loop in the rolling log and the persisted scheduler state, and
+/// surfaces it to the UI.
+async fn report_error(
+    app_handle: &AppHandle,
+    db: &Database,
+    errors: &Mutex<ErrorLog>,
+    error: AppError,
+    task_id: Option<i64>,
+) {
+    let payload = SchedulerErrorPayload::new(&error, task_id);
+    let _ = db.record_scheduler_error(&payload.message, payload.occurred_at).await;
+    errors.lock().await.record(payload.clone());
+    emit_scheduler_error(app_handle, &payload);
+}
+
+/// Applies the missed policy to occurrences missed `reason`, surfacing the result to the UI.
+async fn run_backfill(
+    app_handle: &AppHandle,
+    db: &Database,
+    errors: &Mutex<ErrorLog>,
+    reason: &str,
+) -> Option<BackfillReport> {
+    let policy = db
+        .get_settings()
+        .await
+        .map(|s| s.missed_policy)
+        .unwrap_or_default();
+
+    match backfill::backfill_missed(db, app_handle, policy, reason).await {
+        Ok(report) => {
+            if !report.tasks.is_empty() {
+                emit_missed_executions(app_handle, &report);
+            }
+            Some(report)
+        }
+        Err(e) => {
+            eprintln!("Failed to backfill missed executions: {}", e);
+            report_error(app_handle, db, errors, e, None).await;
+            None
+        }
+    }
+}
+
+/// What came of one look at the next scheduled action.
+#[derive(Debug)]
+enum DueAction {
+    /// The action ran; the next one may already be due
+    Ran,
+    /// The task is being run manually right now
+    Busy,
+    /// The action ran and failed
+    Failed(AppError, Option<i64>),
+    /// Nothing is due yet; look again after this long
+    Wait(Duration),
+}
+
+/// Runs the next scheduled action if it is due, spacing opens out by the launch stagger.
+async fn run_due_action(
+    db: &Database,
+    executor: &TaskExecutor,
+    last_open: &mut Option<std::time::Instant>,
+) -> Result<DueAction> {
+    // Get next action to execute (either open or close)
+    let Some((task, action)) = db.get_next_action().await? else {
+        // No active tasks, sleep for 10 seconds
+        return Ok(DueAction::Wait(Duration::from_secs(10)));
+    };
+
+    // Determine which execution time to check based on action
+    let action_time = match action {
+        ExecutionAction::Open => task.next_open_execution,
+        ExecutionAction::Close => task.next_close_execution,
+    };
+    let Some(execution_time) = action_time else {
+        // No execution time set, sleep briefly
+        return Ok(DueAction::Wait(Duration::from_secs(10)));
+    };
+
+    let now = Utc::now();
+    if execution_time > now {
+        // Sleep until next action (with max 60 seconds interval)
+        let duration = (execution_time - now)
+            .to_std()
+            .unwrap_or(Duration::from_secs(60))
+            .min(Duration::from_secs(60));
+        return Ok(DueAction::Wait(duration));
+    }
+
+    if action == ExecutionAction::Open {
+        let stagger = db
+            .get_settings()
+            .await
+            .map(|s| Duration::from_secs(s.launch_stagger_secs.min(MAX_LAUNCH_STAGGER_SECS) as u64))
+            .unwrap_or_default();
+        if let Some(elapsed) = last_open.map(|at| at.elapsed()) {
+            if elapsed < stagger {
+                sleep(stagger - elapsed).await;
+            }
+        }
+        *last_open = Some(std::time::Instant::now());
+    }
+
+    // Execute task with the specific action
+    let task_name = task.name.clone();
+    let task_id = task.id;
+    Ok(match executor.execute(task, action.clone(), Some(execution_time)).await {
+        Ok(()) => {
+            let _ = db.record_scheduler_execution().await;
+            DueAction::Ran
+        }
+        Err(AppError::TaskBusy(_)) => DueAction::Busy,
+        Err(e) => {
+            eprintln!("Failed to {} task '{}': {}", action, task_name, e);
+            DueAction::Failed(e, task_id)
+        }
+    })
+}
+
 pub struct TaskScheduler {
     db: Arc<Database>,
     executor: Arc<TaskExecutor>,
     running: Arc<RwLock<bool>>,
+    app_handle: AppHandle,
+    errors: Arc<Mutex<ErrorLog>>,
+    backfill_report: Mutex<Option<BackfillReport>>,
+    /// Signalled by the power monitor when the system resumes, cutting the loop's wait short
+    resumed: Arc<Notify>,
 }
 
 impl TaskScheduler {
     pub fn new(db: Arc<Database>, app_handle: AppHandle) -> Self {
-        let executor = Arc::new(TaskExecutor::new(Arc::clone(&db), app_handle));
+        let executor = Arc::new(TaskExecutor::new(Arc::clone(&db), app_handle.clone()));
         Self {
             db,
             executor,
             running: Arc::new(RwLock::new(false)),
+            app_handle,
+            errors: Arc::new(Mutex::new(ErrorLog::default())),
+            backfill_report: Mutex::new(None),
+            resumed: Arc::new(Notify::new()),
         }
     }
 
@@ -31,56 +209,119 @@ impl TaskScheduler {
         *running = true;
         drop(running);
 
+        if let Err(e) = self.db.record_scheduler_start(Utc::now()).await {
+            eprintln!("Failed to record scheduler start: {}", e);
+        }
+
+        // Deal with occurrences missed while the scheduler was not running before the loop
+        // gets a chance to fire them all late
+        self.backfill_missed().await;
+
         // Main scheduler loop
         let running_clone = Arc::clone(&self.running);
         let db_clone = Arc::clone(&self.db);
         let executor_clone = Arc::clone(&self.executor);
+        let app_handle = self.app_handle.clone();
+        let errors = Arc::clone(&self.errors);
+        let resumed = Arc::clone(&self.resumed);
 
         tokio::spawn(async move {
+            // When the last scheduled open was launched, to space out opens due together
+            let mut last_open: Option<std::time::Instant> = None;
+            let mut was_locked = false;
+            let mut was_quiet = false;
+            let mut last_tick = Utc::now();
+
             while *running_clone.read().await {
-                // Get next action to execute (either open or close)
-                match db_clone.get_next_action().await {
-                    Ok(Some((task, action))) => {
-                        let now = Utc::now();
-
-                        // Determine which execution time to check based on action
-                        let action_time = match action {
-                            crate::db::ExecutionAction::Open => task.next_open_execution,
-                            crate::db::ExecutionAction::Close => task.next_close_execution,
-                        };
-
-                        if let Some(execution_time) = action_time {
-                            if execution_time <= now {
-                                // Execute task with the specific action
-                                let task_name = task.name.clone();
-                                let action_str = match action {
-                                    crate::db::ExecutionAction::Open => "open",
-                                    crate::db::ExecutionAction::Close => "close",
-                                };
-
-                                if let Err(e) = executor_clone.execute(task, action).await {
-                                    eprintln!("Failed to {} task '{}': {}", action_str, task_name, e);
-                                }
-                            } else {
-                                // Sleep until next action (with max 60 seconds interval)
-                                let duration = (execution_time - now)
-                                    .to_std()
-                                    .unwrap_or(Duration::from_secs(60))
-                                    .min(Duration::from_secs(60));
-
-                                sleep(duration).await;
-                            }
-                        } else {
-                            // No execution time set, sleep briefly
-                            sleep(Duration::from_secs(10)).await;
+                // After sleep, what came due meanwhile is handled by the missed policy rather
+                // than all firing late
+                let tick = Utc::now();
+                if tick - last_tick > chrono::Duration::seconds(WAKE_GAP_SECS) {
+                    println!("Woke from sleep: applying the missed policy");
+                    run_backfill(&app_handle, &db_clone, &errors, "while the computer was asleep").await;
+                }
+                last_tick = tick;
+
+                let _ = db_clone.record_scheduler_tick(Utc::now()).await;
+                tray::refresh_tooltip(&app_handle, &db_clone).await;
+
+                let settings = db_clone.get_settings().await.ok();
+
+                // Nothing runs while the screen is locked; on unlock, what came due meanwhile is
+                // handled like occurrences missed while the app was closed
+                let defer_while_locked = settings.as_ref().is_some_and(|s| s.defer_while_locked);
+                if defer_while_locked && session_lock::is_locked() {
+                    if !was_locked {
+                        println!("Screen locked: holding back scheduled actions");
+                        was_locked = true;
+                    }
+                    sleep_until_resumed(&resumed, Duration::from_secs(LOCK_POLL_SECS)).await;
+                    continue;
+                }
+                if was_locked {
+                    was_locked = false;
+                    println!("Screen unlocked: resuming scheduled actions");
+                    run_backfill(&app_handle, &db_clone, &errors, "while the screen was locked").await;
+                }
+
+                // Nothing runs during quiet hours either; once they end, what came due meanwhile
+                // is handled by the missed policy
+                if let Some(until) = settings.as_ref().and_then(quiet_hours::active_now) {
+                    if !was_quiet {
+                        println!("Quiet hours: holding back scheduled actions until {}", until);
+                        was_quiet = true;
+                    }
+                    let wait = (until - Utc::now()).clamp(
+                        chrono::Duration::seconds(1),
+                        chrono::Duration::seconds(QUIET_POLL_SECS),
+                    );
+                    sleep_until_resumed(&resumed, wait.to_std().unwrap_or(Duration::from_secs(1))).await;
+                    continue;
+                }
+                if was_quiet {
+                    was_quiet = false;
+                    println!("Quiet hours over: resuming scheduled actions");
+                    run_backfill(&app_handle, &db_clone, &errors, "during quiet hours").await;
+                }
+
+                // A timed pause resumes on its own once its end has passed
+                if let Ok(Some(until)) = db_clone.get_paused_until().await {
+                    if until <= Utc::now() {
+                        println!("Pause ended: resuming scheduled actions");
+                        if let Err(e) = db_clone.set_paused_until(None).await {
+                            eprintln!("Failed to end the scheduler pause: {}", e);
                         }
+                        tray::refresh_tooltip(&app_handle, &db_clone).await;
                     }
-                    Ok(None) => {
-                        // No active tasks, sleep for 10 seconds
-                        sleep(Duration::from_secs(10)).await;
+                }
+
+                if let Err(e) = executor_clone.run_due_warnings().await {
+                    eprintln!("Failed to warn about upcoming tasks: {}", e);
+                }
+
+                if let Err(e) = executor_clone.run_due_countdowns().await {
+                    eprintln!("Failed to show close countdowns: {}", e);
+                }
+
+                if let Err(e) = executor_clone.run_due_prelaunches().await {
+                    eprintln!("Failed to warm up browsers: {}", e);
+                    report_error(&app_handle, &db_clone, &errors, e, None).await;
+                }
+
+                match run_due_action(&db_clone, &executor_clone, &mut last_open).await {
+                    Ok(DueAction::Ran) => {}
+                    Ok(DueAction::Busy) => {
+                        // Already being run manually; let that run finish first
+                        sleep(Duration::from_secs(1)).await;
+                    }
+                    Ok(DueAction::Failed(e, task_id)) => {
+                        report_error(&app_handle, &db_clone, &errors, e, task_id).await;
+                        sleep_until_resumed(&resumed, Duration::from_secs(FAILED_BACKOFF_SECS)).await;
                     }
+                    Ok(DueAction::Wait(duration)) => sleep_until_resumed(&resumed, duration).await,
                     Err(e) => {
                         eprintln!("Error fetching next action: {}", e);
+                        report_error(&app_handle, &db_clone, &errors, e, None).await;
                         sleep(Duration::from_secs(5)).await;
                     }
                 }
@@ -90,6 +331,18 @@ impl TaskScheduler {
         Ok(())
     }
 
+    async fn backfill_missed(&self) {
+        let reason = "while the app was not running";
+        if let Some(report) = run_backfill(&self.app_handle, &self.db, &self.errors, reason).await {
+            *self.backfill_report.lock().await = Some(report);
+        }
+    }
+
+    /// Report of the missed occurrences handled when the scheduler last started.
+    pub async fn backfill_report(&self) -> Option<BackfillReport> {
+        self.backfill_report.lock().await.clone()
+    }
+
     pub async fn stop(&self) -> Result<()> {
         let mut running = self.running.write().await;
         if !*running {
@@ -99,7 +352,194 @@ impl TaskScheduler {
         Ok(())
     }
 
+    /// Holds back scheduled opens until `until`, then resumes on its own. The loop keeps running
+    /// meanwhile, so occurrences that fall into the pause are skipped and schedules stay current.
+    pub async fn pause_until(&self, until: DateTime<Utc>) -> Result<()> {
+        if until <= Utc::now() {
+            return Err(AppError::Scheduler("The end of the pause must be in the future".to_string()));
+        }
+
+        self.db.set_paused_until(Some(until)).await?;
+        println!("Scheduling paused until {}", until);
+        tray::refresh_tooltip(&self.app_handle, &self.db).await;
+        Ok(())
+    }
+
+    /// Ends a pause set with `pause_until` before its time.
+    pub async fn resume_from_pause(&self) -> Result<()> {
+        self.db.set_paused_until(None).await?;
+        tray::refresh_tooltip(&self.app_handle, &self.db).await;
+        Ok(())
+    }
+
+    /// End of the current pause, or `None` when scheduling is not paused.
+    pub async fn paused_until(&self) -> Option<DateTime<Utc>> {
+        self.db
+            .get_paused_until()
+            .await
+            .ok()
+            .flatten()
+            .filter(|until| *until > Utc::now())
+    }
+
+    /// Tells the loop the system just resumed from sleep, so it looks at the schedule right
+    /// away instead of finishing a wait that started before the sleep.
+    pub fn notify_resumed(&self) {
+        self.resumed.notify_one();
+    }
+
+    /// End of the quiet hours in effect, or `None` outside of them.
+    pub async fn quiet_until(&self) -> Option<DateTime<Utc>> {
+        let settings = self.db.get_settings().await.ok()?;
+        quiet_hours::active_now(&settings)
+    }
+
+    /// Opens a task immediately, outside of its schedule. The schedule itself is left untouched.
+    pub async fn run_task_now(&self, task_id: i64) -> Result<()> {
+        let task = self.db.get_task(task_id).await?;
+        self.executor.execute(task, ExecutionAction::Open, None).await
+    }
+
+    /// Pushes a task's next open, and its close with it, `minutes` later than it is now due.
+    pub async fn snooze_task(&self, task_id: i64, minutes: i64) -> Result<Task> {
+        let mut task = self.db.get_task(task_id).await?;
+        let due = task
+            .next_open_execution
+            .ok_or_else(|| AppError::InvalidTask(format!("Task {} has no upcoming open", task_id)))?;
+
+        let snoozed = due.max(Utc::now()) + chrono::Duration::minutes(minutes);
+        let delay = snoozed - due;
+        task.next_open_execution = Some(snoozed);
+        task.next_close_execution = task.next_close_execution.map(|close| close + delay);
+
+        let task = self.db.update_task(task_id, task, false, AuditSource::Ui).await?;
+        emit_task_updated(&self.app_handle, &task, TaskUpdateCause::Edited);
+        Ok(task)
+    }
+
+    /// Pushes a task's pending close `minutes` later, leaving its browser open for longer.
+    pub async fn extend_close(&self, task_id: i64, minutes: i64) -> Result<Task> {
+        let mut task = self.db.get_task(task_id).await?;
+        let due = task
+            .next_close_execution
+            .ok_or_else(|| AppError::InvalidTask(format!("Task {} has no pending close", task_id)))?;
+
+        task.next_close_execution = Some(due.max(Utc::now()) + chrono::Duration::minutes(minutes));
+
+        let task = self.db.update_task(task_id, task, false, AuditSource::Ui).await?;
+        emit_task_updated(&self.app_handle, &task, TaskUpdateCause::Edited);
+        Ok(task)
+    }
+
+    /// Runs a task's pending close right away, as if it had come due.
+    pub async fn close_now(&self, task_id: i64) -> Result<()> {
+        let task = self.db.get_task(task_id).await?;
+        let due = task
+            .next_close_execution
+            .ok_or_else(|| AppError::InvalidTask(format!("Task {} has no pending close", task_id)))?;
+        self.executor.execute(task, ExecutionAction::Close, Some(due)).await
+    }
+
+    /// Skips a task's next occurrence, moving it to the one after; a one-off task completes.
+    pub async fn skip_next(&self, task_id: i64) -> Result<Task> {
+        let mut task = self.db.get_task(task_id).await?;
+        let due = task.next_open_execution.unwrap_or_else(Utc::now);
+        backfill::skip_to_next_occurrence(&mut task, due)?;
+
+        let task = self.db.update_task(task_id, task, false, AuditSource::Ui).await?;
+        emit_task_updated(&self.app_handle, &task, TaskUpdateCause::Edited);
+        Ok(task)
+    }
+
+    /// Number of browsers the scheduler opened and has not closed yet.
+    pub fn opened_count(&self) -> usize {
+        self.executor.opened_count()
+    }
+
+    /// Closes every browser the scheduler opened and has not closed yet.
+    pub async fn close_all_opened(&self) -> usize {
+        self.executor.close_all_opened().await
+    }
+
     pub async fn is_running(&self) -> bool {
         *self.running.read().await
     }
+
+    /// Number of loop errors within the rolling error window.
+    pub async fn recent_error_count(&self) -> usize {
+        let mut errors = self.errors.lock().await;
+        errors.prune();
+        errors.recent.len()
+    }
+
+    pub async fn last_error(&self) -> Option<SchedulerErrorPayload> {
+        let mut errors = self.errors.lock().await;
+        errors.prune();
+        errors.recent.back().cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::launcher_backend::{BackendCall, MockBackend};
+    use crate::db::{BrowserType, RepeatConfig, RepeatInterval, TaskStatus};
+
+    #[tokio::test]
+    async fn test_run_due_action_follows_the_schedule() {
+        let db = Arc::new(Database::in_memory().await.unwrap());
+        let backend = Arc::new(MockBackend::default());
+        let executor = TaskExecutor::with_launcher(Arc::clone(&db), None, backend.clone());
+        let mut last_open = None;
+
+        assert!(matches!(
+            run_due_action(&db, &executor, &mut last_open).await.unwrap(),
+            DueAction::Wait(_)
+        ));
+
+        let start = Utc::now() - chrono::Duration::minutes(1);
+        let mut task = Task::new("Standup".to_string(), BrowserType::Chrome, start, "UTC".to_string());
+        task.url = Some("https://example.com/standup".to_string());
+        task.close_time = Some(start + chrono::Duration::minutes(30));
+        task.repeat_config = Some(RepeatConfig { interval: RepeatInterval::Daily, end_after: None, end_date: None });
+        let id = db.create_task(task, AuditSource::Ui).await.unwrap().id.unwrap();
+
+        // The open is due; the close is not yet
+        assert!(matches!(run_due_action(&db, &executor, &mut last_open).await.unwrap(), DueAction::Ran));
+        assert!(last_open.is_some());
+        assert!(matches!(
+            run_due_action(&db, &executor, &mut last_open).await.unwrap(),
+            DueAction::Wait(_)
+        ));
+
+        let task = db.get_task(id).await.unwrap();
+        assert_eq!(task.status, TaskStatus::Active);
+        assert_eq!(task.next_open_execution, Some(start + chrono::Duration::days(1)));
+        assert_eq!(
+            backend.calls(),
+            vec![BackendCall::Open {
+                browser: BrowserType::Chrome,
+                url: Some("https://example.com/standup".to_string()),
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_run_due_action_reports_failures() {
+        let db = Arc::new(Database::in_memory().await.unwrap());
+        let backend = Arc::new(MockBackend { fail_opens: Some("no display".to_string()), ..Default::default() });
+        let executor = TaskExecutor::with_launcher(Arc::clone(&db), None, backend);
+
+        let start = Utc::now() - chrono::Duration::minutes(1);
+        let mut task = Task::new("Standup".to_string(), BrowserType::Chrome, start, "UTC".to_string());
+        task.url = Some("https://example.com/standup".to_string());
+        let id = db.create_task(task, AuditSource::Ui).await.unwrap().id;
+
+        match run_due_action(&db, &executor, &mut None).await.unwrap() {
+            DueAction::Failed(_, task_id) => assert_eq!(task_id, id),
+            other => panic!("expected a failure, got {:?}", other),
+        }
+        // The failed one-time task is no longer scheduled
+        assert!(matches!(run_due_action(&db, &executor, &mut None).await.unwrap(), DueAction::Wait(_)));
+    }
 }
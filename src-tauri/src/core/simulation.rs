@@ -0,0 +1,236 @@
+use std::collections::HashMap;
+use chrono::{DateTime, Duration, Utc};
+use crate::core::recurrence;
+use crate::db::{ExecutionAction, SimulatedAction, StepAction, Task, TaskKind, TaskStatus};
+use crate::error::Result;
+use crate::utils::validation::{host_matches_domain, url_host};
+
+/// Upper bound on simulated occurrences per task, so a huge range cannot run away
+const MAX_OCCURRENCES_PER_TASK: usize = 1000;
+
+/// Runs the recurrence engine for every active task and returns the actions that would fire
+/// between `from` and `to`, in order, annotated with conflicts.
+pub fn simulate_schedule(tasks: &[Task], from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<SimulatedAction>> {
+    let mut timeline = Vec::new();
+
+    for task in tasks.iter().filter(|t| t.status == TaskStatus::Active) {
+        simulate_task(task, from, to, &mut timeline)?;
+    }
+
+    timeline.sort_by_key(|action| action.at);
+    mark_blocked_opens(tasks, from, &mut timeline);
+    mark_simultaneous_actions(&mut timeline);
+
+    Ok(timeline)
+}
+
+fn simulate_task(
+    task: &Task,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    timeline: &mut Vec<SimulatedAction>,
+) -> Result<()> {
+    // The execution count drives `end_after`, so it evolves along with the simulation
+    let mut task = task.clone();
+
+    // A close still pending from a window that is already open
+    if task.is_open_window() {
+        if let Some(close_at) = task.next_close_execution {
+            push(timeline, &task, ExecutionAction::Close, close_at, task.url.clone(), from, to);
+        }
+    }
+
+    let Some(mut occurrence) = task.next_open_execution else {
+        return Ok(());
+    };
+
+    // The first open may resume a pomodoro session or step sequence that is already running
+    let mut resumes = task.pomodoro_cycle > 0 || task.current_step > 0;
+
+    for _ in 0..MAX_OCCURRENCES_PER_TASK {
+        if occurrence >= to {
+            break;
+        }
+
+        // An occurrence on an excluded date is skipped by the executor
+        let excluded = !resumes && recurrence::is_excluded(&task, occurrence);
+        let finished_at = if excluded {
+            occurrence
+        } else {
+            expand_occurrence(&task, occurrence, resumes, from, to, timeline)
+        };
+        if !resumes && !excluded {
+            task.execution_count += 1;
+        }
+        resumes = false;
+
+        let Some(repeat_config) = task.repeat_config.clone() else {
+            break;
+        };
+        let next = recurrence::next_occurrence_after(&task, finished_at)?;
+        if !recurrence::should_continue_repeating(&task, next, &repeat_config) {
+            break;
+        }
+        occurrence = next;
+    }
+
+    Ok(())
+}
+
+/// Adds the actions of one occurrence starting at `at` and returns the time the executor would
+/// move the schedule on from.
+fn expand_occurrence(
+    task: &Task,
+    at: DateTime<Utc>,
+    resumes: bool,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    timeline: &mut Vec<SimulatedAction>,
+) -> DateTime<Utc> {
+    if let Some(ref pomodoro) = task.pomodoro {
+        let first_cycle = if resumes { task.pomodoro_cycle.max(1) } else { 1 };
+        let mut open_at = at;
+        let mut close_at = at;
+
+        for cycle in first_cycle..=pomodoro.cycles {
+            close_at = open_at + Duration::minutes(pomodoro.work_minutes as i64);
+            push(timeline, task, ExecutionAction::Open, open_at, task.url.clone(), from, to);
+            push(timeline, task, ExecutionAction::Close, close_at, task.url.clone(), from, to);
+
+            if cycle < pomodoro.cycles {
+                open_at = close_at + Duration::minutes(pomodoro.break_minutes as i64);
+            }
+        }
+
+        return close_at;
+    }
+
+    if !task.steps.is_empty() {
+        let first_step = if resumes { task.current_step as usize } else { 0 };
+        let mut step_at = at;
+
+        for step in task.steps.iter().skip(first_step) {
+            let action = match step.action {
+                StepAction::Open => ExecutionAction::Open,
+                StepAction::Close => ExecutionAction::Close,
+                StepAction::Wait => {
+                    step_at = step_at + Duration::minutes(step.wait_minutes.unwrap_or(0) as i64);
+                    continue;
+                }
+            };
+            push(timeline, task, action, step_at, step.url.clone(), from, to);
+        }
+
+        return step_at;
+    }
+
+    push(timeline, task, ExecutionAction::Open, at, task.url.clone(), from, to);
+    if let Some(length) = task.open_length() {
+        let close_at = at + length;
+        push(timeline, task, ExecutionAction::Close, close_at, task.url.clone(), from, to);
+    }
+
+    at
+}
+
+fn push(
+    timeline: &mut Vec<SimulatedAction>,
+    task: &Task,
+    action: ExecutionAction,
+    at: DateTime<Utc>,
+    url: Option<String>,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) {
+    if at < from || at >= to {
+        return;
+    }
+
+    timeline.push(SimulatedAction {
+        task_id: task.id.unwrap_or_default(),
+        task_name: task.name.clone(),
+        kind: task.kind.clone(),
+        action,
+        at,
+        url,
+        conflict: None,
+    });
+}
+
+/// Flags browser opens that a focus-mode window would skip.
+fn mark_blocked_opens(tasks: &[Task], from: DateTime<Utc>, timeline: &mut [SimulatedAction]) {
+    let block_tasks: HashMap<i64, &Task> = tasks
+        .iter()
+        .filter(|t| t.kind == TaskKind::Block)
+        .filter_map(|t| Some((t.id?, t)))
+        .collect();
+
+    // Blocking windows as (task id, start, end); a window without a close in range stays open
+    let mut windows: Vec<(i64, DateTime<Utc>, Option<DateTime<Utc>>)> = Vec::new();
+    for task in block_tasks.values().filter(|t| t.is_block_window_open()) {
+        windows.push((task.id.unwrap_or_default(), from, None));
+    }
+    for action in timeline.iter().filter(|a| a.kind == TaskKind::Block) {
+        match action.action {
+            ExecutionAction::Open => windows.push((action.task_id, action.at, None)),
+            ExecutionAction::Close => {
+                if let Some(window) = windows
+                    .iter_mut()
+                    .find(|(id, _, end)| *id == action.task_id && end.is_none())
+                {
+                    window.2 = Some(action.at);
+                }
+            }
+        }
+    }
+
+    for action in timeline.iter_mut() {
+        if action.kind != TaskKind::Browser || action.action != ExecutionAction::Open {
+            continue;
+        }
+        let Some(host) = action.url.as_deref().and_then(url_host) else {
+            continue;
+        };
+
+        let blocked_by = windows
+            .iter()
+            .filter(|(_, start, end)| *start <= action.at && end.map_or(true, |end| action.at < end))
+            .filter_map(|(id, _, _)| block_tasks.get(id))
+            .find_map(|task| {
+                task.blocked_domains
+                    .iter()
+                    .find(|domain| host_matches_domain(&host, domain))
+                    .map(|domain| (domain, &task.name))
+            });
+
+        if let Some((domain, name)) = blocked_by {
+            action.conflict = Some(format!("Skipped: {} is blocked by focus mode '{}'", domain, name));
+        }
+    }
+}
+
+/// Flags actions of different tasks that fire within the same minute.
+fn mark_simultaneous_actions(timeline: &mut [SimulatedAction]) {
+    let mut by_minute: HashMap<i64, Vec<usize>> = HashMap::new();
+    for (index, action) in timeline.iter().enumerate() {
+        by_minute.entry(action.at.timestamp() / 60).or_default().push(index);
+    }
+
+    for indices in by_minute.values() {
+        for &index in indices {
+            if timeline[index].conflict.is_some() {
+                continue;
+            }
+
+            let others: Vec<String> = indices
+                .iter()
+                .filter(|&&other| timeline[other].task_id != timeline[index].task_id)
+                .map(|&other| format!("'{}'", timeline[other].task_name))
+                .collect();
+
+            if !others.is_empty() {
+                timeline[index].conflict = Some(format!("Runs at the same time as {}", others.join(", ")));
+            }
+        }
+    }
+}
@@ -0,0 +1,117 @@
+//! Enumerates the tabs open in running browsers. Chromium browsers are read through the
+//! DevTools endpoint when one is configured; on macOS scriptable browsers are also read through
+//! AppleScript. Firefox and Zen expose neither and are not supported.
+
+use crate::core::devtools;
+use crate::db::{BrowserType, OpenTab};
+use crate::error::{AppError, Result};
+
+/// Tabs open in `browser`, or in every readable browser when `None`. `devtools_port` of 0
+/// skips the DevTools endpoint.
+pub fn list_open_tabs(browser: Option<&BrowserType>, devtools_port: u16) -> Result<Vec<OpenTab>> {
+    let mut tabs = Vec::new();
+    let mut read_any = false;
+
+    if devtools_port != 0 {
+        if let Ok(devtools_browser) = devtools::browser(devtools_port) {
+            if browser.map_or(true, |b| *b == devtools_browser) {
+                tabs.extend(devtools::list_pages(devtools_port)?.into_iter().map(|target| OpenTab {
+                    browser: devtools_browser.clone(),
+                    title: target.title,
+                    url: target.url,
+                }));
+                read_any = true;
+            }
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        const SCRIPTABLE: [BrowserType; 8] = [
+            BrowserType::Chrome,
+            BrowserType::Edge,
+            BrowserType::Brave,
+            BrowserType::Opera,
+            BrowserType::Vivaldi,
+            BrowserType::Chromium,
+            BrowserType::Arc,
+            BrowserType::Safari,
+        ];
+
+        for candidate in SCRIPTABLE.iter().filter(|b| browser.map_or(true, |wanted| wanted == *b)) {
+            for tab in macos::list_tabs(candidate)? {
+                if !tabs.contains(&tab) {
+                    tabs.push(tab);
+                }
+            }
+            read_any = true;
+        }
+    }
+
+    if !read_any {
+        return Err(AppError::BrowserNotFound(match browser {
+            Some(browser) => format!(
+                "Cannot read the tabs of {}; start a Chromium browser with --remote-debugging-port and set it in settings",
+                browser
+            ),
+            None => "No browser whose tabs can be read; start a Chromium browser with --remote-debugging-port and set it in settings".to_string(),
+        }));
+    }
+
+    Ok(tabs)
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use std::process::Command;
+    use crate::core::browser_launcher::BrowserLauncher;
+    use crate::db::{BrowserType, OpenTab};
+    use crate::error::{AppError, Result};
+
+    /// Reads the tabs of a browser if it is running; asking a closed app would launch it.
+    pub fn list_tabs(browser: &BrowserType) -> Result<Vec<OpenTab>> {
+        let app_name = BrowserLauncher::macos_app_name(browser);
+        let title_property = if *browser == BrowserType::Safari { "name" } else { "title" };
+
+        let script = format!(
+            r#"if application "{app}" is running then
+                tell application "{app}"
+                    set out to ""
+                    repeat with w in windows
+                        repeat with t in tabs of w
+                            set out to out & (URL of t) & tab & ({title} of t) & linefeed
+                        end repeat
+                    end repeat
+                    return out
+                end tell
+            end if
+            return """#,
+            app = app_name,
+            title = title_property,
+        );
+
+        let output = Command::new("/usr/bin/osascript")
+            .arg("-e")
+            .arg(&script)
+            .output()
+            .map_err(|e| AppError::Scheduler(format!("Failed to execute AppleScript: {}", e)))?;
+
+        if !output.status.success() {
+            return Err(AppError::Scheduler(format!(
+                "AppleScript error: {}",
+                String::from_utf8_lossy(&output.stderr)
+            )));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| line.split_once('\t'))
+            .filter(|(url, _)| !url.is_empty() && *url != "missing value")
+            .map(|(url, title)| OpenTab {
+                browser: browser.clone(),
+                title: title.to_string(),
+                url: url.to_string(),
+            })
+            .collect())
+    }
+}
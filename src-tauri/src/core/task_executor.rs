@@ -1,206 +1,1325 @@
+use std::collections::HashMap;
 use std::sync::Arc;
-use chrono::{Datelike, Duration, Timelike, TimeZone, Utc};
-use chrono_tz::Tz;
-use crate::core::browser_launcher::BrowserLauncher;
-use crate::db::{Database, ExecutionAction, RepeatInterval, Task, TaskStatus};
-use crate::error::Result;
-use crate::utils::validation::{validate_browser_profile, validate_url};
-use tauri::{AppHandle, Emitter};
-use tauri_plugin_notification::NotificationExt;
+use tokio::sync::{oneshot, Mutex};
+use chrono::{DateTime, Duration, Utc};
+use crate::commands::window_commands;
+use crate::core::browser_launcher::{self, BrowserLauncher, LaunchOptions, LaunchedBrowser};
+use crate::core::events::{emit_task_executed, emit_task_failed, emit_task_updated, TaskFailedPayload, TaskUpdateCause};
+use crate::core::launch_registry::{Launch, LaunchRegistry};
+use crate::core::launcher_backend::{DevtoolsBackend, LauncherBackend};
+use crate::core::reaper::ProcessExit;
+use crate::core::{displays, recurrence, tabs};
+use crate::db::{
+    AppSettings, AuditSource, BrowserType, ConcurrentOpenPolicy, Database, ExecutionAction, ExecutionStatus, LauncherKind, LocaleConfig,
+    NotificationKind, SessionSnapshot, StepAction, Task, TaskExecution, TaskKind, TaskStatus, WindowState,
+};
+use crate::error::{AppError, Result};
+use crate::notifications::{self, template::{self, TemplateValues}};
+use crate::utils::validation::{host_matches_domain, url_host, url_scheme, validate_browser_profile, validate_url, validate_url_allowing};
+use crate::utils::{i18n, locale, secret_url, system_load};
+use tauri::AppHandle;
+
+/// Seconds until a deferred open retries while max_concurrent_opens browsers are open
+const CAPACITY_RETRY_SECS: i64 = 60;
+
+/// Seconds until an open deferred because the system is busy retries
+const LOAD_RETRY_SECS: i64 = 60;
+
+/// Longest an open waits for a busy system before it runs anyway
+const LOAD_MAX_DEFER_MINUTES: i64 = 10;
+
+/// Minutes the notification asking to open a link of a confirmed scheme stays up
+const CONFIRM_WAIT_MINUTES: i64 = 10;
+
+/// Opens or closes the browser of a browser task on `backend`. Returns the launched browser
+/// for an open.
+async fn run_browser_action(
+    backend: &dyn LauncherBackend,
+    task: &Task,
+    action: &ExecutionAction,
+    options: &LaunchOptions,
+    ui_automation: bool,
+) -> Result<Option<LaunchedBrowser>> {
+    let task_id = task.id.ok_or_else(|| AppError::InvalidTask("Task has no ID".to_string()))?;
+    match action {
+        ExecutionAction::Open => {
+            // The resolved URL may carry a secret, so it is only handed to the browser
+            let url = secret_url::resolve(task)?;
+            backend
+                .open(&task.browser, url.as_deref(), task.browser_profile.as_deref(), options)
+                .await
+                .map(Some)
+        }
+        ExecutionAction::Close if task.fresh_session && task.browser.is_chromium() => {
+            backend.close_fresh_session(&browser_launcher::fresh_session_dir(task_id)).await.map(|_| None)
+        }
+        ExecutionAction::Close if task.managed_profile && task.browser.is_chromium() => {
+            let dir = Database::managed_profile_dir(task_id)?;
+            backend.close_profile(&dir).await.map(|_| None)
+        }
+        ExecutionAction::Close => match &task.url {
+            Some(url) if task.app_mode && task.browser.is_chromium() => {
+                backend.close_app(&task.browser, url, ui_automation).await.map(|_| None)
+            }
+            Some(url) => backend.close_url(&task.browser, url, ui_automation).await.map(|_| None),
+            None if task.allow_close_all => backend.close_all(&task.browser).await.map(|_| None),
+            None => Err(AppError::InvalidTask(
+                "Close without URL is blocked unless 'allow_close_all' is enabled for this task".to_string(),
+            )),
+        },
+    }
+}
+
+/// Seconds after launch within which a browser exiting with an error fails the open
+const EARLY_EXIT_SECS: i64 = 10;
+
+/// The error logged for a browser that exited with an error right after launch.
+fn early_exit_error(exit: &ProcessExit) -> String {
+    let status = exit.code.map_or("was killed by a signal".to_string(), |code| format!("exited with code {}", code));
+    if exit.output.is_empty() {
+        format!("Browser {} right after launch", status)
+    } else {
+        format!("Browser {} right after launch: {}", status, exit.output)
+    }
+}
 
 pub struct TaskExecutor {
-    browser_launcher: BrowserLauncher,
+    launcher: Arc<dyn LauncherBackend>,
     db: Arc<Database>,
-    app_handle: AppHandle,
+    // Absent when the executor runs without a UI, as in tests
+    app_handle: Option<AppHandle>,
+    // Per-task locks so the scheduler and manual runs never execute the same task at once
+    task_locks: Mutex<HashMap<i64, Arc<Mutex<()>>>>,
+    // Open occurrence each task's browser was last warmed up for
+    prelaunched: Mutex<HashMap<i64, DateTime<Utc>>>,
+    launches: Arc<LaunchRegistry>,
+    // When each task's pending open was first deferred because the system was busy
+    load_deferred: Mutex<HashMap<i64, DateTime<Utc>>>,
+    // Open occurrence each task was last warned about
+    warned: Mutex<HashMap<i64, DateTime<Utc>>>,
+    // Close occurrence each task last showed a countdown for
+    counted_down: Mutex<HashMap<i64, DateTime<Utc>>>,
 }
 
 impl TaskExecutor {
     pub fn new(db: Arc<Database>, app_handle: AppHandle) -> Self {
+        Self::with_launcher(db, Some(app_handle), Arc::new(BrowserLauncher::new()))
+    }
+
+    /// An executor that launches browsers through `launcher`. Without an app handle no
+    /// events, notifications or windows are shown.
+    pub fn with_launcher(db: Arc<Database>, app_handle: Option<AppHandle>, launcher: Arc<dyn LauncherBackend>) -> Self {
         Self {
-            browser_launcher: BrowserLauncher::new(),
+            launcher,
             db,
             app_handle,
+            task_locks: Mutex::new(HashMap::new()),
+            prelaunched: Mutex::new(HashMap::new()),
+            launches: Arc::new(LaunchRegistry::default()),
+            load_deferred: Mutex::new(HashMap::new()),
+            warned: Mutex::new(HashMap::new()),
+            counted_down: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Warns `warn_before_minutes` ahead of each open, once per occurrence, with actions to
+    /// snooze or skip it or to open the task right away.
+    pub async fn run_due_warnings(&self) -> Result<()> {
+        let settings = self.db.get_settings().await?;
+        if settings.warn_before_minutes == 0 || !settings.show_notifications || self.paused_until().await?.is_some() {
+            return Ok(());
+        }
+
+        let now = Utc::now();
+        let until = now + Duration::minutes(settings.warn_before_minutes as i64);
+        let locale_config = LocaleConfig::from(&settings);
+
+        for task in self.db.get_tasks_opening_between(now, until).await? {
+            let (Some(task_id), Some(open_at)) = (task.id, task.next_open_execution) else { continue };
+            {
+                let mut warned = self.warned.lock().await;
+                if warned.get(&task_id) == Some(&open_at) {
+                    continue;
+                }
+                warned.insert(task_id, open_at);
+            }
+
+            let Some(app_handle) = &self.app_handle else { continue };
+            let body = format!("Opens {}", locale::humanize_until(open_at, &locale_config));
+            notifications::actions::notify_upcoming(
+                app_handle,
+                task_id,
+                &format!("Upcoming: {}", task.name),
+                &body,
+                &settings.action_sound,
+                open_at - now,
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Shows the countdown window `close_countdown_minutes` ahead of each scheduled close, once
+    /// per occurrence. Closes run while scheduling is paused, so their countdowns do too.
+    pub async fn run_due_countdowns(&self) -> Result<()> {
+        let settings = self.db.get_settings().await?;
+        if settings.close_countdown_minutes == 0 {
+            return Ok(());
+        }
+
+        let now = Utc::now();
+        let until = now + Duration::minutes(settings.close_countdown_minutes as i64);
+
+        for task in self.db.get_tasks_closing_between(now, until).await? {
+            let (Some(task_id), Some(close_at)) = (task.id, task.next_close_execution) else { continue };
+            {
+                let mut counted_down = self.counted_down.lock().await;
+                if counted_down.get(&task_id) == Some(&close_at) {
+                    continue;
+                }
+                counted_down.insert(task_id, close_at);
+            }
+
+            let Some(app_handle) = &self.app_handle else { continue };
+            if let Err(e) = window_commands::show_close_countdown(app_handle, task_id, &task.name) {
+                eprintln!("Failed to show the close countdown of task '{}': {}", task.name, e);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Starts the browsers of tasks whose open is within their warm-up time, once per
+    /// occurrence. The warm-up window is minimized so it stays out of the way.
+    pub async fn run_due_prelaunches(&self) -> Result<()> {
+        if self.paused_until().await?.is_some() {
+            return Ok(());
+        }
+
+        let now = Utc::now();
+        let dry_run = self.db.get_settings().await?.dry_run;
+        let active_workspace = self.db.get_active_workspace().await?;
+
+        for task in self.db.get_prelaunch_tasks().await? {
+            let (Some(task_id), Some(open_at), Some(minutes)) =
+                (task.id, task.next_open_execution, task.prelaunch_minutes)
+            else {
+                continue;
+            };
+            if open_at <= now || open_at - Duration::minutes(minutes as i64) > now {
+                continue;
+            }
+            if task.kind != TaskKind::Browser
+                || task.workspace_id.zip(active_workspace).is_some_and(|(own, active)| own != active)
+            {
+                continue;
+            }
+            if self.prelaunched.lock().await.get(&task_id) == Some(&open_at) {
+                continue;
+            }
+            self.prelaunched.lock().await.insert(task_id, open_at);
+
+            if dry_run {
+                println!("Dry run: would warm up {} for task '{}'", task.browser, task.name);
+                continue;
+            }
+
+            // The fresh profile is reset now, since the open will reuse the warmed-up instance
+            if task.fresh_session {
+                browser_launcher::remove_profile_dir(&browser_launcher::fresh_session_dir(task_id))?;
+            }
+
+            let options = LaunchOptions {
+                window_position: None,
+                window_size: None,
+                kiosk: false,
+                app_mode: false,
+                window_state: WindowState::Minimized,
+                ..self.launch_options(&task)
+            };
+            println!("Warming up {} for task '{}' due at {}", task.browser, task.name, open_at);
+            self.launcher.open(&task.browser, None, task.browser_profile.as_deref(), &options).await?;
         }
+
+        Ok(())
     }
 
-    pub async fn execute(&self, mut task: Task, action: ExecutionAction) -> Result<()> {
+    /// Executes an action for a task.
+    ///
+    /// `occurrence` is the scheduled time being fired, or `None` for a manual run. Scheduled
+    /// occurrences advance the task's schedule; manual runs are recorded but leave it untouched.
+    pub async fn execute(
+        &self,
+        task: Task,
+        action: ExecutionAction,
+        occurrence: Option<DateTime<Utc>>,
+    ) -> Result<()> {
         let task_id = task.id.expect("Task must have an ID");
 
-        // Defense-in-depth: validate inputs again right before any system interaction.
-        if let Some(ref url) = task.url {
-            validate_url(url)?;
+        let task_lock = {
+            let mut locks = self.task_locks.lock().await;
+            Arc::clone(locks.entry(task_id).or_default())
+        };
+
+        // A concurrent run of the same task is rejected rather than queued, otherwise it would
+        // fire a second time as soon as the first one finished.
+        let _guard = task_lock
+            .try_lock()
+            .map_err(|_| AppError::TaskBusy(task_id))?;
+
+        self.execute_locked(task_id, task, action, occurrence).await
+    }
+
+    async fn execute_locked(
+        &self,
+        task_id: i64,
+        mut task: Task,
+        action: ExecutionAction,
+        occurrence: Option<DateTime<Utc>>,
+    ) -> Result<()> {
+        // Defense-in-depth: validate inputs again right before any system interaction,
+        // against the URL schemes allowed now rather than when the task was saved. A scheduled
+        // occurrence with a URL no longer allowed is skipped below, so the schedule moves on.
+        let settings = self.db.get_settings().await?;
+        let mut url_error = task
+            .url
+            .as_deref()
+            .and_then(|url| validate_url_allowing(url, &settings.extra_url_schemes()).err());
+        if occurrence.is_none() {
+            if let Some(e) = url_error.take() {
+                return Err(e);
+            }
         }
         if let Some(ref profile) = task.browser_profile {
             validate_browser_profile(profile)?;
         }
 
-        // Execute the browser action
-        let result = match action {
-            ExecutionAction::Open => {
-                self.browser_launcher
-                    .open_browser(
-                        &task.browser,
-                        task.url.as_deref(),
-                        task.browser_profile.as_deref(),
-                    )
-                    .await
-                    .map(|_| ())
+        // A deferred or retried open still fires the occurrence it was scheduled as
+        let occurrence = match occurrence {
+            Some(_) if action == ExecutionAction::Open => task.pending_occurrence.or(occurrence),
+            _ => occurrence,
+        };
+        // A later try of an open must still come before the occurrence's close
+        let occurrence_close = occurrence.and_then(|occurrence| task.occurrence_close(occurrence));
+
+        // Schedule arithmetic is based on the planned time, not on when the loop got to it
+        let fired_at = occurrence.unwrap_or_else(Utc::now);
+
+        // While the system is busy, scheduled opens wait, but only for so long
+        if occurrence.is_some() && action == ExecutionAction::Open && task.kind == TaskKind::Browser {
+            if let Some(reason) = self.system_busy().await? {
+                let now = Utc::now();
+                let since = *self.load_deferred.lock().await.entry(task_id).or_insert(now);
+                let retry_at = now + Duration::seconds(LOAD_RETRY_SECS);
+                if now - since < Duration::minutes(LOAD_MAX_DEFER_MINUTES)
+                    && occurrence_close.map_or(true, |close_at| retry_at < close_at)
+                {
+                    println!("Deferring open of task '{}': {}", task.name, reason);
+                    task.next_open_execution = Some(retry_at);
+                    task.pending_occurrence = occurrence;
+                    let task = self.save_execution_state(task_id, task).await?;
+                    self.emit_updated(&task, TaskUpdateCause::Executed);
+                    return Ok(());
+                }
+                println!("Opening task '{}' although {}", task.name, reason);
             }
-            ExecutionAction::Close => {
-                if let Some(url) = &task.url {
-                    self.browser_launcher
-                        .close_browser_by_url(&task.browser, url)
-                        .await
-                } else {
-                    if task.allow_close_all {
-                        self.browser_launcher
-                            .close_browser(&task.browser)
-                            .await
-                    } else {
-                        Err(crate::error::AppError::InvalidTask(
-                            "Close without URL is blocked unless 'allow_close_all' is enabled for this task"
-                                .to_string(),
-                        ))
-                    }
+            self.load_deferred.lock().await.remove(&task_id);
+        }
+
+        // Scheduled opens beyond max_concurrent_opens are deferred, or skipped further down
+        let at_capacity = occurrence.is_some()
+            && action == ExecutionAction::Open
+            && task.kind == TaskKind::Browser
+            && self.at_open_capacity().await?;
+        if at_capacity && settings.concurrent_open_policy == ConcurrentOpenPolicy::Defer {
+            let retry_at = Utc::now() + Duration::seconds(CAPACITY_RETRY_SECS);
+            if occurrence_close.map_or(true, |close_at| retry_at < close_at) {
+                println!("Deferring open of task '{}': too many browsers open", task.name);
+                task.next_open_execution = Some(retry_at);
+                task.pending_occurrence = occurrence;
+                let task = self.save_execution_state(task_id, task).await?;
+                self.emit_updated(&task, TaskUpdateCause::Executed);
+                return Ok(());
+            }
+        }
+
+        // Claim the occurrence before touching the browser so it can never fire twice
+        let execution_id = match self.db.begin_execution(task_id, &action, occurrence).await? {
+            Some(id) => id,
+            None => {
+                println!(
+                    "Skipping duplicate {} of task '{}' for occurrence {:?}",
+                    action, task.name, occurrence
+                );
+                // The occurrence already ran; only move the schedule past it
+                self.advance_schedule(&mut task, &action, fired_at)?;
+                let task = self.save_execution_state(task_id, task).await?;
+                self.emit_updated(&task, TaskUpdateCause::Executed);
+                return Ok(());
+            }
+        };
+
+        if let Some(e) = url_error {
+            self.skip_occurrence(task_id, task, execution_id, &action, fired_at, e.to_string()).await?;
+            return Ok(());
+        }
+
+        // Focus mode: scheduled opens of blocked domains are skipped, not failed
+        if occurrence.is_some() && action == ExecutionAction::Open && task.kind == TaskKind::Browser {
+            if let Some(domain) = self.find_blocking_domain(&task).await? {
+                let reason = format!("{} is blocked by focus mode", domain);
+                self.skip_occurrence(task_id, task, execution_id, &action, fired_at, reason).await?;
+                return Ok(());
+            }
+        }
+
+        // Tasks of a workspace that is switched off are silenced: skipped, but kept on schedule
+        if occurrence.is_some() {
+            if let (Some(workspace_id), Some(active_id)) =
+                (task.workspace_id, self.db.get_active_workspace().await?)
+            {
+                if workspace_id != active_id {
+                    let reason = "Task's workspace is not active".to_string();
+                    self.skip_occurrence(task_id, task, execution_id, &action, fired_at, reason).await?;
+                    return Ok(());
                 }
             }
+        }
+
+        // Occurrences on the task's excluded dates are skipped; later ones already step over them
+        let starts_occurrence = task.pomodoro_cycle == 0 && task.current_step == 0;
+        if occurrence.is_some()
+            && action == ExecutionAction::Open
+            && starts_occurrence
+            && recurrence::is_excluded(&task, fired_at)
+        {
+            let reason = "The date is excluded from the task's schedule".to_string();
+            self.skip_occurrence(task_id, task, execution_id, &action, fired_at, reason).await?;
+            return Ok(());
+        }
+
+        // While scheduling is paused, scheduled opens are skipped but their schedules move on
+        if occurrence.is_some() && action == ExecutionAction::Open {
+            if let Some(until) = self.paused_until().await? {
+                let reason = format!(
+                    "Scheduling is paused until {}",
+                    locale::format_datetime(until, &LocaleConfig::from(&settings))
+                );
+                self.skip_occurrence(task_id, task, execution_id, &action, fired_at, reason).await?;
+                return Ok(());
+            }
+        }
+
+        // Links of schemes that need confirmation are not opened by the schedule; the user
+        // opens them from the notification instead
+        if occurrence.is_some() && action == ExecutionAction::Open && task.kind == TaskKind::Browser {
+            if let Some(url) = task.url.clone().filter(|url| settings.needs_open_confirmation(url)) {
+                let reason = "Waiting for confirmation to open the link".to_string();
+                let task = self.skip_occurrence(task_id, task, execution_id, &action, fired_at, reason).await?;
+
+                self.ask_open_confirmation(&task, &url, &settings).await;
+                return Ok(());
+            }
+        }
+
+        if at_capacity {
+            let reason = "Too many scheduler-opened browsers are open".to_string();
+            self.skip_occurrence(task_id, task, execution_id, &action, fired_at, reason).await?;
+            return Ok(());
+        }
+
+        // An open that resumes a paused step sequence is part of the same run
+        let resumes_sequence = task.current_step > 0;
+
+        // In dry-run mode everything below runs except for touching browsers
+        let dry_run = settings.dry_run;
+        if dry_run && task.steps.is_empty() {
+            println!("Dry run: would {}", self.describe_action(&task, &action));
+        }
+
+        // A warmed-up browser is already running on the task's profile
+        let warmed_up = match occurrence {
+            Some(occurrence) if action == ExecutionAction::Open => {
+                self.prelaunched.lock().await.remove(&task_id) == Some(occurrence)
+            }
+            _ => false,
         };
+        // A fresh session starts from an empty profile, also after a close that failed to clean up
+        if task.fresh_session
+            && action == ExecutionAction::Open
+            && !dry_run
+            && !resumes_sequence
+            && !warmed_up
+        {
+            let dir = browser_launcher::fresh_session_dir(task_id);
+            if let Err(e) = browser_launcher::remove_profile_dir(&dir) {
+                println!("⚠ Could not clear the fresh session profile of task '{}': {}", task.name, e);
+            }
+        }
+
+        // Execute the browser action. Block tasks have nothing to launch: their window is
+        // tracked purely through the schedule.
+        let mut launched = None;
+        let result = match action {
+            _ if task.kind == TaskKind::Block => Ok(()),
+            ExecutionAction::Open if !task.steps.is_empty() => {
+                // Manual runs start from the top and never move the stored position
+                let from = if occurrence.is_some() { task.current_step as usize } else { 0 };
+                self.run_steps(&task, from, dry_run).await.map(|resume_at| {
+                    if occurrence.is_some() {
+                        task.current_step = resume_at.unwrap_or(0) as i32;
+                    }
+                })
+            }
+            _ if dry_run => Ok(()),
+            _ if task.kind == TaskKind::Reminder => self.run_reminder(&task, &action).await,
+            _ if matches!(task.kind, TaskKind::SaveSession | TaskKind::RestoreSession) => {
+                self.run_session(&task, &action).await
+            }
+            ExecutionAction::Open | ExecutionAction::Close => {
+                let options = match action {
+                    ExecutionAction::Open => self.launch_options(&task),
+                    ExecutionAction::Close => LaunchOptions::default(),
+                };
+                let ui_automation = settings.windows_ui_automation;
+                let backend = self.backend_for(&task).await;
+                run_browser_action(backend.as_ref(), &task, &action, &options, ui_automation)
+                    .await
+                    .map(|browser| launched = browser)
+            }
+        };
+
+        // Track the browsers the scheduler has open for the max_concurrent_opens cap
+        let pid = launched.as_ref().and_then(|browser| browser.pid);
+        if action == ExecutionAction::Close {
+            if let Some(Launch { pid: Some(pid), exit_code: Some(code), .. }) = self.launches.release(task_id) {
+                // The browser handed the URL to a running instance, was closed by hand or crashed
+                println!(
+                    "Browser process {} of task '{}' had already exited ({})",
+                    pid,
+                    task.name,
+                    code.map_or("killed by a signal".to_string(), |code| format!("exit code {}", code))
+                );
+            }
+        } else if result.is_ok() && occurrence.is_some() && task.kind == TaskKind::Browser && !dry_run {
+            self.launches.register(task_id, pid);
+        }
+        if let Some(exit) = launched.and_then(|browser| browser.exit) {
+            self.watch_exit(task_id, execution_id, exit);
+        }
 
         // Update task record based on execution result
         match result {
+            Ok(_) if dry_run => {
+                let execution = self.db
+                    .finish_execution(
+                        execution_id,
+                        ExecutionStatus::Simulated,
+                        Some(format!("Would {}", self.describe_action(&task, &action))),
+                    )
+                    .await?;
+                self.emit_executed(&execution);
+
+                if action == ExecutionAction::Open && task.pomodoro_cycle == 0 && !resumes_sequence {
+                    task.execution_count += 1;
+                }
+
+                if occurrence.is_some() {
+                    self.advance_schedule(&mut task, &action, fired_at)?;
+                }
+
+                let task = self.save_execution_state(task_id, task).await?;
+                self.emit_updated(&task, TaskUpdateCause::Executed);
+
+                Ok(())
+            }
             Ok(_) => {
-                // Increment execution count for open actions
-                if action == ExecutionAction::Open {
+                let execution = self.db
+                    .finish_execution(execution_id, ExecutionStatus::Success, None)
+                    .await?;
+                self.emit_executed(&execution);
+
+                // Increment execution count for open actions; a pomodoro session or step
+                // sequence counts once
+                if action == ExecutionAction::Open && task.pomodoro_cycle == 0 && !resumes_sequence {
                     task.execution_count += 1;
                 }
 
-                // Handle repeat logic
-                if let Some(repeat_config) = &task.repeat_config {
+                task.consecutive_failures = 0;
+                task.last_success_at = Some(Utc::now());
+
+                if action == ExecutionAction::Close {
+                    self.db.record_open_duration(task_id, execution.executed_at).await?;
+                }
+
+                if occurrence.is_some() {
+                    self.advance_schedule(&mut task, &action, fired_at)?;
+                }
+
+                let task = self.save_execution_state(task_id, task).await?;
+                self.emit_updated(&task, TaskUpdateCause::Executed);
+
+                // Send notification if enabled; reminders already showed theirs
+                if task.kind != TaskKind::Reminder {
+                    self.send_notification_if_enabled(&task, &action).await;
+                }
+
+                Ok(())
+            }
+            Err(e) => {
+                // A scheduled run with attempts left is tried again after its backoff; an open
+                // only if the retry still comes before the close
+                let failed = task.retry_attempt + 1;
+                let max_attempts = task.retry_max_attempts.unwrap_or(1);
+                let retry = occurrence
+                    .and_then(|_| task.retry_delay(failed))
+                    .map(|delay| (delay, Utc::now() + delay))
+                    .filter(|(_, retry_at)| {
+                        action == ExecutionAction::Close || occurrence_close.map_or(true, |close_at| *retry_at < close_at)
+                    });
+
+                let message = match retry {
+                    Some((delay, _)) => format!(
+                        "{} (attempt {} of {}, retrying in {})",
+                        e,
+                        failed,
+                        max_attempts,
+                        locale::humanize_duration(delay)
+                    ),
+                    None => e.to_string(),
+                };
+                let execution = self.db
+                    .finish_execution(execution_id, ExecutionStatus::Failed, Some(message))
+                    .await?;
+                self.emit_executed(&execution);
+                self.emit_failed(&TaskFailedPayload {
+                    task_id,
+                    task_name: task.name.clone(),
+                    action: action.clone(),
+                    code: e.code().to_string(),
+                    message: e.to_string(),
+                    attempt: failed,
+                    retry_at: retry.map(|(_, retry_at)| retry_at),
+                    failed_at: Utc::now(),
+                });
+
+                if let Some((_, retry_at)) = retry {
+                    println!(
+                        "Task '{}' failed to {} (attempt {} of {}), retrying at {}",
+                        task.name, action, failed, max_attempts, retry_at
+                    );
+                    task.retry_attempt = failed;
                     match action {
                         ExecutionAction::Open => {
-                            let next = self.calculate_next_execution(&task, task.start_time)?;
-
-                            let should_continue = self.should_continue_repeating(&task, next, repeat_config);
-
-                            if should_continue {
-                                task.next_open_execution = Some(next);
-                                if let Some(close_time) = task.close_time {
-                                    let time_diff = close_time.signed_duration_since(task.start_time);
-                                    task.next_close_execution = Some(next + time_diff);
-                                }
-                                task.status = TaskStatus::Active;
-                            } else {
-                                task.next_open_execution = None;
-                                task.next_close_execution = None;
-                                task.status = TaskStatus::Completed;
-                            }
+                            task.next_open_execution = Some(retry_at);
+                            task.pending_occurrence = occurrence;
                         }
-                        ExecutionAction::Close => {
-                            task.next_close_execution = None;
+                        ExecutionAction::Close => task.next_close_execution = Some(retry_at),
+                    }
+                    let task = self.save_execution_state(task_id, task).await?;
+                    self.emit_updated(&task, TaskUpdateCause::Failed);
+                    return Err(e);
+                }
+
+                let (title, body) = self.failure_notification_text(&task, &action, &e).await;
+                self.record_notification(NotificationKind::Failure, Some(task_id), &title, &body).await;
+
+                if settings.notify_on_failure {
+                    self.notify_task(task_id, &title, &body, &settings.failure_sound);
+                }
+
+                if occurrence.is_some() {
+                    task.consecutive_failures += 1;
+                    task.retry_attempt = 0;
+                    task.pending_occurrence = None;
+
+                    // A failed step abandons the rest of the sequence
+                    task.current_step = 0;
+
+                    let max_failures = settings.max_consecutive_failures;
+                    if max_failures > 0 && task.consecutive_failures as u32 >= max_failures {
+                        task.status = TaskStatus::Disabled;
+                    } else if task.repeat_config.is_some() || task.pomodoro.is_some() {
+                        // Keep recurring tasks on schedule; only this occurrence is lost
+                        self.advance_schedule(&mut task, &action, fired_at)?;
+                    } else {
+                        task.status = TaskStatus::Failed;
+                    }
+
+                    let task = self.save_execution_state(task_id, task).await?;
+                    self.emit_updated(&task, TaskUpdateCause::Failed);
+
+                    if task.status == TaskStatus::Disabled {
+                        self.notify_disabled(&task).await;
+                    }
+                }
+
+                Err(e)
+            }
+        }
+    }
+
+    /// Runs the steps of a multi-step task starting at `from`. In dry-run mode the steps are
+    /// only logged.
+    ///
+    /// Stops at the first wait step and returns the index of the step to resume at, or `None`
+    /// once the sequence is finished.
+    async fn run_steps(&self, task: &Task, from: usize, dry_run: bool) -> Result<Option<usize>> {
+        for (index, step) in task.steps.iter().enumerate().skip(from) {
+            match step.action {
+                StepAction::Wait => return Ok(Some(index + 1)),
+                _ if dry_run => println!(
+                    "Dry run: would {} {} in {}",
+                    step.action,
+                    step.url.as_deref().unwrap_or_default(),
+                    task.browser
+                ),
+                StepAction::Open => {
+                    let url = step.url.as_deref().ok_or_else(|| {
+                        AppError::InvalidTask("Open step has no URL".to_string())
+                    })?;
+                    let settings = self.db.get_settings().await?;
+                    validate_url_allowing(url, &settings.extra_url_schemes())?;
+                    self.backend_for(task)
+                        .await
+                        .open(&task.browser, Some(url), task.browser_profile.as_deref(), &self.launch_options(task))
+                        .await?;
+                }
+                StepAction::Close => {
+                    let url = step.url.as_deref().ok_or_else(|| {
+                        AppError::InvalidTask("Close step has no URL".to_string())
+                    })?;
+                    self.close_by_url(task, url).await?;
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Records how the browser process of an open exits, in the launch registry and on the
+    /// execution, once the reaper reports it.
+    fn watch_exit(&self, task_id: i64, execution_id: i64, exit: oneshot::Receiver<ProcessExit>) {
+        let db = Arc::clone(&self.db);
+        let launches = Arc::clone(&self.launches);
+        let app_handle = self.app_handle.clone();
+        let launched_at = Utc::now();
+        tauri::async_runtime::spawn(async move {
+            let Ok(exit) = exit.await else { return };
+            launches.record_exit(task_id, exit.pid, exit.code);
+            if let Err(e) = db.record_process_exit(execution_id, exit.pid, exit.code).await {
+                eprintln!("Failed to record the exit of process {}: {}", exit.pid, e);
+            }
+
+            // A browser that quits with an error straight away never showed the page, so the
+            // open that was logged as a success is turned into a failure with its output
+            let early = Utc::now() - launched_at < Duration::seconds(EARLY_EXIT_SECS);
+            if early && exit.code != Some(0) {
+                match db.finish_execution(execution_id, ExecutionStatus::Failed, Some(early_exit_error(&exit))).await {
+                    Ok(execution) => {
+                        if let Some(app_handle) = &app_handle {
+                            emit_task_executed(app_handle, &execution);
                         }
                     }
-                } else {
-                    // One-time task
+                    Err(e) => eprintln!("Failed to record the early exit of process {}: {}", exit.pid, e),
+                }
+            }
+        });
+    }
+
+    /// End of the current scheduler pause, or `None` when scheduling is not paused
+    async fn paused_until(&self) -> Result<Option<DateTime<Utc>>> {
+        let now = Utc::now();
+        Ok(self.db.get_paused_until().await?.filter(|until| *until > now))
+    }
+
+    /// Number of browsers the scheduler opened that are still open, as far as it knows
+    pub fn opened_count(&self) -> usize {
+        self.launches.count()
+    }
+
+    /// Runs the close action of every task whose browser the scheduler opened and has not
+    /// closed yet. The schedules are left untouched. Returns how many tasks were closed.
+    pub async fn close_all_opened(&self) -> usize {
+        let mut closed = 0;
+        for task_id in self.launches.task_ids() {
+            let result = match self.db.get_task(task_id).await {
+                Ok(task) => self.execute(task, ExecutionAction::Close, None).await,
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(()) => closed += 1,
+                Err(e) => eprintln!("Failed to close task {}: {}", task_id, e),
+            }
+            self.launches.release(task_id);
+        }
+        closed
+    }
+
+    /// Why the system counts as too busy for a launch, or None if it is not, or the
+    /// thresholds are off. A failure to read the load does not hold launches back.
+    async fn system_busy(&self) -> Result<Option<String>> {
+        let settings = self.db.get_settings().await?;
+        if settings.max_cpu_percent == 0 && settings.max_memory_percent == 0 {
+            return Ok(None);
+        }
+
+        let load = match system_load::current().await {
+            Ok(load) => load,
+            Err(e) => {
+                println!("⚠ Could not check the system load: {}", e);
+                return Ok(None);
+            }
+        };
+
+        Ok(if settings.max_cpu_percent > 0 && load.cpu_percent > settings.max_cpu_percent as f64 {
+            Some(format!("CPU usage is at {:.0}%", load.cpu_percent))
+        } else if settings.max_memory_percent > 0 && load.memory_percent > settings.max_memory_percent as f64 {
+            Some(format!("memory usage is at {:.0}%", load.memory_percent))
+        } else {
+            None
+        })
+    }
+
+    /// Whether the scheduler already has `max_concurrent_opens` browsers open. Registered
+    /// opens whose task no longer has a close pending are dropped first.
+    async fn at_open_capacity(&self) -> Result<bool> {
+        let max = self.db.get_settings().await?.max_concurrent_opens;
+        if max == 0 {
+            return Ok(false);
+        }
+
+        let mut pending = Vec::new();
+        for task_id in self.launches.task_ids() {
+            if let Ok(task) = self.db.get_task(task_id).await {
+                if task.status == TaskStatus::Active && task.next_close_execution.is_some() {
+                    pending.push(task_id);
+                }
+            }
+        }
+        self.launches.retain(&pending);
+
+        Ok(self.launches.count() >= max as usize)
+    }
+
+    /// Profile directory of a task opening on a fresh session or a managed profile
+    fn profile_dir(&self, task: &Task) -> Option<std::path::PathBuf> {
+        let task_id = task.id?;
+        if task.fresh_session {
+            return Some(browser_launcher::fresh_session_dir(task_id));
+        }
+        if task.managed_profile {
+            return match Database::managed_profile_dir(task_id) {
+                Ok(dir) => Some(dir),
+                Err(e) => {
+                    println!("⚠ No managed profile for task '{}': {}", task.name, e);
+                    None
+                }
+            };
+        }
+        None
+    }
+
+    /// Window settings for the browser windows `task` opens. A window position is taken
+    /// from the target display's corner when the task has one.
+    fn launch_options(&self, task: &Task) -> LaunchOptions {
+        let origin = task.target_display.and_then(|index| {
+            let origin = self.app_handle.as_ref().and_then(|app_handle| displays::display_origin(app_handle, index));
+            if origin.is_none() {
+                println!("Display {} of task '{}' is not connected; using the default placement", index, task.name);
+            }
+            origin
+        });
+        let window_position = match (origin, task.window_position) {
+            (Some((x, y)), Some(offset)) => Some((x + offset.x, y + offset.y)),
+            (None, Some(position)) => Some((position.x, position.y)),
+            (origin, None) => origin,
+        };
+
+        LaunchOptions {
+            window_position,
+            window_size: task.window_size.map(|size| (size.width, size.height)),
+            kiosk: task.kiosk,
+            app_mode: task.app_mode,
+            window_state: task.window_state.clone(),
+            zoom: task.zoom,
+            user_agent: task.user_agent.clone().filter(|ua| !ua.trim().is_empty()),
+            lang: task.lang.clone().filter(|lang| !lang.is_empty()),
+            user_data_dir: self.profile_dir(task),
+        }
+    }
+
+    /// Closes the tabs of the task's browser showing `url`, through UI Automation on Windows
+    /// when the setting allows it.
+    async fn close_by_url(&self, task: &Task, url: &str) -> Result<()> {
+        let ui_automation = self.db.get_settings().await?.windows_ui_automation;
+        self.backend_for(task).await.close_url(&task.browser, url, ui_automation).await
+    }
+
+    /// The backend for a task's browser: the DevTools endpoint when the task asks for it and a
+    /// port is set, the native launcher otherwise.
+    async fn backend_for(&self, task: &Task) -> Arc<dyn LauncherBackend> {
+        if task.launcher == LauncherKind::Devtools {
+            let port = self.db.get_settings().await.map(|s| s.devtools_port).unwrap_or(0);
+            if port != 0 {
+                return Arc::new(DevtoolsBackend::new(port, Arc::clone(&self.launcher)));
+            }
+        }
+        Arc::clone(&self.launcher)
+    }
+
+    /// Human-readable description of what an action does, for dry-run logs.
+    fn describe_action(&self, task: &Task, action: &ExecutionAction) -> String {
+        match (&task.kind, action) {
+            (TaskKind::Block, ExecutionAction::Open) => {
+                format!("start blocking {}", task.blocked_domains.join(", "))
+            }
+            (TaskKind::Block, ExecutionAction::Close) => {
+                format!("stop blocking {}", task.blocked_domains.join(", "))
+            }
+            (TaskKind::Reminder, ExecutionAction::Open) => format!("show break reminder '{}'", task.name),
+            (TaskKind::Reminder, ExecutionAction::Close) => format!("end break '{}'", task.name),
+            (TaskKind::SaveSession, ExecutionAction::Open) => format!(
+                "save the tabs of {} as session '{}'",
+                task.browser,
+                task.session_name.as_deref().unwrap_or_default()
+            ),
+            (TaskKind::SaveSession, ExecutionAction::Close) => "nothing".to_string(),
+            (TaskKind::RestoreSession, _) => format!(
+                "{} the tabs of session '{}' in {}",
+                action,
+                task.session_name.as_deref().unwrap_or_default(),
+                task.browser
+            ),
+            (TaskKind::Browser, _) if !task.steps.is_empty() => {
+                format!("run {} steps of '{}'", task.steps.len(), task.name)
+            }
+            (TaskKind::Browser, _) => match &task.url {
+                Some(url) => format!("{} {} in {}", action, url, task.browser),
+                None => format!("{} {}", action, task.browser),
+            },
+        }
+    }
+
+    /// Saves the browser's open tabs as the task's session snapshot, or opens (and at close
+    /// time closes) the tabs of that snapshot.
+    async fn run_session(&self, task: &Task, action: &ExecutionAction) -> Result<()> {
+        let name = task
+            .session_name
+            .clone()
+            .ok_or_else(|| AppError::InvalidTask("Session task without a session name".to_string()))?;
+
+        match (&task.kind, action) {
+            (TaskKind::SaveSession, ExecutionAction::Open) => {
+                let devtools_port = self.db.get_settings().await?.devtools_port;
+                let browser = task.browser.clone();
+                let tabs = tokio::task::spawn_blocking(move || tabs::list_open_tabs(Some(&browser), devtools_port))
+                    .await
+                    .map_err(|e| AppError::Scheduler(e.to_string()))??;
+
+                println!("Saving {} tab(s) of {} as session '{}'", tabs.len(), task.browser, name);
+                self.db
+                    .save_session_snapshot(&SessionSnapshot {
+                        name,
+                        browser: task.browser.clone(),
+                        browser_profile: task.browser_profile.clone(),
+                        tabs,
+                        saved_at: Utc::now(),
+                    })
+                    .await
+            }
+            (TaskKind::SaveSession, ExecutionAction::Close) => Ok(()),
+            (_, action) => {
+                let snapshot = self
+                    .db
+                    .get_session_snapshot(&name)
+                    .await?
+                    .ok_or_else(|| AppError::InvalidTask(format!("No saved session named '{}'", name)))?;
+
+                for tab in &snapshot.tabs {
+                    // Saved tabs can hold anything the browser showed, e.g. chrome:// pages
+                    if validate_url(&tab.url).is_err() {
+                        continue;
+                    }
                     match action {
                         ExecutionAction::Open => {
-                            task.next_open_execution = None;
-                            if task.close_time.is_none() {
-                                task.status = TaskStatus::Completed;
-                            }
+                            self.backend_for(task)
+                                .await
+                                .open(&task.browser, Some(&tab.url), task.browser_profile.as_deref(), &self.launch_options(task))
+                                .await?;
                         }
                         ExecutionAction::Close => {
-                            task.next_close_execution = None;
-                            task.status = TaskStatus::Completed;
+                            self.close_by_url(task, &tab.url).await?;
                         }
                     }
                 }
+                Ok(())
+            }
+        }
+    }
 
-                self.db.update_task(task_id, task.clone()).await?;
-                let _ = self.app_handle.emit("task-updated", task_id);
+    /// Shows a break reminder on open and, if requested, keeps scheduler-opened browsers
+    /// minimized until the reminder's close.
+    async fn run_reminder(&self, task: &Task, action: &ExecutionAction) -> Result<()> {
+        let browsers = if task.minimize_browsers {
+            self.scheduler_opened_browsers().await?
+        } else {
+            Vec::new()
+        };
 
-                // Send notification if enabled
-                self.send_notification_if_enabled(&task, &action).await;
+        match action {
+            ExecutionAction::Open => {
+                let body = match task.open_length() {
+                    Some(length) => format!("Take a {} break", locale::humanize_duration(length)),
+                    None => "Time for a break".to_string(),
+                };
+                if let Some(task_id) = task.id {
+                    let sound = self.db.get_settings().await.map(|s| s.reminder_sound).unwrap_or_default();
+                    self.notify_task(task_id, &task.name, &body, &sound);
 
-                Ok(())
+                    // Recorded so reminders also reach channels like Telegram
+                    self.record_notification(NotificationKind::Reminder, Some(task_id), &task.name, &body).await;
+                }
+
+                for browser in &browsers {
+                    self.launcher.set_minimized(browser, true).await?;
+                }
             }
-            Err(e) => {
-                task.status = TaskStatus::Failed;
-                self.db.update_task(task_id, task).await?;
-                let _ = self.app_handle.emit("task-updated", task_id);
+            ExecutionAction::Close => {
+                for browser in &browsers {
+                    self.launcher.set_minimized(browser, false).await?;
+                }
+            }
+        }
 
-                Err(e)
+        Ok(())
+    }
+
+    /// Browsers of tasks whose page is currently open because the scheduler opened it.
+    async fn scheduler_opened_browsers(&self) -> Result<Vec<BrowserType>> {
+        let mut browsers = Vec::new();
+        for task in self.db.get_all_tasks().await? {
+            if task.kind == TaskKind::Browser && task.is_open_window() && !browsers.contains(&task.browser) {
+                browsers.push(task.browser);
             }
         }
+        Ok(browsers)
     }
 
-    fn should_continue_repeating(
+    /// Returns the blocked domain matching the task's URL if a block window is open.
+    async fn find_blocking_domain(&self, task: &Task) -> Result<Option<String>> {
+        let Some(host) = task.url.as_deref().and_then(url_host) else {
+            return Ok(None);
+        };
+
+        let blocking_domain = self
+            .db
+            .get_all_tasks()
+            .await?
+            .into_iter()
+            .filter(|t| t.is_block_window_open())
+            .flat_map(|t| t.blocked_domains)
+            .find(|domain| host_matches_domain(&host, domain));
+
+        Ok(blocking_domain)
+    }
+
+    /// Moves the task's next execution times past the action that fired at `fired_at`.
+    fn advance_schedule(&self, task: &mut Task, action: &ExecutionAction, fired_at: DateTime<Utc>) -> Result<()> {
+        // Retries and deferrals belong to the occurrence being moved past
+        task.retry_attempt = 0;
+        task.pending_occurrence = None;
+
+        if let Some(pomodoro) = task.pomodoro.clone() {
+            return self.advance_pomodoro(task, action, &pomodoro, fired_at);
+        }
+
+        if *action == ExecutionAction::Open && task.current_step > 0 {
+            return self.schedule_step_resume(task, fired_at);
+        }
+
+        self.advance_recurrence(task, action, fired_at)
+    }
+
+    /// Schedules a step sequence paused at a wait step to resume once the wait is over.
+    /// `current_step` points just past the wait step.
+    fn schedule_step_resume(&self, task: &mut Task, fired_at: DateTime<Utc>) -> Result<()> {
+        let wait_minutes = task
+            .steps
+            .get(task.current_step as usize - 1)
+            .and_then(|step| step.wait_minutes)
+            .ok_or_else(|| AppError::InvalidTask("Sequence is not paused at a wait step".to_string()))?;
+
+        task.next_open_execution = Some(fired_at + Duration::minutes(wait_minutes as i64));
+        task.status = TaskStatus::Active;
+        Ok(())
+    }
+
+    /// Pomodoro state machine. `pomodoro_cycle` is 0 between sessions and otherwise holds the
+    /// current work cycle: an open starts (or continues) a cycle and schedules its close after
+    /// the work period, a close schedules the next cycle's open after the break, and the close
+    /// of the last cycle ends the session and moves on to the next scheduled start.
+    fn advance_pomodoro(
         &self,
-        task: &Task,
-        next: chrono::DateTime<Utc>,
-        repeat_config: &crate::db::RepeatConfig,
-    ) -> bool {
-        match (&repeat_config.end_after, &repeat_config.end_date) {
-            (Some(count), _) => task.execution_count < *count,
-            (None, Some(end_date)) => next < *end_date,
-            (None, None) => true,
-        }
-    }
-
-    fn calculate_next_execution(&self, task: &Task, base_time: chrono::DateTime<Utc>) -> Result<chrono::DateTime<Utc>> {
-        let repeat_config = task
-            .repeat_config
-            .as_ref()
-            .expect("Task must have repeat config");
-
-        // Parse timezone
-        let tz: Tz = task
-            .timezone
-            .parse()
-            .map_err(|_| crate::error::AppError::TimeParse(format!("Invalid timezone: {}", task.timezone)))?;
-
-        // Convert base time to task's timezone
-        let local_time = base_time.with_timezone(&tz);
-
-        // Calculate next occurrence based on interval
-        let next_local = match repeat_config.interval {
-            RepeatInterval::Daily => local_time + Duration::days(1),
-            RepeatInterval::Weekly => local_time + Duration::weeks(1),
-            RepeatInterval::Monthly => {
-                let month = local_time.month();
-                let year = local_time.year();
-
-                let (next_month, next_year) = if month == 12 {
-                    (1, year + 1)
-                } else {
-                    (month + 1, year)
-                };
+        task: &mut Task,
+        action: &ExecutionAction,
+        pomodoro: &crate::db::PomodoroConfig,
+        fired_at: DateTime<Utc>,
+    ) -> Result<()> {
+        match action {
+            ExecutionAction::Open => {
+                if task.pomodoro_cycle == 0 {
+                    task.pomodoro_cycle = 1;
+                }
+                task.next_open_execution = None;
+                task.next_close_execution = Some(fired_at + Duration::minutes(pomodoro.work_minutes as i64));
+                task.status = TaskStatus::Active;
+            }
+            ExecutionAction::Close if task.pomodoro_cycle > 0 && task.pomodoro_cycle < pomodoro.cycles => {
+                task.pomodoro_cycle += 1;
+                task.next_open_execution = Some(fired_at + Duration::minutes(pomodoro.break_minutes as i64));
+                task.next_close_execution = None;
+            }
+            ExecutionAction::Close => {
+                // Session finished: continue with the regular schedule
+                task.pomodoro_cycle = 0;
+                self.advance_open(task, fired_at, None)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Moves a regular task's next execution times past the action that just fired.
+    fn advance_recurrence(&self, task: &mut Task, action: &ExecutionAction, fired_at: DateTime<Utc>) -> Result<()> {
+        // The close of the occurrence that just opened stays pending until it fires, so the
+        // task's window (e.g. a block window) counts as open in between. A duration is counted
+        // from when the open ran if it ran late.
+        let current_close = match (action, task.close_time, task.duration_minutes) {
+            (ExecutionAction::Open, Some(_), _) => task.occurrence_close(fired_at),
+            (ExecutionAction::Open, None, Some(minutes)) => {
+                Some(fired_at.max(Utc::now()) + Duration::minutes(minutes as i64))
+            }
+            _ => None,
+        };
+
+        if task.repeat_config.is_some() {
+            match action {
+                ExecutionAction::Open => self.advance_open(task, fired_at, current_close)?,
+                ExecutionAction::Close => {
+                    task.next_close_execution = None;
+                    if task.next_open_execution.is_none() {
+                        task.status = TaskStatus::Completed;
+                    }
+                }
+            }
+        } else {
+            // One-time task
+            match action {
+                ExecutionAction::Open => {
+                    task.next_open_execution = None;
+                    // A fixed close_time was scheduled along with the open
+                    if task.close_time.is_none() {
+                        task.next_close_execution = current_close;
+                        if current_close.is_none() {
+                            task.status = TaskStatus::Completed;
+                        }
+                    }
+                }
+                ExecutionAction::Close => {
+                    task.next_close_execution = None;
+                    task.status = TaskStatus::Completed;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Records a claimed occurrence as skipped with `reason` and moves the schedule past it.
+    async fn skip_occurrence(
+        &self,
+        task_id: i64,
+        mut task: Task,
+        execution_id: i64,
+        action: &ExecutionAction,
+        fired_at: DateTime<Utc>,
+        reason: String,
+    ) -> Result<Task> {
+        let execution = self.db
+            .finish_execution(execution_id, ExecutionStatus::Skipped, Some(reason))
+            .await?;
+        self.emit_executed(&execution);
+
+        self.advance_schedule(&mut task, action, fired_at)?;
+        // Nothing was opened, so the skipped occurrence has no close to run
+        if *action == ExecutionAction::Open && task.pomodoro.is_none() {
+            task.next_close_execution = None;
+            if task.next_open_execution.is_none() {
+                task.status = TaskStatus::Completed;
+            }
+        }
+        let task = self.save_execution_state(task_id, task).await?;
+        self.emit_updated(&task, TaskUpdateCause::Executed);
+        Ok(task)
+    }
 
-                let last_day_of_month = chrono::NaiveDate::from_ymd_opt(
-                    next_year,
-                    next_month + 1,
-                    1,
-                )
-                .unwrap_or_else(|| chrono::NaiveDate::from_ymd_opt(next_year + 1, 1, 1).unwrap())
-                .pred_opt()
-                .unwrap()
-                .day();
+    /// Schedules the open of a repeating task's next occurrence after the one that fired at
+    /// `fired_at`, keeping `current_close` pending for the occurrence that just opened. The
+    /// task completes once it stops repeating and has no close left to run.
+    fn advance_open(&self, task: &mut Task, fired_at: DateTime<Utc>, current_close: Option<DateTime<Utc>>) -> Result<()> {
+        let Some(repeat_config) = task.repeat_config.clone() else {
+            task.next_open_execution = None;
+            task.next_close_execution = current_close;
+            if current_close.is_none() {
+                task.status = TaskStatus::Completed;
+            }
+            return Ok(());
+        };
 
-                let day = local_time.day().min(last_day_of_month);
+        // A jittered open may have fired early; look past the whole window so the same
+        // occurrence is not scheduled again
+        let next = recurrence::next_occurrence_after(task, fired_at + task.jitter_window())?;
 
-                let next_date = chrono::NaiveDate::from_ymd_opt(next_year, next_month, day)
-                    .ok_or_else(|| crate::error::AppError::TimeParse("Failed to calculate next month".to_string()))?;
+        task.next_close_execution = current_close;
+        if recurrence::should_continue_repeating(task, next, &repeat_config) {
+            task.next_open_execution = Some(task.jittered(next));
+            task.status = TaskStatus::Active;
+        } else {
+            task.next_open_execution = None;
+            if current_close.is_none() {
+                task.status = TaskStatus::Completed;
+            }
+        }
 
-                let next_datetime = next_date
-                    .and_hms_opt(local_time.hour(), local_time.minute(), local_time.second())
-                    .ok_or_else(|| crate::error::AppError::TimeParse("Failed to create next datetime".to_string()))?;
+        Ok(())
+    }
+
+    /// Persists the scheduling bookkeeping of an execution without clobbering concurrent edits.
+    ///
+    /// If the task was edited while it was executing, the bookkeeping fields are re-applied on top
+    /// of the latest row. When the edit changed the schedule itself, the user's recalculated
+    /// next executions win and the edited row is kept as-is.
+    async fn save_execution_state(&self, task_id: i64, task: Task) -> Result<Task> {
+        match self.db.update_task(task_id, task.clone(), false, AuditSource::Scheduler).await {
+            Err(AppError::Conflict(_)) => {
+                let mut latest = self.db.get_task(task_id).await?;
+
+                if latest.start_time != task.start_time || latest.close_time != task.close_time {
+                    return Ok(latest);
+                }
+
+                latest.execution_count = task.execution_count;
+                latest.pomodoro_cycle = task.pomodoro_cycle;
+                if latest.steps == task.steps {
+                    latest.current_step = task.current_step;
+                }
+                latest.consecutive_failures = task.consecutive_failures;
+                latest.retry_attempt = task.retry_attempt;
+                latest.pending_occurrence = task.pending_occurrence;
+                latest.last_success_at = task.last_success_at;
+                latest.status = task.status;
+                latest.next_open_execution = task.next_open_execution;
+                latest.next_close_execution = task.next_close_execution;
 
-                tz.from_local_datetime(&next_datetime)
-                    .single()
-                    .ok_or_else(|| crate::error::AppError::TimeParse("Ambiguous local time".to_string()))?
+                self.db.update_task(task_id, latest, false, AuditSource::Scheduler).await
             }
+            result => result,
+        }
+    }
+
+    /// Title and body of a failure notification, rendered from the user's templates if set.
+    async fn failure_notification_text(&self, task: &Task, action: &ExecutionAction, error: &AppError) -> (String, String) {
+        let settings = self.db.get_settings().await.unwrap_or_default();
+        let time = locale::format_time(Utc::now(), &LocaleConfig::from(&settings));
+        let error = i18n::error_message(error, settings.language);
+        let values = TemplateValues {
+            task: &task.name,
+            url: task.url.as_deref().unwrap_or_default(),
+            time: &time,
+            error: &error,
         };
 
-        Ok(next_local.with_timezone(&Utc))
+        (
+            template::render(&settings.failure_notification_title, &values, format!("Task failed: {}", task.name)),
+            template::render(
+                &settings.failure_notification_body,
+                &values,
+                format!("Failed to {} {}: {}", action, task.browser, error),
+            ),
+        )
+    }
+
+    fn emit_updated(&self, task: &Task, cause: TaskUpdateCause) {
+        if let Some(app_handle) = &self.app_handle {
+            emit_task_updated(app_handle, task, cause);
+        }
+    }
+
+    fn emit_executed(&self, execution: &TaskExecution) {
+        if let Some(app_handle) = &self.app_handle {
+            emit_task_executed(app_handle, execution);
+        }
+    }
+
+    fn emit_failed(&self, payload: &TaskFailedPayload) {
+        if let Some(app_handle) = &self.app_handle {
+            emit_task_failed(app_handle, payload);
+        }
+    }
+
+    fn notify_task(&self, task_id: i64, title: &str, body: &str, sound: &str) {
+        if let Some(app_handle) = &self.app_handle {
+            notifications::notify_task(app_handle, task_id, title, body, sound);
+        }
+    }
+
+    /// Adds a notification to the notification center and forwards it to the channels.
+    async fn record_notification(&self, kind: NotificationKind, task_id: Option<i64>, title: &str, body: &str) {
+        let Ok(notification) = self.db.create_notification(kind, task_id, title, body).await else { return };
+        if let Some(app_handle) = &self.app_handle {
+            notifications::publish(app_handle, &notification);
+        }
+    }
+
+    /// Asks the user to open a link whose scheme needs confirmation, with an open-now button
+    /// where the platform supports one.
+    async fn ask_open_confirmation(&self, task: &Task, url: &str, settings: &AppSettings) {
+        let Some(task_id) = task.id else { return };
+        let title = format!("Open {}?", task.name);
+        let body = format!(
+            "{} wants to open a {}: link. Open it now to continue.",
+            task.name,
+            url_scheme(url).unwrap_or_default()
+        );
+
+        self.record_notification(NotificationKind::Info, Some(task_id), &title, &body).await;
+
+        let Some(app_handle) = &self.app_handle else { return };
+        notifications::actions::notify_confirm_open(
+            app_handle,
+            task_id,
+            &title,
+            &body,
+            &settings.action_sound,
+            Duration::minutes(CONFIRM_WAIT_MINUTES),
+        );
+    }
+
+    async fn notify_disabled(&self, task: &Task) {
+        let Some(task_id) = task.id else { return };
+        let title = format!("Task disabled: {}", task.name);
+        let body = format!(
+            "Disabled after {} consecutive failures. Fix the task and resume it to re-enable.",
+            task.consecutive_failures
+        );
+
+        self.record_notification(NotificationKind::Failure, Some(task_id), &title, &body).await;
+
+        let Ok(settings) = self.db.get_settings().await else { return };
+        if settings.show_notifications {
+            self.notify_task(task_id, &title, &body, &settings.failure_sound);
+        }
     }
 
     async fn send_notification_if_enabled(&self, task: &Task, action: &ExecutionAction) {
@@ -221,18 +1340,306 @@ impl TaskExecutor {
             ExecutionAction::Close => "closed",
         };
 
-        let message = if let Some(ref url) = task.url {
+        let message = if task.kind == TaskKind::Block {
+            let state = match action {
+                ExecutionAction::Open => "started",
+                ExecutionAction::Close => "ended",
+            };
+            format!("Focus mode {}: {}", state, task.blocked_domains.join(", "))
+        } else if let Some(ref url) = task.url {
             format!("{} {} in {}", action_text, url, task.browser)
         } else {
             format!("{} {}", action_text, task.browser)
         };
 
-        // Send notification using tauri-plugin-notification
-        let _ = self.app_handle
-            .notification()
-            .builder()
-            .title(format!("Task: {}", task.name))
-            .body(message)
-            .show();
+        // Say when an opened task closes again
+        let locale_config = LocaleConfig::from(&settings);
+        let message = match task.next_close_execution.filter(|at| *action == ExecutionAction::Open && *at > Utc::now()) {
+            Some(close_at) => format!("{}; closes {}", message, locale::humanize_until(close_at, &locale_config)),
+            None => message,
+        };
+
+        let time = locale::format_time(Utc::now(), &locale_config);
+        let values = TemplateValues {
+            task: &task.name,
+            url: task.url.as_deref().unwrap_or_default(),
+            time: &time,
+            error: "",
+        };
+        let title = template::render(&settings.action_notification_title, &values, format!("Task: {}", task.name));
+        let body = template::render(&settings.action_notification_body, &values, message);
+
+        if let Some(task_id) = task.id {
+            self.notify_task(task_id, &title, &body, &settings.action_sound);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::launcher_backend::{BackendCall, MockBackend};
+    use crate::db::{AllowedUrlScheme, RepeatConfig, RepeatInterval};
+
+    fn browser_task(url: Option<&str>) -> Task {
+        let mut task = Task::new("Dashboard".to_string(), BrowserType::Chrome, Utc::now(), "UTC".to_string());
+        task.id = Some(1);
+        task.url = url.map(str::to_string);
+        task
     }
+
+    async fn run(backend: &MockBackend, task: &Task, action: ExecutionAction) -> Result<Option<LaunchedBrowser>> {
+        run_browser_action(backend, task, &action, &LaunchOptions::default(), false).await
+    }
+
+    #[tokio::test]
+    async fn test_browser_action_dispatch() {
+        let backend = MockBackend::default();
+        let task = browser_task(Some("https://example.com"));
+        run(&backend, &task, ExecutionAction::Open).await.unwrap();
+        run(&backend, &task, ExecutionAction::Close).await.unwrap();
+
+        let mut fresh = browser_task(Some("https://example.com"));
+        fresh.fresh_session = true;
+        run(&backend, &fresh, ExecutionAction::Close).await.unwrap();
+
+        let mut close_all = browser_task(None);
+        assert!(run(&backend, &close_all, ExecutionAction::Close).await.is_err());
+        close_all.allow_close_all = true;
+        run(&backend, &close_all, ExecutionAction::Close).await.unwrap();
+
+        let mut app = browser_task(Some("https://example.com"));
+        app.app_mode = true;
+        run(&backend, &app, ExecutionAction::Close).await.unwrap();
+
+        assert_eq!(
+            backend.calls(),
+            vec![
+                BackendCall::Open { browser: BrowserType::Chrome, url: Some("https://example.com".to_string()) },
+                BackendCall::CloseUrl { browser: BrowserType::Chrome, url: "https://example.com".to_string() },
+                BackendCall::CloseFreshSession(browser_launcher::fresh_session_dir(1)),
+                BackendCall::CloseAll(BrowserType::Chrome),
+                BackendCall::CloseApp { browser: BrowserType::Chrome, url: "https://example.com".to_string() },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_browser_action_reports_failed_open() {
+        let backend = MockBackend { fail_opens: Some("no display".to_string()), ..Default::default() };
+        let error = run(&backend, &browser_task(Some("https://example.com")), ExecutionAction::Open).await.unwrap_err();
+        assert_eq!(error.to_string(), AppError::Scheduler("no display".to_string()).to_string());
+    }
+
+    #[test]
+    fn test_early_exit_error_includes_output() {
+        let exit = ProcessExit { pid: 42, code: Some(1), output: "Missing X server or $DISPLAY".to_string() };
+        assert_eq!(
+            early_exit_error(&exit),
+            "Browser exited with code 1 right after launch: Missing X server or $DISPLAY"
+        );
+
+        let killed = ProcessExit { pid: 42, code: None, output: String::new() };
+        assert_eq!(early_exit_error(&killed), "Browser was killed by a signal right after launch");
+    }
+
+    /// An executor over a fresh database that launches through a mock backend
+    async fn mock_executor() -> (Arc<Database>, Arc<MockBackend>, TaskExecutor) {
+        let db = Arc::new(Database::in_memory().await.unwrap());
+        let backend = Arc::new(MockBackend::default());
+        let executor = TaskExecutor::with_launcher(Arc::clone(&db), None, backend.clone());
+        (db, backend, executor)
+    }
+
+    /// A browser task opening at `start` and closing half an hour later
+    async fn create_task(db: &Database, start: DateTime<Utc>, repeat: Option<RepeatInterval>) -> Task {
+        let mut task = Task::new("Standup".to_string(), BrowserType::Chrome, start, "UTC".to_string());
+        task.url = Some("https://example.com/standup".to_string());
+        task.close_time = Some(start + Duration::minutes(30));
+        task.repeat_config = repeat.map(|interval| RepeatConfig { interval, end_after: None, end_date: None });
+        db.create_task(task, AuditSource::Ui).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_execute_opens_and_closes_one_time_task() {
+        let (db, backend, executor) = mock_executor().await;
+        let start = Utc::now() - Duration::minutes(1);
+        let task = create_task(&db, start, None).await;
+        let id = task.id.unwrap();
+
+        executor.execute(task, ExecutionAction::Open, Some(start)).await.unwrap();
+        let task = db.get_task(id).await.unwrap();
+        assert_eq!(task.next_open_execution, None);
+        assert_eq!(task.next_close_execution, Some(start + Duration::minutes(30)));
+        assert_eq!(task.execution_count, 1);
+        assert_eq!(executor.opened_count(), 1);
+
+        executor.execute(task, ExecutionAction::Close, Some(start + Duration::minutes(30))).await.unwrap();
+        let task = db.get_task(id).await.unwrap();
+        assert_eq!(task.next_close_execution, None);
+        assert_eq!(task.status, TaskStatus::Completed);
+        assert_eq!(executor.opened_count(), 0);
+
+        let url = "https://example.com/standup".to_string();
+        assert_eq!(
+            backend.calls(),
+            vec![
+                BackendCall::Open { browser: BrowserType::Chrome, url: Some(url.clone()) },
+                BackendCall::CloseUrl { browser: BrowserType::Chrome, url },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_execute_moves_recurring_task_to_next_occurrence() {
+        let (db, backend, executor) = mock_executor().await;
+        let start = Utc::now() - Duration::minutes(1);
+        let task = create_task(&db, start, Some(RepeatInterval::Daily)).await;
+        let id = task.id.unwrap();
+
+        executor.execute(task, ExecutionAction::Open, Some(start)).await.unwrap();
+        let task = db.get_task(id).await.unwrap();
+        assert_eq!(task.next_open_execution, Some(start + Duration::days(1)));
+        assert_eq!(task.status, TaskStatus::Active);
+
+        // A manual run is recorded without touching the schedule
+        executor.execute(task.clone(), ExecutionAction::Open, None).await.unwrap();
+        assert_eq!(db.get_task(id).await.unwrap().next_open_execution, task.next_open_execution);
+        assert_eq!(backend.calls().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_recurring_block_window_stays_open_until_its_close() {
+        let (db, backend, executor) = mock_executor().await;
+        let start = Utc::now() - Duration::minutes(1);
+        let mut block = Task::new("Focus".to_string(), BrowserType::Chrome, start, "UTC".to_string());
+        block.kind = TaskKind::Block;
+        block.blocked_domains = vec!["example.com".to_string()];
+        block.close_time = Some(start + Duration::minutes(30));
+        block.repeat_config = Some(RepeatConfig { interval: RepeatInterval::Daily, end_after: None, end_date: None });
+        let block = db.create_task(block, AuditSource::Ui).await.unwrap();
+        let block_id = block.id.unwrap();
+
+        executor.execute(block, ExecutionAction::Open, Some(start)).await.unwrap();
+        let block = db.get_task(block_id).await.unwrap();
+        assert!(block.is_block_window_open());
+        assert_eq!(block.next_close_execution, Some(start + Duration::minutes(30)));
+        assert_eq!(block.next_open_execution, Some(start + Duration::days(1)));
+
+        // While the window is open, scheduled opens of blocked domains are skipped
+        let site = create_task(&db, start, None).await;
+        let site_id = site.id.unwrap();
+        executor.execute(site, ExecutionAction::Open, Some(start)).await.unwrap();
+        assert!(backend.calls().is_empty());
+        let site = db.get_task(site_id).await.unwrap();
+        assert_eq!(site.next_close_execution, None);
+        assert_eq!(site.status, TaskStatus::Completed);
+
+        executor.execute(block, ExecutionAction::Close, Some(start + Duration::minutes(30))).await.unwrap();
+        let block = db.get_task(block_id).await.unwrap();
+        assert!(!block.is_block_window_open());
+        assert_eq!(block.next_close_execution, None);
+        assert_eq!(block.next_open_execution, Some(start + Duration::days(1)));
+        assert_eq!(block.status, TaskStatus::Active);
+
+        // The next occurrence opens the window again with its own close
+        executor.execute(block, ExecutionAction::Open, Some(start + Duration::days(1))).await.unwrap();
+        let block = db.get_task(block_id).await.unwrap();
+        assert!(block.is_block_window_open());
+        assert_eq!(block.next_close_execution, Some(start + Duration::days(1) + Duration::minutes(30)));
+    }
+
+    #[tokio::test]
+    async fn test_execute_records_failed_open() {
+        let db = Arc::new(Database::in_memory().await.unwrap());
+        let backend = Arc::new(MockBackend { fail_opens: Some("no display".to_string()), ..Default::default() });
+        let executor = TaskExecutor::with_launcher(Arc::clone(&db), None, backend);
+        let start = Utc::now() - Duration::minutes(1);
+        let task = create_task(&db, start, None).await;
+        let id = task.id.unwrap();
+
+        assert!(executor.execute(task, ExecutionAction::Open, Some(start)).await.is_err());
+        let task = db.get_task(id).await.unwrap();
+        assert_eq!(task.consecutive_failures, 1);
+        assert_eq!(task.status, TaskStatus::Failed);
+        assert_eq!(executor.opened_count(), 0);
+
+        // Without an app handle the failure still reaches the notification center
+        let notifications = db.get_notifications(false).await.unwrap();
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(notifications[0].kind, NotificationKind::Failure);
+        assert_eq!(notifications[0].task_id, Some(id));
+    }
+
+    #[tokio::test]
+    async fn test_execute_skips_url_of_removed_scheme() {
+        let (db, backend, executor) = mock_executor().await;
+        let mut settings = db.get_settings().await.unwrap();
+        settings.allowed_url_schemes = vec![AllowedUrlScheme { scheme: "zoommtg".to_string(), confirm: false }];
+        db.update_settings(settings.clone()).await.unwrap();
+
+        let start = Utc::now() - Duration::minutes(1);
+        let mut task = Task::new("Call".to_string(), BrowserType::Chrome, start, "UTC".to_string());
+        task.url = Some("zoommtg://zoom.us/join?confno=1".to_string());
+        task.repeat_config = Some(RepeatConfig { interval: RepeatInterval::Daily, end_after: None, end_date: None });
+        let task = db.create_task(task, AuditSource::Ui).await.unwrap();
+        let id = task.id.unwrap();
+
+        settings.allowed_url_schemes.clear();
+        db.update_settings(settings).await.unwrap();
+
+        // The occurrence is skipped and the schedule moves on instead of coming due again
+        executor.execute(task, ExecutionAction::Open, Some(start)).await.unwrap();
+        let task = db.get_task(id).await.unwrap();
+        assert_eq!(task.next_open_execution, Some(start + Duration::days(1)));
+        assert!(backend.calls().is_empty());
+
+        // A manual run is refused outright
+        assert!(executor.execute(task, ExecutionAction::Open, None).await.is_err());
+    }
+
+
+    #[tokio::test]
+    async fn test_retried_open_keeps_its_occurrence() {
+        let db = Arc::new(Database::in_memory().await.unwrap());
+        let failing = Arc::new(MockBackend { fail_opens: Some("no display".to_string()), ..Default::default() });
+        let executor = TaskExecutor::with_launcher(Arc::clone(&db), None, failing);
+        let start = Utc::now() - Duration::minutes(1);
+        let mut task = create_task(&db, start, Some(RepeatInterval::Daily)).await;
+        task.retry_max_attempts = Some(2);
+        task.retry_backoff_secs = Some(60 * 60);
+        // Later occurrences have no close pending when their open fires
+        task.next_close_execution = None;
+        let task = db.update_task(task.id.unwrap(), task, false, AuditSource::Ui).await.unwrap();
+        let id = task.id.unwrap();
+
+        // A retry an hour later would come after the 30-minute window has closed
+        assert!(executor.execute(task, ExecutionAction::Open, Some(start)).await.is_err());
+        let mut task = db.get_task(id).await.unwrap();
+        assert_eq!(task.retry_attempt, 0);
+        assert_eq!(task.next_open_execution, Some(start + Duration::days(1)));
+
+        // A retry within the window fires as the original occurrence
+        task.retry_backoff_secs = Some(60);
+        task.next_open_execution = Some(start);
+        let task = db.update_task(id, task, false, AuditSource::Ui).await.unwrap();
+        let next_day = start + Duration::days(1);
+        assert!(executor.execute(task, ExecutionAction::Open, Some(start)).await.is_err());
+        let task = db.get_task(id).await.unwrap();
+        assert_eq!(task.retry_attempt, 1);
+        assert_eq!(task.pending_occurrence, Some(start));
+        let retry_at = task.next_open_execution.unwrap();
+
+        let executor = TaskExecutor::with_launcher(Arc::clone(&db), None, Arc::new(MockBackend::default()));
+        executor.execute(task, ExecutionAction::Open, Some(retry_at)).await.unwrap();
+        let task = db.get_task(id).await.unwrap();
+        assert_eq!(task.pending_occurrence, None);
+        assert_eq!(task.next_close_execution, Some(start + Duration::minutes(30)));
+        assert_eq!(task.next_open_execution, Some(next_day));
+
+        let executions = db.get_task_executions(id, 10, 0).await.unwrap();
+        assert_eq!(executions.items[0].occurrence, Some(start));
+        assert_eq!(executions.items[0].status, ExecutionStatus::Success);
+    }
+
 }
@@ -0,0 +1,87 @@
+//! Best-effort tab closing on Windows through UI Automation, for Chromium browsers that were not
+//! started with a DevTools port. Each tab of the browser's windows is selected in turn and the
+//! address bar read, so the user sees the tabs flick past; the tab that was active is selected
+//! again afterwards. Chromium's accessibility tree changes between versions, which is why this
+//! is opt-in through the `windows_ui_automation` setting.
+
+use std::os::windows::process::CommandExt;
+use std::process::Command;
+use crate::error::{AppError, Result};
+
+/// Keeps PowerShell from flashing a console window
+const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+/// The process name or ids and the URL come in through environment variables, so nothing from
+/// a task reaches the script text. Prints how many tabs were closed.
+const CLOSE_TABS_SCRIPT: &str = r#"
+Add-Type -AssemblyName UIAutomationClient
+Add-Type -AssemblyName UIAutomationTypes
+$A = [System.Windows.Automation.AutomationElement]
+$Scope = [System.Windows.Automation.TreeScope]
+$Type = [System.Windows.Automation.ControlType]
+function Normalize($url) { ($url.ToLower() -replace '^[a-z]+://', '' -replace '^www\.', '').TrimEnd('/') }
+function OfType($type) { New-Object System.Windows.Automation.PropertyCondition($A::ControlTypeProperty, $type) }
+
+$target = Normalize $env:DIT_UIA_URL
+if ($env:DIT_UIA_PIDS) {
+    $pids = @($env:DIT_UIA_PIDS -split ',' | ForEach-Object { [int]$_ })
+} else {
+    $pids = @(Get-Process -Name $env:DIT_UIA_PROCESS -ErrorAction SilentlyContinue | ForEach-Object { $_.Id })
+}
+$closed = 0
+foreach ($window in $A::RootElement.FindAll($Scope::Children, [System.Windows.Automation.Condition]::TrueCondition)) {
+    if ($pids -notcontains $window.Current.ProcessId) { continue }
+    $address = $window.FindFirst($Scope::Descendants, (OfType $Type::Edit))
+    if (-not $address) { continue }
+    $active = $null
+    $matching = @()
+    foreach ($tab in $window.FindAll($Scope::Descendants, (OfType $Type::TabItem))) {
+        try {
+            $selection = $tab.GetCurrentPattern([System.Windows.Automation.SelectionItemPattern]::Pattern)
+            if ($selection.Current.IsSelected) { $active = $tab }
+            $selection.Select()
+            Start-Sleep -Milliseconds 150
+            $value = $address.GetCurrentPattern([System.Windows.Automation.ValuePattern]::Pattern).Current.Value
+            if ((Normalize $value).StartsWith($target)) { $matching += $tab }
+        } catch { }
+    }
+    if ($active -and $matching -notcontains $active) {
+        try { $active.GetCurrentPattern([System.Windows.Automation.SelectionItemPattern]::Pattern).Select() } catch { }
+    }
+    foreach ($tab in $matching) {
+        try {
+            $button = $tab.FindFirst($Scope::Children, (OfType $Type::Button))
+            $button.GetCurrentPattern([System.Windows.Automation.InvokePattern]::Pattern).Invoke()
+            $closed++
+        } catch { }
+    }
+}
+[Console]::Out.Write($closed)
+"#;
+
+/// Closes the tabs showing `url` in the windows of the browser process `process_name`, e.g.
+/// `chrome.exe`, or only in those of the processes `pids` when given. Returns how many tabs
+/// were closed.
+pub fn close_tabs(process_name: &str, pids: Option<&[u32]>, url: &str) -> Result<usize> {
+    if pids.is_some_and(|pids| pids.is_empty()) {
+        return Ok(0);
+    }
+    let pids = pids.map(|pids| pids.iter().map(u32::to_string).collect::<Vec<_>>().join(","));
+
+    let output = Command::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command", CLOSE_TABS_SCRIPT])
+        .env("DIT_UIA_PROCESS", process_name.trim_end_matches(".exe"))
+        .env("DIT_UIA_PIDS", pids.unwrap_or_default())
+        .env("DIT_UIA_URL", url)
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .map_err(|e| AppError::Scheduler(format!("Failed to run UI Automation: {}", e)))?;
+
+    if !output.status.success() {
+        return Err(AppError::Scheduler(format!(
+            "UI Automation failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().parse().unwrap_or(0))
+}
@@ -0,0 +1,166 @@
+use std::sync::Arc;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use crate::db::{Database, NotificationKind};
+use crate::error::{AppError, Result};
+use crate::notifications;
+use crate::utils::curl;
+
+const LATEST_RELEASE_URL: &str = "https://api.github.com/repos/vcostin/do-in-time/releases/latest";
+
+/// How often the background check looks for a new version when enabled
+const AUTO_CHECK_INTERVAL_DAYS: i64 = 7;
+
+/// How often the background task wakes up to see whether a check is due
+const AUTO_CHECK_POLL: std::time::Duration = std::time::Duration::from_secs(6 * 60 * 60);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UpdateInfo {
+    pub current_version: String,
+    pub latest_version: String,
+    pub update_available: bool,
+    pub release_url: String,
+    pub checked_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize)]
+struct GithubRelease {
+    tag_name: String,
+    html_url: String,
+}
+
+/// Compares the running version with the latest GitHub release.
+pub async fn check_for_updates(db: &Database) -> Result<UpdateInfo> {
+    let release = tokio::task::spawn_blocking(fetch_latest_release)
+        .await
+        .map_err(|e| AppError::Scheduler(format!("Update check failed: {}", e)))??;
+
+    let current_version = env!("CARGO_PKG_VERSION").to_string();
+    let latest_version = release.tag_name.trim_start_matches('v').to_string();
+    let checked_at = Utc::now();
+
+    db.update_setting_value("last_update_check", &checked_at.to_rfc3339()).await?;
+
+    Ok(UpdateInfo {
+        update_available: is_newer(&latest_version, &current_version),
+        current_version,
+        latest_version,
+        release_url: release.html_url,
+        checked_at,
+    })
+}
+
+/// Checks for updates in the background once a week while `auto_check_updates` is enabled,
+/// recording a notification when a new version is out.
+pub fn spawn_auto_check(app_handle: AppHandle, db: Arc<Database>) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            if auto_check_due(&db).await {
+                match check_for_updates(&db).await {
+                    Ok(info) if info.update_available => {
+                        let _ = notifications::record(
+                            &app_handle,
+                            &db,
+                            NotificationKind::Info,
+                            None,
+                            &format!("Version {} is available", info.latest_version),
+                            &format!("You are running {}. Download: {}", info.current_version, info.release_url),
+                        )
+                        .await;
+                    }
+                    Ok(_) => {}
+                    Err(e) => eprintln!("Automatic update check failed: {}", e),
+                }
+            }
+
+            tokio::time::sleep(AUTO_CHECK_POLL).await;
+        }
+    });
+}
+
+async fn auto_check_due(db: &Database) -> bool {
+    let enabled = db
+        .get_settings()
+        .await
+        .map(|s| s.auto_check_updates)
+        .unwrap_or(false);
+    if !enabled {
+        return false;
+    }
+
+    let last_check = db
+        .get_setting_value("last_update_check")
+        .await
+        .ok()
+        .flatten()
+        .and_then(|s| s.parse::<DateTime<Utc>>().ok());
+
+    last_check.map_or(true, |at| Utc::now() - at >= Duration::days(AUTO_CHECK_INTERVAL_DAYS))
+}
+
+fn fetch_latest_release() -> Result<GithubRelease> {
+    let output = curl::command()
+        .args([
+            "--fail",
+            "--silent",
+            "--show-error",
+            "--location",
+            "--max-time",
+            "15",
+            "--header",
+            "Accept: application/vnd.github+json",
+            "--user-agent",
+            concat!("do-in-time/", env!("CARGO_PKG_VERSION")),
+            LATEST_RELEASE_URL,
+        ])
+        .output()?;
+
+    if !output.status.success() {
+        return Err(AppError::Scheduler(format!(
+            "Update check failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    serde_json::from_slice(&output.stdout)
+        .map_err(|e| AppError::Scheduler(format!("Unexpected release data: {}", e)))
+}
+
+/// Whether dotted version `candidate` is newer than `current`; pre-release suffixes are ignored.
+fn is_newer(candidate: &str, current: &str) -> bool {
+    fn parts(version: &str) -> Vec<u64> {
+        version
+            .split(['-', '+'])
+            .next()
+            .unwrap_or_default()
+            .split('.')
+            .map(|part| part.parse().unwrap_or(0))
+            .collect()
+    }
+
+    let (candidate, current) = (parts(candidate), parts(current));
+    for i in 0..candidate.len().max(current.len()) {
+        let (a, b) = (candidate.get(i).copied().unwrap_or(0), current.get(i).copied().unwrap_or(0));
+        if a != b {
+            return a > b;
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_newer() {
+        assert!(is_newer("0.2.0", "0.1.0"));
+        assert!(is_newer("1.0.0", "0.9.9"));
+        assert!(is_newer("0.1.10", "0.1.9"));
+        assert!(is_newer("0.1.1", "0.1"));
+        assert!(!is_newer("0.1.0", "0.1.0"));
+        assert!(!is_newer("0.1.0-beta", "0.1.0"));
+        assert!(!is_newer("0.0.9", "0.1.0"));
+    }
+}
@@ -0,0 +1,182 @@
+//! Automatic daily snapshots of the database, restored when the database fails its startup
+//! integrity check, and the snapshot taken before each schema migration.
+
+use std::path::{Path, PathBuf};
+use chrono::{Duration, Local, NaiveDateTime};
+use sqlx::sqlite::SqlitePool;
+use crate::error::Result;
+
+/// Directory next to the database holding the snapshots
+const BACKUP_DIR_NAME: &str = "backups";
+
+/// Snapshots kept; older ones are deleted after each new snapshot
+const KEEP_BACKUPS: usize = 7;
+
+/// Minimum age of the newest snapshot before another one is taken
+const BACKUP_INTERVAL_HOURS: i64 = 24;
+
+const NAME_PREFIX: &str = "data-";
+const NAME_SUFFIX: &str = ".db";
+const TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S";
+
+/// What startup did about a corrupt database, for telling the user.
+#[derive(Debug, Clone)]
+pub struct Recovery {
+    /// Snapshot the database was restored from
+    pub backup: PathBuf,
+    /// Where the corrupt database was moved
+    pub moved_aside: PathBuf,
+}
+
+pub fn backup_dir(db_path: &Path) -> PathBuf {
+    db_path.parent().unwrap_or(Path::new(".")).join(BACKUP_DIR_NAME)
+}
+
+fn backup_name(at: NaiveDateTime) -> String {
+    format!("{}{}{}", NAME_PREFIX, at.format(TIMESTAMP_FORMAT), NAME_SUFFIX)
+}
+
+/// When a snapshot file name says it was taken
+fn backup_time(name: &str) -> Option<NaiveDateTime> {
+    let timestamp = name.strip_prefix(NAME_PREFIX)?.strip_suffix(NAME_SUFFIX)?;
+    NaiveDateTime::parse_from_str(timestamp, TIMESTAMP_FORMAT).ok()
+}
+
+/// Snapshots in `dir`, newest first
+fn list_backups(dir: &Path) -> Vec<(NaiveDateTime, PathBuf)> {
+    let mut backups: Vec<(NaiveDateTime, PathBuf)> = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name();
+            backup_time(&name.to_string_lossy()).map(|at| (at, entry.path()))
+        })
+        .collect();
+    backups.sort_by(|a, b| b.0.cmp(&a.0));
+    backups
+}
+
+/// The newest snapshot of the database at `db_path`, if any.
+pub fn latest_backup(db_path: &Path) -> Option<PathBuf> {
+    list_backups(&backup_dir(db_path)).into_iter().next().map(|(_, path)| path)
+}
+
+/// Takes a snapshot unless the newest one is less than a day old, then prunes old snapshots.
+pub async fn create_automatic_backup(pool: &SqlitePool, db_path: &Path) -> Result<Option<PathBuf>> {
+    let dir = backup_dir(db_path);
+    let now = Local::now().naive_local();
+    if list_backups(&dir)
+        .first()
+        .is_some_and(|(at, _)| now - *at < Duration::hours(BACKUP_INTERVAL_HOURS))
+    {
+        return Ok(None);
+    }
+
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join(backup_name(now));
+    // VACUUM INTO writes a consistent snapshot even while other connections are active
+    sqlx::query("VACUUM INTO ?")
+        .bind(path.to_string_lossy().to_string())
+        .execute(pool)
+        .await?;
+
+    for (_, old) in list_backups(&dir).into_iter().skip(KEEP_BACKUPS) {
+        let _ = std::fs::remove_file(old);
+    }
+
+    Ok(Some(path))
+}
+
+/// Pre-migration snapshots kept next to the database
+const KEEP_PRE_MIGRATION: usize = 3;
+
+const PRE_MIGRATION_SUFFIX: &str = ".pre-migration";
+
+/// Copies the database to a timestamped `.pre-migration` file next to it before a schema
+/// migration. Returns `None` for a brand-new database, which has nothing to lose.
+pub async fn pre_migration_snapshot(pool: &SqlitePool, db_path: &Path) -> Result<Option<PathBuf>> {
+    let has_tables = sqlx::query("SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'tasks'")
+        .fetch_optional(pool)
+        .await?
+        .is_some();
+    if !has_tables {
+        return Ok(None);
+    }
+
+    let file_name = db_path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let path = db_path.with_file_name(format!(
+        "{}.{}{}",
+        file_name,
+        Local::now().format(TIMESTAMP_FORMAT),
+        PRE_MIGRATION_SUFFIX
+    ));
+    sqlx::query("VACUUM INTO ?")
+        .bind(path.to_string_lossy().to_string())
+        .execute(pool)
+        .await?;
+
+    // Timestamps sort chronologically, so the oldest snapshots come first
+    let dir = db_path.parent().unwrap_or(Path::new("."));
+    let mut snapshots: Vec<PathBuf> = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|p| {
+            p.file_name()
+                .map(|n| n.to_string_lossy())
+                .is_some_and(|n| n.starts_with(&file_name) && n.ends_with(PRE_MIGRATION_SUFFIX))
+        })
+        .collect();
+    snapshots.sort();
+    let excess = snapshots.len().saturating_sub(KEEP_PRE_MIGRATION);
+    for old in snapshots.into_iter().take(excess) {
+        let _ = std::fs::remove_file(old);
+    }
+
+    Ok(Some(path))
+}
+
+/// Puts a pre-migration snapshot back in place of a half-migrated database.
+pub fn restore_snapshot(db_path: &Path, snapshot: &Path) -> Result<()> {
+    for journal in ["db-wal", "db-shm", "db-journal"] {
+        let _ = std::fs::remove_file(db_path.with_extension(journal));
+    }
+    std::fs::copy(snapshot, db_path)?;
+    Ok(())
+}
+
+/// Moves the corrupt database and its journal files aside and copies `backup` in its place.
+/// Returns where the corrupt database was moved.
+pub fn restore(db_path: &Path, backup: &Path) -> Result<PathBuf> {
+    let suffix = format!("corrupt-{}", Local::now().format(TIMESTAMP_FORMAT));
+    let moved_aside = db_path.with_extension(format!("db.{}", suffix));
+    std::fs::rename(db_path, &moved_aside)?;
+
+    // Stale journal files would be replayed into the restored copy
+    for journal in ["db-wal", "db-shm", "db-journal"] {
+        let path = db_path.with_extension(journal);
+        if path.exists() {
+            let _ = std::fs::rename(&path, db_path.with_extension(format!("{}.{}", journal, suffix)));
+        }
+    }
+
+    std::fs::copy(backup, db_path)?;
+    Ok(moved_aside)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_backup_name_round_trip() {
+        let at = NaiveDate::from_ymd_opt(2024, 3, 9).unwrap().and_hms_opt(14, 5, 7).unwrap();
+        assert_eq!(backup_name(at), "data-20240309-140507.db");
+        assert_eq!(backup_time(&backup_name(at)), Some(at));
+        assert_eq!(backup_time("data.db"), None);
+        assert_eq!(backup_time("data-20240309-140507.db.corrupt-20240310-000000"), None);
+    }
+}
@@ -1,15 +1,108 @@
-use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions};
+use sqlx::sqlite::{
+    SqliteConnectOptions, SqliteConnection, SqliteJournalMode, SqlitePool, SqlitePoolOptions, SqliteSynchronous,
+};
+use sqlx::{Connection, Row};
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
-use crate::error::Result;
+use std::sync::RwLock;
+use std::time::Duration;
+use crate::db::backup::{self, Recovery};
+use crate::db::schema;
+use crate::error::{AppError, Result};
+
+const DB_FILE_NAME: &str = "data.db";
+
+/// File in the default data directory pointing at a relocated database directory
+const LOCATION_FILE_NAME: &str = "location";
+
+/// Command-line flag that keeps all app data next to the executable
+const PORTABLE_FLAG: &str = "--portable";
+
+/// Environment variable that enables portable mode when set to `1` or `true`
+const PORTABLE_ENV: &str = "DO_IN_TIME_PORTABLE";
+
+/// How long a write waits for another connection's write to finish before failing with
+/// "database is locked"
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Pool size used until the db_max_connections setting can be read
+const DEFAULT_POOL_CONNECTIONS: u32 = 5;
+
+/// Largest pool the db_max_connections setting may ask for
+pub const MAX_POOL_CONNECTIONS: u32 = 32;
 
 pub struct Database {
-    pool: SqlitePool,
+    // Swapped out when the database is moved to another directory
+    pool: RwLock<SqlitePool>,
+    /// Set when startup found the database corrupt and restored a backup
+    recovery: Option<Recovery>,
 }
 
 impl Database {
+    /// Opens the database, restoring the newest automatic backup if it is corrupt.
     pub async fn new() -> Result<Self> {
         let db_path = Self::get_db_path()?;
 
+        let (pool, recovery) = match Self::open_checked(&db_path).await {
+            Ok(pool) => (pool, None),
+            Err(AppError::Corrupt(reason)) => {
+                let Some(backup) = backup::latest_backup(&db_path) else {
+                    return Err(AppError::Corrupt(format!("{}; no backup to restore", reason)));
+                };
+                eprintln!("Database is corrupt ({}); restoring {}", reason, backup.display());
+                let moved_aside = backup::restore(&db_path, &backup)?;
+                (Self::open_checked(&db_path).await?, Some(Recovery { backup, moved_aside }))
+            }
+            Err(e) => return Err(e),
+        };
+
+        if let Err(e) = backup::create_automatic_backup(&pool, &db_path).await {
+            eprintln!("Failed to back up the database: {}", e);
+        }
+
+        Ok(Self {
+            pool: RwLock::new(pool),
+            recovery,
+        })
+    }
+
+    /// The backup restored at startup because the database was corrupt, if that happened.
+    pub fn recovery(&self) -> Option<&Recovery> {
+        self.recovery.as_ref()
+    }
+
+    /// Opens the pool and runs a quick integrity check, reporting damage as `AppError::Corrupt`.
+    async fn open_checked(db_path: &Path) -> Result<SqlitePool> {
+        let pool = Self::open_pool(db_path).await.map_err(|e| match e {
+            AppError::Database(ref err) if Self::is_corruption(err) => AppError::Corrupt(err.to_string()),
+            e => e,
+        })?;
+
+        let result: String = sqlx::query("PRAGMA quick_check")
+            .fetch_one(&pool)
+            .await
+            .map_err(|e| if Self::is_corruption(&e) { AppError::Corrupt(e.to_string()) } else { AppError::Database(e) })?
+            .get(0);
+        if result != "ok" {
+            pool.close().await;
+            return Err(AppError::Corrupt(result));
+        }
+
+        Ok(pool)
+    }
+
+    /// Whether SQLite reported a damaged file (SQLITE_CORRUPT or SQLITE_NOTADB)
+    fn is_corruption(error: &sqlx::Error) -> bool {
+        match error {
+            sqlx::Error::Database(e) => {
+                let message = e.message();
+                message.contains("malformed") || message.contains("not a database")
+            }
+            _ => false,
+        }
+    }
+
+    async fn open_pool(db_path: &Path) -> Result<SqlitePool> {
         // Ensure parent directory exists
         if let Some(parent) = db_path.parent() {
             std::fs::create_dir_all(parent)?;
@@ -17,26 +110,196 @@ impl Database {
 
         let connection_string = format!("sqlite://{}?mode=rwc", db_path.display());
 
+        // WAL lets the UI read while the scheduler writes; NORMAL sync is safe in WAL mode and
+        // only risks the last commits on power loss, not the file
         let options = SqliteConnectOptions::from_str(&connection_string)?
-            .create_if_missing(true);
+            .create_if_missing(true)
+            .journal_mode(SqliteJournalMode::Wal)
+            .synchronous(SqliteSynchronous::Normal)
+            .busy_timeout(BUSY_TIMEOUT);
 
         let pool = SqlitePoolOptions::new()
-            .max_connections(5)
+            .max_connections(Self::configured_pool_size(&options).await)
             .connect_with(options)
             .await?;
 
-        // Initialize schema
+        if !schema::needs_migration(&pool).await? {
+            schema::initialize_schema(&pool).await?;
+            return Ok(pool);
+        }
+
+        // A failed step must not leave a half-migrated database behind
+        let snapshot = backup::pre_migration_snapshot(&pool, db_path).await?;
+        if let Err(e) = schema::initialize_schema(&pool).await {
+            pool.close().await;
+            if let Some(snapshot) = &snapshot {
+                eprintln!("Migration failed ({}); restoring {}", e, snapshot.display());
+                backup::restore_snapshot(db_path, snapshot)?;
+            }
+            return Err(e);
+        }
+        schema::set_schema_version(&pool).await?;
+
+        Ok(pool)
+    }
+
+    /// Pool size from the db_max_connections setting, read over a connection of its own since
+    /// the pool has to be sized before it opens. The connection is read-only so a wrong path
+    /// is never created here; a new or damaged database gets the default.
+    async fn configured_pool_size(options: &SqliteConnectOptions) -> u32 {
+        let options = options.clone().read_only(true).create_if_missing(false);
+        let Ok(mut conn) = SqliteConnection::connect_with(&options).await else {
+            return DEFAULT_POOL_CONNECTIONS;
+        };
+        let size = sqlx::query_scalar::<_, String>("SELECT value FROM settings WHERE key = 'db_max_connections'")
+            .fetch_optional(&mut conn)
+            .await
+            .ok()
+            .flatten()
+            .and_then(|value| value.parse::<u32>().ok())
+            .filter(|size| (1..=MAX_POOL_CONNECTIONS).contains(size));
+        let _ = conn.close().await;
+
+        size.unwrap_or(DEFAULT_POOL_CONNECTIONS)
+    }
+
+    /// A private in-memory database with the full schema, for tests.
+    #[cfg(test)]
+    pub(crate) async fn in_memory() -> Result<Self> {
+        // Each in-memory connection is its own database, so the pool keeps exactly one
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await?;
         crate::db::schema::initialize_schema(&pool).await?;
 
-        Ok(Self { pool })
+        Ok(Self {
+            pool: RwLock::new(pool),
+            recovery: None,
+        })
     }
 
-    pub fn pool(&self) -> &SqlitePool {
-        &self.pool
+    pub fn pool(&self) -> SqlitePool {
+        self.pool
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Copies the database into `new_dir`, verifies the copy and switches to it.
+    ///
+    /// The old file is left in place so nothing is lost if the new location turns out to be
+    /// unreliable. Returns the path of the new database file.
+    pub async fn move_to(&self, new_dir: &Path) -> Result<PathBuf> {
+        let current_path = Self::get_db_path()?;
+        let new_path = new_dir.join(DB_FILE_NAME);
+
+        if new_path == current_path {
+            return Err(AppError::InvalidTask("The database is already in that directory".to_string()));
+        }
+        if new_path.exists() {
+            return Err(AppError::InvalidTask(format!(
+                "{} already exists; refusing to overwrite it",
+                new_path.display()
+            )));
+        }
+        std::fs::create_dir_all(new_dir)?;
+
+        // VACUUM INTO writes a consistent snapshot even while other connections are active
+        sqlx::query("VACUUM INTO ?")
+            .bind(new_path.to_string_lossy().to_string())
+            .execute(&self.pool())
+            .await?;
+
+        let new_pool = Self::open_pool(&new_path).await?;
+        let integrity: String = sqlx::query("PRAGMA integrity_check")
+            .fetch_one(&new_pool)
+            .await?
+            .get(0);
+        if integrity != "ok" {
+            new_pool.close().await;
+            let _ = std::fs::remove_file(&new_path);
+            return Err(AppError::Io(std::io::Error::other(format!(
+                "Integrity check of the copied database failed: {}",
+                integrity
+            ))));
+        }
+
+        std::fs::write(
+            Self::default_data_dir()?.join(LOCATION_FILE_NAME),
+            new_dir.to_string_lossy().as_bytes(),
+        )?;
+
+        let old_pool = std::mem::replace(
+            &mut *self.pool.write().unwrap_or_else(|e| e.into_inner()),
+            new_pool,
+        );
+        old_pool.close().await;
+
+        Ok(new_path)
+    }
+
+    /// Directory holding the database and other app data such as reports.
+    pub fn data_dir() -> Result<std::path::PathBuf> {
+        let db_path = Self::get_db_path()?;
+        Ok(db_path
+            .parent()
+            .map(|p| p.to_path_buf())
+            .unwrap_or_else(|| std::path::PathBuf::from(".")))
+    }
+
+    /// Browser profile directory the app creates and owns for a task with a managed profile.
+    pub fn managed_profile_dir(task_id: i64) -> Result<std::path::PathBuf> {
+        Ok(Self::data_dir()?.join("profiles").join(format!("task-{}", task_id)))
+    }
+
+    /// Whether the app runs in portable mode, keeping its data next to the executable so it can
+    /// run from a USB stick or a synced folder.
+    pub fn is_portable() -> bool {
+        std::env::args().any(|arg| arg == PORTABLE_FLAG)
+            || std::env::var(PORTABLE_ENV).is_ok_and(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+    }
+
+    /// Directory the portable data folder is created in: next to the executable, or next to
+    /// the app bundle on macOS.
+    fn portable_base_dir() -> Result<std::path::PathBuf> {
+        let exe = std::env::current_exe()?;
+        let exe_dir = exe
+            .parent()
+            .map(|p| p.to_path_buf())
+            .unwrap_or_else(|| std::path::PathBuf::from("."));
+
+        let bundle = exe_dir
+            .ancestors()
+            .find(|dir| dir.extension().is_some_and(|ext| ext == "app"));
+
+        Ok(match bundle.and_then(|b| b.parent()) {
+            Some(dir) => dir.to_path_buf(),
+            None => exe_dir,
+        })
+    }
+
+    /// Path of the database file, following a relocation made by `move_to` if there is one.
+    pub fn get_db_path() -> Result<std::path::PathBuf> {
+        let default_dir = Self::default_data_dir()?;
+
+        let relocated = std::fs::read_to_string(default_dir.join(LOCATION_FILE_NAME))
+            .ok()
+            .map(|dir| dir.trim().to_string())
+            .filter(|dir| !dir.is_empty());
+
+        Ok(match relocated {
+            Some(dir) => PathBuf::from(dir).join(DB_FILE_NAME),
+            None => default_dir.join(DB_FILE_NAME),
+        })
     }
 
-    fn get_db_path() -> Result<std::path::PathBuf> {
-        let data_dir = if cfg!(target_os = "windows") {
+    /// App data directory before any relocation: next to the executable in portable mode,
+    /// otherwise the OS data directory.
+    fn default_data_dir() -> Result<std::path::PathBuf> {
+        let data_dir = if Self::is_portable() {
+            Self::portable_base_dir()?
+        } else if cfg!(target_os = "windows") {
             std::env::var("APPDATA")
                 .map(std::path::PathBuf::from)
                 .unwrap_or_else(|_| std::path::PathBuf::from("."))
@@ -50,6 +313,6 @@ impl Database {
                 .unwrap_or_else(|| std::path::PathBuf::from("."))
         };
 
-        Ok(data_dir.join("do-in-time").join("data.db"))
+        Ok(data_dir.join("do-in-time"))
     }
 }
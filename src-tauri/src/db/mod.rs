@@ -1,3 +1,4 @@
+pub mod backup;
 pub mod models;
 pub mod schema;
 pub mod connection;
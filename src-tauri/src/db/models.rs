@@ -1,6 +1,13 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc, Weekday};
 use serde::{Deserialize, Serialize};
 
+/// Seconds before the first retry of a failed occurrence when a task sets no backoff
+pub const DEFAULT_RETRY_BACKOFF_SECS: u32 = 60;
+/// Longest wait before any retry, however far the backoff has doubled: one week
+const MAX_RETRY_DELAY_SECS: i64 = 7 * 86_400;
+/// Longest launch stagger in seconds; the scheduler loop waits it out before anything else runs
+pub const MAX_LAUNCH_STAGGER_SECS: u32 = 60;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Task {
     pub id: Option<i64>,
@@ -15,10 +22,130 @@ pub struct Task {
     pub timezone: String,
     pub repeat_config: Option<RepeatConfig>,
     #[serde(default)]
+    pub pomodoro: Option<PomodoroConfig>,
+    #[serde(default)]
     pub execution_count: i32,
     pub status: TaskStatus,
     pub next_open_execution: Option<DateTime<Utc>>,
     pub next_close_execution: Option<DateTime<Utc>>,
+    /// Failed scheduled executions in a row; reset by a success
+    #[serde(default)]
+    pub consecutive_failures: i32,
+    #[serde(default)]
+    pub last_success_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub kind: TaskKind,
+    /// Domains a block task keeps the scheduler from opening during its window
+    #[serde(default)]
+    pub blocked_domains: Vec<String>,
+    /// Current work cycle of a running pomodoro session; 0 when no session is running
+    #[serde(default)]
+    pub pomodoro_cycle: i32,
+    /// For reminders, minimize scheduler-opened browsers until the break ends
+    #[serde(default)]
+    pub minimize_browsers: bool,
+    /// Ordered steps run in place of a single open; stored in the task_steps table
+    #[serde(default)]
+    pub steps: Vec<TaskStep>,
+    /// Index of the next step of a multi-step task that is waiting between steps; 0 otherwise
+    #[serde(default)]
+    pub current_step: i32,
+    /// Workspace the task belongs to; tasks without one run in every workspace
+    #[serde(default)]
+    pub workspace_id: Option<i64>,
+    /// Labels grouping the task, like "work" or "standup"; stored in the task_tags table
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Dates a recurring task does not run on, like holidays or a vacation; stored in the
+    /// task_exclusions table
+    #[serde(default)]
+    pub exclusions: Vec<DateExclusion>,
+    /// Keep the URL's query string, e.g. an access token, in the OS keyring instead of the
+    /// database; the URL is returned without it
+    #[serde(default)]
+    pub secret_url_params: bool,
+    /// Keyring key of the URL's secret query string
+    #[serde(skip)]
+    pub url_secret_ref: Option<String>,
+    /// Snapshot a save_session task writes or a restore_session task opens
+    #[serde(default)]
+    pub session_name: Option<String>,
+    /// Monitor the task's browser windows are placed on, as an index into `get_displays`;
+    /// None leaves placement to the browser
+    #[serde(default)]
+    pub target_display: Option<u32>,
+    /// Window state the task's browser windows open in
+    #[serde(default)]
+    pub window_state: WindowState,
+    /// Where the task's browser windows open; None leaves it to the browser or target_display
+    #[serde(default)]
+    pub window_position: Option<WindowPosition>,
+    /// Size the task's browser windows open at; None leaves it to the browser
+    #[serde(default)]
+    pub window_size: Option<WindowSize>,
+    /// Open in kiosk mode: fullscreen without tabs or toolbars, e.g. for a dashboard on a wall
+    /// screen. Only takes effect when the open starts the browser
+    #[serde(default)]
+    pub kiosk: bool,
+    /// Open the URL as an app: a window of its own without tabs or toolbars, which closing the
+    /// task targets instead of tabs. Chromium-based browsers only
+    #[serde(default)]
+    pub app_mode: bool,
+    /// Scale of the task's browser windows, e.g. 1.5 for 150%; None keeps the browser's own
+    #[serde(default)]
+    pub zoom: Option<f64>,
+    /// User-agent string Chromium-based browsers present instead of their own
+    #[serde(default)]
+    pub user_agent: Option<String>,
+    /// UI and Accept-Language locale for Chromium-based browsers, e.g. "de" or "pt-BR"
+    #[serde(default)]
+    pub lang: Option<String>,
+    /// Open Chromium-based browsers on a throwaway profile with no cookies or history, deleted
+    /// again on close
+    #[serde(default)]
+    pub fresh_session: bool,
+    /// Open Chromium-based browsers on a persistent profile the app creates for this task,
+    /// keeping the site's cookies apart from the user's profiles
+    #[serde(default)]
+    pub managed_profile: bool,
+    /// Start the browser this many minutes before each open, without a URL, so the open itself
+    /// is fast
+    #[serde(default)]
+    pub prelaunch_minutes: Option<u32>,
+    /// Move each open up to this many minutes earlier or later at random, so machines running
+    /// the same schedule don't all hit a site at the same second; the close moves with it
+    #[serde(default)]
+    pub jitter_minutes: Option<u32>,
+    /// How this task's missed occurrences are handled; None follows the missed_policy setting
+    #[serde(default)]
+    pub missed_policy: Option<MissedPolicy>,
+    /// Times a scheduled open or close is tried before the task counts as failed, including the
+    /// first; None tries once
+    #[serde(default)]
+    pub retry_max_attempts: Option<u32>,
+    /// Seconds before the first retry, doubling for each one after; one minute when unset
+    #[serde(default)]
+    pub retry_backoff_secs: Option<u32>,
+    /// Failed tries of the pending occurrence so far; 0 when it has not failed
+    #[serde(default)]
+    pub retry_attempt: i32,
+    /// Scheduled time of an open that was deferred or is waiting for a retry. The open still
+    /// fires as this occurrence, so its close and the next occurrence keep to the schedule.
+    #[serde(default)]
+    pub pending_occurrence: Option<DateTime<Utc>>,
+    /// Close this many minutes after each open instead of at a fixed close_time. The close is
+    /// scheduled when the open runs, so late, snoozed and retried opens still get the full time
+    #[serde(default)]
+    pub duration_minutes: Option<u32>,
+    /// Free-form notes about the task, included in search
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Backend that opens and closes the browser
+    #[serde(default)]
+    pub launcher: LauncherKind,
+    /// Last modification time, used as the optimistic concurrency token on updates.
+    #[serde(default)]
+    pub updated_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -30,6 +157,32 @@ pub enum BrowserType {
     Safari,
     Brave,
     Opera,
+    Vivaldi,
+    Chromium,
+    /// macOS only
+    Arc,
+    Zen,
+}
+
+impl BrowserType {
+    /// Whether the browser takes Chromium command-line switches
+    pub fn is_chromium(&self) -> bool {
+        matches!(
+            self,
+            BrowserType::Chrome
+                | BrowserType::Edge
+                | BrowserType::Brave
+                | BrowserType::Opera
+                | BrowserType::Vivaldi
+                | BrowserType::Chromium
+                | BrowserType::Arc
+        )
+    }
+
+    /// Whether the browser is built on Firefox and takes its command-line switches
+    pub fn is_firefox(&self) -> bool {
+        matches!(self, BrowserType::Firefox | BrowserType::Zen)
+    }
 }
 
 impl std::fmt::Display for BrowserType {
@@ -41,6 +194,10 @@ impl std::fmt::Display for BrowserType {
             BrowserType::Safari => "safari",
             BrowserType::Brave => "brave",
             BrowserType::Opera => "opera",
+            BrowserType::Vivaldi => "vivaldi",
+            BrowserType::Chromium => "chromium",
+            BrowserType::Arc => "arc",
+            BrowserType::Zen => "zen",
         };
         write!(f, "{}", s)
     }
@@ -57,25 +214,249 @@ impl std::str::FromStr for BrowserType {
             "safari" => Ok(BrowserType::Safari),
             "brave" => Ok(BrowserType::Brave),
             "opera" => Ok(BrowserType::Opera),
+            "vivaldi" => Ok(BrowserType::Vivaldi),
+            "chromium" => Ok(BrowserType::Chromium),
+            "arc" => Ok(BrowserType::Arc),
+            "zen" => Ok(BrowserType::Zen),
             _ => Err(format!("Unknown browser type: {}", s)),
         }
     }
 }
 
+/// What a task does when its schedule fires.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskKind {
+    /// Opens and closes a browser
+    #[default]
+    Browser,
+    /// Focus mode: between open and close, blocks the scheduler from opening listed domains
+    Block,
+    /// Break reminder: only shows a notification, optionally minimizing browsers until close
+    Reminder,
+    /// Saves the tabs open in the task's browser as the named session snapshot
+    SaveSession,
+    /// Opens the tabs of the named session snapshot, closing them again at close time
+    RestoreSession,
+}
+
+impl std::fmt::Display for TaskKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            TaskKind::Browser => "browser",
+            TaskKind::Block => "block",
+            TaskKind::Reminder => "reminder",
+            TaskKind::SaveSession => "save_session",
+            TaskKind::RestoreSession => "restore_session",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl std::str::FromStr for TaskKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "browser" => Ok(TaskKind::Browser),
+            "block" => Ok(TaskKind::Block),
+            "reminder" => Ok(TaskKind::Reminder),
+            "save_session" => Ok(TaskKind::SaveSession),
+            "restore_session" => Ok(TaskKind::RestoreSession),
+            _ => Err(format!("Unknown task kind: {}", s)),
+        }
+    }
+}
+
+/// How a task's browser window opens.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum WindowState {
+    /// Wherever and however large the browser opens it
+    #[default]
+    Normal,
+    Maximized,
+    Fullscreen,
+    /// Minimized right after the launch, e.g. for a background dashboard
+    Minimized,
+}
+
+impl std::fmt::Display for WindowState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            WindowState::Normal => "normal",
+            WindowState::Maximized => "maximized",
+            WindowState::Fullscreen => "fullscreen",
+            WindowState::Minimized => "minimized",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl std::str::FromStr for WindowState {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "normal" => Ok(WindowState::Normal),
+            "maximized" => Ok(WindowState::Maximized),
+            "fullscreen" => Ok(WindowState::Fullscreen),
+            "minimized" => Ok(WindowState::Minimized),
+            _ => Err(format!("Unknown window state: {}", s)),
+        }
+    }
+}
+
+/// Top-left corner of a task's browser window in physical pixels, relative to its
+/// target_display or, without one, to the primary monitor. Stored as `x,y`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct WindowPosition {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl std::fmt::Display for WindowPosition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{},{}", self.x, self.y)
+    }
+}
+
+impl std::str::FromStr for WindowPosition {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid window position: {}", s);
+        let (x, y) = s.split_once(',').ok_or_else(invalid)?;
+        Ok(WindowPosition {
+            x: x.trim().parse().map_err(|_| invalid())?,
+            y: y.trim().parse().map_err(|_| invalid())?,
+        })
+    }
+}
+
+/// Size of a task's browser window in physical pixels. Stored as `width,height`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct WindowSize {
+    pub width: u32,
+    pub height: u32,
+}
+
+impl std::fmt::Display for WindowSize {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{},{}", self.width, self.height)
+    }
+}
+
+impl std::str::FromStr for WindowSize {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid window size: {}", s);
+        let (width, height) = s.split_once(',').ok_or_else(invalid)?;
+        Ok(WindowSize {
+            width: width.trim().parse().map_err(|_| invalid())?,
+            height: height.trim().parse().map_err(|_| invalid())?,
+        })
+    }
+}
+
+/// Which backend opens and closes a task's browser.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum LauncherKind {
+    /// The browser's own command line, and AppleScript or process signals to close it
+    #[default]
+    Native,
+    /// The DevTools endpoint set in the settings, falling back to the native launcher while it
+    /// is unreachable
+    Devtools,
+}
+
+impl std::fmt::Display for LauncherKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            LauncherKind::Native => "native",
+            LauncherKind::Devtools => "devtools",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl std::str::FromStr for LauncherKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "native" => Ok(LauncherKind::Native),
+            "devtools" => Ok(LauncherKind::Devtools),
+            _ => Err(format!("Unknown launcher: {}", s)),
+        }
+    }
+}
+
+/// One step of a multi-step task.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TaskStep {
+    pub action: StepAction,
+    /// Page to open or close; unused by wait steps
+    #[serde(default)]
+    pub url: Option<String>,
+    /// How long a wait step pauses the sequence
+    #[serde(default)]
+    pub wait_minutes: Option<i32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum StepAction {
+    Open,
+    Close,
+    Wait,
+}
+
+impl std::fmt::Display for StepAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            StepAction::Open => "open",
+            StepAction::Close => "close",
+            StepAction::Wait => "wait",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl std::str::FromStr for StepAction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "open" => Ok(StepAction::Open),
+            "close" => Ok(StepAction::Close),
+            "wait" => Ok(StepAction::Wait),
+            _ => Err(format!("Unknown step action: {}", s)),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum TaskStatus {
     Active,
+    Paused,
     Completed,
     Failed,
+    /// Turned off automatically after too many consecutive failures
+    Disabled,
 }
 
 impl std::fmt::Display for TaskStatus {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let s = match self {
             TaskStatus::Active => "active",
+            TaskStatus::Paused => "paused",
             TaskStatus::Completed => "completed",
             TaskStatus::Failed => "failed",
+            TaskStatus::Disabled => "disabled",
         };
         write!(f, "{}", s)
     }
@@ -87,36 +468,79 @@ impl std::str::FromStr for TaskStatus {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s.to_lowercase().as_str() {
             "active" => Ok(TaskStatus::Active),
+            "paused" => Ok(TaskStatus::Paused),
             "completed" => Ok(TaskStatus::Completed),
             "failed" => Ok(TaskStatus::Failed),
+            "disabled" => Ok(TaskStatus::Disabled),
             _ => Err(format!("Unknown task status: {}", s)),
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct RepeatConfig {
     pub interval: RepeatInterval,
     pub end_after: Option<i32>,
     pub end_date: Option<DateTime<Utc>>,
 }
 
+/// Work/break cadence: each scheduled start runs `cycles` rounds of opening for
+/// `work_minutes` and closing for `break_minutes`.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-#[serde(rename_all = "lowercase")]
+pub struct PomodoroConfig {
+    pub work_minutes: i32,
+    pub break_minutes: i32,
+    pub cycles: i32,
+}
+
+/// How often a task repeats. Custom intervals serialize as e.g. `{"every_n_minutes": 120}`
+/// and are stored as `every_n_minutes:120`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
 pub enum RepeatInterval {
+    Hourly,
     Daily,
     Weekly,
     Monthly,
+    EveryNMinutes(u32),
+    EveryNDays(u32),
+    /// The `week`th `weekday` of every month, e.g. its second Tuesday; week 5 is the last.
+    /// Serializes as `{"monthly_by_weekday": {"week": 2, "weekday": "Tue"}}` and is stored as
+    /// `monthly_by_weekday:2:tue`.
+    MonthlyByWeekday { week: u8, weekday: Weekday },
 }
 
+/// Week number of `RepeatInterval::MonthlyByWeekday` that means the last week of the month
+pub const LAST_WEEK_OF_MONTH: u8 = 5;
+
 impl std::fmt::Display for RepeatInterval {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let s = match self {
-            RepeatInterval::Daily => "daily",
-            RepeatInterval::Weekly => "weekly",
-            RepeatInterval::Monthly => "monthly",
-        };
-        write!(f, "{}", s)
+        match self {
+            RepeatInterval::Hourly => write!(f, "hourly"),
+            RepeatInterval::Daily => write!(f, "daily"),
+            RepeatInterval::Weekly => write!(f, "weekly"),
+            RepeatInterval::Monthly => write!(f, "monthly"),
+            RepeatInterval::EveryNMinutes(n) => write!(f, "every_n_minutes:{}", n),
+            RepeatInterval::EveryNDays(n) => write!(f, "every_n_days:{}", n),
+            RepeatInterval::MonthlyByWeekday { week, weekday } => {
+                write!(f, "monthly_by_weekday:{}:{}", week, weekday.to_string().to_lowercase())
+            }
+        }
+    }
+}
+
+impl RepeatInterval {
+    /// Shortest time between two occurrences, e.g. 28 days for monthly repeats
+    pub fn min_gap(&self) -> chrono::Duration {
+        match self {
+            RepeatInterval::Hourly => chrono::Duration::hours(1),
+            RepeatInterval::Daily => chrono::Duration::days(1),
+            RepeatInterval::Weekly => chrono::Duration::weeks(1),
+            RepeatInterval::Monthly => chrono::Duration::days(28),
+            RepeatInterval::EveryNMinutes(n) => chrono::Duration::minutes(*n as i64),
+            RepeatInterval::EveryNDays(n) => chrono::Duration::days(*n as i64),
+            RepeatInterval::MonthlyByWeekday { .. } => chrono::Duration::days(28),
+        }
     }
 }
 
@@ -124,17 +548,33 @@ impl std::str::FromStr for RepeatInterval {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s.to_lowercase().as_str() {
-            "daily" => Ok(RepeatInterval::Daily),
-            "weekly" => Ok(RepeatInterval::Weekly),
-            "monthly" => Ok(RepeatInterval::Monthly),
-            _ => Err(format!("Unknown repeat interval: {}", s)),
+        let lower = s.to_lowercase();
+        let count = |value: &str| value.trim().parse::<u32>().map_err(|_| format!("Unknown repeat interval: {}", s));
+        match lower.split_once(':') {
+            Some(("every_n_minutes", n)) => Ok(RepeatInterval::EveryNMinutes(count(n)?)),
+            Some(("every_n_days", n)) => Ok(RepeatInterval::EveryNDays(count(n)?)),
+            Some(("monthly_by_weekday", rest)) => {
+                let (week, weekday) = rest.split_once(':').ok_or_else(|| format!("Unknown repeat interval: {}", s))?;
+                Ok(RepeatInterval::MonthlyByWeekday {
+                    week: week.trim().parse().map_err(|_| format!("Unknown repeat interval: {}", s))?,
+                    weekday: weekday.trim().parse().map_err(|_| format!("Unknown repeat interval: {}", s))?,
+                })
+            }
+            Some(_) => Err(format!("Unknown repeat interval: {}", s)),
+            None => match lower.as_str() {
+                "hourly" => Ok(RepeatInterval::Hourly),
+                "daily" => Ok(RepeatInterval::Daily),
+                "weekly" => Ok(RepeatInterval::Weekly),
+                "monthly" => Ok(RepeatInterval::Monthly),
+                _ => Err(format!("Unknown repeat interval: {}", s)),
+            },
         }
     }
 }
 
-/// Action type used internally by the scheduler to determine what to execute.
-#[derive(Debug, Clone, PartialEq)]
+/// Action type used by the scheduler to determine what to execute.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
 pub enum ExecutionAction {
     Open,
     Close,
@@ -162,39 +602,1166 @@ impl std::str::FromStr for ExecutionAction {
     }
 }
 
-impl Task {
-    #[allow(dead_code)]
-    pub fn new(
-        name: String,
-        browser: BrowserType,
-        start_time: DateTime<Utc>,
-        timezone: String,
-    ) -> Self {
-        Self {
-            id: None,
-            name,
-            browser,
-            browser_profile: None,
-            url: None,
-            allow_close_all: false,
-            start_time,
-            close_time: None,
-            timezone,
-            repeat_config: None,
-            execution_count: 0,
-            status: TaskStatus::Active,
-            next_open_execution: Some(start_time),
-            next_close_execution: None,
+/// Outcome of a single execution recorded in `task_executions`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ExecutionStatus {
+    Running,
+    Success,
+    Failed,
+    /// Deliberately not carried out, e.g. blocked by focus mode
+    Skipped,
+    /// Recorded in dry-run mode instead of actually running
+    Simulated,
+}
+
+impl std::fmt::Display for ExecutionStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            ExecutionStatus::Running => "running",
+            ExecutionStatus::Success => "success",
+            ExecutionStatus::Failed => "failed",
+            ExecutionStatus::Skipped => "skipped",
+            ExecutionStatus::Simulated => "simulated",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl std::str::FromStr for ExecutionStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "running" => Ok(ExecutionStatus::Running),
+            "success" => Ok(ExecutionStatus::Success),
+            "failed" => Ok(ExecutionStatus::Failed),
+            "skipped" => Ok(ExecutionStatus::Skipped),
+            "simulated" => Ok(ExecutionStatus::Simulated),
+            _ => Err(format!("Unknown execution status: {}", s)),
         }
     }
 }
 
+/// A single recorded execution of a task action.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct AppSettings {
-    pub minimize_to_tray: bool,
-    pub start_minimized: bool,
-    pub show_notifications: bool,
-    pub auto_start: bool,
+pub struct TaskExecution {
+    pub id: i64,
+    pub task_id: i64,
+    pub action: ExecutionAction,
+    /// Scheduled time that was fired, or `None` for manual runs
+    pub occurrence: Option<DateTime<Utc>>,
+    pub status: ExecutionStatus,
+    pub error: Option<String>,
+    pub executed_at: DateTime<Utc>,
+    /// For opens, how long the page stayed open until the matching close
+    pub duration_secs: Option<i64>,
+    /// Browser process the open started, once it has exited
+    #[serde(default)]
+    pub pid: Option<i64>,
+    /// Exit code of that process; `None` while running or when killed by a signal
+    #[serde(default)]
+    pub exit_code: Option<i32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum NotificationKind {
+    Failure,
+    Missed,
+    Upcoming,
+    Reminder,
+    Info,
+}
+
+impl std::fmt::Display for NotificationKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            NotificationKind::Failure => "failure",
+            NotificationKind::Missed => "missed",
+            NotificationKind::Upcoming => "upcoming",
+            NotificationKind::Reminder => "reminder",
+            NotificationKind::Info => "info",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl std::str::FromStr for NotificationKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "failure" => Ok(NotificationKind::Failure),
+            "missed" => Ok(NotificationKind::Missed),
+            "upcoming" => Ok(NotificationKind::Upcoming),
+            "reminder" => Ok(NotificationKind::Reminder),
+            "info" => Ok(NotificationKind::Info),
+            _ => Err(format!("Unknown notification kind: {}", s)),
+        }
+    }
+}
+
+/// An entry in the in-app notification center.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppNotification {
+    pub id: i64,
+    pub kind: NotificationKind,
+    pub task_id: Option<i64>,
+    pub title: String,
+    pub body: String,
+    pub read: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A tab currently open in a browser.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct OpenTab {
+    pub browser: BrowserType,
+    pub title: String,
+    pub url: String,
+}
+
+/// A profile of an installed browser, to pick a task's profile from.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BrowserProfile {
+    /// Name shown in the browser's profile picker
+    pub name: String,
+    /// Profile directory, relative to the browser's data directory
+    pub directory: String,
+    /// Value for the task's browser_profile: the directory for Chromium browsers, the name for Firefox
+    pub browser_profile: String,
+}
+
+/// A link dropped on or pasted into the app, to pre-fill a new task.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LinkDraft {
+    pub url: String,
+    /// Suggested task name: the shortcut's file name, or the link's host
+    pub name: String,
+}
+
+/// A day, or an inclusive range of days, on which a recurring task skips its occurrences.
+/// Days are calendar dates in the task's timezone.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DateExclusion {
+    pub start: NaiveDate,
+    /// Last excluded day; `None` excludes `start` only
+    #[serde(default)]
+    pub end: Option<NaiveDate>,
+}
+
+impl DateExclusion {
+    /// Whether `date` falls into this exclusion
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.start <= date && date <= self.end.unwrap_or(self.start)
+    }
+}
+
+/// A tag and the number of tasks carrying it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TagCount {
+    pub tag: String,
+    pub task_count: i64,
+}
+
+/// A calendar event that was not imported as a task, and why.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SkippedEvent {
+    pub summary: String,
+    pub reason: String,
+}
+
+/// Tabs saved by a save_session task, to be reopened by restore_session tasks.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionSnapshot {
+    pub name: String,
+    pub browser: BrowserType,
+    pub browser_profile: Option<String>,
+    pub tabs: Vec<OpenTab>,
+    pub saved_at: DateTime<Utc>,
+}
+
+/// Column a page of the task list is ordered by.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskSort {
+    #[default]
+    StartTime,
+    Name,
+    NextAction,
+    Status,
+    UpdatedAt,
+}
+
+impl TaskSort {
+    pub fn column(&self) -> &'static str {
+        match self {
+            TaskSort::StartTime => "start_time",
+            TaskSort::Name => "name COLLATE NOCASE",
+            TaskSort::NextAction => "next_action_time",
+            TaskSort::Status => "status",
+            TaskSort::UpdatedAt => "updated_at",
+        }
+    }
+}
+
+/// One page of the task list and the number of tasks across all pages.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskPage {
+    pub items: Vec<Task>,
+    pub total: i64,
+}
+
+/// One page of a task's execution history and the number of executions across all pages.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExecutionPage {
+    pub items: Vec<TaskExecution>,
+    pub total: i64,
+}
+
+/// A deleted task waiting in the recycle bin to be restored or purged.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeletedTask {
+    pub task_id: i64,
+    pub name: String,
+    /// The task as it was when deleted
+    pub task: Task,
+    pub deleted_at: DateTime<Utc>,
+}
+
+/// Kind of change recorded in the audit log.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    Create,
+    Update,
+    Delete,
+    StatusChange,
+}
+
+impl std::fmt::Display for AuditAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            AuditAction::Create => "create",
+            AuditAction::Update => "update",
+            AuditAction::Delete => "delete",
+            AuditAction::StatusChange => "status_change",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl std::str::FromStr for AuditAction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "create" => Ok(AuditAction::Create),
+            "update" => Ok(AuditAction::Update),
+            "delete" => Ok(AuditAction::Delete),
+            "status_change" => Ok(AuditAction::StatusChange),
+            _ => Err(format!("Unknown audit action: {}", s)),
+        }
+    }
+}
+
+/// What made a change: a command invoked from the UI, or the scheduler itself.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditSource {
+    Ui,
+    Scheduler,
+    /// Home Assistant and other automation calling in
+    Integration,
+}
+
+impl std::fmt::Display for AuditSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            AuditSource::Ui => "ui",
+            AuditSource::Scheduler => "scheduler",
+            AuditSource::Integration => "integration",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl std::str::FromStr for AuditSource {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "ui" => Ok(AuditSource::Ui),
+            "scheduler" => Ok(AuditSource::Scheduler),
+            "integration" => Ok(AuditSource::Integration),
+            _ => Err(format!("Unknown audit source: {}", s)),
+        }
+    }
+}
+
+/// A recorded task mutation with snapshots of the task before and after it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub id: i64,
+    pub task_id: i64,
+    pub action: AuditAction,
+    pub source: AuditSource,
+    /// `None` for creations
+    pub before: Option<Task>,
+    /// `None` for deletions
+    pub after: Option<Task>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A task as it was saved at one point, numbered per task from 1.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskRevision {
+    pub task_id: i64,
+    pub revision: i64,
+    pub source: AuditSource,
+    pub task: Task,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A scheduled action that has not fired yet.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpcomingExecution {
+    pub task_id: i64,
+    pub task_name: String,
+    pub action: ExecutionAction,
+    pub scheduled_at: DateTime<Utc>,
+}
+
+/// Aggregated open time for one task over a period.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskUsage {
+    pub task_id: i64,
+    pub task_name: String,
+    pub open_count: i64,
+    pub total_open_secs: i64,
+}
+
+/// Per-task execution totals for a reporting period.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskExecutionSummary {
+    pub task_id: i64,
+    pub task_name: String,
+    pub opens: i64,
+    pub closes: i64,
+    pub failures: i64,
+    pub total_open_secs: i64,
+}
+
+/// How occurrences missed while the app was not running or the computer was asleep are handled.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum MissedPolicy {
+    /// Run the most recent missed occurrence if its window is still open; skip the rest
+    #[default]
+    RunLatest,
+    /// Skip every missed occurrence and continue with the next future one
+    Skip,
+    /// Skip like `Skip`, but if the latest missed occurrence's window is still open, ask with a
+    /// notification whether to run it now
+    Ask,
+}
+
+impl std::fmt::Display for MissedPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            MissedPolicy::RunLatest => "run_latest",
+            MissedPolicy::Skip => "skip",
+            MissedPolicy::Ask => "ask",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl std::str::FromStr for MissedPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "run_latest" => Ok(MissedPolicy::RunLatest),
+            "skip" => Ok(MissedPolicy::Skip),
+            "ask" => Ok(MissedPolicy::Ask),
+            _ => Err(format!("Unknown missed policy: {}", s)),
+        }
+    }
+}
+
+/// What happens to a scheduled open while `max_concurrent_opens` browsers are already open.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ConcurrentOpenPolicy {
+    /// Retry the open every minute until a slot frees up or its close time passes
+    #[default]
+    Defer,
+    /// Skip the occurrence
+    Skip,
+}
+
+impl std::fmt::Display for ConcurrentOpenPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            ConcurrentOpenPolicy::Defer => "defer",
+            ConcurrentOpenPolicy::Skip => "skip",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl std::str::FromStr for ConcurrentOpenPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "defer" => Ok(ConcurrentOpenPolicy::Defer),
+            "skip" => Ok(ConcurrentOpenPolicy::Skip),
+            _ => Err(format!("Unknown concurrent open policy: {}", s)),
+        }
+    }
+}
+
+/// Persisted heartbeat of the scheduler loop, for diagnosing whether it is doing anything.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SchedulerState {
+    pub started_at: Option<DateTime<Utc>>,
+    /// Last time the loop went around, whether or not anything was due
+    pub last_tick_at: Option<DateTime<Utc>>,
+    pub executions_since_start: i64,
+    pub last_error: Option<String>,
+    pub last_error_at: Option<DateTime<Utc>>,
+}
+
+/// A separate set of tasks, e.g. Work or Home, with its own copy of the workspace-scoped settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Workspace {
+    pub id: i64,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Settings a workspace can override; everything else in `AppSettings` is global.
+pub const WORKSPACE_SCOPED_SETTINGS: &[&str] = &[
+    "show_notifications",
+    "max_consecutive_failures",
+    "dry_run",
+    "missed_policy",
+];
+
+/// Occurrences of one task that were missed while the app was not running.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MissedTask {
+    pub task_id: i64,
+    pub task_name: String,
+    /// Missed open times, oldest first; capped for tasks that missed a lot
+    pub missed: Vec<DateTime<Utc>>,
+    pub missed_count: usize,
+    /// Whether the latest missed occurrence is run now; all others are skipped
+    pub runs_latest: bool,
+    /// Whether a notification asks to run the latest missed occurrence
+    #[serde(default)]
+    pub asked: bool,
+}
+
+/// What the startup backfill found and did.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackfillReport {
+    pub generated_at: DateTime<Utc>,
+    pub policy: MissedPolicy,
+    pub tasks: Vec<MissedTask>,
+}
+
+/// An action the scheduler would take, as predicted by the schedule simulation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimulatedAction {
+    pub task_id: i64,
+    pub task_name: String,
+    pub kind: TaskKind,
+    pub action: ExecutionAction,
+    pub at: DateTime<Utc>,
+    pub url: Option<String>,
+    /// Why the action would not run as planned, or what it collides with
+    pub conflict: Option<String>,
+}
+
+/// Outcome of a test launch used to verify a browser and profile configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BrowserLaunchTest {
+    pub browser: BrowserType,
+    /// Whether the browser process was seen running after the launch
+    pub launched: bool,
+    pub pid: Option<u32>,
+    /// Whether the test page was closed again
+    pub closed: bool,
+    pub message: String,
+}
+
+/// A connected monitor a task's browser window can be placed on.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DisplayInfo {
+    /// Index stored in a task's `target_display`
+    pub index: u32,
+    pub name: Option<String>,
+    /// Top-left corner in physical pixels on the virtual desktop
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub primary: bool,
+}
+
+/// A problem found while checking a task before it is saved.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidationIssue {
+    /// Task field the issue is about, for highlighting it in the form
+    pub field: String,
+    pub severity: IssueSeverity,
+    pub message: String,
+}
+
+impl ValidationIssue {
+    pub fn error(field: &str, message: impl Into<String>) -> Self {
+        Self {
+            field: field.to_string(),
+            severity: IssueSeverity::Error,
+            message: message.into(),
+        }
+    }
+
+    pub fn warning(field: &str, message: impl Into<String>) -> Self {
+        Self {
+            field: field.to_string(),
+            severity: IssueSeverity::Warning,
+            message: message.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum IssueSeverity {
+    /// Blocks saving the task
+    Error,
+    /// Saving is allowed, but the task may not behave as intended
+    Warning,
+}
+
+/// Everything the home screen needs, fetched in a single call.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Dashboard {
+    pub active_count: i64,
+    pub paused_count: i64,
+    pub failed_count: i64,
+    pub completed_today: Vec<TaskExecution>,
+    pub upcoming_today: Vec<UpcomingExecution>,
+    pub next_action: Option<UpcomingExecution>,
+}
+
+impl Task {
+    /// Whether the task's open has fired and the matching close is still pending.
+    pub fn is_open_window(&self) -> bool {
+        if self.status != TaskStatus::Active {
+            return false;
+        }
+
+        match (self.next_open_execution, self.next_close_execution) {
+            (_, None) => false,
+            (None, Some(_)) => true,
+            (Some(open), Some(close)) => open > close,
+        }
+    }
+
+    /// Whether a block task is currently inside its blocking window.
+    pub fn is_block_window_open(&self) -> bool {
+        self.kind == TaskKind::Block && self.is_open_window()
+    }
+
+    /// How long each occurrence stays open: until its close_time, or for duration_minutes.
+    /// None when the task does not close on its own.
+    pub fn open_length(&self) -> Option<chrono::Duration> {
+        match (self.close_time, self.duration_minutes) {
+            (Some(close_time), _) => Some(close_time.signed_duration_since(self.start_time)),
+            (None, Some(minutes)) => Some(chrono::Duration::minutes(minutes as i64)),
+            (None, None) => None,
+        }
+    }
+
+    /// Close of the occurrence opening at `occurrence` under a fixed close_time. None when the
+    /// close is only scheduled once the open runs, or there is none.
+    pub fn occurrence_close(&self, occurrence: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.close_time.map(|close_time| occurrence + close_time.signed_duration_since(self.start_time))
+    }
+
+    /// Largest shift either way the jitter window applies to an occurrence.
+    pub fn jitter_window(&self) -> chrono::Duration {
+        chrono::Duration::minutes(self.jitter_minutes.unwrap_or(0) as i64)
+    }
+
+    /// `occurrence` moved by a random offset within the task's jitter window.
+    pub fn jittered(&self, occurrence: DateTime<Utc>) -> DateTime<Utc> {
+        let window = self.jitter_window().num_seconds();
+        if window == 0 {
+            return occurrence;
+        }
+        occurrence + chrono::Duration::seconds(rand::Rng::gen_range(&mut rand::thread_rng(), -window..=window))
+    }
+
+    /// Wait before retrying an occurrence that has failed `failed` times, or `None` once its
+    /// attempts are used up.
+    pub fn retry_delay(&self, failed: i32) -> Option<chrono::Duration> {
+        let max_attempts = self.retry_max_attempts.unwrap_or(1) as i32;
+        if failed < 1 || failed >= max_attempts {
+            return None;
+        }
+        let backoff = self.retry_backoff_secs.unwrap_or(DEFAULT_RETRY_BACKOFF_SECS) as i64;
+        // Rows saved before backoffs were validated can hold any u32; saturate rather than overflow
+        let delay = backoff.saturating_mul(1 << (failed - 1).min(16)).min(MAX_RETRY_DELAY_SECS);
+        Some(chrono::Duration::seconds(delay))
+    }
+
+    #[allow(dead_code)]
+    pub fn new(
+        name: String,
+        browser: BrowserType,
+        start_time: DateTime<Utc>,
+        timezone: String,
+    ) -> Self {
+        Self {
+            id: None,
+            name,
+            browser,
+            browser_profile: None,
+            url: None,
+            allow_close_all: false,
+            start_time,
+            close_time: None,
+            timezone,
+            repeat_config: None,
+            pomodoro: None,
+            execution_count: 0,
+            status: TaskStatus::Active,
+            next_open_execution: Some(start_time),
+            next_close_execution: None,
+            consecutive_failures: 0,
+            last_success_at: None,
+            kind: TaskKind::Browser,
+            blocked_domains: Vec::new(),
+            pomodoro_cycle: 0,
+            minimize_browsers: false,
+            steps: Vec::new(),
+            current_step: 0,
+            workspace_id: None,
+            tags: Vec::new(),
+            exclusions: Vec::new(),
+            secret_url_params: false,
+            url_secret_ref: None,
+            session_name: None,
+            target_display: None,
+            window_state: WindowState::Normal,
+            window_position: None,
+            window_size: None,
+            kiosk: false,
+            app_mode: false,
+            zoom: None,
+            user_agent: None,
+            lang: None,
+            fresh_session: false,
+            managed_profile: false,
+            prelaunch_minutes: None,
+            jitter_minutes: None,
+            missed_policy: None,
+            retry_max_attempts: None,
+            retry_backoff_secs: None,
+            retry_attempt: 0,
+            pending_occurrence: None,
+            duration_minutes: None,
+            description: None,
+            launcher: LauncherKind::Native,
+            updated_at: None,
+        }
+    }
+}
+
+/// Day calendars and week-based views start on.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum FirstDayOfWeek {
+    #[default]
+    Monday,
+    Saturday,
+    Sunday,
+}
+
+impl std::fmt::Display for FirstDayOfWeek {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            FirstDayOfWeek::Monday => "monday",
+            FirstDayOfWeek::Saturday => "saturday",
+            FirstDayOfWeek::Sunday => "sunday",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl std::str::FromStr for FirstDayOfWeek {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "monday" => Ok(FirstDayOfWeek::Monday),
+            "saturday" => Ok(FirstDayOfWeek::Saturday),
+            "sunday" => Ok(FirstDayOfWeek::Sunday),
+            _ => Err(format!("Unknown first day of week: {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub enum ClockFormat {
+    #[default]
+    #[serde(rename = "24h")]
+    H24,
+    #[serde(rename = "12h")]
+    H12,
+}
+
+impl std::fmt::Display for ClockFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            ClockFormat::H24 => "24h",
+            ClockFormat::H12 => "12h",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl std::str::FromStr for ClockFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "24h" => Ok(ClockFormat::H24),
+            "12h" => Ok(ClockFormat::H12),
+            _ => Err(format!("Unknown clock format: {}", s)),
+        }
+    }
+}
+
+/// Order and separators of dates in backend-generated text.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum DateFormat {
+    /// 2024-03-31
+    #[default]
+    Iso,
+    /// 03/31/2024
+    Us,
+    /// 31/03/2024
+    Dmy,
+    /// 31.03.2024
+    Dotted,
+}
+
+impl std::fmt::Display for DateFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            DateFormat::Iso => "iso",
+            DateFormat::Us => "us",
+            DateFormat::Dmy => "dmy",
+            DateFormat::Dotted => "dotted",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl std::str::FromStr for DateFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "iso" => Ok(DateFormat::Iso),
+            "us" => Ok(DateFormat::Us),
+            "dmy" => Ok(DateFormat::Dmy),
+            "dotted" => Ok(DateFormat::Dotted),
+            _ => Err(format!("Unknown date format: {}", s)),
+        }
+    }
+}
+
+/// Language of backend-generated text such as schedule descriptions and error messages.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    #[default]
+    En,
+    De,
+    Fr,
+    Es,
+}
+
+impl std::fmt::Display for Language {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Language::En => "en",
+            Language::De => "de",
+            Language::Fr => "fr",
+            Language::Es => "es",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl std::str::FromStr for Language {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "en" => Ok(Language::En),
+            "de" => Ok(Language::De),
+            "fr" => Ok(Language::Fr),
+            "es" => Ok(Language::Es),
+            _ => Err(format!("Unknown language: {}", s)),
+        }
+    }
+}
+
+/// A URL scheme besides http(s) that tasks may open, e.g. `zoommtg` for Zoom meetings.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AllowedUrlScheme {
+    pub scheme: String,
+    /// Ask with a notification before each scheduled open instead of opening right away
+    #[serde(default)]
+    pub confirm: bool,
+}
+
+/// The user's date and time conventions, shared by the frontend and backend-generated text.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct LocaleConfig {
+    pub first_day_of_week: FirstDayOfWeek,
+    pub clock_format: ClockFormat,
+    pub date_format: DateFormat,
+    #[serde(default)]
+    pub language: Language,
+}
+
+impl From<&AppSettings> for LocaleConfig {
+    fn from(settings: &AppSettings) -> Self {
+        Self {
+            first_day_of_week: settings.first_day_of_week.clone(),
+            clock_format: settings.clock_format.clone(),
+            date_format: settings.date_format.clone(),
+            language: settings.language,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppSettings {
+    pub minimize_to_tray: bool,
+    pub start_minimized: bool,
+    pub show_notifications: bool,
+    pub auto_start: bool,
+    /// Consecutive failures after which a task is disabled; 0 never disables
+    #[serde(default = "default_max_consecutive_failures")]
+    pub max_consecutive_failures: u32,
+    /// Log and record what the executor would do without launching or closing anything
+    #[serde(default)]
+    pub dry_run: bool,
+    /// What to do on startup with occurrences missed while the app was not running
+    #[serde(default = "default_missed_policy")]
+    pub missed_policy: MissedPolicy,
+    /// Check GitHub releases for a newer version once a week
+    #[serde(default)]
+    pub auto_check_updates: bool,
+    /// Days deleted tasks stay in the recycle bin; 0 keeps them until purged
+    #[serde(default = "default_deleted_retention_days")]
+    pub deleted_retention_days: u32,
+    /// Connections the database pool opens at most; takes effect on the next start
+    #[serde(default = "default_db_max_connections")]
+    pub db_max_connections: u32,
+    /// Port of a Chromium browser started with --remote-debugging-port, used to read and arrange its tabs; 0 disables
+    #[serde(default = "default_devtools_port")]
+    pub devtools_port: u16,
+    /// Seconds between scheduled browser launches that fall due together; 0 launches them at once
+    #[serde(default = "default_launch_stagger_secs")]
+    pub launch_stagger_secs: u32,
+    /// Browsers the scheduler keeps open at most at once, counting opens it has yet to close; 0 is unlimited
+    #[serde(default = "default_max_concurrent_opens")]
+    pub max_concurrent_opens: u32,
+    /// What happens to an open while max_concurrent_opens browsers are open
+    #[serde(default = "default_concurrent_open_policy")]
+    pub concurrent_open_policy: ConcurrentOpenPolicy,
+    /// CPU usage in percent above which scheduled opens wait for the system to calm down; 0 disables the check
+    #[serde(default = "default_max_cpu_percent")]
+    pub max_cpu_percent: u32,
+    /// Memory usage in percent above which scheduled opens wait for the system to calm down; 0 disables the check
+    #[serde(default = "default_max_memory_percent")]
+    pub max_memory_percent: u32,
+    /// Close the browsers the scheduler opened when the app quits
+    #[serde(default)]
+    pub close_opened_on_quit: bool,
+    /// Hold back scheduled actions while the screen is locked and apply the missed policy on unlock
+    #[serde(default)]
+    pub defer_while_locked: bool,
+    /// Hold back scheduled actions between quiet_hours_start and quiet_hours_end, local time
+    #[serde(default)]
+    pub quiet_hours_enabled: bool,
+    /// Start of quiet hours as HH:MM; a start after the end spans midnight
+    #[serde(default = "default_quiet_hours_start")]
+    pub quiet_hours_start: String,
+    /// End of quiet hours as HH:MM; what came due meanwhile runs then
+    #[serde(default = "default_quiet_hours_end")]
+    pub quiet_hours_end: String,
+    /// Day calendars and week views start on
+    #[serde(default = "default_first_day_of_week")]
+    pub first_day_of_week: FirstDayOfWeek,
+    /// 12 or 24 hour clock for times in notifications, reports and the tray
+    #[serde(default = "default_clock_format")]
+    pub clock_format: ClockFormat,
+    /// How dates are written in notifications, reports and the tray
+    #[serde(default = "default_date_format")]
+    pub date_format: DateFormat,
+    /// Sound of task open, close and pomodoro notifications; `default`, `alert`, `none` or a sound name of the platform
+    #[serde(default = "default_action_sound")]
+    pub action_sound: String,
+    /// Sound of break reminders; `default`, `alert`, `none` or a sound name of the platform
+    #[serde(default = "default_reminder_sound")]
+    pub reminder_sound: String,
+    /// Sound of failure notifications, so they stand out; `default`, `alert`, `none` or a sound name of the platform
+    #[serde(default = "default_failure_sound")]
+    pub failure_sound: String,
+    /// Show a system notification when a task fails for good, after any retries
+    #[serde(default = "default_notify_on_failure")]
+    pub notify_on_failure: bool,
+    /// Title of task open and close notifications; placeholders {task}, {url}, {time} and {error}, empty for the built-in wording
+    #[serde(default = "default_action_notification_title")]
+    pub action_notification_title: String,
+    /// Body of task open and close notifications; placeholders {task}, {url}, {time} and {error}, empty for the built-in wording
+    #[serde(default = "default_action_notification_body")]
+    pub action_notification_body: String,
+    /// Title of task failure notifications; placeholders {task}, {url}, {time} and {error}, empty for the built-in wording
+    #[serde(default = "default_failure_notification_title")]
+    pub failure_notification_title: String,
+    /// Body of task failure notifications; placeholders {task}, {url}, {time} and {error}, empty for the built-in wording
+    #[serde(default = "default_failure_notification_body")]
+    pub failure_notification_body: String,
+    /// Send failure notifications by email through the SMTP server below
+    #[serde(default)]
+    pub smtp_enabled: bool,
+    /// SMTP server host name
+    #[serde(default = "default_smtp_host")]
+    pub smtp_host: String,
+    /// SMTP server port; 465 uses implicit TLS, any other port STARTTLS
+    #[serde(default = "default_smtp_port")]
+    pub smtp_port: u16,
+    /// SMTP user name; the password is kept in the OS keyring
+    #[serde(default = "default_smtp_username")]
+    pub smtp_username: String,
+    /// Sender address of notification emails
+    #[serde(default = "default_smtp_from")]
+    pub smtp_from: String,
+    /// Comma-separated recipients of notification emails
+    #[serde(default = "default_smtp_to")]
+    pub smtp_to: String,
+    /// Also email a summary of the previous day's executions once a day
+    #[serde(default)]
+    pub smtp_daily_summary: bool,
+    /// Slack incoming webhook that task failures are posted to; empty to disable
+    #[serde(default = "default_slack_webhook_url")]
+    pub slack_webhook_url: String,
+    /// Discord webhook that task failures are posted to; empty to disable
+    #[serde(default = "default_discord_webhook_url")]
+    pub discord_webhook_url: String,
+    /// Telegram chat that reminders and failures are sent to; the bot token is kept in the OS keyring
+    #[serde(default = "default_telegram_chat_id")]
+    pub telegram_chat_id: String,
+    /// Serve the Home Assistant REST endpoint; takes effect on the next start
+    #[serde(default)]
+    pub home_assistant_enabled: bool,
+    /// Port of the Home Assistant REST endpoint
+    #[serde(default = "default_home_assistant_port")]
+    pub home_assistant_port: u16,
+    /// Accept Home Assistant requests from other machines, not only this one
+    #[serde(default)]
+    pub home_assistant_listen_all: bool,
+    /// Minutes before a task opens to show a warning with snooze, skip and open-now actions; 0 to disable
+    #[serde(default = "default_warn_before_minutes")]
+    pub warn_before_minutes: u32,
+    /// Minutes before a scheduled close to show an always-on-top countdown with extend and
+    /// close-now buttons; 0 to disable
+    #[serde(default = "default_close_countdown_minutes")]
+    pub close_countdown_minutes: u32,
+    /// Language of schedule descriptions, notifications and error messages
+    #[serde(default = "default_language")]
+    pub language: Language,
+    /// Close matching Chromium tabs on Windows through UI Automation; best effort, off by default
+    #[serde(default)]
+    pub windows_ui_automation: bool,
+    /// Let `do-in-time://` links run tasks and pause or resume the scheduler without asking
+    #[serde(default)]
+    pub url_actions_enabled: bool,
+    /// Schemes besides http(s) that task URLs may use, e.g. `zoommtg` or `slack`
+    #[serde(default)]
+    pub allowed_url_schemes: Vec<AllowedUrlScheme>,
+}
+
+impl AppSettings {
+    /// Names of the extra URL schemes tasks may use, for `validate_url_allowing`
+    pub fn extra_url_schemes(&self) -> Vec<&str> {
+        self.allowed_url_schemes.iter().map(|s| s.scheme.as_str()).collect()
+    }
+
+    /// Whether scheduled opens of `url` wait for the user to confirm them
+    pub fn needs_open_confirmation(&self, url: &str) -> bool {
+        crate::utils::validation::url_scheme(url).is_some_and(|scheme| {
+            self.allowed_url_schemes
+                .iter()
+                .any(|allowed| allowed.confirm && allowed.scheme.eq_ignore_ascii_case(&scheme))
+        })
+    }
+}
+
+fn default_max_consecutive_failures() -> u32 {
+    3
+}
+
+fn default_missed_policy() -> MissedPolicy {
+    MissedPolicy::RunLatest
+}
+
+fn default_deleted_retention_days() -> u32 {
+    30
+}
+
+fn default_devtools_port() -> u16 {
+    0
+}
+
+fn default_launch_stagger_secs() -> u32 {
+    0
+}
+
+fn default_max_concurrent_opens() -> u32 {
+    0
+}
+
+fn default_concurrent_open_policy() -> ConcurrentOpenPolicy {
+    ConcurrentOpenPolicy::Defer
+}
+
+fn default_max_cpu_percent() -> u32 {
+    0
+}
+
+fn default_max_memory_percent() -> u32 {
+    0
+}
+
+fn default_first_day_of_week() -> FirstDayOfWeek {
+    FirstDayOfWeek::Monday
+}
+
+fn default_clock_format() -> ClockFormat {
+    ClockFormat::H24
+}
+
+fn default_date_format() -> DateFormat {
+    DateFormat::Iso
+}
+
+fn default_action_sound() -> String {
+    "default".to_string()
+}
+
+fn default_reminder_sound() -> String {
+    "default".to_string()
+}
+
+fn default_failure_sound() -> String {
+    "alert".to_string()
+}
+
+fn default_action_notification_title() -> String {
+    String::new()
+}
+
+fn default_action_notification_body() -> String {
+    String::new()
+}
+
+fn default_failure_notification_title() -> String {
+    String::new()
+}
+
+fn default_failure_notification_body() -> String {
+    String::new()
+}
+
+fn default_smtp_host() -> String {
+    String::new()
+}
+
+fn default_smtp_port() -> u16 {
+    587
+}
+
+fn default_smtp_username() -> String {
+    String::new()
+}
+
+fn default_smtp_from() -> String {
+    String::new()
+}
+
+fn default_smtp_to() -> String {
+    String::new()
+}
+
+fn default_slack_webhook_url() -> String {
+    String::new()
+}
+
+fn default_discord_webhook_url() -> String {
+    String::new()
+}
+
+fn default_telegram_chat_id() -> String {
+    String::new()
+}
+
+fn default_home_assistant_port() -> u16 {
+    8765
+}
+
+fn default_warn_before_minutes() -> u32 {
+    0
+}
+
+fn default_close_countdown_minutes() -> u32 {
+    0
+}
+
+fn default_language() -> Language {
+    Language::En
+}
+
+fn default_notify_on_failure() -> bool {
+    true
+}
+
+fn default_quiet_hours_start() -> String {
+    "22:00".to_string()
+}
+
+fn default_quiet_hours_end() -> String {
+    "07:00".to_string()
+}
+
+fn default_db_max_connections() -> u32 {
+    5
 }
 
 impl Default for AppSettings {
@@ -204,6 +1771,53 @@ impl Default for AppSettings {
             start_minimized: false,
             show_notifications: false,
             auto_start: false,
+            max_consecutive_failures: default_max_consecutive_failures(),
+            dry_run: false,
+            missed_policy: default_missed_policy(),
+            auto_check_updates: false,
+            deleted_retention_days: default_deleted_retention_days(),
+            db_max_connections: default_db_max_connections(),
+            devtools_port: default_devtools_port(),
+            launch_stagger_secs: default_launch_stagger_secs(),
+            max_concurrent_opens: default_max_concurrent_opens(),
+            concurrent_open_policy: default_concurrent_open_policy(),
+            max_cpu_percent: default_max_cpu_percent(),
+            max_memory_percent: default_max_memory_percent(),
+            close_opened_on_quit: false,
+            defer_while_locked: false,
+            quiet_hours_enabled: false,
+            quiet_hours_start: default_quiet_hours_start(),
+            quiet_hours_end: default_quiet_hours_end(),
+            first_day_of_week: default_first_day_of_week(),
+            clock_format: default_clock_format(),
+            date_format: default_date_format(),
+            action_sound: default_action_sound(),
+            reminder_sound: default_reminder_sound(),
+            failure_sound: default_failure_sound(),
+            notify_on_failure: default_notify_on_failure(),
+            action_notification_title: default_action_notification_title(),
+            action_notification_body: default_action_notification_body(),
+            failure_notification_title: default_failure_notification_title(),
+            failure_notification_body: default_failure_notification_body(),
+            smtp_enabled: false,
+            smtp_host: default_smtp_host(),
+            smtp_port: default_smtp_port(),
+            smtp_username: default_smtp_username(),
+            smtp_from: default_smtp_from(),
+            smtp_to: default_smtp_to(),
+            smtp_daily_summary: false,
+            slack_webhook_url: default_slack_webhook_url(),
+            discord_webhook_url: default_discord_webhook_url(),
+            telegram_chat_id: default_telegram_chat_id(),
+            home_assistant_enabled: false,
+            home_assistant_port: default_home_assistant_port(),
+            home_assistant_listen_all: false,
+            warn_before_minutes: default_warn_before_minutes(),
+            close_countdown_minutes: default_close_countdown_minutes(),
+            language: default_language(),
+            windows_ui_automation: false,
+            url_actions_enabled: false,
+            allowed_url_schemes: Vec::new(),
         }
     }
 }
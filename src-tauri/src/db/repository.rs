@@ -1,28 +1,50 @@
-use sqlx::Row;
+use chrono::{DateTime, Utc};
+use sqlx::{Row, SqliteConnection};
 use crate::db::models::*;
-use crate::db::connection::Database;
+use crate::db::connection::{Database, MAX_POOL_CONNECTIONS};
 use crate::error::{AppError, Result};
-use crate::utils::validation::{validate_url, validate_browser_profile};
+use crate::utils::{keyring, secret_url, task_validation, validation};
+use std::collections::HashMap;
 use std::str::FromStr;
 
+/// Revisions kept per task; older ones are dropped as new edits come in
+const MAX_REVISIONS_PER_TASK: i64 = 20;
+
 impl Database {
-    pub async fn create_task(&self, mut task: Task) -> Result<Task> {
-        // Validate inputs for security
-        if let Some(ref url) = task.url {
-            validate_url(url)?;
-        }
-        if let Some(ref profile) = task.browser_profile {
-            validate_browser_profile(profile)?;
+    pub async fn create_task(&self, mut task: Task, source: AuditSource) -> Result<Task> {
+        task.id = None;
+        task.url_secret_ref = None;
+        self.insert_task(task, source).await
+    }
+
+    /// Re-inserts a deleted task under its original id, so audit history and undo stay linked.
+    pub async fn restore_task(&self, task: Task, source: AuditSource) -> Result<Task> {
+        if task.id.is_none() {
+            return Err(AppError::InvalidTask("A restored task needs its original id".to_string()));
         }
+        self.insert_task(task, source).await
+    }
+
+    async fn insert_task(&self, mut task: Task, source: AuditSource) -> Result<Task> {
+        task.tags = normalize_tags(&task.tags);
+        self.validate_task_fields(&task, source).await?;
+        // A restored task brings the keyring entry it was deleted with
+        let existing_ref = task.url_secret_ref.take();
+        let secret_writes = Self::prepare_secret_url(&mut task, existing_ref).await?;
+        secret_writes.apply().await?;
 
+        // The close keeps its distance to a jittered first open
+        let offset = task.jittered(task.start_time).signed_duration_since(task.start_time);
         if task.next_open_execution.is_none() {
-            task.next_open_execution = Some(task.start_time);
+            task.next_open_execution = Some(task.start_time + offset);
         }
 
         if task.close_time.is_some() && task.next_close_execution.is_none() {
-            task.next_close_execution = task.close_time;
+            task.next_close_execution = task.close_time.map(|close| close + offset);
         }
 
+        task.updated_at = Some(chrono::Utc::now());
+
         let repeat_interval = task.repeat_config.as_ref().map(|r| r.interval.to_string());
         let repeat_end_after = task.repeat_config.as_ref().and_then(|r| r.end_after);
         let repeat_end_date = task.repeat_config.as_ref().and_then(|r| r.end_date.map(|d| d.to_rfc3339()));
@@ -30,14 +52,48 @@ impl Database {
         let result = sqlx::query(
             r#"
             INSERT INTO tasks (
-                name, browser, browser_profile, url, allow_close_all,
+                id, name, browser, browser_profile, url, allow_close_all,
                 start_time, close_time, timezone,
                 repeat_interval, repeat_end_after, repeat_end_date,
                 execution_count, status,
-                next_open_execution, next_close_execution
-            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                next_open_execution, next_close_execution,
+                consecutive_failures,
+                last_success_at,
+                kind,
+                blocked_domains,
+                pomodoro_work_minutes, pomodoro_break_minutes, pomodoro_cycles,
+                pomodoro_cycle,
+                minimize_browsers,
+                current_step,
+                workspace_id,
+                url_secret_ref,
+                session_name,
+                target_display,
+                window_state,
+                zoom,
+                user_agent,
+                lang,
+                fresh_session,
+                managed_profile,
+                prelaunch_minutes,
+                jitter_minutes,
+                missed_policy,
+                retry_max_attempts,
+                retry_backoff_secs,
+                retry_attempt,
+                pending_occurrence,
+                duration_minutes,
+                window_position,
+                window_size,
+                kiosk,
+                app_mode,
+                description,
+                launcher,
+                updated_at
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
         )
+        .bind(task.id)
         .bind(&task.name)
         .bind(task.browser.to_string())
         .bind(&task.browser_profile)
@@ -53,10 +109,52 @@ impl Database {
         .bind(task.status.to_string())
         .bind(task.next_open_execution.map(|d| d.to_rfc3339()))
         .bind(task.next_close_execution.map(|d| d.to_rfc3339()))
-        .execute(self.pool())
+        .bind(task.consecutive_failures)
+        .bind(task.last_success_at.map(|d| d.to_rfc3339()))
+        .bind(task.kind.to_string())
+        .bind((!task.blocked_domains.is_empty()).then(|| task.blocked_domains.join(",")))
+        .bind(task.pomodoro.as_ref().map(|p| p.work_minutes))
+        .bind(task.pomodoro.as_ref().map(|p| p.break_minutes))
+        .bind(task.pomodoro.as_ref().map(|p| p.cycles))
+        .bind(task.pomodoro_cycle)
+        .bind(task.minimize_browsers)
+        .bind(task.current_step)
+        .bind(task.workspace_id)
+        .bind(&task.url_secret_ref)
+        .bind(&task.session_name)
+        .bind(task.target_display)
+        .bind(task.window_state.to_string())
+        .bind(task.zoom)
+        .bind(&task.user_agent)
+        .bind(&task.lang)
+        .bind(task.fresh_session)
+        .bind(task.managed_profile)
+        .bind(task.prelaunch_minutes)
+        .bind(task.jitter_minutes)
+        .bind(task.missed_policy.as_ref().map(|p| p.to_string()))
+        .bind(task.retry_max_attempts)
+        .bind(task.retry_backoff_secs)
+        .bind(task.retry_attempt)
+        .bind(task.pending_occurrence.map(|d| d.to_rfc3339()))
+        .bind(task.duration_minutes)
+        .bind(task.window_position.map(|position| position.to_string()))
+        .bind(task.window_size.map(|size| size.to_string()))
+        .bind(task.kiosk)
+        .bind(task.app_mode)
+        .bind(&task.description)
+        .bind(task.launcher.to_string())
+        .bind(task.updated_at.map(|d| d.to_rfc3339()))
+        .execute(&self.pool())
         .await?;
 
-        task.id = Some(result.last_insert_rowid());
+        let id = result.last_insert_rowid();
+        self.replace_task_steps(id, &task.steps).await?;
+        self.replace_task_tags(id, &task.tags).await?;
+        self.replace_task_exclusions(id, &task.exclusions).await?;
+
+        task.id = Some(id);
+        self.record_audit(id, AuditAction::Create, source, None, Some(&task)).await?;
+        self.record_revision(id, source, &task).await?;
         Ok(task)
     }
 
@@ -67,46 +165,113 @@ impl Database {
             "#,
         )
         .bind(id)
-        .fetch_optional(self.pool())
+        .fetch_optional(&self.pool())
         .await?
         .ok_or_else(|| AppError::TaskNotFound(id))?;
 
-        Self::row_to_task(row)
+        let mut task = Self::row_to_task(row)?;
+        task.steps = self.get_task_steps(id).await?;
+        task.tags = self.get_task_tags(id).await?;
+        task.exclusions = self.get_task_exclusions(id).await?;
+        Ok(task)
     }
 
     pub async fn get_all_tasks(&self) -> Result<Vec<Task>> {
         let rows = sqlx::query("SELECT * FROM tasks ORDER BY start_time ASC")
-            .fetch_all(self.pool())
+            .fetch_all(&self.pool())
             .await?;
 
-        rows.into_iter()
-            .map(Self::row_to_task)
-            .collect()
+        self.rows_to_tasks(rows, false).await
+    }
+
+    /// Tasks of `rows` with their steps, tags and exclusions, loaded with one query each. With
+    /// `only_these` the queries are limited to the tasks in `rows`, otherwise they read all.
+    async fn rows_to_tasks(&self, rows: Vec<sqlx::sqlite::SqliteRow>, only_these: bool) -> Result<Vec<Task>> {
+        let mut tasks = rows.into_iter().map(Self::row_to_task).collect::<Result<Vec<_>>>()?;
+        let ids: Vec<i64> = tasks.iter().filter_map(|task| task.id).collect();
+        let task_ids = only_these.then_some(ids.as_slice());
+
+        let mut steps = self.get_all_task_steps(task_ids).await?;
+        let mut tags = self.get_all_task_tags(task_ids).await?;
+        let mut exclusions = self.get_all_task_exclusions(task_ids).await?;
+
+        for task in &mut tasks {
+            if let Some(id) = task.id {
+                task.steps = steps.remove(&id).unwrap_or_default();
+                task.tags = tags.remove(&id).unwrap_or_default();
+                task.exclusions = exclusions.remove(&id).unwrap_or_default();
+            }
+        }
+        Ok(tasks)
+    }
+
+    /// A page of tasks ordered by `sort`, with the total count for the UI to size its list.
+    /// Without a `limit` every task from `offset` on is returned.
+    pub async fn get_tasks_page(
+        &self,
+        limit: Option<i64>,
+        offset: i64,
+        sort: TaskSort,
+        descending: bool,
+    ) -> Result<TaskPage> {
+        let total: i64 = sqlx::query("SELECT COUNT(*) FROM tasks")
+            .fetch_one(&self.pool())
+            .await?
+            .get(0);
+
+        // Tasks without a value sort last either way; the id keeps pages stable between calls
+        let direction = if descending { "DESC" } else { "ASC" };
+        let rows = sqlx::query(&format!(
+            "SELECT * FROM tasks ORDER BY {0} IS NULL, {0} {1}, id {1} LIMIT ? OFFSET ?",
+            sort.column(),
+            direction
+        ))
+        .bind(limit.unwrap_or(-1))
+        .bind(offset.max(0))
+        .fetch_all(&self.pool())
+        .await?;
+
+        // Without a limit the page can hold more ids than a query takes parameters
+        let items = self.rows_to_tasks(rows, limit.is_some()).await?;
+        Ok(TaskPage { items, total })
+    }
+
+    /// Tasks matching every word of `query` in their name, URL or description, best matches
+    /// first. Words match as prefixes, so "quart rep" finds "Quarterly report".
+    pub async fn search_tasks(&self, query: &str, limit: i64) -> Result<Vec<Task>> {
+        let Some(fts_query) = fts_query(query) else {
+            return Ok(Vec::new());
+        };
+
+        let rows = sqlx::query(
+            r#"
+            SELECT tasks.* FROM tasks_fts
+            JOIN tasks ON tasks.id = tasks_fts.rowid
+            WHERE tasks_fts MATCH ?
+            ORDER BY bm25(tasks_fts, 10.0, 1.0, 2.0)
+            LIMIT ?
+            "#,
+        )
+        .bind(fts_query)
+        .bind(limit)
+        .fetch_all(&self.pool())
+        .await?;
+
+        self.rows_to_tasks(rows, true).await
     }
 
     pub async fn get_next_action(&self) -> Result<Option<(Task, ExecutionAction)>> {
-        // Find the earliest upcoming action (either open or close)
+        // Find the earliest upcoming action (either open or close). The trigger-maintained
+        // columns let this walk idx_tasks_next_action instead of sorting all active tasks.
         let row = sqlx::query(
             r#"
-            SELECT *,
-                CASE
-                    WHEN next_open_execution IS NOT NULL AND (next_close_execution IS NULL OR next_open_execution <= next_close_execution)
-                        THEN next_open_execution
-                    ELSE next_close_execution
-                END as next_action_time,
-                CASE
-                    WHEN next_open_execution IS NOT NULL AND (next_close_execution IS NULL OR next_open_execution <= next_close_execution)
-                        THEN 'open'
-                    ELSE 'close'
-                END as next_action
-            FROM tasks
-            WHERE status = 'active'
-                AND (next_open_execution IS NOT NULL OR next_close_execution IS NOT NULL)
+            SELECT * FROM tasks
+            WHERE status = 'active' AND next_action_time IS NOT NULL
             ORDER BY next_action_time ASC
             LIMIT 1
             "#,
         )
-        .fetch_optional(self.pool())
+        .fetch_optional(&self.pool())
         .await?;
 
         match row {
@@ -114,24 +279,84 @@ impl Database {
                 let action_str: String = r.try_get("next_action")?;
                 let action = ExecutionAction::from_str(&action_str)
                     .map_err(|e| AppError::InvalidTask(e))?;
-                let task = Self::row_to_task(r)?;
+                let mut task = Self::row_to_task(r)?;
+                if let Some(id) = task.id {
+                    task.steps = self.get_task_steps(id).await?;
+                    task.tags = self.get_task_tags(id).await?;
+                    task.exclusions = self.get_task_exclusions(id).await?;
+                }
                 Ok(Some((task, action)))
             }
             None => Ok(None),
         }
     }
 
-    pub async fn update_task(&self, id: i64, mut task: Task) -> Result<Task> {
-        // Validate inputs for security
-        if let Some(ref url) = task.url {
-            validate_url(url)?;
-        }
-        if let Some(ref profile) = task.browser_profile {
-            validate_browser_profile(profile)?;
-        }
+    /// Active tasks with a warm-up launch before their next open.
+    pub async fn get_prelaunch_tasks(&self) -> Result<Vec<Task>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT * FROM tasks
+            WHERE status = 'active'
+                AND prelaunch_minutes > 0
+                AND next_open_execution IS NOT NULL
+            "#,
+        )
+        .fetch_all(&self.pool())
+        .await?;
+
+        rows.into_iter().map(Self::row_to_task).collect()
+    }
+
+    /// Active tasks whose next close falls within `from..=to`.
+    pub async fn get_tasks_closing_between(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<Task>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT * FROM tasks
+            WHERE status = 'active'
+                AND next_close_execution > ?
+                AND next_close_execution <= ?
+            "#,
+        )
+        .bind(from.to_rfc3339())
+        .bind(to.to_rfc3339())
+        .fetch_all(&self.pool())
+        .await?;
+
+        rows.into_iter().map(Self::row_to_task).collect()
+    }
+
+    /// Active tasks whose next open falls within `from..=to`.
+    pub async fn get_tasks_opening_between(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<Task>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT * FROM tasks
+            WHERE status = 'active'
+                AND next_open_execution > ?
+                AND next_open_execution <= ?
+            "#,
+        )
+        .bind(from.to_rfc3339())
+        .bind(to.to_rfc3339())
+        .fetch_all(&self.pool())
+        .await?;
+
+        rows.into_iter().map(Self::row_to_task).collect()
+    }
+
+    /// Updates a task, rejecting the write with `AppError::Conflict` if the stored row
+    /// changed since `task.updated_at` was read. Pass `force` to overwrite regardless.
+    pub async fn update_task(&self, id: i64, mut task: Task, force: bool, source: AuditSource) -> Result<Task> {
+        task.tags = normalize_tags(&task.tags);
+        self.validate_task_fields(&task, source).await?;
 
         // Get old task to check if times have changed
         let old_task = self.get_task(id).await?;
+        let secret_writes = Self::prepare_secret_url(&mut task, old_task.url_secret_ref.clone()).await?;
+
+        // A sequence paused between steps cannot resume into different steps
+        if old_task.steps != task.steps {
+            task.current_step = 0;
+        }
 
         // Check if times have changed
         let times_changed = old_task.start_time != task.start_time
@@ -140,21 +365,29 @@ impl Database {
         if times_changed {
             let now = chrono::Utc::now();
 
+            // Any running pomodoro session, step sequence or retry belongs to the old schedule
+            task.pomodoro_cycle = 0;
+            task.current_step = 0;
+            task.retry_attempt = 0;
+            task.pending_occurrence = None;
+
             // If task was completed/failed, reactivate it
             if task.status == TaskStatus::Completed || task.status == TaskStatus::Failed {
                 task.status = TaskStatus::Active;
             }
 
-            // Recalculate next execution times based on current time and new scheduled times
+            // Recalculate next execution times based on current time and new scheduled times;
+            // the close keeps its distance to a jittered open
+            let offset = task.jittered(task.start_time).signed_duration_since(task.start_time);
             if task.start_time > now {
-                task.next_open_execution = Some(task.start_time);
+                task.next_open_execution = Some(task.start_time + offset);
             } else {
                 task.next_open_execution = None;
             }
 
             if let Some(close_time) = task.close_time {
                 if close_time > now {
-                    task.next_close_execution = Some(close_time);
+                    task.next_close_execution = Some(close_time + offset);
                 } else {
                     task.next_close_execution = None;
                 }
@@ -167,15 +400,51 @@ impl Database {
         let repeat_end_after = task.repeat_config.as_ref().and_then(|r| r.end_after);
         let repeat_end_date = task.repeat_config.as_ref().and_then(|r| r.end_date.map(|d| d.to_rfc3339()));
 
-        sqlx::query(
+        let expected_updated_at = task.updated_at.map(|d| d.to_rfc3339());
+        let new_updated_at = chrono::Utc::now();
+
+        let result = sqlx::query(
             r#"
             UPDATE tasks SET
                 name = ?, browser = ?, browser_profile = ?, url = ?, allow_close_all = ?,
                 start_time = ?, close_time = ?, timezone = ?,
                 repeat_interval = ?, repeat_end_after = ?, repeat_end_date = ?,
                 execution_count = ?, status = ?,
-                next_open_execution = ?, next_close_execution = ?
-            WHERE id = ?
+                next_open_execution = ?, next_close_execution = ?,
+                consecutive_failures = ?,
+                last_success_at = ?,
+                kind = ?,
+                blocked_domains = ?,
+                pomodoro_work_minutes = ?, pomodoro_break_minutes = ?, pomodoro_cycles = ?,
+                pomodoro_cycle = ?,
+                minimize_browsers = ?,
+                current_step = ?,
+                workspace_id = ?,
+                url_secret_ref = ?,
+                session_name = ?,
+                target_display = ?,
+                window_state = ?,
+                zoom = ?,
+                user_agent = ?,
+                lang = ?,
+                fresh_session = ?,
+                managed_profile = ?,
+                prelaunch_minutes = ?,
+                jitter_minutes = ?,
+                missed_policy = ?,
+                retry_max_attempts = ?,
+                retry_backoff_secs = ?,
+                retry_attempt = ?,
+                pending_occurrence = ?,
+                duration_minutes = ?,
+                window_position = ?,
+                window_size = ?,
+                kiosk = ?,
+                app_mode = ?,
+                description = ?,
+                launcher = ?,
+                updated_at = ?
+            WHERE id = ? AND (? OR updated_at IS ?)
             "#,
         )
         .bind(&task.name)
@@ -193,94 +462,2260 @@ impl Database {
         .bind(task.status.to_string())
         .bind(task.next_open_execution.map(|d| d.to_rfc3339()))
         .bind(task.next_close_execution.map(|d| d.to_rfc3339()))
+        .bind(task.consecutive_failures)
+        .bind(task.last_success_at.map(|d| d.to_rfc3339()))
+        .bind(task.kind.to_string())
+        .bind((!task.blocked_domains.is_empty()).then(|| task.blocked_domains.join(",")))
+        .bind(task.pomodoro.as_ref().map(|p| p.work_minutes))
+        .bind(task.pomodoro.as_ref().map(|p| p.break_minutes))
+        .bind(task.pomodoro.as_ref().map(|p| p.cycles))
+        .bind(task.pomodoro_cycle)
+        .bind(task.minimize_browsers)
+        .bind(task.current_step)
+        .bind(task.workspace_id)
+        .bind(&task.url_secret_ref)
+        .bind(&task.session_name)
+        .bind(task.target_display)
+        .bind(task.window_state.to_string())
+        .bind(task.zoom)
+        .bind(&task.user_agent)
+        .bind(&task.lang)
+        .bind(task.fresh_session)
+        .bind(task.managed_profile)
+        .bind(task.prelaunch_minutes)
+        .bind(task.jitter_minutes)
+        .bind(task.missed_policy.as_ref().map(|p| p.to_string()))
+        .bind(task.retry_max_attempts)
+        .bind(task.retry_backoff_secs)
+        .bind(task.retry_attempt)
+        .bind(task.pending_occurrence.map(|d| d.to_rfc3339()))
+        .bind(task.duration_minutes)
+        .bind(task.window_position.map(|position| position.to_string()))
+        .bind(task.window_size.map(|size| size.to_string()))
+        .bind(task.kiosk)
+        .bind(task.app_mode)
+        .bind(&task.description)
+        .bind(task.launcher.to_string())
+        .bind(new_updated_at.to_rfc3339())
         .bind(id)
-        .execute(self.pool())
+        .bind(force)
+        .bind(expected_updated_at)
+        .execute(&self.pool())
         .await?;
 
+        if result.rows_affected() == 0 {
+            return Err(AppError::Conflict(id));
+        }
+        // Only a save that went through may touch the keyring
+        secret_writes.apply().await?;
+
+        if old_task.steps != task.steps {
+            self.replace_task_steps(id, &task.steps).await?;
+        }
+        if old_task.tags != task.tags {
+            self.replace_task_tags(id, &task.tags).await?;
+        }
+        if old_task.exclusions != task.exclusions {
+            self.replace_task_exclusions(id, &task.exclusions).await?;
+        }
+
         task.id = Some(id);
+        task.updated_at = Some(new_updated_at);
+
+        // A scheduler run that only moved its bookkeeping on is not worth a snapshot; one that
+        // e.g. completed or disabled the task is
+        if source != AuditSource::Scheduler || !only_bookkeeping_changed(&old_task, &task) {
+            let action = if old_task.status != task.status { AuditAction::StatusChange } else { AuditAction::Update };
+            self.record_audit(id, action, source, Some(&old_task), Some(&task)).await?;
+        }
+
+        // The scheduler rewrites next-execution times on every run; only edits are revisions
+        if source != AuditSource::Scheduler {
+            // Tasks from before revisions existed start their history with the state just replaced
+            if self.get_task_revisions(id).await?.is_empty() {
+                self.record_revision(id, source, &old_task).await?;
+            }
+            self.record_revision(id, source, &task).await?;
+        }
         Ok(task)
     }
 
-    pub async fn delete_task(&self, id: i64) -> Result<()> {
+    /// Moves a task into the recycle bin. Its execution history and keyring entry are kept
+    /// until it is purged.
+    pub async fn delete_task(&self, id: i64, source: AuditSource) -> Result<()> {
+        let task = match self.get_task(id).await {
+            Ok(task) => task,
+            Err(AppError::TaskNotFound(_)) => return Ok(()),
+            Err(e) => return Err(e),
+        };
+
+        let mut tx = self.pool().begin().await?;
+        Self::move_to_bin(&mut tx, &task, source).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Moves several tasks into the recycle bin in one transaction. Returns the ids deleted;
+    /// ids of tasks that do not exist are left out.
+    pub async fn bulk_delete(&self, ids: &[i64], source: AuditSource) -> Result<Vec<i64>> {
+        let tasks = self.get_existing_tasks(ids).await?;
+
+        let mut tx = self.pool().begin().await?;
+        for task in &tasks {
+            Self::move_to_bin(&mut tx, task, source).await?;
+        }
+        tx.commit().await?;
+
+        Ok(tasks.iter().filter_map(|task| task.id).collect())
+    }
+
+    async fn move_to_bin(conn: &mut SqliteConnection, task: &Task, source: AuditSource) -> Result<()> {
+        let id = task.id.ok_or_else(|| AppError::InvalidTask("Task has no id".to_string()))?;
+        let snapshot = serde_json::to_string(task)
+            .map_err(|e| AppError::InvalidTask(format!("Failed to serialize task snapshot: {}", e)))?;
+
+        sqlx::query(
+            r#"
+            INSERT OR REPLACE INTO deleted_tasks (task_id, name, snapshot, url_secret_ref, deleted_at)
+            VALUES (?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(id)
+        .bind(&task.name)
+        .bind(snapshot)
+        .bind(&task.url_secret_ref)
+        .bind(Utc::now().to_rfc3339())
+        .execute(&mut *conn)
+        .await?;
+
+        sqlx::query("DELETE FROM task_steps WHERE task_id = ?")
+            .bind(id)
+            .execute(&mut *conn)
+            .await?;
+
+        sqlx::query("DELETE FROM task_tags WHERE task_id = ?")
+            .bind(id)
+            .execute(&mut *conn)
+            .await?;
+
+        sqlx::query("DELETE FROM task_exclusions WHERE task_id = ?")
+            .bind(id)
+            .execute(&mut *conn)
+            .await?;
+
         sqlx::query("DELETE FROM tasks WHERE id = ?")
             .bind(id)
-            .execute(self.pool())
+            .execute(&mut *conn)
             .await?;
 
+        Self::insert_audit(conn, id, AuditAction::Delete, source, Some(task), None).await
+    }
+
+    /// The tasks among `ids` that exist, once each and in the order given
+    async fn get_existing_tasks(&self, ids: &[i64]) -> Result<Vec<Task>> {
+        let mut tasks: Vec<Task> = Vec::with_capacity(ids.len());
+        for &id in ids {
+            if tasks.iter().any(|task| task.id == Some(id)) {
+                continue;
+            }
+            match self.get_task(id).await {
+                Ok(task) => tasks.push(task),
+                Err(AppError::TaskNotFound(_)) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(tasks)
+    }
+
+    // Session snapshot operations
+
+    pub async fn save_session_snapshot(&self, snapshot: &SessionSnapshot) -> Result<()> {
+        let tabs = serde_json::to_string(&snapshot.tabs)
+            .map_err(|e| AppError::InvalidTask(format!("Failed to serialize session tabs: {}", e)))?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO session_snapshots (name, browser, browser_profile, tabs, saved_at)
+            VALUES (?, ?, ?, ?, ?)
+            ON CONFLICT(name) DO UPDATE SET
+                browser = excluded.browser,
+                browser_profile = excluded.browser_profile,
+                tabs = excluded.tabs,
+                saved_at = excluded.saved_at
+            "#,
+        )
+        .bind(&snapshot.name)
+        .bind(snapshot.browser.to_string())
+        .bind(&snapshot.browser_profile)
+        .bind(tabs)
+        .bind(snapshot.saved_at.to_rfc3339())
+        .execute(&self.pool())
+        .await?;
+
         Ok(())
     }
 
-    fn row_to_task(row: sqlx::sqlite::SqliteRow) -> Result<Task> {
-        let repeat_config = if let Some(interval_str) = row.get::<Option<String>, _>("repeat_interval") {
-            Some(RepeatConfig {
-                interval: RepeatInterval::from_str(&interval_str).map_err(|e| AppError::InvalidTask(e))?,
-                end_after: row.get("repeat_end_after"),
-                end_date: row.get::<Option<String>, _>("repeat_end_date")
-                    .and_then(|s| s.parse().ok()),
-            })
-        } else {
-            None
-        };
+    pub async fn get_session_snapshot(&self, name: &str) -> Result<Option<SessionSnapshot>> {
+        sqlx::query("SELECT * FROM session_snapshots WHERE name = ?")
+            .bind(name)
+            .fetch_optional(&self.pool())
+            .await?
+            .map(Self::row_to_session_snapshot)
+            .transpose()
+    }
 
-        Ok(Task {
-            id: Some(row.get("id")),
+    pub async fn get_session_snapshots(&self) -> Result<Vec<SessionSnapshot>> {
+        let rows = sqlx::query("SELECT * FROM session_snapshots ORDER BY name ASC")
+            .fetch_all(&self.pool())
+            .await?;
+
+        rows.into_iter().map(Self::row_to_session_snapshot).collect()
+    }
+
+    pub async fn delete_session_snapshot(&self, name: &str) -> Result<()> {
+        sqlx::query("DELETE FROM session_snapshots WHERE name = ?")
+            .bind(name)
+            .execute(&self.pool())
+            .await?;
+
+        Ok(())
+    }
+
+    fn row_to_session_snapshot(row: sqlx::sqlite::SqliteRow) -> Result<SessionSnapshot> {
+        Ok(SessionSnapshot {
             name: row.get("name"),
             browser: BrowserType::from_str(&row.get::<String, _>("browser")).map_err(|e| AppError::InvalidTask(e))?,
             browser_profile: row.get("browser_profile"),
-            url: row.get("url"),
-            allow_close_all: row.get("allow_close_all"),
-            start_time: row.get::<String, _>("start_time").parse().map_err(|e| AppError::TimeParse(format!("{}", e)))?,
-            close_time: row.get::<Option<String>, _>("close_time").and_then(|s| s.parse().ok()),
-            timezone: row.get("timezone"),
-            repeat_config,
-            execution_count: row.get("execution_count"),
-            status: TaskStatus::from_str(&row.get::<String, _>("status")).map_err(|e| AppError::InvalidTask(e))?,
-            next_open_execution: row.get::<Option<String>, _>("next_open_execution").and_then(|s| s.parse().ok()),
-            next_close_execution: row.get::<Option<String>, _>("next_close_execution").and_then(|s| s.parse().ok()),
+            tabs: serde_json::from_str(&row.get::<String, _>("tabs"))
+                .map_err(|e| AppError::InvalidTask(format!("Invalid session tabs: {}", e)))?,
+            saved_at: row.get::<String, _>("saved_at").parse().map_err(|e| AppError::TimeParse(format!("{}", e)))?,
         })
     }
 
-    pub async fn get_settings(&self) -> Result<AppSettings> {
-        let rows = sqlx::query("SELECT key, value FROM settings")
-            .fetch_all(self.pool())
+    // Recycle bin operations
+
+    pub async fn get_deleted_tasks(&self) -> Result<Vec<DeletedTask>> {
+        let rows = sqlx::query("SELECT * FROM deleted_tasks ORDER BY deleted_at DESC")
+            .fetch_all(&self.pool())
             .await?;
 
-        let mut settings = AppSettings::default();
+        rows.into_iter().map(Self::row_to_deleted_task).collect()
+    }
 
-        for row in rows {
-            let key: String = row.get("key");
-            let value: String = row.get("value");
-            let bool_value = value == "true";
+    pub async fn is_task_deleted(&self, id: i64) -> Result<bool> {
+        Ok(sqlx::query("SELECT 1 FROM deleted_tasks WHERE task_id = ?")
+            .bind(id)
+            .fetch_optional(&self.pool())
+            .await?
+            .is_some())
+    }
+
+    /// Moves a task out of the recycle bin, under its original id.
+    pub async fn restore_deleted_task(&self, id: i64, source: AuditSource) -> Result<Task> {
+        let row = sqlx::query("SELECT * FROM deleted_tasks WHERE task_id = ?")
+            .bind(id)
+            .fetch_optional(&self.pool())
+            .await?
+            .ok_or(AppError::TaskNotFound(id))?;
+
+        let url_secret_ref: Option<String> = row.get("url_secret_ref");
+        let mut task = Self::row_to_deleted_task(row)?.task;
+        task.id = Some(id);
+        task.url_secret_ref = url_secret_ref;
+
+        let task = self.restore_task(task, source).await?;
+
+        sqlx::query("DELETE FROM deleted_tasks WHERE task_id = ?")
+            .bind(id)
+            .execute(&self.pool())
+            .await?;
+
+        Ok(task)
+    }
+
+    /// Permanently removes tasks from the recycle bin together with their execution history and
+    /// keyring entries: all of them, or only those deleted before `deleted_before`. Returns how
+    /// many were purged.
+    pub async fn purge_deleted_tasks(&self, deleted_before: Option<DateTime<Utc>>) -> Result<u64> {
+        let rows = sqlx::query(
+            "SELECT task_id, url_secret_ref FROM deleted_tasks WHERE ? IS NULL OR deleted_at < ?",
+        )
+        .bind(deleted_before.map(|d| d.to_rfc3339()))
+        .bind(deleted_before.map(|d| d.to_rfc3339()))
+        .fetch_all(&self.pool())
+        .await?;
+
+        for row in &rows {
+            let task_id: i64 = row.get("task_id");
+            if let Some(secret_ref) = row.get::<Option<String>, _>("url_secret_ref") {
+                keyring::delete_secret(&secret_ref)?;
+            }
+
+            let mut tx = self.pool().begin().await?;
+            sqlx::query("DELETE FROM task_executions WHERE task_id = ?")
+                .bind(task_id)
+                .execute(&mut *tx)
+                .await?;
+            sqlx::query("DELETE FROM task_revisions WHERE task_id = ?")
+                .bind(task_id)
+                .execute(&mut *tx)
+                .await?;
+            sqlx::query("DELETE FROM deleted_tasks WHERE task_id = ?")
+                .bind(task_id)
+                .execute(&mut *tx)
+                .await?;
+            tx.commit().await?;
 
-            match key.as_str() {
-                "minimize_to_tray" => settings.minimize_to_tray = bool_value,
-                "start_minimized" => settings.start_minimized = bool_value,
-                "show_notifications" => settings.show_notifications = bool_value,
-                "auto_start" => settings.auto_start = bool_value,
-                _ => {}
+            // The task's managed browser profile goes with it, if it had one
+            let profile_dir = Database::managed_profile_dir(task_id)?;
+            if let Err(e) = std::fs::remove_dir_all(&profile_dir) {
+                if e.kind() != std::io::ErrorKind::NotFound {
+                    println!("⚠ Could not delete profile {}: {}", profile_dir.display(), e);
+                }
             }
         }
 
-        Ok(settings)
+        Ok(rows.len() as u64)
     }
 
-    pub async fn update_setting(&self, key: &str, value: bool) -> Result<()> {
-        let value_str = if value { "true" } else { "false" };
+    /// Purges tasks that have been in the recycle bin longer than the retention setting allows.
+    pub async fn purge_expired_deleted_tasks(&self) -> Result<u64> {
+        let retention_days = self.get_settings().await?.deleted_retention_days;
+        if retention_days == 0 {
+            return Ok(0);
+        }
 
-        sqlx::query("UPDATE settings SET value = ? WHERE key = ?")
-            .bind(value_str)
-            .bind(key)
-            .execute(self.pool())
+        self.purge_deleted_tasks(Some(Utc::now() - chrono::Duration::days(retention_days as i64)))
+            .await
+    }
+
+    fn row_to_deleted_task(row: sqlx::sqlite::SqliteRow) -> Result<DeletedTask> {
+        Ok(DeletedTask {
+            task_id: row.get("task_id"),
+            name: row.get("name"),
+            task: serde_json::from_str(&row.get::<String, _>("snapshot"))
+                .map_err(|e| AppError::InvalidTask(format!("Invalid task snapshot: {}", e)))?,
+            deleted_at: row.get::<String, _>("deleted_at").parse().map_err(|e| AppError::TimeParse(format!("{}", e)))?,
+        })
+    }
+
+    /// Moves the query string of a URL marked secret out of the task, or back into the URL
+    /// when the task stops being secret. A secret URL saved without a query string keeps the
+    /// one already stored. The keyring is only read here; the returned writes are applied once
+    /// the row is saved.
+    async fn prepare_secret_url(task: &mut Task, old_ref: Option<String>) -> Result<SecretUrlWrites> {
+        let mut writes = SecretUrlWrites::default();
+        if task.secret_url_params {
+            let secret_ref = old_ref.unwrap_or_else(secret_url::new_ref);
+            if let Some((stored, query)) = task.url.as_deref().and_then(secret_url::split) {
+                writes.set = Some((secret_ref.clone(), query));
+                task.url = Some(stored);
+            }
+            task.url_secret_ref = Some(secret_ref);
+        } else {
+            if let Some(old_ref) = old_ref {
+                let key = old_ref.clone();
+                let query = keyring_blocking(move || keyring::get_secret(&key)).await?;
+                if let (Some(url), Some(query)) = (task.url.as_deref(), query) {
+                    if secret_url::split(url).is_none() {
+                        task.url = Some(secret_url::join(url, &query));
+                    }
+                }
+                writes.delete = Some(old_ref);
+            }
+            task.url_secret_ref = None;
+        }
+
+        Ok(writes)
+    }
+
+    async fn get_task_steps(&self, task_id: i64) -> Result<Vec<TaskStep>> {
+        let rows = sqlx::query("SELECT * FROM task_steps WHERE task_id = ? ORDER BY position ASC")
+            .bind(task_id)
+            .fetch_all(&self.pool())
+            .await?;
+
+        rows.into_iter()
+            .map(Self::row_to_step)
+            .collect()
+    }
+
+    /// Steps of every task, keyed by task id.
+    async fn get_all_task_steps(&self, task_ids: Option<&[i64]>) -> Result<HashMap<i64, Vec<TaskStep>>> {
+        let sql = format!("SELECT * FROM task_steps{} ORDER BY task_id, position ASC", task_id_filter(task_ids));
+        let rows = bind_task_ids(sqlx::query(&sql), task_ids)
+            .fetch_all(&self.pool())
+            .await?;
+
+        let mut steps: HashMap<i64, Vec<TaskStep>> = HashMap::new();
+        for row in rows {
+            let task_id: i64 = row.get("task_id");
+            steps.entry(task_id).or_default().push(Self::row_to_step(row)?);
+        }
+        Ok(steps)
+    }
+
+    async fn replace_task_steps(&self, task_id: i64, steps: &[TaskStep]) -> Result<()> {
+        let mut tx = self.pool().begin().await?;
+
+        sqlx::query("DELETE FROM task_steps WHERE task_id = ?")
+            .bind(task_id)
+            .execute(&mut *tx)
+            .await?;
+
+        for (position, step) in steps.iter().enumerate() {
+            sqlx::query(
+                r#"
+                INSERT INTO task_steps (task_id, position, action, url, wait_minutes)
+                VALUES (?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(task_id)
+            .bind(position as i64)
+            .bind(step.action.to_string())
+            .bind(&step.url)
+            .bind(step.wait_minutes)
+            .execute(&mut *tx)
             .await?;
+        }
 
+        tx.commit().await?;
         Ok(())
     }
 
-    pub async fn update_settings(&self, settings: AppSettings) -> Result<()> {
-        self.update_setting("minimize_to_tray", settings.minimize_to_tray).await?;
-        self.update_setting("start_minimized", settings.start_minimized).await?;
-        self.update_setting("show_notifications", settings.show_notifications).await?;
-        self.update_setting("auto_start", settings.auto_start).await?;
+    fn row_to_step(row: sqlx::sqlite::SqliteRow) -> Result<TaskStep> {
+        Ok(TaskStep {
+            action: StepAction::from_str(&row.get::<String, _>("action")).map_err(|e| AppError::InvalidTask(e))?,
+            url: row.get("url"),
+            wait_minutes: row.get("wait_minutes"),
+        })
+    }
+
+    async fn get_task_tags(&self, task_id: i64) -> Result<Vec<String>> {
+        let rows = sqlx::query("SELECT tag FROM task_tags WHERE task_id = ? ORDER BY tag ASC")
+            .bind(task_id)
+            .fetch_all(&self.pool())
+            .await?;
+
+        Ok(rows.iter().map(|row| row.get("tag")).collect())
+    }
+
+    /// Tags of every task, keyed by task id.
+    async fn get_all_task_tags(&self, task_ids: Option<&[i64]>) -> Result<HashMap<i64, Vec<String>>> {
+        let sql = format!("SELECT task_id, tag FROM task_tags{} ORDER BY task_id, tag ASC", task_id_filter(task_ids));
+        let rows = bind_task_ids(sqlx::query(&sql), task_ids)
+            .fetch_all(&self.pool())
+            .await?;
+
+        let mut tags: HashMap<i64, Vec<String>> = HashMap::new();
+        for row in rows {
+            tags.entry(row.get("task_id")).or_default().push(row.get("tag"));
+        }
+        Ok(tags)
+    }
+
+    async fn replace_task_tags(&self, task_id: i64, tags: &[String]) -> Result<()> {
+        let mut tx = self.pool().begin().await?;
+
+        sqlx::query("DELETE FROM task_tags WHERE task_id = ?")
+            .bind(task_id)
+            .execute(&mut *tx)
+            .await?;
+
+        for tag in tags {
+            sqlx::query("INSERT OR IGNORE INTO task_tags (task_id, tag) VALUES (?, ?)")
+                .bind(task_id)
+                .bind(tag)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
         Ok(())
     }
+
+    async fn get_task_exclusions(&self, task_id: i64) -> Result<Vec<DateExclusion>> {
+        let rows = sqlx::query("SELECT * FROM task_exclusions WHERE task_id = ? ORDER BY start_date ASC, id ASC")
+            .bind(task_id)
+            .fetch_all(&self.pool())
+            .await?;
+
+        rows.into_iter().map(Self::row_to_exclusion).collect()
+    }
+
+    /// Exclusions of every task, keyed by task id.
+    async fn get_all_task_exclusions(&self, task_ids: Option<&[i64]>) -> Result<HashMap<i64, Vec<DateExclusion>>> {
+        let sql = format!(
+            "SELECT * FROM task_exclusions{} ORDER BY task_id, start_date ASC, id ASC",
+            task_id_filter(task_ids)
+        );
+        let rows = bind_task_ids(sqlx::query(&sql), task_ids)
+            .fetch_all(&self.pool())
+            .await?;
+
+        let mut exclusions: HashMap<i64, Vec<DateExclusion>> = HashMap::new();
+        for row in rows {
+            let task_id: i64 = row.get("task_id");
+            exclusions.entry(task_id).or_default().push(Self::row_to_exclusion(row)?);
+        }
+        Ok(exclusions)
+    }
+
+    async fn replace_task_exclusions(&self, task_id: i64, exclusions: &[DateExclusion]) -> Result<()> {
+        let mut tx = self.pool().begin().await?;
+
+        sqlx::query("DELETE FROM task_exclusions WHERE task_id = ?")
+            .bind(task_id)
+            .execute(&mut *tx)
+            .await?;
+
+        for exclusion in exclusions {
+            sqlx::query("INSERT INTO task_exclusions (task_id, start_date, end_date) VALUES (?, ?, ?)")
+                .bind(task_id)
+                .bind(exclusion.start.to_string())
+                .bind(exclusion.end.map(|end| end.to_string()))
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    fn row_to_exclusion(row: sqlx::sqlite::SqliteRow) -> Result<DateExclusion> {
+        let parse = |value: String| value.parse().map_err(|e| AppError::TimeParse(format!("{}", e)));
+        Ok(DateExclusion {
+            start: parse(row.get("start_date"))?,
+            end: row.get::<Option<String>, _>("end_date").map(parse).transpose()?,
+        })
+    }
+
+    /// Every tag in use with the number of tasks carrying it, alphabetically.
+    pub async fn get_tags(&self) -> Result<Vec<TagCount>> {
+        let rows = sqlx::query("SELECT tag, COUNT(*) AS task_count FROM task_tags GROUP BY tag ORDER BY tag ASC")
+            .fetch_all(&self.pool())
+            .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| TagCount { tag: row.get("tag"), task_count: row.get("task_count") })
+            .collect())
+    }
+
+    /// Tasks carrying `tag`, in start time order. Tags match regardless of case.
+    pub async fn get_tasks_by_tag(&self, tag: &str) -> Result<Vec<Task>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT tasks.* FROM tasks
+            JOIN task_tags ON task_tags.task_id = tasks.id
+            WHERE task_tags.tag = ?
+            ORDER BY tasks.start_time ASC
+            "#,
+        )
+        .bind(validation::normalize_tag(tag))
+        .fetch_all(&self.pool())
+        .await?;
+
+        let mut tasks = Vec::with_capacity(rows.len());
+        for row in rows {
+            let mut task = Self::row_to_task(row)?;
+            if let Some(id) = task.id {
+                task.steps = self.get_task_steps(id).await?;
+                task.tags = self.get_task_tags(id).await?;
+                task.exclusions = self.get_task_exclusions(id).await?;
+            }
+            tasks.push(task);
+        }
+        Ok(tasks)
+    }
+
+    /// Claims an execution slot for the given occurrence. An occurrence whose last try failed
+    /// can be claimed again by its retry.
+    ///
+    /// Returns `None` if this (task, action, occurrence) was already claimed, meaning the
+    /// occurrence has fired before and must not be executed again.
+    pub async fn begin_execution(
+        &self,
+        task_id: i64,
+        action: &ExecutionAction,
+        occurrence: Option<DateTime<Utc>>,
+    ) -> Result<Option<i64>> {
+        let row = sqlx::query(
+            r#"
+            INSERT INTO task_executions (task_id, action, occurrence, status, executed_at)
+            VALUES (?, ?, ?, ?, ?)
+            ON CONFLICT(task_id, action, occurrence) DO UPDATE SET
+                status = excluded.status,
+                error = NULL,
+                executed_at = excluded.executed_at,
+                pid = NULL,
+                exit_code = NULL
+            WHERE task_executions.status = ?
+            RETURNING id
+            "#,
+        )
+        .bind(task_id)
+        .bind(action.to_string())
+        .bind(occurrence.map(|d| d.to_rfc3339()))
+        .bind(ExecutionStatus::Running.to_string())
+        .bind(Utc::now().to_rfc3339())
+        .bind(ExecutionStatus::Failed.to_string())
+        .fetch_optional(&self.pool())
+        .await?;
+
+        Ok(row.map(|row| row.get("id")))
+    }
+
+    pub async fn finish_execution(
+        &self,
+        execution_id: i64,
+        status: ExecutionStatus,
+        error: Option<String>,
+    ) -> Result<TaskExecution> {
+        sqlx::query("UPDATE task_executions SET status = ?, error = ? WHERE id = ?")
+            .bind(status.to_string())
+            .bind(error)
+            .bind(execution_id)
+            .execute(&self.pool())
+            .await?;
+
+        self.get_execution(execution_id).await
+    }
+
+    /// Stores how the browser process started by an execution exited.
+    pub async fn record_process_exit(&self, execution_id: i64, pid: u32, exit_code: Option<i32>) -> Result<()> {
+        sqlx::query("UPDATE task_executions SET pid = ?, exit_code = ? WHERE id = ?")
+            .bind(pid as i64)
+            .bind(exit_code)
+            .bind(execution_id)
+            .execute(&self.pool())
+            .await?;
+        Ok(())
+    }
+
+    /// Stamps the duration on the latest successful open that has not been closed yet.
+    pub async fn record_open_duration(&self, task_id: i64, closed_at: DateTime<Utc>) -> Result<Option<i64>> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, executed_at FROM task_executions
+            WHERE task_id = ? AND action = 'open' AND status = 'success' AND duration_secs IS NULL
+            ORDER BY executed_at DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(task_id)
+        .fetch_optional(&self.pool())
+        .await?;
+
+        let Some(row) = row else { return Ok(None) };
+
+        let opened_at: DateTime<Utc> = row
+            .get::<String, _>("executed_at")
+            .parse()
+            .map_err(|e| AppError::TimeParse(format!("{}", e)))?;
+        let duration_secs = (closed_at - opened_at).num_seconds().max(0);
+
+        sqlx::query("UPDATE task_executions SET duration_secs = ? WHERE id = ?")
+            .bind(duration_secs)
+            .bind(row.get::<i64, _>("id"))
+            .execute(&self.pool())
+            .await?;
+
+        Ok(Some(duration_secs))
+    }
+
+    /// Total time each task's pages were open between `from` and `to`.
+    pub async fn get_usage_stats(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<TaskUsage>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT e.task_id, t.name AS task_name,
+                COUNT(*) AS open_count,
+                COALESCE(SUM(e.duration_secs), 0) AS total_open_secs
+            FROM task_executions e
+            JOIN tasks t ON t.id = e.task_id
+            WHERE e.action = 'open' AND e.status = 'success'
+                AND e.executed_at >= ? AND e.executed_at < ?
+            GROUP BY e.task_id, t.name
+            ORDER BY total_open_secs DESC
+            "#,
+        )
+        .bind(from.to_rfc3339())
+        .bind(to.to_rfc3339())
+        .fetch_all(&self.pool())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| TaskUsage {
+                task_id: row.get("task_id"),
+                task_name: row.get("task_name"),
+                open_count: row.get("open_count"),
+                total_open_secs: row.get("total_open_secs"),
+            })
+            .collect())
+    }
+
+    pub async fn get_execution_summary(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<TaskExecutionSummary>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT e.task_id, t.name AS task_name,
+                COALESCE(SUM(e.action = 'open' AND e.status = 'success'), 0) AS opens,
+                COALESCE(SUM(e.action = 'close' AND e.status = 'success'), 0) AS closes,
+                COALESCE(SUM(e.status = 'failed'), 0) AS failures,
+                COALESCE(SUM(e.duration_secs), 0) AS total_open_secs
+            FROM task_executions e
+            JOIN tasks t ON t.id = e.task_id
+            WHERE e.executed_at >= ? AND e.executed_at < ?
+            GROUP BY e.task_id, t.name
+            ORDER BY t.name ASC
+            "#,
+        )
+        .bind(from.to_rfc3339())
+        .bind(to.to_rfc3339())
+        .fetch_all(&self.pool())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| TaskExecutionSummary {
+                task_id: row.get("task_id"),
+                task_name: row.get("task_name"),
+                opens: row.get("opens"),
+                closes: row.get("closes"),
+                failures: row.get("failures"),
+                total_open_secs: row.get("total_open_secs"),
+            })
+            .collect())
+    }
+
+    pub async fn get_execution(&self, id: i64) -> Result<TaskExecution> {
+        let row = sqlx::query("SELECT * FROM task_executions WHERE id = ?")
+            .bind(id)
+            .fetch_one(&self.pool())
+            .await?;
+
+        Self::row_to_execution(row)
+    }
+
+    /// A page of a task's executions, newest first, with the total count for the UI to size
+    /// its list.
+    pub async fn get_task_executions(&self, task_id: i64, limit: i64, offset: i64) -> Result<ExecutionPage> {
+        let total: i64 = sqlx::query("SELECT COUNT(*) FROM task_executions WHERE task_id = ?")
+            .bind(task_id)
+            .fetch_one(&self.pool())
+            .await?
+            .get(0);
+
+        let rows = sqlx::query(
+            r#"
+            SELECT * FROM task_executions
+            WHERE task_id = ?
+            ORDER BY executed_at DESC, id DESC
+            LIMIT ? OFFSET ?
+            "#,
+        )
+        .bind(task_id)
+        .bind(limit)
+        .bind(offset.max(0))
+        .fetch_all(&self.pool())
+        .await?;
+
+        let items = rows.into_iter().map(Self::row_to_execution).collect::<Result<Vec<_>>>()?;
+        Ok(ExecutionPage { items, total })
+    }
+
+    /// Skipped scheduled opens of each task, oldest first; the exceptions of its recurrence.
+    pub async fn get_skipped_occurrences(&self) -> Result<HashMap<i64, Vec<DateTime<Utc>>>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT task_id, occurrence FROM task_executions
+            WHERE action = 'open' AND status = 'skipped' AND occurrence IS NOT NULL
+            ORDER BY occurrence
+            "#,
+        )
+        .fetch_all(&self.pool())
+        .await?;
+
+        let mut skipped: HashMap<i64, Vec<DateTime<Utc>>> = HashMap::new();
+        for row in rows {
+            if let Ok(occurrence) = row.get::<String, _>("occurrence").parse() {
+                skipped.entry(row.get("task_id")).or_default().push(occurrence);
+            }
+        }
+        Ok(skipped)
+    }
+
+    /// The latest executions across all tasks, newest first.
+    pub async fn get_recent_executions(&self, limit: i64) -> Result<Vec<TaskExecution>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT * FROM task_executions
+            ORDER BY executed_at DESC, id DESC
+            LIMIT ?
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(&self.pool())
+        .await?;
+
+        rows.into_iter().map(Self::row_to_execution).collect()
+    }
+
+    /// Sets a task's status without touching its schedule, e.g. to pause or resume it.
+    /// Reactivating a task also clears its consecutive failure count.
+    pub async fn set_task_status(&self, id: i64, status: TaskStatus, source: AuditSource) -> Result<Task> {
+        let before = self.get_task(id).await?;
+
+        let result = sqlx::query(
+            r#"
+            UPDATE tasks SET
+                status = ?,
+                consecutive_failures = CASE WHEN ? THEN 0 ELSE consecutive_failures END,
+                updated_at = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(status.to_string())
+        .bind(status == TaskStatus::Active)
+        .bind(Utc::now().to_rfc3339())
+        .bind(id)
+        .execute(&self.pool())
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::TaskNotFound(id));
+        }
+
+        let after = self.get_task(id).await?;
+        self.record_audit(id, AuditAction::StatusChange, source, Some(&before), Some(&after)).await?;
+        Ok(after)
+    }
+
+    /// Sets the status of several tasks in one transaction, as `set_task_status` does for one.
+    /// Returns the updated tasks; ids of tasks that do not exist are left out.
+    pub async fn bulk_update_status(&self, ids: &[i64], status: TaskStatus, source: AuditSource) -> Result<Vec<Task>> {
+        let before = self.get_existing_tasks(ids).await?;
+        let now = Utc::now();
+
+        let mut tx = self.pool().begin().await?;
+        let mut updated = Vec::with_capacity(before.len());
+        for old in before {
+            let mut task = old.clone();
+            task.status = status.clone();
+            if status == TaskStatus::Active {
+                task.consecutive_failures = 0;
+            }
+            task.updated_at = Some(now);
+
+            sqlx::query("UPDATE tasks SET status = ?, consecutive_failures = ?, updated_at = ? WHERE id = ?")
+                .bind(task.status.to_string())
+                .bind(task.consecutive_failures)
+                .bind(now.to_rfc3339())
+                .bind(task.id)
+                .execute(&mut *tx)
+                .await?;
+
+            if let Some(id) = task.id {
+                Self::insert_audit(&mut tx, id, AuditAction::StatusChange, source, Some(&old), Some(&task)).await?;
+            }
+            updated.push(task);
+        }
+        tx.commit().await?;
+
+        Ok(updated)
+    }
+
+    /// Moves the schedule of several tasks by `minutes`, earlier when negative, in one
+    /// transaction. Pending opens and closes move with it. Returns the updated tasks; ids of
+    /// tasks that do not exist are left out.
+    pub async fn bulk_shift_time(&self, ids: &[i64], minutes: i64, source: AuditSource) -> Result<Vec<Task>> {
+        let shift = chrono::Duration::minutes(minutes);
+        let before = self.get_existing_tasks(ids).await?;
+        let now = Utc::now();
+
+        let mut tx = self.pool().begin().await?;
+        let mut updated = Vec::with_capacity(before.len());
+        for old in before {
+            let Some(id) = old.id else { continue };
+            let mut task = old.clone();
+            task.start_time += shift;
+            task.close_time = task.close_time.map(|close| close + shift);
+            task.next_open_execution = task.next_open_execution.map(|open| open + shift);
+            task.next_close_execution = task.next_close_execution.map(|close| close + shift);
+            task.updated_at = Some(now);
+
+            sqlx::query(
+                r#"
+                UPDATE tasks SET
+                    start_time = ?, close_time = ?,
+                    next_open_execution = ?, next_close_execution = ?,
+                    updated_at = ?
+                WHERE id = ?
+                "#,
+            )
+            .bind(task.start_time.to_rfc3339())
+            .bind(task.close_time.map(|d| d.to_rfc3339()))
+            .bind(task.next_open_execution.map(|d| d.to_rfc3339()))
+            .bind(task.next_close_execution.map(|d| d.to_rfc3339()))
+            .bind(now.to_rfc3339())
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+
+            Self::insert_audit(&mut tx, id, AuditAction::Update, source, Some(&old), Some(&task)).await?;
+
+            // As in `update_task`, tasks without revisions start with the state just replaced
+            let has_revisions = sqlx::query("SELECT 1 FROM task_revisions WHERE task_id = ? LIMIT 1")
+                .bind(id)
+                .fetch_optional(&mut *tx)
+                .await?
+                .is_some();
+            if !has_revisions {
+                Self::insert_revision(&mut tx, id, source, &old).await?;
+            }
+            Self::insert_revision(&mut tx, id, source, &task).await?;
+
+            updated.push(task);
+        }
+        tx.commit().await?;
+
+        Ok(updated)
+    }
+
+    // Audit log operations
+
+    async fn record_audit(
+        &self,
+        task_id: i64,
+        action: AuditAction,
+        source: AuditSource,
+        before: Option<&Task>,
+        after: Option<&Task>,
+    ) -> Result<()> {
+        let mut conn = self.pool().acquire().await?;
+        Self::insert_audit(&mut conn, task_id, action, source, before, after).await
+    }
+
+    /// Writes an audit entry on `conn`, so it can be part of a transaction.
+    async fn insert_audit(
+        conn: &mut SqliteConnection,
+        task_id: i64,
+        action: AuditAction,
+        source: AuditSource,
+        before: Option<&Task>,
+        after: Option<&Task>,
+    ) -> Result<()> {
+        let snapshot = |task: Option<&Task>| {
+            task.map(serde_json::to_string)
+                .transpose()
+                .map_err(|e| AppError::InvalidTask(format!("Failed to serialize task snapshot: {}", e)))
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO audit_log (task_id, action, source, before, after, created_at)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(task_id)
+        .bind(action.to_string())
+        .bind(source.to_string())
+        .bind(snapshot(before)?)
+        .bind(snapshot(after)?)
+        .bind(Utc::now().to_rfc3339())
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_audit_entry(&self, id: i64) -> Result<AuditEntry> {
+        let row = sqlx::query("SELECT * FROM audit_log WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&self.pool())
+            .await?
+            .ok_or_else(|| AppError::InvalidTask(format!("Audit entry {} not found", id)))?;
+
+        Self::row_to_audit_entry(row)
+    }
+
+    /// Id of the most recent change made to a task from the UI.
+    pub async fn latest_ui_audit_id(&self, task_id: i64) -> Result<Option<i64>> {
+        let id = sqlx::query("SELECT MAX(id) AS id FROM audit_log WHERE task_id = ? AND source = 'ui'")
+            .bind(task_id)
+            .fetch_one(&self.pool())
+            .await?
+            .get("id");
+
+        Ok(id)
+    }
+
+    /// Saves `task` as the next revision of the task, for restoring it later.
+    async fn record_revision(&self, task_id: i64, source: AuditSource, task: &Task) -> Result<()> {
+        let mut conn = self.pool().acquire().await?;
+        Self::insert_revision(&mut conn, task_id, source, task).await
+    }
+
+    /// Saves a revision on `conn`, so it can be part of a transaction.
+    async fn insert_revision(conn: &mut SqliteConnection, task_id: i64, source: AuditSource, task: &Task) -> Result<()> {
+        let snapshot = serde_json::to_string(task)
+            .map_err(|e| AppError::InvalidTask(format!("Failed to serialize task snapshot: {}", e)))?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO task_revisions (task_id, revision, source, snapshot, created_at)
+            SELECT ?, COALESCE(MAX(revision), 0) + 1, ?, ?, ?
+            FROM task_revisions WHERE task_id = ?
+            "#,
+        )
+        .bind(task_id)
+        .bind(source.to_string())
+        .bind(snapshot)
+        .bind(Utc::now().to_rfc3339())
+        .bind(task_id)
+        .execute(&mut *conn)
+        .await?;
+
+        // Keep the newest revisions; numbering carries on from the highest
+        sqlx::query(
+            r#"
+            DELETE FROM task_revisions
+            WHERE task_id = ? AND revision <= (SELECT MAX(revision) FROM task_revisions WHERE task_id = ?) - ?
+            "#,
+        )
+        .bind(task_id)
+        .bind(task_id)
+        .bind(MAX_REVISIONS_PER_TASK)
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Saved versions of a task, newest first.
+    pub async fn get_task_revisions(&self, task_id: i64) -> Result<Vec<TaskRevision>> {
+        let rows = sqlx::query("SELECT * FROM task_revisions WHERE task_id = ? ORDER BY revision DESC")
+            .bind(task_id)
+            .fetch_all(&self.pool())
+            .await?;
+
+        rows.into_iter()
+            .map(|row| {
+                Ok(TaskRevision {
+                    task_id: row.get("task_id"),
+                    revision: row.get("revision"),
+                    source: AuditSource::from_str(&row.get::<String, _>("source")).map_err(AppError::InvalidTask)?,
+                    task: serde_json::from_str(&row.get::<String, _>("snapshot"))
+                        .map_err(|e| AppError::InvalidTask(format!("Invalid task snapshot: {}", e)))?,
+                    created_at: row
+                        .get::<String, _>("created_at")
+                        .parse()
+                        .map_err(|e| AppError::TimeParse(format!("{}", e)))?,
+                })
+            })
+            .collect()
+    }
+
+    /// Puts a task's configuration back to how it was at `revision`. Run state such as
+    /// execution counts and the current status is kept; the restore becomes a new revision.
+    pub async fn restore_task_revision(&self, task_id: i64, revision: i64, source: AuditSource) -> Result<Task> {
+        let row = sqlx::query("SELECT snapshot FROM task_revisions WHERE task_id = ? AND revision = ?")
+            .bind(task_id)
+            .bind(revision)
+            .fetch_optional(&self.pool())
+            .await?
+            .ok_or_else(|| AppError::InvalidTask(format!("Task {} has no revision {}", task_id, revision)))?;
+        let mut restored: Task = serde_json::from_str(&row.get::<String, _>("snapshot"))
+            .map_err(|e| AppError::InvalidTask(format!("Invalid task snapshot: {}", e)))?;
+
+        let current = self.get_task(task_id).await?;
+        restored.id = current.id;
+        restored.status = current.status;
+        restored.execution_count = current.execution_count;
+        restored.consecutive_failures = current.consecutive_failures;
+        restored.last_success_at = current.last_success_at;
+        restored.pomodoro_cycle = current.pomodoro_cycle;
+        restored.current_step = current.current_step;
+        restored.next_open_execution = current.next_open_execution;
+        restored.next_close_execution = current.next_close_execution;
+        restored.updated_at = current.updated_at;
+
+        self.update_task(task_id, restored, false, source).await
+    }
+
+    /// Audit entries, newest first, optionally for a single task.
+    pub async fn get_audit_log(&self, task_id: Option<i64>, limit: i64) -> Result<Vec<AuditEntry>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT * FROM audit_log
+            WHERE ? IS NULL OR task_id = ?
+            ORDER BY created_at DESC, id DESC
+            LIMIT ?
+            "#,
+        )
+        .bind(task_id)
+        .bind(task_id)
+        .bind(limit)
+        .fetch_all(&self.pool())
+        .await?;
+
+        rows.into_iter().map(Self::row_to_audit_entry).collect()
+    }
+
+    fn row_to_audit_entry(row: sqlx::sqlite::SqliteRow) -> Result<AuditEntry> {
+        let snapshot = |column: &str| -> Result<Option<Task>> {
+            row.get::<Option<String>, _>(column)
+                .map(|json| serde_json::from_str(&json))
+                .transpose()
+                .map_err(|e| AppError::InvalidTask(format!("Invalid task snapshot: {}", e)))
+        };
+
+        Ok(AuditEntry {
+            id: row.get("id"),
+            task_id: row.get("task_id"),
+            action: AuditAction::from_str(&row.get::<String, _>("action")).map_err(|e| AppError::InvalidTask(e))?,
+            source: AuditSource::from_str(&row.get::<String, _>("source")).map_err(|e| AppError::InvalidTask(e))?,
+            before: snapshot("before")?,
+            after: snapshot("after")?,
+            created_at: row.get::<String, _>("created_at").parse().map_err(|e| AppError::TimeParse(format!("{}", e)))?,
+        })
+    }
+
+    pub async fn get_dashboard(&self, day_start: DateTime<Utc>, day_end: DateTime<Utc>) -> Result<Dashboard> {
+        let counts = sqlx::query(
+            r#"
+            SELECT
+                COALESCE(SUM(status = 'active'), 0) AS active_count,
+                COALESCE(SUM(status = 'paused'), 0) AS paused_count,
+                COALESCE(SUM(status IN ('failed', 'disabled')), 0) AS failed_count
+            FROM tasks
+            "#,
+        )
+        .fetch_one(&self.pool())
+        .await?;
+
+        let completed_today = sqlx::query(
+            r#"
+            SELECT * FROM task_executions
+            WHERE status = 'success' AND executed_at >= ? AND executed_at < ?
+            ORDER BY executed_at DESC
+            "#,
+        )
+        .bind(day_start.to_rfc3339())
+        .bind(day_end.to_rfc3339())
+        .fetch_all(&self.pool())
+        .await?
+        .into_iter()
+        .map(Self::row_to_execution)
+        .collect::<Result<Vec<_>>>()?;
+
+        let now = Utc::now();
+        let mut upcoming_today = Vec::new();
+        for task in self.get_all_tasks().await? {
+            if task.status != TaskStatus::Active {
+                continue;
+            }
+            let task_id = task.id.unwrap_or_default();
+            let pending = [
+                (ExecutionAction::Open, task.next_open_execution),
+                (ExecutionAction::Close, task.next_close_execution),
+            ];
+            for (action, scheduled_at) in pending {
+                if let Some(at) = scheduled_at.filter(|at| *at >= now && *at < day_end) {
+                    upcoming_today.push(UpcomingExecution {
+                        task_id,
+                        task_name: task.name.clone(),
+                        action,
+                        scheduled_at: at,
+                    });
+                }
+            }
+        }
+        upcoming_today.sort_by_key(|u| u.scheduled_at);
+
+        let next_action = self.get_next_action().await?.and_then(|(task, action)| {
+            let scheduled_at = match action {
+                ExecutionAction::Open => task.next_open_execution,
+                ExecutionAction::Close => task.next_close_execution,
+            }?;
+            Some(UpcomingExecution {
+                task_id: task.id?,
+                task_name: task.name,
+                action,
+                scheduled_at,
+            })
+        });
+
+        Ok(Dashboard {
+            active_count: counts.get("active_count"),
+            paused_count: counts.get("paused_count"),
+            failed_count: counts.get("failed_count"),
+            completed_today,
+            upcoming_today,
+            next_action,
+        })
+    }
+
+    fn row_to_execution(row: sqlx::sqlite::SqliteRow) -> Result<TaskExecution> {
+        Ok(TaskExecution {
+            id: row.get("id"),
+            task_id: row.get("task_id"),
+            action: ExecutionAction::from_str(&row.get::<String, _>("action")).map_err(|e| AppError::InvalidTask(e))?,
+            occurrence: row.get::<Option<String>, _>("occurrence").and_then(|s| s.parse().ok()),
+            status: ExecutionStatus::from_str(&row.get::<String, _>("status")).map_err(|e| AppError::InvalidTask(e))?,
+            error: row.get("error"),
+            executed_at: row.get::<String, _>("executed_at").parse().map_err(|e| AppError::TimeParse(format!("{}", e)))?,
+            duration_secs: row.get("duration_secs"),
+            pid: row.get("pid"),
+            exit_code: row.get("exit_code"),
+        })
+    }
+
+    /// Scheduler saves only move bookkeeping on, so the schemes the task already uses stay
+    /// allowed for them even after they were removed from the settings.
+    async fn validate_task_fields(&self, task: &Task, source: AuditSource) -> Result<()> {
+        let settings = self.get_settings().await?;
+        let mut schemes = settings.extra_url_schemes();
+        let own_schemes: Vec<String> = match source {
+            AuditSource::Scheduler => task
+                .url
+                .iter()
+                .chain(task.steps.iter().filter_map(|step| step.url.as_ref()))
+                .filter_map(|url| validation::url_scheme(url))
+                .collect(),
+            _ => Vec::new(),
+        };
+        schemes.extend(own_schemes.iter().map(String::as_str));
+        task_validation::ensure_valid(task, &schemes)
+    }
+
+    fn row_to_task(row: sqlx::sqlite::SqliteRow) -> Result<Task> {
+        let repeat_config = if let Some(interval_str) = row.get::<Option<String>, _>("repeat_interval") {
+            Some(RepeatConfig {
+                interval: RepeatInterval::from_str(&interval_str).map_err(|e| AppError::InvalidTask(e))?,
+                end_after: row.get("repeat_end_after"),
+                end_date: row.get::<Option<String>, _>("repeat_end_date")
+                    .and_then(|s| s.parse().ok()),
+            })
+        } else {
+            None
+        };
+
+        let pomodoro = match (
+            row.get::<Option<i32>, _>("pomodoro_work_minutes"),
+            row.get::<Option<i32>, _>("pomodoro_break_minutes"),
+            row.get::<Option<i32>, _>("pomodoro_cycles"),
+        ) {
+            (Some(work_minutes), Some(break_minutes), Some(cycles)) => Some(PomodoroConfig {
+                work_minutes,
+                break_minutes,
+                cycles,
+            }),
+            _ => None,
+        };
+
+        Ok(Task {
+            id: Some(row.get("id")),
+            name: row.get("name"),
+            browser: BrowserType::from_str(&row.get::<String, _>("browser")).map_err(|e| AppError::InvalidTask(e))?,
+            browser_profile: row.get("browser_profile"),
+            url: row.get("url"),
+            allow_close_all: row.get("allow_close_all"),
+            start_time: row.get::<String, _>("start_time").parse().map_err(|e| AppError::TimeParse(format!("{}", e)))?,
+            close_time: row.get::<Option<String>, _>("close_time").and_then(|s| s.parse().ok()),
+            timezone: row.get("timezone"),
+            repeat_config,
+            pomodoro,
+            execution_count: row.get("execution_count"),
+            status: TaskStatus::from_str(&row.get::<String, _>("status")).map_err(|e| AppError::InvalidTask(e))?,
+            next_open_execution: row.get::<Option<String>, _>("next_open_execution").and_then(|s| s.parse().ok()),
+            next_close_execution: row.get::<Option<String>, _>("next_close_execution").and_then(|s| s.parse().ok()),
+            consecutive_failures: row.get("consecutive_failures"),
+            last_success_at: row.get::<Option<String>, _>("last_success_at").and_then(|s| s.parse().ok()),
+            kind: TaskKind::from_str(&row.get::<String, _>("kind")).map_err(|e| AppError::InvalidTask(e))?,
+            blocked_domains: row.get::<Option<String>, _>("blocked_domains").map(|s| s.split(',').map(str::to_string).collect()).unwrap_or_default(),
+            pomodoro_cycle: row.get("pomodoro_cycle"),
+            minimize_browsers: row.get("minimize_browsers"),
+            steps: Vec::new(),
+            tags: Vec::new(),
+            exclusions: Vec::new(),
+            current_step: row.get("current_step"),
+            workspace_id: row.get("workspace_id"),
+            secret_url_params: row.get::<Option<String>, _>("url_secret_ref").is_some(),
+            url_secret_ref: row.get("url_secret_ref"),
+            session_name: row.get("session_name"),
+            target_display: row.get::<Option<i64>, _>("target_display").map(|i| i as u32),
+            window_state: WindowState::from_str(&row.get::<String, _>("window_state")).map_err(|e| AppError::InvalidTask(e))?,
+            zoom: row.get("zoom"),
+            user_agent: row.get("user_agent"),
+            lang: row.get("lang"),
+            fresh_session: row.get("fresh_session"),
+            managed_profile: row.get("managed_profile"),
+            prelaunch_minutes: row.get::<Option<i64>, _>("prelaunch_minutes").map(|m| m as u32),
+            jitter_minutes: row.get::<Option<i64>, _>("jitter_minutes").map(|m| m as u32),
+            missed_policy: row
+                .get::<Option<String>, _>("missed_policy")
+                .map(|p| MissedPolicy::from_str(&p))
+                .transpose()
+                .map_err(AppError::InvalidTask)?,
+            retry_max_attempts: row.get::<Option<i64>, _>("retry_max_attempts").map(|n| n as u32),
+            retry_backoff_secs: row.get::<Option<i64>, _>("retry_backoff_secs").map(|s| s as u32),
+            retry_attempt: row.get("retry_attempt"),
+            pending_occurrence: row.get::<Option<String>, _>("pending_occurrence").and_then(|s| s.parse().ok()),
+            duration_minutes: row.get::<Option<i64>, _>("duration_minutes").map(|m| m as u32),
+            window_position: row.get::<Option<String>, _>("window_position").and_then(|s| s.parse().ok()),
+            window_size: row.get::<Option<String>, _>("window_size").and_then(|s| s.parse().ok()),
+            kiosk: row.get("kiosk"),
+            app_mode: row.get("app_mode"),
+            description: row.get("description"),
+            launcher: LauncherKind::from_str(&row.get::<String, _>("launcher")).map_err(AppError::InvalidTask)?,
+            updated_at: row.get::<Option<String>, _>("updated_at").and_then(|s| s.parse().ok()),
+        })
+    }
+
+    pub async fn get_scheduler_state(&self) -> Result<SchedulerState> {
+        let row = sqlx::query("SELECT * FROM scheduler_state WHERE id = 1")
+            .fetch_optional(&self.pool())
+            .await?;
+
+        let Some(row) = row else {
+            return Ok(SchedulerState::default());
+        };
+
+        Ok(SchedulerState {
+            started_at: row.get::<Option<String>, _>("started_at").and_then(|s| s.parse().ok()),
+            last_tick_at: row.get::<Option<String>, _>("last_tick_at").and_then(|s| s.parse().ok()),
+            executions_since_start: row.get("executions_since_start"),
+            last_error: row.get("last_error"),
+            last_error_at: row.get::<Option<String>, _>("last_error_at").and_then(|s| s.parse().ok()),
+        })
+    }
+
+    /// Resets the scheduler state for a fresh start of the loop.
+    pub async fn record_scheduler_start(&self, started_at: DateTime<Utc>) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO scheduler_state (id, started_at, last_tick_at, executions_since_start, last_error, last_error_at)
+            VALUES (1, ?, ?, 0, NULL, NULL)
+            ON CONFLICT(id) DO UPDATE SET
+                started_at = excluded.started_at,
+                last_tick_at = excluded.last_tick_at,
+                executions_since_start = 0,
+                last_error = NULL,
+                last_error_at = NULL
+            "#,
+        )
+        .bind(started_at.to_rfc3339())
+        .bind(started_at.to_rfc3339())
+        .execute(&self.pool())
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn record_scheduler_tick(&self, at: DateTime<Utc>) -> Result<()> {
+        sqlx::query("UPDATE scheduler_state SET last_tick_at = ? WHERE id = 1")
+            .bind(at.to_rfc3339())
+            .execute(&self.pool())
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn record_scheduler_execution(&self) -> Result<()> {
+        sqlx::query("UPDATE scheduler_state SET executions_since_start = executions_since_start + 1 WHERE id = 1")
+            .execute(&self.pool())
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn record_scheduler_error(&self, message: &str, at: DateTime<Utc>) -> Result<()> {
+        sqlx::query("UPDATE scheduler_state SET last_error = ?, last_error_at = ? WHERE id = 1")
+            .bind(message)
+            .bind(at.to_rfc3339())
+            .execute(&self.pool())
+            .await?;
+
+        Ok(())
+    }
+
+    /// End of the pause set with `pause_scheduler_until`; `None` when scheduling is not paused.
+    pub async fn get_paused_until(&self) -> Result<Option<DateTime<Utc>>> {
+        Ok(self
+            .get_setting_value("paused_until")
+            .await?
+            .and_then(|s| s.parse().ok()))
+    }
+
+    pub async fn set_paused_until(&self, until: Option<DateTime<Utc>>) -> Result<()> {
+        let value = until.map(|at| at.to_rfc3339()).unwrap_or_default();
+        self.update_setting_value("paused_until", &value).await
+    }
+
+    pub async fn create_notification(
+        &self,
+        kind: NotificationKind,
+        task_id: Option<i64>,
+        title: &str,
+        body: &str,
+    ) -> Result<AppNotification> {
+        let created_at = Utc::now();
+
+        let result = sqlx::query(
+            r#"
+            INSERT INTO notifications (kind, task_id, title, body, read, created_at)
+            VALUES (?, ?, ?, ?, 0, ?)
+            "#,
+        )
+        .bind(kind.to_string())
+        .bind(task_id)
+        .bind(title)
+        .bind(body)
+        .bind(created_at.to_rfc3339())
+        .execute(&self.pool())
+        .await?;
+
+        Ok(AppNotification {
+            id: result.last_insert_rowid(),
+            kind,
+            task_id,
+            title: title.to_string(),
+            body: body.to_string(),
+            read: false,
+            created_at,
+        })
+    }
+
+    pub async fn get_notifications(&self, unread_only: bool) -> Result<Vec<AppNotification>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT * FROM notifications
+            WHERE (? = 0 OR read = 0)
+            ORDER BY created_at DESC
+            "#,
+        )
+        .bind(unread_only)
+        .fetch_all(&self.pool())
+        .await?;
+
+        rows.into_iter()
+            .map(Self::row_to_notification)
+            .collect()
+    }
+
+    pub async fn mark_notification_read(&self, id: i64) -> Result<()> {
+        sqlx::query("UPDATE notifications SET read = 1 WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool())
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn mark_all_notifications_read(&self) -> Result<()> {
+        sqlx::query("UPDATE notifications SET read = 1 WHERE read = 0")
+            .execute(&self.pool())
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn clear_notifications(&self) -> Result<()> {
+        sqlx::query("DELETE FROM notifications")
+            .execute(&self.pool())
+            .await?;
+
+        Ok(())
+    }
+
+    fn row_to_notification(row: sqlx::sqlite::SqliteRow) -> Result<AppNotification> {
+        Ok(AppNotification {
+            id: row.get("id"),
+            kind: NotificationKind::from_str(&row.get::<String, _>("kind")).map_err(|e| AppError::InvalidTask(e))?,
+            task_id: row.get("task_id"),
+            title: row.get("title"),
+            body: row.get("body"),
+            read: row.get("read"),
+            created_at: row.get::<String, _>("created_at").parse().map_err(|e| AppError::TimeParse(format!("{}", e)))?,
+        })
+    }
+
+    /// Date and time conventions for backend-generated text.
+    pub async fn get_locale_config(&self) -> Result<LocaleConfig> {
+        Ok(LocaleConfig::from(&self.get_settings().await?))
+    }
+
+    /// Settings with the active workspace's overrides applied on top of the global values.
+    pub async fn get_settings(&self) -> Result<AppSettings> {
+        let rows = sqlx::query("SELECT key, value FROM settings")
+            .fetch_all(&self.pool())
+            .await?;
+
+        let values: HashMap<String, String> = rows
+            .into_iter()
+            .map(|row| (row.get("key"), row.get("value")))
+            .collect();
+
+        let mut settings = AppSettings::default();
+        for (key, value) in &values {
+            Self::apply_setting(&mut settings, key, value);
+        }
+
+        if let Some(workspace_id) = values.get("active_workspace").and_then(|v| v.parse::<i64>().ok()) {
+            for key in WORKSPACE_SCOPED_SETTINGS {
+                if let Some(value) = values.get(&Self::workspace_setting_key(workspace_id, key)) {
+                    Self::apply_setting(&mut settings, key, value);
+                }
+            }
+        }
+
+        Ok(settings)
+    }
+
+    fn apply_setting(settings: &mut AppSettings, key: &str, value: &str) {
+        let bool_value = value == "true";
+
+        match key {
+            "minimize_to_tray" => settings.minimize_to_tray = bool_value,
+            "start_minimized" => settings.start_minimized = bool_value,
+            "show_notifications" => settings.show_notifications = bool_value,
+            "auto_start" => settings.auto_start = bool_value,
+            "max_consecutive_failures" => {
+                settings.max_consecutive_failures = value.parse().unwrap_or(settings.max_consecutive_failures)
+            }
+            "dry_run" => settings.dry_run = bool_value,
+            "missed_policy" => {
+                if let Ok(policy) = value.parse() {
+                    settings.missed_policy = policy;
+                }
+            }
+            "auto_check_updates" => settings.auto_check_updates = bool_value,
+            "deleted_retention_days" => {
+                settings.deleted_retention_days = value.parse().unwrap_or(settings.deleted_retention_days)
+            }
+            "db_max_connections" => {
+                settings.db_max_connections = value.parse().unwrap_or(settings.db_max_connections)
+            }
+            "devtools_port" => {
+                settings.devtools_port = value.parse().unwrap_or(settings.devtools_port)
+            }
+            "launch_stagger_secs" => {
+                settings.launch_stagger_secs = value.parse().unwrap_or(settings.launch_stagger_secs)
+            }
+            "max_concurrent_opens" => {
+                settings.max_concurrent_opens = value.parse().unwrap_or(settings.max_concurrent_opens)
+            }
+            "concurrent_open_policy" => {
+                if let Ok(policy) = value.parse() {
+                    settings.concurrent_open_policy = policy;
+                }
+            }
+            "max_cpu_percent" => {
+                settings.max_cpu_percent = value.parse().unwrap_or(settings.max_cpu_percent)
+            }
+            "max_memory_percent" => {
+                settings.max_memory_percent = value.parse().unwrap_or(settings.max_memory_percent)
+            }
+            "close_opened_on_quit" => settings.close_opened_on_quit = bool_value,
+            "defer_while_locked" => settings.defer_while_locked = bool_value,
+            "quiet_hours_enabled" => settings.quiet_hours_enabled = bool_value,
+            "quiet_hours_start" => settings.quiet_hours_start = value.to_string(),
+            "quiet_hours_end" => settings.quiet_hours_end = value.to_string(),
+            "first_day_of_week" => {
+                if let Ok(value) = value.parse() {
+                    settings.first_day_of_week = value;
+                }
+            }
+            "clock_format" => {
+                if let Ok(value) = value.parse() {
+                    settings.clock_format = value;
+                }
+            }
+            "date_format" => {
+                if let Ok(value) = value.parse() {
+                    settings.date_format = value;
+                }
+            }
+            "action_sound" => settings.action_sound = value.to_string(),
+            "reminder_sound" => settings.reminder_sound = value.to_string(),
+            "failure_sound" => settings.failure_sound = value.to_string(),
+            "notify_on_failure" => settings.notify_on_failure = bool_value,
+            "action_notification_title" => settings.action_notification_title = value.to_string(),
+            "action_notification_body" => settings.action_notification_body = value.to_string(),
+            "failure_notification_title" => settings.failure_notification_title = value.to_string(),
+            "failure_notification_body" => settings.failure_notification_body = value.to_string(),
+            "smtp_enabled" => settings.smtp_enabled = bool_value,
+            "smtp_host" => settings.smtp_host = value.to_string(),
+            "smtp_port" => {
+                settings.smtp_port = value.parse().unwrap_or(settings.smtp_port)
+            }
+            "smtp_username" => settings.smtp_username = value.to_string(),
+            "smtp_from" => settings.smtp_from = value.to_string(),
+            "smtp_to" => settings.smtp_to = value.to_string(),
+            "smtp_daily_summary" => settings.smtp_daily_summary = bool_value,
+            "slack_webhook_url" => settings.slack_webhook_url = value.to_string(),
+            "discord_webhook_url" => settings.discord_webhook_url = value.to_string(),
+            "telegram_chat_id" => settings.telegram_chat_id = value.to_string(),
+            "home_assistant_enabled" => settings.home_assistant_enabled = bool_value,
+            "home_assistant_port" => {
+                settings.home_assistant_port = value.parse().unwrap_or(settings.home_assistant_port)
+            }
+            "home_assistant_listen_all" => settings.home_assistant_listen_all = bool_value,
+            "warn_before_minutes" => {
+                settings.warn_before_minutes = value.parse().unwrap_or(settings.warn_before_minutes)
+            }
+            "close_countdown_minutes" => {
+                settings.close_countdown_minutes = value.parse().unwrap_or(settings.close_countdown_minutes)
+            }
+            "language" => {
+                if let Ok(value) = value.parse() {
+                    settings.language = value;
+                }
+            }
+            "windows_ui_automation" => settings.windows_ui_automation = bool_value,
+            "url_actions_enabled" => settings.url_actions_enabled = bool_value,
+            "allowed_url_schemes" => {
+                if let Ok(schemes) = serde_json::from_str(value) {
+                    settings.allowed_url_schemes = schemes;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn workspace_setting_key(workspace_id: i64, key: &str) -> String {
+        format!("workspace.{}.{}", workspace_id, key)
+    }
+
+    /// Raw value of a setting, including internal ones that are not part of `AppSettings`.
+    pub async fn get_setting_value(&self, key: &str) -> Result<Option<String>> {
+        let value = sqlx::query("SELECT value FROM settings WHERE key = ?")
+            .bind(key)
+            .fetch_optional(&self.pool())
+            .await?
+            .map(|row| row.get("value"));
+
+        Ok(value)
+    }
+
+    pub async fn update_setting(&self, key: &str, value: bool) -> Result<()> {
+        let value_str = if value { "true" } else { "false" };
+        self.update_setting_value(key, value_str).await
+    }
+
+    pub async fn update_setting_value(&self, key: &str, value: &str) -> Result<()> {
+        sqlx::query("UPDATE settings SET value = ? WHERE key = ?")
+            .bind(value)
+            .bind(key)
+            .execute(&self.pool())
+            .await?;
+
+        Ok(())
+    }
+
+    /// Saves settings. Workspace-scoped settings are written to the active workspace, if any.
+    pub async fn update_settings(&self, mut settings: AppSettings) -> Result<()> {
+        for allowed in &mut settings.allowed_url_schemes {
+            validation::validate_url_scheme(&allowed.scheme)?;
+            allowed.scheme = allowed.scheme.trim().to_lowercase();
+        }
+        validation::parse_time_of_day(&settings.quiet_hours_start)?;
+        validation::parse_time_of_day(&settings.quiet_hours_end)?;
+        if !(1..=MAX_POOL_CONNECTIONS).contains(&settings.db_max_connections) {
+            return Err(AppError::InvalidSettings(format!(
+                "The database pool must have between 1 and {} connections",
+                MAX_POOL_CONNECTIONS
+            )));
+        }
+        if settings.launch_stagger_secs > MAX_LAUNCH_STAGGER_SECS {
+            return Err(AppError::InvalidSettings(format!(
+                "The launch stagger can be at most {} seconds",
+                MAX_LAUNCH_STAGGER_SECS
+            )));
+        }
+
+        let workspace = self.get_active_workspace().await?;
+
+        self.update_setting("minimize_to_tray", settings.minimize_to_tray).await?;
+        self.update_setting("start_minimized", settings.start_minimized).await?;
+        self.update_scoped_setting(workspace, "show_notifications", &settings.show_notifications.to_string()).await?;
+        self.update_setting("auto_start", settings.auto_start).await?;
+        self.update_scoped_setting(workspace, "max_consecutive_failures", &settings.max_consecutive_failures.to_string()).await?;
+        self.update_scoped_setting(workspace, "dry_run", &settings.dry_run.to_string()).await?;
+        self.update_scoped_setting(workspace, "missed_policy", &settings.missed_policy.to_string()).await?;
+        self.update_setting("auto_check_updates", settings.auto_check_updates).await?;
+        self.update_setting_value("deleted_retention_days", &settings.deleted_retention_days.to_string()).await?;
+        self.update_setting_value("db_max_connections", &settings.db_max_connections.to_string()).await?;
+        self.update_setting_value("devtools_port", &settings.devtools_port.to_string()).await?;
+        self.update_setting_value("launch_stagger_secs", &settings.launch_stagger_secs.to_string()).await?;
+        self.update_setting_value("max_concurrent_opens", &settings.max_concurrent_opens.to_string()).await?;
+        self.update_setting_value("concurrent_open_policy", &settings.concurrent_open_policy.to_string()).await?;
+        self.update_setting_value("max_cpu_percent", &settings.max_cpu_percent.to_string()).await?;
+        self.update_setting_value("max_memory_percent", &settings.max_memory_percent.to_string()).await?;
+        self.update_setting("close_opened_on_quit", settings.close_opened_on_quit).await?;
+        self.update_setting("defer_while_locked", settings.defer_while_locked).await?;
+        self.update_setting("quiet_hours_enabled", settings.quiet_hours_enabled).await?;
+        self.update_setting_value("quiet_hours_start", &settings.quiet_hours_start).await?;
+        self.update_setting_value("quiet_hours_end", &settings.quiet_hours_end).await?;
+        self.update_setting_value("first_day_of_week", &settings.first_day_of_week.to_string()).await?;
+        self.update_setting_value("clock_format", &settings.clock_format.to_string()).await?;
+        self.update_setting_value("date_format", &settings.date_format.to_string()).await?;
+        self.update_setting_value("action_sound", &settings.action_sound).await?;
+        self.update_setting_value("reminder_sound", &settings.reminder_sound).await?;
+        self.update_setting_value("failure_sound", &settings.failure_sound).await?;
+        self.update_setting("notify_on_failure", settings.notify_on_failure).await?;
+        self.update_setting_value("action_notification_title", &settings.action_notification_title).await?;
+        self.update_setting_value("action_notification_body", &settings.action_notification_body).await?;
+        self.update_setting_value("failure_notification_title", &settings.failure_notification_title).await?;
+        self.update_setting_value("failure_notification_body", &settings.failure_notification_body).await?;
+        self.update_setting("smtp_enabled", settings.smtp_enabled).await?;
+        self.update_setting_value("smtp_host", &settings.smtp_host).await?;
+        self.update_setting_value("smtp_port", &settings.smtp_port.to_string()).await?;
+        self.update_setting_value("smtp_username", &settings.smtp_username).await?;
+        self.update_setting_value("smtp_from", &settings.smtp_from).await?;
+        self.update_setting_value("smtp_to", &settings.smtp_to).await?;
+        self.update_setting("smtp_daily_summary", settings.smtp_daily_summary).await?;
+        self.update_setting_value("slack_webhook_url", &settings.slack_webhook_url).await?;
+        self.update_setting_value("discord_webhook_url", &settings.discord_webhook_url).await?;
+        self.update_setting_value("telegram_chat_id", &settings.telegram_chat_id).await?;
+        self.update_setting("home_assistant_enabled", settings.home_assistant_enabled).await?;
+        self.update_setting_value("home_assistant_port", &settings.home_assistant_port.to_string()).await?;
+        self.update_setting("home_assistant_listen_all", settings.home_assistant_listen_all).await?;
+        self.update_setting_value("warn_before_minutes", &settings.warn_before_minutes.to_string()).await?;
+        self.update_setting_value("close_countdown_minutes", &settings.close_countdown_minutes.to_string()).await?;
+        self.update_setting_value("language", &settings.language.to_string()).await?;
+        self.update_setting("windows_ui_automation", settings.windows_ui_automation).await?;
+        self.update_setting("url_actions_enabled", settings.url_actions_enabled).await?;
+        let schemes = serde_json::to_string(&settings.allowed_url_schemes)
+            .map_err(|e| AppError::InvalidTask(format!("Failed to serialize URL schemes: {}", e)))?;
+        self.update_setting_value("allowed_url_schemes", &schemes).await?;
+        Ok(())
+    }
+
+    async fn update_scoped_setting(&self, workspace: Option<i64>, key: &str, value: &str) -> Result<()> {
+        let Some(workspace_id) = workspace else {
+            return self.update_setting_value(key, value).await;
+        };
+
+        sqlx::query(
+            "INSERT INTO settings (key, value) VALUES (?, ?) ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+        )
+        .bind(Self::workspace_setting_key(workspace_id, key))
+        .bind(value)
+        .execute(&self.pool())
+        .await?;
+
+        Ok(())
+    }
+
+    // Workspace operations
+
+    pub async fn get_workspaces(&self) -> Result<Vec<Workspace>> {
+        let rows = sqlx::query("SELECT * FROM workspaces ORDER BY name ASC")
+            .fetch_all(&self.pool())
+            .await?;
+
+        rows.into_iter().map(Self::row_to_workspace).collect()
+    }
+
+    pub async fn create_workspace(&self, name: &str) -> Result<Workspace> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(AppError::InvalidWorkspace("name cannot be empty".to_string()));
+        }
+
+        let row = sqlx::query("INSERT INTO workspaces (name, created_at) VALUES (?, ?) RETURNING *")
+            .bind(name)
+            .bind(Utc::now().to_rfc3339())
+            .fetch_one(&self.pool())
+            .await?;
+
+        Self::row_to_workspace(row)
+    }
+
+    /// Deletes a workspace. Its tasks are kept and move to no workspace, so they run everywhere.
+    pub async fn delete_workspace(&self, id: i64) -> Result<()> {
+        let mut tx = self.pool().begin().await?;
+
+        let result = sqlx::query("DELETE FROM workspaces WHERE id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::WorkspaceNotFound(id));
+        }
+
+        sqlx::query("UPDATE tasks SET workspace_id = NULL WHERE workspace_id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("DELETE FROM settings WHERE key LIKE ?")
+            .bind(format!("workspace.{}.%", id))
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("UPDATE settings SET value = 'all' WHERE key = 'active_workspace' AND value = ?")
+            .bind(id.to_string())
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// The workspace whose tasks the scheduler runs, or `None` when all workspaces are active.
+    pub async fn get_active_workspace(&self) -> Result<Option<i64>> {
+        Ok(self
+            .get_setting_value("active_workspace")
+            .await?
+            .and_then(|value| value.parse().ok()))
+    }
+
+    pub async fn set_active_workspace(&self, workspace_id: Option<i64>) -> Result<()> {
+        let value = match workspace_id {
+            Some(id) => {
+                let exists = sqlx::query("SELECT 1 FROM workspaces WHERE id = ?")
+                    .bind(id)
+                    .fetch_optional(&self.pool())
+                    .await?
+                    .is_some();
+                if !exists {
+                    return Err(AppError::WorkspaceNotFound(id));
+                }
+                id.to_string()
+            }
+            None => "all".to_string(),
+        };
+
+        self.update_setting_value("active_workspace", &value).await
+    }
+
+    fn row_to_workspace(row: sqlx::sqlite::SqliteRow) -> Result<Workspace> {
+        Ok(Workspace {
+            id: row.get("id"),
+            name: row.get("name"),
+            created_at: row.get::<String, _>("created_at").parse().map_err(|e| AppError::TimeParse(format!("{}", e)))?,
+        })
+    }
+}
+
+/// Whether `before` and `after` differ at most in the bookkeeping the scheduler updates on
+/// every run: next executions, counters and sequence positions.
+fn only_bookkeeping_changed(before: &Task, after: &Task) -> bool {
+    let settings_of = |task: &Task| {
+        let mut task = task.clone();
+        task.next_open_execution = None;
+        task.next_close_execution = None;
+        task.execution_count = 0;
+        task.consecutive_failures = 0;
+        task.retry_attempt = 0;
+        task.pending_occurrence = None;
+        task.last_success_at = None;
+        task.pomodoro_cycle = 0;
+        task.current_step = 0;
+        task.updated_at = None;
+        serde_json::to_value(task).ok()
+    };
+    let before = settings_of(before);
+    before.is_some() && before == settings_of(after)
+}
+
+/// Keyring changes of a task save, made once its row is written
+#[derive(Default)]
+struct SecretUrlWrites {
+    set: Option<(String, String)>,
+    delete: Option<String>,
+}
+
+impl SecretUrlWrites {
+    async fn apply(self) -> Result<()> {
+        if self.set.is_none() && self.delete.is_none() {
+            return Ok(());
+        }
+        keyring_blocking(move || {
+            if let Some((secret_ref, query)) = &self.set {
+                keyring::set_secret(secret_ref, query)?;
+            }
+            if let Some(old_ref) = &self.delete {
+                keyring::delete_secret(old_ref)?;
+            }
+            Ok(())
+        })
+        .await
+    }
+}
+
+/// Runs keyring access off the async runtime; it can block on the OS keyring service.
+async fn keyring_blocking<T: Send + 'static>(work: impl FnOnce() -> Result<T> + Send + 'static) -> Result<T> {
+    tokio::task::spawn_blocking(work)
+        .await
+        .map_err(|e| AppError::Keyring(e.to_string()))?
+}
+
+/// `WHERE task_id IN (?, …)` with a placeholder per id, or nothing to match every task
+fn task_id_filter(task_ids: Option<&[i64]>) -> String {
+    match task_ids {
+        Some(ids) => format!(" WHERE task_id IN ({})", vec!["?"; ids.len()].join(", ")),
+        None => String::new(),
+    }
+}
+
+/// Binds the ids of a `task_id_filter` clause
+fn bind_task_ids<'q>(
+    mut query: sqlx::query::Query<'q, sqlx::Sqlite, sqlx::sqlite::SqliteArguments<'q>>,
+    task_ids: Option<&[i64]>,
+) -> sqlx::query::Query<'q, sqlx::Sqlite, sqlx::sqlite::SqliteArguments<'q>> {
+    for id in task_ids.unwrap_or_default() {
+        query = query.bind(*id);
+    }
+    query
+}
+
+/// Tags as they are stored: normalized, sorted, and without blanks or duplicates
+fn normalize_tags(tags: &[String]) -> Vec<String> {
+    let mut tags: Vec<String> = tags
+        .iter()
+        .map(|tag| validation::normalize_tag(tag))
+        .filter(|tag| !tag.is_empty())
+        .collect();
+    tags.sort();
+    tags.dedup();
+    tags
+}
+
+/// Turns what the user typed into an FTS5 query: each word becomes a quoted prefix term, so
+/// operators and punctuation in the input are matched literally instead of parsed.
+fn fts_query(input: &str) -> Option<String> {
+    let terms: Vec<String> = input
+        .split_whitespace()
+        .map(|word| format!("\"{}\"*", word.replace('"', "\"\"")))
+        .collect();
+    (!terms.is_empty()).then(|| terms.join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    /// Inserts `count` active tasks opening a minute apart, each closing half an hour later
+    async fn seed_tasks(db: &Database, count: i64) {
+        let start = Utc::now() + Duration::hours(1);
+        let mut tx = db.pool().begin().await.unwrap();
+        for i in 0..count {
+            let open = start + Duration::minutes(count - i);
+            sqlx::query(
+                "INSERT INTO tasks (name, browser, start_time, timezone, status, next_open_execution, next_close_execution)
+                 VALUES (?, 'chrome', ?, 'UTC', 'active', ?, ?)",
+            )
+            .bind(format!("Task {}", i))
+            .bind(open.to_rfc3339())
+            .bind(open.to_rfc3339())
+            .bind((open + Duration::minutes(30)).to_rfc3339())
+            .execute(&mut *tx)
+            .await
+            .unwrap();
+        }
+        tx.commit().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_next_action_column_follows_updates() {
+        let db = Database::in_memory().await.unwrap();
+        seed_tasks(&db, 3).await;
+
+        let (task, action) = db.get_next_action().await.unwrap().unwrap();
+        assert_eq!(task.name, "Task 2");
+        assert_eq!(action, ExecutionAction::Open);
+
+        // Once opened, only the close is left and the next action moves to it
+        sqlx::query("UPDATE tasks SET next_open_execution = NULL WHERE id = ?")
+            .bind(task.id)
+            .execute(&db.pool())
+            .await
+            .unwrap();
+        let (next, action) = db.get_next_action().await.unwrap().unwrap();
+        assert_eq!(next.name, "Task 1");
+        assert_eq!(action, ExecutionAction::Open);
+
+        let row = sqlx::query("SELECT next_action FROM tasks WHERE id = ?")
+            .bind(task.id)
+            .fetch_one(&db.pool())
+            .await
+            .unwrap();
+        assert_eq!(row.get::<String, _>("next_action"), "close");
+    }
+
+    #[tokio::test]
+    async fn test_next_action_uses_index() {
+        let db = Database::in_memory().await.unwrap();
+        let plan: Vec<String> = sqlx::query(
+            "EXPLAIN QUERY PLAN SELECT * FROM tasks WHERE status = 'active' AND next_action_time IS NOT NULL
+             ORDER BY next_action_time ASC LIMIT 1",
+        )
+        .fetch_all(&db.pool())
+        .await
+        .unwrap()
+        .iter()
+        .map(|row| row.get("detail"))
+        .collect();
+
+        assert!(plan.iter().any(|detail| detail.contains("idx_tasks_next_action")), "{:?}", plan);
+        assert!(!plan.iter().any(|detail| detail.contains("TEMP B-TREE")), "{:?}", plan);
+    }
+
+    #[tokio::test]
+    async fn test_get_tasks_page() {
+        let db = Database::in_memory().await.unwrap();
+        seed_tasks(&db, 5).await;
+
+        let page = db.get_tasks_page(Some(2), 1, TaskSort::Name, true).await.unwrap();
+        assert_eq!(page.total, 5);
+        let names: Vec<&str> = page.items.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, ["Task 3", "Task 2"]);
+
+        let rest = db.get_tasks_page(None, 3, TaskSort::StartTime, false).await.unwrap();
+        assert_eq!(rest.items.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_get_task_executions() {
+        let db = Database::in_memory().await.unwrap();
+        seed_tasks(&db, 2).await;
+        let start = Utc::now() - Duration::days(5);
+        for day in 0..5 {
+            db.begin_execution(1, &ExecutionAction::Open, Some(start + Duration::days(day))).await.unwrap();
+        }
+        db.begin_execution(2, &ExecutionAction::Open, None).await.unwrap();
+
+        let page = db.get_task_executions(1, 2, 1).await.unwrap();
+        assert_eq!(page.total, 5);
+        assert_eq!(page.items.len(), 2);
+        assert!(page.items.iter().all(|e| e.task_id == 1));
+        assert!(page.items[0].executed_at >= page.items[1].executed_at);
+
+        let recent = db.get_recent_executions(3).await.unwrap();
+        assert_eq!(recent.len(), 3);
+        assert_eq!(recent[0].task_id, 2);
+    }
+
+    #[tokio::test]
+    async fn test_scheduler_saves_keep_removed_url_schemes() {
+        let db = Database::in_memory().await.unwrap();
+        seed_tasks(&db, 1).await;
+        let mut settings = db.get_settings().await.unwrap();
+        settings.allowed_url_schemes = vec![AllowedUrlScheme { scheme: "zoommtg".to_string(), confirm: false }];
+        db.update_settings(settings.clone()).await.unwrap();
+
+        let mut task = db.get_all_tasks().await.unwrap().remove(0);
+        let id = task.id.unwrap();
+        task.url = Some("zoommtg://zoom.us/join?confno=123".to_string());
+        let mut task = db.update_task(id, task, false, AuditSource::Ui).await.unwrap();
+
+        settings.allowed_url_schemes.clear();
+        db.update_settings(settings).await.unwrap();
+
+        task.execution_count += 1;
+        let task = db.update_task(id, task, false, AuditSource::Scheduler).await.unwrap();
+        assert!(matches!(
+            db.update_task(id, task, false, AuditSource::Ui).await,
+            Err(AppError::InvalidTask(_))
+        ));
+    }
+
+    #[test]
+    fn test_fts_query() {
+        assert_eq!(fts_query("quarterly report").as_deref(), Some("\"quarterly\"* \"report\"*"));
+        assert_eq!(fts_query("say \"hi\" OR").as_deref(), Some("\"say\"* \"\"\"hi\"\"\"* \"OR\"*"));
+        assert_eq!(fts_query("   "), None);
+    }
+
+    #[tokio::test]
+    async fn test_bulk_operations() {
+        let db = Database::in_memory().await.unwrap();
+        seed_tasks(&db, 3).await;
+        let ids: Vec<i64> = db.get_all_tasks().await.unwrap().iter().filter_map(|task| task.id).collect();
+
+        let paused = db.bulk_update_status(&[ids[0], ids[1], 999], TaskStatus::Paused, AuditSource::Ui).await.unwrap();
+        assert_eq!(paused.len(), 2);
+        assert_eq!(db.get_task(ids[1]).await.unwrap().status, TaskStatus::Paused);
+        assert_eq!(db.get_task(ids[2]).await.unwrap().status, TaskStatus::Active);
+
+        let before = db.get_task(ids[2]).await.unwrap();
+        db.bulk_shift_time(&[ids[2]], -15, AuditSource::Ui).await.unwrap();
+        let shifted = db.get_task(ids[2]).await.unwrap();
+        assert_eq!(shifted.start_time, before.start_time - Duration::minutes(15));
+        assert_eq!(
+            shifted.next_close_execution,
+            before.next_close_execution.map(|close| close - Duration::minutes(15))
+        );
+        assert_eq!(db.get_task_revisions(ids[2]).await.unwrap().len(), 2);
+
+        let deleted = db.bulk_delete(&[ids[0], ids[0], ids[2]], AuditSource::Ui).await.unwrap();
+        assert_eq!(deleted, vec![ids[0], ids[2]]);
+        assert_eq!(db.get_all_tasks().await.unwrap().len(), 1);
+        assert_eq!(db.get_deleted_tasks().await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_task_tags() {
+        let db = Database::in_memory().await.unwrap();
+        let start = Utc::now() + Duration::hours(1);
+
+        let mut standup = Task::new("Standup".to_string(), BrowserType::Chrome, start, "UTC".to_string());
+        standup.tags = vec!["Work".to_string(), " standup".to_string(), "work".to_string()];
+        let standup = db.create_task(standup, AuditSource::Ui).await.unwrap();
+        assert_eq!(standup.tags, vec!["standup", "work"]);
+
+        let mut news = Task::new("News".to_string(), BrowserType::Chrome, start, "UTC".to_string());
+        news.tags = vec!["personal".to_string()];
+        db.create_task(news, AuditSource::Ui).await.unwrap();
+
+        let tagged = db.get_tasks_by_tag("WORK").await.unwrap();
+        assert_eq!(tagged.len(), 1);
+        assert_eq!(tagged[0].tags, vec!["standup", "work"]);
+
+        let page = db.get_tasks_page(Some(10), 0, TaskSort::Name, false).await.unwrap();
+        assert_eq!(page.items[0].tags, vec!["personal"]);
+        assert_eq!(page.items[1].tags, vec!["standup", "work"]);
+
+        let counts: Vec<_> = db.get_tags().await.unwrap().into_iter().map(|t| (t.tag, t.task_count)).collect();
+        assert_eq!(
+            counts,
+            vec![("personal".to_string(), 1), ("standup".to_string(), 1), ("work".to_string(), 1)]
+        );
+
+        let mut edited = db.get_task(standup.id.unwrap()).await.unwrap();
+        edited.tags = vec!["personal".to_string()];
+        db.update_task(standup.id.unwrap(), edited, false, AuditSource::Ui).await.unwrap();
+        assert_eq!(db.get_tasks_by_tag("personal").await.unwrap().len(), 2);
+        assert!(db.get_tasks_by_tag("work").await.unwrap().is_empty());
+
+        db.delete_task(standup.id.unwrap(), AuditSource::Ui).await.unwrap();
+        assert_eq!(db.get_tasks_by_tag("personal").await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_task_exclusions() {
+        let db = Database::in_memory().await.unwrap();
+        let start = Utc::now() + Duration::hours(1);
+        let day = |d: u32| chrono::NaiveDate::from_ymd_opt(2030, 12, d).unwrap();
+
+        let mut standup = Task::new("Standup".to_string(), BrowserType::Chrome, start, "UTC".to_string());
+        standup.repeat_config = Some(RepeatConfig { interval: RepeatInterval::Daily, end_after: None, end_date: None });
+        standup.exclusions = vec![
+            DateExclusion { start: day(27), end: Some(day(31)) },
+            DateExclusion { start: day(25), end: None },
+        ];
+        let standup = db.create_task(standup, AuditSource::Ui).await.unwrap();
+        let id = standup.id.unwrap();
+
+        let loaded = db.get_task(id).await.unwrap();
+        assert_eq!(
+            loaded.exclusions,
+            vec![DateExclusion { start: day(25), end: None }, DateExclusion { start: day(27), end: Some(day(31)) }]
+        );
+        assert_eq!(db.get_all_tasks().await.unwrap()[0].exclusions, loaded.exclusions);
+
+        let mut edited = loaded;
+        edited.exclusions.remove(0);
+        db.update_task(id, edited, false, AuditSource::Ui).await.unwrap();
+        assert_eq!(db.get_task(id).await.unwrap().exclusions.len(), 1);
+
+        db.delete_task(id, AuditSource::Ui).await.unwrap();
+        let left: i64 = sqlx::query("SELECT COUNT(*) FROM task_exclusions").fetch_one(&db.pool()).await.unwrap().get(0);
+        assert_eq!(left, 0);
+    }
+
+    #[tokio::test]
+    async fn test_search_tasks() {
+        let db = Database::in_memory().await.unwrap();
+        seed_tasks(&db, 3).await;
+        sqlx::query("UPDATE tasks SET description = 'Quarterly report for finance' WHERE name = 'Task 1'")
+            .execute(&db.pool())
+            .await
+            .unwrap();
+
+        sqlx::query("INSERT INTO task_tags (task_id, tag) SELECT id, 'finance' FROM tasks WHERE name = 'Task 1'")
+            .execute(&db.pool())
+            .await
+            .unwrap();
+
+        let found = db.search_tasks("quarterly rep", 10).await.unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].name, "Task 1");
+        assert_eq!(found[0].tags, vec!["finance"]);
+
+        sqlx::query("DELETE FROM tasks WHERE name = 'Task 1'").execute(&db.pool()).await.unwrap();
+        assert!(db.search_tasks("quarterly", 10).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_scheduler_bookkeeping_is_not_audited() {
+        let db = Database::in_memory().await.unwrap();
+        seed_tasks(&db, 1).await;
+        let mut task = db.get_all_tasks().await.unwrap().remove(0);
+        let id = task.id.unwrap();
+
+        task.execution_count += 1;
+        task.last_success_at = Some(Utc::now());
+        task.next_open_execution = task.next_open_execution.map(|next| next + Duration::days(1));
+        let mut task = db.update_task(id, task, false, AuditSource::Scheduler).await.unwrap();
+        assert!(db.get_audit_log(Some(id), 10).await.unwrap().is_empty());
+
+        task.status = TaskStatus::Completed;
+        task.next_open_execution = None;
+        task.next_close_execution = None;
+        db.update_task(id, task, false, AuditSource::Scheduler).await.unwrap();
+        let entries = db.get_audit_log(Some(id), 10).await.unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].action, AuditAction::StatusChange);
+    }
+
+    #[tokio::test]
+    async fn test_task_revisions_are_capped_and_restorable() {
+        let db = Database::in_memory().await.unwrap();
+        let start = Utc::now() + Duration::hours(1);
+        let task = Task::new("Standup".to_string(), BrowserType::Chrome, start, "UTC".to_string());
+        let mut task = db.create_task(task, AuditSource::Ui).await.unwrap();
+        let id = task.id.unwrap();
+
+        for i in 0..MAX_REVISIONS_PER_TASK + 5 {
+            task.name = format!("Standup {}", i);
+            task = db.update_task(id, task, false, AuditSource::Ui).await.unwrap();
+        }
+        // Scheduler writes are not edits
+        task = db.update_task(id, task, false, AuditSource::Scheduler).await.unwrap();
+
+        let revisions = db.get_task_revisions(id).await.unwrap();
+        assert_eq!(revisions.len() as i64, MAX_REVISIONS_PER_TASK);
+        assert_eq!(revisions[0].revision, MAX_REVISIONS_PER_TASK + 6);
+        assert_eq!(revisions[0].task.name, task.name);
+
+        let oldest = revisions.last().unwrap();
+        let restored = db.restore_task_revision(id, oldest.revision, AuditSource::Ui).await.unwrap();
+        assert_eq!(restored.name, oldest.task.name);
+        assert!(db.restore_task_revision(id, 1, AuditSource::Ui).await.is_err());
+    }
+
+    /// Run with `cargo test --release bench_get_next_action -- --ignored --nocapture`
+    #[tokio::test]
+    #[ignore]
+    async fn bench_get_next_action_10k_tasks() {
+        let db = Database::in_memory().await.unwrap();
+        seed_tasks(&db, 10_000).await;
+
+        let iterations = 1_000;
+        let started = std::time::Instant::now();
+        for _ in 0..iterations {
+            db.get_next_action().await.unwrap().unwrap();
+        }
+        println!("get_next_action over 10k tasks: {:?} per call", started.elapsed() / iterations);
+    }
 }
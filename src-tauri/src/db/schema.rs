@@ -1,49 +1,347 @@
 use sqlx::sqlite::SqlitePool;
+use sqlx::Row;
 use crate::error::Result;
 
+/// Bumped whenever `initialize_schema` changes tables, columns, indexes or triggers, so the
+/// next start takes a pre-migration snapshot first
+pub const SCHEMA_VERSION: i64 = 17;
+
+const TASKS_TABLE_SQL: &str = r#"
+    CREATE TABLE IF NOT EXISTS tasks (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        name TEXT NOT NULL,
+        browser TEXT NOT NULL,
+        browser_profile TEXT,
+        url TEXT,
+        allow_close_all INTEGER NOT NULL DEFAULT 0,
+        start_time TEXT NOT NULL,
+        close_time TEXT,
+        timezone TEXT NOT NULL,
+        repeat_interval TEXT,
+        repeat_end_after INTEGER,
+        repeat_end_date TEXT,
+        execution_count INTEGER NOT NULL DEFAULT 0,
+        status TEXT NOT NULL,
+        next_open_execution TEXT,
+        next_close_execution TEXT,
+        consecutive_failures INTEGER NOT NULL DEFAULT 0,
+        last_success_at TEXT,
+        kind TEXT NOT NULL DEFAULT 'browser',
+        blocked_domains TEXT,
+        pomodoro_work_minutes INTEGER,
+        pomodoro_break_minutes INTEGER,
+        pomodoro_cycles INTEGER,
+        pomodoro_cycle INTEGER NOT NULL DEFAULT 0,
+        minimize_browsers INTEGER NOT NULL DEFAULT 0,
+        current_step INTEGER NOT NULL DEFAULT 0,
+        workspace_id INTEGER,
+        url_secret_ref TEXT,
+        session_name TEXT,
+        target_display INTEGER,
+        window_state TEXT NOT NULL DEFAULT 'normal',
+        zoom REAL,
+        user_agent TEXT,
+        lang TEXT,
+        fresh_session INTEGER NOT NULL DEFAULT 0,
+        managed_profile INTEGER NOT NULL DEFAULT 0,
+        prelaunch_minutes INTEGER,
+        jitter_minutes INTEGER,
+        missed_policy TEXT,
+        retry_max_attempts INTEGER,
+        retry_backoff_secs INTEGER,
+        retry_attempt INTEGER NOT NULL DEFAULT 0,
+        pending_occurrence TEXT,
+        duration_minutes INTEGER,
+        window_position TEXT,
+        window_size TEXT,
+        kiosk INTEGER NOT NULL DEFAULT 0,
+        app_mode INTEGER NOT NULL DEFAULT 0,
+        next_action_time TEXT,
+        next_action TEXT,
+        description TEXT,
+        launcher TEXT NOT NULL DEFAULT 'native',
+        updated_at TEXT
+    )
+"#;
+
+/// Earliest of the two next executions; open wins a tie
+const NEXT_ACTION_TIME_SQL: &str = "CASE \
+    WHEN next_open_execution IS NOT NULL AND (next_close_execution IS NULL OR next_open_execution <= next_close_execution) \
+        THEN next_open_execution \
+    ELSE next_close_execution END";
+
+/// Which action `NEXT_ACTION_TIME_SQL` picked, or NULL when nothing is scheduled
+const NEXT_ACTION_SQL: &str = "CASE \
+    WHEN next_open_execution IS NOT NULL AND (next_close_execution IS NULL OR next_open_execution <= next_close_execution) \
+        THEN 'open' \
+    WHEN next_close_execution IS NOT NULL THEN 'close' \
+    ELSE NULL END";
+
+/// Whether an existing database was last migrated by an older version. Fresh databases have
+/// nothing to protect and report `false` after their version is set.
+pub async fn needs_migration(pool: &SqlitePool) -> Result<bool> {
+    let version: i64 = sqlx::query("PRAGMA user_version").fetch_one(pool).await?.get(0);
+    Ok(version < SCHEMA_VERSION)
+}
+
+/// Records that the database matches `SCHEMA_VERSION`.
+pub async fn set_schema_version(pool: &SqlitePool) -> Result<()> {
+    // PRAGMA values cannot be bound as parameters
+    sqlx::query(&format!("PRAGMA user_version = {}", SCHEMA_VERSION))
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
 pub async fn initialize_schema(pool: &SqlitePool) -> Result<()> {
     // Create tasks table
+    sqlx::query(TASKS_TABLE_SQL)
+        .execute(pool)
+        .await?;
+
+    // Columns added after the initial release
+    add_column_if_missing(pool, "tasks", "updated_at", "TEXT").await?;
+    add_column_if_missing(pool, "tasks", "consecutive_failures", "INTEGER NOT NULL DEFAULT 0").await?;
+    add_column_if_missing(pool, "tasks", "last_success_at", "TEXT").await?;
+    add_column_if_missing(pool, "tasks", "kind", "TEXT NOT NULL DEFAULT 'browser'").await?;
+    add_column_if_missing(pool, "tasks", "blocked_domains", "TEXT").await?;
+    add_column_if_missing(pool, "tasks", "pomodoro_work_minutes", "INTEGER").await?;
+    add_column_if_missing(pool, "tasks", "pomodoro_break_minutes", "INTEGER").await?;
+    add_column_if_missing(pool, "tasks", "pomodoro_cycles", "INTEGER").await?;
+    add_column_if_missing(pool, "tasks", "pomodoro_cycle", "INTEGER NOT NULL DEFAULT 0").await?;
+    add_column_if_missing(pool, "tasks", "minimize_browsers", "INTEGER NOT NULL DEFAULT 0").await?;
+    add_column_if_missing(pool, "tasks", "current_step", "INTEGER NOT NULL DEFAULT 0").await?;
+    add_column_if_missing(pool, "tasks", "workspace_id", "INTEGER").await?;
+    add_column_if_missing(pool, "tasks", "url_secret_ref", "TEXT").await?;
+    add_column_if_missing(pool, "tasks", "session_name", "TEXT").await?;
+    add_column_if_missing(pool, "tasks", "target_display", "INTEGER").await?;
+    add_column_if_missing(pool, "tasks", "window_state", "TEXT NOT NULL DEFAULT 'normal'").await?;
+    add_column_if_missing(pool, "tasks", "zoom", "REAL").await?;
+    add_column_if_missing(pool, "tasks", "user_agent", "TEXT").await?;
+    add_column_if_missing(pool, "tasks", "lang", "TEXT").await?;
+    add_column_if_missing(pool, "tasks", "fresh_session", "INTEGER NOT NULL DEFAULT 0").await?;
+    add_column_if_missing(pool, "tasks", "managed_profile", "INTEGER NOT NULL DEFAULT 0").await?;
+    add_column_if_missing(pool, "tasks", "prelaunch_minutes", "INTEGER").await?;
+    add_column_if_missing(pool, "tasks", "jitter_minutes", "INTEGER").await?;
+    add_column_if_missing(pool, "tasks", "missed_policy", "TEXT").await?;
+    add_column_if_missing(pool, "tasks", "retry_max_attempts", "INTEGER").await?;
+    add_column_if_missing(pool, "tasks", "retry_backoff_secs", "INTEGER").await?;
+    add_column_if_missing(pool, "tasks", "retry_attempt", "INTEGER NOT NULL DEFAULT 0").await?;
+    add_column_if_missing(pool, "tasks", "duration_minutes", "INTEGER").await?;
+    add_column_if_missing(pool, "tasks", "window_position", "TEXT").await?;
+    add_column_if_missing(pool, "tasks", "window_size", "TEXT").await?;
+    add_column_if_missing(pool, "tasks", "kiosk", "INTEGER NOT NULL DEFAULT 0").await?;
+    add_column_if_missing(pool, "tasks", "app_mode", "INTEGER NOT NULL DEFAULT 0").await?;
+    add_column_if_missing(pool, "tasks", "next_action_time", "TEXT").await?;
+    add_column_if_missing(pool, "tasks", "next_action", "TEXT").await?;
+    add_column_if_missing(pool, "tasks", "description", "TEXT").await?;
+    add_column_if_missing(pool, "tasks", "launcher", "TEXT NOT NULL DEFAULT 'native'").await?;
+    add_column_if_missing(pool, "tasks", "pending_occurrence", "TEXT").await?;
+
+    // Older databases restrict the status column to the original three values
+    drop_tasks_status_check(pool).await?;
+
+    // Create indexes for scheduler efficiency
     sqlx::query(
         r#"
-        CREATE TABLE IF NOT EXISTS tasks (
+        CREATE INDEX IF NOT EXISTS idx_tasks_next_open_execution
+        ON tasks(next_open_execution)
+        WHERE status = 'active' AND next_open_execution IS NOT NULL
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query(
+        r#"
+        CREATE INDEX IF NOT EXISTS idx_tasks_next_close_execution
+        ON tasks(next_close_execution)
+        WHERE status = 'active' AND next_close_execution IS NOT NULL
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query(
+        r#"
+        CREATE INDEX IF NOT EXISTS idx_tasks_status
+        ON tasks(status)
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    maintain_next_action(pool).await?;
+    maintain_search_index(pool).await?;
+
+    // Create task executions table. The unique constraint makes each scheduled occurrence
+    // fire at most once; manual runs have no occurrence and are never deduplicated.
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS task_executions (
             id INTEGER PRIMARY KEY AUTOINCREMENT,
-            name TEXT NOT NULL,
+            task_id INTEGER NOT NULL,
+            action TEXT NOT NULL,
+            occurrence TEXT,
+            status TEXT NOT NULL,
+            error TEXT,
+            executed_at TEXT NOT NULL,
+            duration_secs INTEGER,
+            pid INTEGER,
+            exit_code INTEGER,
+            UNIQUE(task_id, action, occurrence)
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    add_column_if_missing(pool, "task_executions", "duration_secs", "INTEGER").await?;
+    add_column_if_missing(pool, "task_executions", "pid", "INTEGER").await?;
+    add_column_if_missing(pool, "task_executions", "exit_code", "INTEGER").await?;
+
+    sqlx::query(
+        r#"
+        CREATE INDEX IF NOT EXISTS idx_task_executions_task_id
+        ON task_executions(task_id, executed_at)
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    // Create task steps table for multi-step tasks
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS task_steps (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            task_id INTEGER NOT NULL,
+            position INTEGER NOT NULL,
+            action TEXT NOT NULL,
+            url TEXT,
+            wait_minutes INTEGER,
+            UNIQUE(task_id, position)
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    // Create task tags table; a tag is kept once per task, lowercased
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS task_tags (
+            task_id INTEGER NOT NULL,
+            tag TEXT NOT NULL,
+            PRIMARY KEY (task_id, tag)
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query(
+        r#"
+        CREATE INDEX IF NOT EXISTS idx_task_tags_tag
+        ON task_tags(tag)
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    // Days recurring tasks skip; end_date is NULL for a single day
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS task_exclusions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            task_id INTEGER NOT NULL,
+            start_date TEXT NOT NULL,
+            end_date TEXT
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query(
+        r#"
+        CREATE INDEX IF NOT EXISTS idx_task_exclusions_task_id
+        ON task_exclusions(task_id)
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    // Create scheduler state table; a single row describing the running scheduler loop
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS scheduler_state (
+            id INTEGER PRIMARY KEY CHECK(id = 1),
+            started_at TEXT,
+            last_tick_at TEXT,
+            executions_since_start INTEGER NOT NULL DEFAULT 0,
+            last_error TEXT,
+            last_error_at TEXT
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    // Create workspaces table; separate task sets such as Work and Home
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS workspaces (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL UNIQUE,
+            created_at TEXT NOT NULL
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    // Create session snapshots table; tabs are stored as JSON
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS session_snapshots (
+            name TEXT PRIMARY KEY,
             browser TEXT NOT NULL,
             browser_profile TEXT,
-            url TEXT,
-            allow_close_all INTEGER NOT NULL DEFAULT 0,
-            start_time TEXT NOT NULL,
-            close_time TEXT,
-            timezone TEXT NOT NULL,
-            repeat_interval TEXT,
-            repeat_end_after INTEGER,
-            repeat_end_date TEXT,
-            execution_count INTEGER NOT NULL DEFAULT 0,
-            status TEXT NOT NULL CHECK(status IN ('active', 'completed', 'failed')),
-            next_open_execution TEXT,
-            next_close_execution TEXT
+            tabs TEXT NOT NULL,
+            saved_at TEXT NOT NULL
         )
         "#,
     )
     .execute(pool)
     .await?;
 
-    // Create indexes for scheduler efficiency
+    // Create recycle bin table; deleted tasks are kept as JSON snapshots until purged
     sqlx::query(
         r#"
-        CREATE INDEX IF NOT EXISTS idx_tasks_next_open_execution
-        ON tasks(next_open_execution)
-        WHERE status = 'active' AND next_open_execution IS NOT NULL
+        CREATE TABLE IF NOT EXISTS deleted_tasks (
+            task_id INTEGER PRIMARY KEY,
+            name TEXT NOT NULL,
+            snapshot TEXT NOT NULL,
+            url_secret_ref TEXT,
+            deleted_at TEXT NOT NULL
+        )
         "#,
     )
     .execute(pool)
     .await?;
 
+    // Create audit log table; task snapshots are stored as JSON
     sqlx::query(
         r#"
-        CREATE INDEX IF NOT EXISTS idx_tasks_next_close_execution
-        ON tasks(next_close_execution)
-        WHERE status = 'active' AND next_close_execution IS NOT NULL
+        CREATE TABLE IF NOT EXISTS audit_log (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            task_id INTEGER NOT NULL,
+            action TEXT NOT NULL,
+            source TEXT NOT NULL,
+            before TEXT,
+            after TEXT,
+            created_at TEXT NOT NULL
+        )
         "#,
     )
     .execute(pool)
@@ -51,8 +349,42 @@ pub async fn initialize_schema(pool: &SqlitePool) -> Result<()> {
 
     sqlx::query(
         r#"
-        CREATE INDEX IF NOT EXISTS idx_tasks_status
-        ON tasks(status)
+        CREATE INDEX IF NOT EXISTS idx_audit_log_task_id
+        ON audit_log(task_id, created_at)
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    // Numbered snapshots of each task after every edit, for looking back and restoring
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS task_revisions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            task_id INTEGER NOT NULL,
+            revision INTEGER NOT NULL,
+            source TEXT NOT NULL,
+            snapshot TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            UNIQUE(task_id, revision)
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    // Create notification center table
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS notifications (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            kind TEXT NOT NULL,
+            task_id INTEGER,
+            title TEXT NOT NULL,
+            body TEXT NOT NULL,
+            read INTEGER NOT NULL DEFAULT 0,
+            created_at TEXT NOT NULL
+        )
         "#,
     )
     .execute(pool)
@@ -77,7 +409,60 @@ pub async fn initialize_schema(pool: &SqlitePool) -> Result<()> {
             ('minimize_to_tray', 'false'),
             ('start_minimized', 'false'),
             ('show_notifications', 'false'),
-            ('auto_start', 'false')
+            ('auto_start', 'false'),
+            ('max_consecutive_failures', '3'),
+            ('dry_run', 'false'),
+            ('missed_policy', 'run_latest'),
+            ('auto_check_updates', 'false'),
+            ('last_update_check', ''),
+            ('paused_until', ''),
+            ('active_workspace', 'all'),
+            ('read_only', 'false'),
+            ('pin_lock_enabled', 'false'),
+            ('deleted_retention_days', '30'),
+            ('db_max_connections', '5'),
+            ('devtools_port', '0'),
+            ('launch_stagger_secs', '0'),
+            ('max_concurrent_opens', '0'),
+            ('concurrent_open_policy', 'defer'),
+            ('max_cpu_percent', '0'),
+            ('max_memory_percent', '0'),
+            ('close_opened_on_quit', 'false'),
+            ('defer_while_locked', 'false'),
+            ('quiet_hours_enabled', 'false'),
+            ('quiet_hours_start', '22:00'),
+            ('quiet_hours_end', '07:00'),
+            ('first_day_of_week', 'monday'),
+            ('clock_format', '24h'),
+            ('date_format', 'iso'),
+            ('action_sound', 'default'),
+            ('reminder_sound', 'default'),
+            ('failure_sound', 'alert'),
+            ('notify_on_failure', 'true'),
+            ('action_notification_title', ''),
+            ('action_notification_body', ''),
+            ('failure_notification_title', ''),
+            ('failure_notification_body', ''),
+            ('smtp_enabled', 'false'),
+            ('smtp_host', ''),
+            ('smtp_port', '587'),
+            ('smtp_username', ''),
+            ('smtp_from', ''),
+            ('smtp_to', ''),
+            ('smtp_daily_summary', 'false'),
+            ('last_daily_summary', ''),
+            ('slack_webhook_url', ''),
+            ('discord_webhook_url', ''),
+            ('telegram_chat_id', ''),
+            ('home_assistant_enabled', 'false'),
+            ('home_assistant_port', '8765'),
+            ('home_assistant_listen_all', 'false'),
+            ('warn_before_minutes', '0'),
+            ('close_countdown_minutes', '0'),
+            ('language', 'en'),
+            ('windows_ui_automation', 'false'),
+            ('url_actions_enabled', 'false'),
+            ('allowed_url_schemes', '[]')
         "#,
     )
     .execute(pool)
@@ -85,3 +470,191 @@ pub async fn initialize_schema(pool: &SqlitePool) -> Result<()> {
 
     Ok(())
 }
+
+/// Adds a column to an existing table when databases created by older versions lack it.
+async fn add_column_if_missing(pool: &SqlitePool, table: &str, column: &str, definition: &str) -> Result<()> {
+    let rows = sqlx::query(&format!("PRAGMA table_info({})", table))
+        .fetch_all(pool)
+        .await?;
+
+    let exists = rows
+        .iter()
+        .any(|row| row.get::<String, _>("name") == column);
+
+    if !exists {
+        sqlx::query(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition))
+            .execute(pool)
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Keeps `next_action_time` and `next_action` in step with the two next-execution columns, so
+/// the scheduler finds the next action with one index seek instead of sorting every active task.
+async fn maintain_next_action(pool: &SqlitePool) -> Result<()> {
+    // Triggers cover every write path, including raw updates elsewhere in the repository
+    sqlx::query(&format!(
+        r#"
+        CREATE TRIGGER IF NOT EXISTS tasks_next_action_insert AFTER INSERT ON tasks
+        BEGIN
+            UPDATE tasks SET next_action_time = {time}, next_action = {action} WHERE id = NEW.id;
+        END
+        "#,
+        time = NEXT_ACTION_TIME_SQL,
+        action = NEXT_ACTION_SQL,
+    ))
+    .execute(pool)
+    .await?;
+
+    sqlx::query(&format!(
+        r#"
+        CREATE TRIGGER IF NOT EXISTS tasks_next_action_update
+        AFTER UPDATE OF next_open_execution, next_close_execution ON tasks
+        BEGIN
+            UPDATE tasks SET next_action_time = {time}, next_action = {action} WHERE id = NEW.id;
+        END
+        "#,
+        time = NEXT_ACTION_TIME_SQL,
+        action = NEXT_ACTION_SQL,
+    ))
+    .execute(pool)
+    .await?;
+
+    // Fills the columns for rows written before they existed
+    sqlx::query(&format!(
+        "UPDATE tasks SET next_action_time = {time}, next_action = {action} \
+         WHERE next_action_time IS NOT {time} OR next_action IS NOT {action}",
+        time = NEXT_ACTION_TIME_SQL,
+        action = NEXT_ACTION_SQL,
+    ))
+    .execute(pool)
+    .await?;
+
+    // Covers the scheduler's lookup: the rowid and action come straight from the index
+    sqlx::query(
+        r#"
+        CREATE INDEX IF NOT EXISTS idx_tasks_next_action
+        ON tasks(next_action_time, next_action)
+        WHERE status = 'active' AND next_action_time IS NOT NULL
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Full-text index over task names, URLs and descriptions for `search_tasks`, kept in sync by
+/// triggers. The index stores only tokens; the text itself is read from `tasks`.
+async fn maintain_search_index(pool: &SqlitePool) -> Result<()> {
+    let exists = sqlx::query("SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'tasks_fts'")
+        .fetch_optional(pool)
+        .await?
+        .is_some();
+
+    sqlx::query(
+        r#"
+        CREATE VIRTUAL TABLE IF NOT EXISTS tasks_fts USING fts5(
+            name, url, description,
+            content = 'tasks', content_rowid = 'id',
+            tokenize = 'unicode61 remove_diacritics 2'
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query(
+        r#"
+        CREATE TRIGGER IF NOT EXISTS tasks_fts_insert AFTER INSERT ON tasks
+        BEGIN
+            INSERT INTO tasks_fts (rowid, name, url, description)
+            VALUES (NEW.id, NEW.name, NEW.url, NEW.description);
+        END
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query(
+        r#"
+        CREATE TRIGGER IF NOT EXISTS tasks_fts_delete AFTER DELETE ON tasks
+        BEGIN
+            INSERT INTO tasks_fts (tasks_fts, rowid, name, url, description)
+            VALUES ('delete', OLD.id, OLD.name, OLD.url, OLD.description);
+        END
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    sqlx::query(
+        r#"
+        CREATE TRIGGER IF NOT EXISTS tasks_fts_update AFTER UPDATE OF name, url, description ON tasks
+        BEGIN
+            INSERT INTO tasks_fts (tasks_fts, rowid, name, url, description)
+            VALUES ('delete', OLD.id, OLD.name, OLD.url, OLD.description);
+            INSERT INTO tasks_fts (rowid, name, url, description)
+            VALUES (NEW.id, NEW.name, NEW.url, NEW.description);
+        END
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    // Index the tasks that existed before the search index did
+    if !exists {
+        sqlx::query("INSERT INTO tasks_fts (tasks_fts) VALUES ('rebuild')")
+            .execute(pool)
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Rebuilds the tasks table without the `CHECK(status IN ...)` constraint of the first release,
+/// which SQLite cannot drop in place.
+async fn drop_tasks_status_check(pool: &SqlitePool) -> Result<()> {
+    let table_sql: Option<String> = sqlx::query("SELECT sql FROM sqlite_master WHERE type = 'table' AND name = 'tasks'")
+        .fetch_optional(pool)
+        .await?
+        .map(|row| row.get("sql"));
+
+    if !table_sql.is_some_and(|sql| sql.contains("CHECK(status IN")) {
+        return Ok(());
+    }
+
+    let columns: Vec<String> = sqlx::query("PRAGMA table_info(tasks)")
+        .fetch_all(pool)
+        .await?
+        .iter()
+        .map(|row| row.get("name"))
+        .collect();
+    let column_list = columns.join(", ");
+
+    let mut tx = pool.begin().await?;
+
+    sqlx::query("ALTER TABLE tasks RENAME TO tasks_old")
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query(TASKS_TABLE_SQL)
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query(&format!(
+        "INSERT INTO tasks ({0}) SELECT {0} FROM tasks_old",
+        column_list
+    ))
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query("DROP TABLE tasks_old")
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok(())
+}
@@ -0,0 +1,344 @@
+//! `do-in-time://` URLs following the x-callback-url convention, so Apple Shortcuts (and Siri)
+//! can create and run tasks and pause the scheduler:
+//!
+//! ```text
+//! do-in-time://x-callback-url/create-task?name=Standup&url=https://meet.example.com&at=tomorrow%209am
+//! do-in-time://x-callback-url/run-task?id=3&x-success=shortcuts://
+//! do-in-time://x-callback-url/pause-scheduler
+//! do-in-time://x-callback-url/pause-scheduler?until=today%205pm
+//! do-in-time://x-callback-url/resume-scheduler
+//! do-in-time://import-task?data=eyJ2IjoxLCJuYW1lIjoi...
+//! ```
+//!
+//! `pause-scheduler` stops the scheduler, or with `until` skips scheduled opens until then.
+//! `import-task` carries a task shared with `share_task` (see `utils::share`). Any page can
+//! navigate to these URLs, so `create-task` and `import-task` only fill in the task form for the
+//! user to save, and the other actions run only while `url_actions_enabled` is set.
+//!
+//! On success the `x-success` URL is opened with the result appended as query parameters; on
+//! failure `x-error` is opened with `errorCode` and `errorMessage`. Callbacks may only use the
+//! `shortcuts` scheme or one of the `allowed_url_schemes`.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use chrono::{DateTime, Local, Utc};
+use tauri::{AppHandle, Manager, Url};
+use crate::core::events::emit_import_task;
+use crate::core::reaper;
+use crate::core::TaskScheduler;
+use crate::db::{AppSettings, BrowserType, Database, Task};
+use crate::error::{AppError, Result};
+use crate::utils::{browser_detector, locale, share};
+
+/// URL scheme registered in Info.plist
+pub const SCHEME: &str = "do-in-time";
+
+#[derive(Debug, PartialEq)]
+enum Action {
+    CreateTask {
+        name: String,
+        url: Option<String>,
+        at: DateTime<Utc>,
+        browser: Option<BrowserType>,
+    },
+    RunTask(i64),
+    /// Share code of a task to import
+    ImportTask(String),
+    /// Stops the scheduler, or with an end time only pauses it until then
+    PauseScheduler(Option<DateTime<Utc>>),
+    ResumeScheduler,
+}
+
+/// Where to report the outcome, from the x-callback-url parameters
+#[derive(Debug, Default, PartialEq)]
+struct Callbacks {
+    success: Option<String>,
+    error: Option<String>,
+}
+
+/// Parses a natural-language or RFC 3339 time such as "tomorrow 9am" relative to `now`
+fn parse_time(value: &str, now: DateTime<Local>) -> Result<DateTime<Utc>> {
+    if let Ok(at) = DateTime::parse_from_rfc3339(value) {
+        return Ok(at.with_timezone(&Utc));
+    }
+    chrono_english::parse_date_string(value, now, chrono_english::Dialect::Uk)
+        .map(|at| at.with_timezone(&Utc))
+        .map_err(|e| AppError::InvalidTask(format!("Cannot understand the time '{}': {}", value, e)))
+}
+
+fn parse(url: &Url, now: DateTime<Local>) -> Result<(Action, Callbacks)> {
+    if url.scheme() != SCHEME {
+        return Err(AppError::InvalidTask(format!("Not a {} URL: {}", SCHEME, url)));
+    }
+
+    let params: HashMap<String, String> = url.query_pairs().into_owned().collect();
+    let callbacks = Callbacks {
+        success: params.get("x-success").cloned(),
+        error: params.get("x-error").cloned(),
+    };
+    let param = |name: &str| params.get(name).map(|v| v.trim()).filter(|v| !v.is_empty());
+
+    // Accept both do-in-time://x-callback-url/run-task and do-in-time://run-task
+    let action = match url.host_str() {
+        Some("x-callback-url") => url.path().trim_matches('/'),
+        host => host.unwrap_or_default(),
+    };
+
+    let action = match action {
+        "create-task" => Action::CreateTask {
+            name: param("name")
+                .ok_or_else(|| AppError::InvalidTask("A task needs a name".to_string()))?
+                .to_string(),
+            url: param("url").map(str::to_string),
+            at: parse_time(param("at").unwrap_or("now"), now)?,
+            browser: param("browser").map(|b| b.parse()).transpose().map_err(AppError::InvalidTask)?,
+        },
+        "run-task" => Action::RunTask(
+            param("id")
+                .and_then(|id| id.parse().ok())
+                .ok_or_else(|| AppError::InvalidTask("run-task needs a numeric id".to_string()))?,
+        ),
+        "import-task" => {
+            let code = param("data")
+                .ok_or_else(|| AppError::InvalidTask("import-task needs the shared task in data".to_string()))?;
+            share::decode(code)?;
+            Action::ImportTask(code.to_string())
+        }
+        "pause-scheduler" => Action::PauseScheduler(param("until").map(|until| parse_time(until, now)).transpose()?),
+        "resume-scheduler" => Action::ResumeScheduler,
+        other => return Err(AppError::InvalidTask(format!("Unknown action '{}'", other))),
+    };
+
+    Ok((action, callbacks))
+}
+
+/// Whether an action runs without the user seeing it first, and so needs `url_actions_enabled`
+fn is_unattended(action: &Action) -> bool {
+    matches!(action, Action::RunTask(_) | Action::PauseScheduler(_) | Action::ResumeScheduler)
+}
+
+/// Brings the main window up with `task` in the task form, for the user to save.
+fn show_draft(app: &AppHandle, task: &Task) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+    emit_import_task(app, task);
+}
+
+/// Runs an action and returns the values reported to `x-success`.
+async fn perform(app: &AppHandle, settings: &AppSettings, action: Action) -> Result<Vec<(&'static str, String)>> {
+    if is_unattended(&action) && !settings.url_actions_enabled {
+        return Err(AppError::PermissionDenied(
+            "Actions from links are turned off; enable them in Settings".to_string(),
+        ));
+    }
+
+    let scheduler = app
+        .try_state::<Arc<TaskScheduler>>()
+        .map(|scheduler| Arc::clone(&scheduler))
+        .ok_or_else(|| AppError::Scheduler("Scheduler was not initialized".to_string()))?;
+
+    match action {
+        Action::CreateTask { name, url, at, browser } => {
+            // Without a browser, the first installed one is used
+            let browser = browser
+                .or_else(|| browser_detector::get_installed_browsers().into_iter().next())
+                .ok_or_else(|| AppError::BrowserNotFound("No supported browser is installed".to_string()))?;
+            let mut task = Task::new(name, browser, at, locale::system_timezone());
+            task.url = url;
+
+            show_draft(app, &task);
+            Ok(Vec::new())
+        }
+        Action::RunTask(id) => {
+            scheduler.run_task_now(id).await?;
+            Ok(vec![("task_id", id.to_string())])
+        }
+        Action::ImportTask(code) => {
+            show_draft(app, &share::import(&code)?);
+            Ok(Vec::new())
+        }
+        Action::PauseScheduler(None) => {
+            scheduler.stop().await?;
+            Ok(Vec::new())
+        }
+        Action::PauseScheduler(Some(until)) => {
+            scheduler.pause_until(until).await?;
+            Ok(vec![("paused_until", until.to_rfc3339())])
+        }
+        Action::ResumeScheduler => {
+            scheduler.start().await?;
+            Ok(Vec::new())
+        }
+    }
+}
+
+/// Whether a callback may be opened: it must return to Shortcuts or to an app whose scheme the
+/// user allowed, never to an arbitrary protocol handler.
+fn is_allowed_callback(url: &Url, settings: &AppSettings) -> bool {
+    url.scheme() == "shortcuts" || settings.extra_url_schemes().contains(&url.scheme())
+}
+
+/// Opens an x-callback URL with `params` appended.
+fn open_callback(callback: &str, params: &[(&str, String)], settings: &AppSettings) {
+    let Ok(mut url) = Url::parse(callback) else {
+        eprintln!("Ignoring invalid callback URL: {}", callback);
+        return;
+    };
+    if !is_allowed_callback(&url, settings) {
+        eprintln!("Ignoring callback URL with scheme {}", url.scheme());
+        return;
+    }
+    url.query_pairs_mut().extend_pairs(params.iter().map(|(k, v)| (*k, v.as_str())));
+
+    #[cfg(target_os = "macos")]
+    let _ = reaper::spawn_detached(std::process::Command::new("/usr/bin/open").arg(url.as_str()));
+    #[cfg(target_os = "windows")]
+    let _ = reaper::spawn_detached(std::process::Command::new("explorer").arg(url.as_str()));
+    #[cfg(target_os = "linux")]
+    let _ = reaper::spawn_detached(std::process::Command::new("xdg-open").arg(url.as_str()));
+}
+
+/// Handles URLs the app was opened with.
+pub fn handle_urls(app: &AppHandle, urls: Vec<Url>) {
+    for url in urls {
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            let Some(db) = app.try_state::<Arc<Database>>().map(|db| Arc::clone(&db)) else {
+                eprintln!("Ignoring {}: database was not initialized", url);
+                return;
+            };
+            let settings = match db.get_settings().await {
+                Ok(settings) => settings,
+                Err(e) => {
+                    eprintln!("Ignoring {}: {}", url, e);
+                    return;
+                }
+            };
+
+            let (action, callbacks) = match parse(&url, Local::now()) {
+                Ok(parsed) => parsed,
+                Err(e) => {
+                    eprintln!("Ignoring {}: {}", url, e);
+                    let params: HashMap<String, String> = url.query_pairs().into_owned().collect();
+                    if let Some(error) = params.get("x-error") {
+                        let report = [("errorCode", e.code().to_string()), ("errorMessage", e.to_string())];
+                        open_callback(error, &report, &settings);
+                    }
+                    return;
+                }
+            };
+
+            match perform(&app, &settings, action).await {
+                Ok(result) => {
+                    if let Some(success) = callbacks.success {
+                        open_callback(&success, &result, &settings);
+                    }
+                }
+                Err(e) => {
+                    eprintln!("Failed to handle {}: {}", url, e);
+                    if let Some(error) = callbacks.error {
+                        let report = [("errorCode", e.code().to_string()), ("errorMessage", e.to_string())];
+                        open_callback(&error, &report, &settings);
+                    }
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use crate::db::AllowedUrlScheme;
+
+    fn now() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2024, 3, 4, 8, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_parse_run_task_with_callbacks() {
+        let url = Url::parse("do-in-time://x-callback-url/run-task?id=3&x-success=shortcuts%3A%2F%2F").unwrap();
+        let (action, callbacks) = parse(&url, now()).unwrap();
+
+        assert_eq!(action, Action::RunTask(3));
+        assert_eq!(callbacks.success.as_deref(), Some("shortcuts://"));
+        assert_eq!(callbacks.error, None);
+    }
+
+    #[test]
+    fn test_parse_create_task() {
+        let url = Url::parse(
+            "do-in-time://create-task?name=Standup&url=https%3A%2F%2Fmeet.example.com&at=2024-03-05T09%3A00%3A00Z",
+        )
+        .unwrap();
+        let (action, _) = parse(&url, now()).unwrap();
+
+        assert_eq!(
+            action,
+            Action::CreateTask {
+                name: "Standup".to_string(),
+                url: Some("https://meet.example.com".to_string()),
+                at: Utc.with_ymd_and_hms(2024, 3, 5, 9, 0, 0).unwrap(),
+                browser: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_import_task() {
+        let start = Utc.with_ymd_and_hms(2024, 3, 4, 10, 0, 0).unwrap();
+        let task = Task::new("Weekly report".to_string(), BrowserType::Chrome, start, "UTC".to_string());
+        let url = Url::parse(&share::share_link(&task).unwrap()).unwrap();
+        let (action, _) = parse(&url, now()).unwrap();
+        assert!(matches!(action, Action::ImportTask(_)));
+
+        let url = Url::parse("do-in-time://import-task?data=garbage").unwrap();
+        assert!(parse(&url, now()).is_err());
+    }
+
+    #[test]
+    fn test_parse_pause_scheduler() {
+        let url = Url::parse("do-in-time://x-callback-url/pause-scheduler").unwrap();
+        assert_eq!(parse(&url, now()).unwrap().0, Action::PauseScheduler(None));
+
+        let url = Url::parse("do-in-time://x-callback-url/pause-scheduler?until=2024-03-04T17%3A00%3A00Z").unwrap();
+        assert_eq!(
+            parse(&url, now()).unwrap().0,
+            Action::PauseScheduler(Some(Utc.with_ymd_and_hms(2024, 3, 4, 17, 0, 0).unwrap()))
+        );
+    }
+
+    #[test]
+    fn test_unattended_actions() {
+        assert!(is_unattended(&Action::RunTask(3)));
+        assert!(is_unattended(&Action::PauseScheduler(None)));
+        assert!(is_unattended(&Action::ResumeScheduler));
+        assert!(!is_unattended(&Action::ImportTask("code".to_string())));
+    }
+
+    #[test]
+    fn test_callbacks_need_an_allowed_scheme() {
+        let mut settings = AppSettings::default();
+        let allowed = |url: &str, settings: &AppSettings| is_allowed_callback(&Url::parse(url).unwrap(), settings);
+
+        assert!(allowed("shortcuts://x-callback-url/done", &settings));
+        assert!(!allowed("ms-msdt:/id%20PCWDiagnostic", &settings));
+        assert!(!allowed("smb://host/share", &settings));
+        assert!(!allowed("https://example.com", &settings));
+
+        settings.allowed_url_schemes = vec![AllowedUrlScheme { scheme: "things".to_string(), confirm: false }];
+        assert!(allowed("things:///add", &settings));
+    }
+
+    #[test]
+    fn test_parse_rejects_unknown_actions() {
+        let url = Url::parse("do-in-time://x-callback-url/delete-everything").unwrap();
+        assert!(parse(&url, now()).is_err());
+
+        let url = Url::parse("do-in-time://x-callback-url/create-task?url=https%3A%2F%2Fexample.com").unwrap();
+        assert!(parse(&url, now()).is_err());
+    }
+}
@@ -5,6 +5,9 @@ pub enum AppError {
     #[error("Database error: {0}")]
     Database(#[from] sqlx::Error),
 
+    #[error("Database is corrupt: {0}")]
+    Corrupt(String),
+
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 
@@ -17,12 +20,39 @@ pub enum AppError {
     #[error("Task not found: {0}")]
     TaskNotFound(i64),
 
+    #[error("Workspace not found: {0}")]
+    WorkspaceNotFound(i64),
+
+    #[error("Invalid workspace: {0}")]
+    InvalidWorkspace(String),
+
     #[error("Scheduler error: {0}")]
     Scheduler(String),
 
     #[error("Invalid task configuration: {0}")]
     InvalidTask(String),
 
+    #[error("Invalid settings: {0}")]
+    InvalidSettings(String),
+
+    #[error("Task {0} was modified elsewhere; reload it or force the update")]
+    Conflict(i64),
+
+    #[error("Task {0} is already executing")]
+    TaskBusy(i64),
+
+    #[error("Keyring error: {0}")]
+    Keyring(String),
+
+    #[error("Notification delivery failed: {0}")]
+    Notifier(String),
+
+    #[error("Invalid PIN: {0}")]
+    InvalidPin(String),
+
+    #[error("Permission denied: {0}")]
+    PermissionDenied(String),
+
     #[error("Already running")]
     AlreadyRunning,
 
@@ -30,4 +60,34 @@ pub enum AppError {
     NotRunning,
 }
 
+impl AppError {
+    /// Stable machine-readable code for surfacing errors to the frontend.
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::Database(sqlx::Error::Database(e)) if e.message().contains("database is locked") => {
+                "database_locked"
+            }
+            AppError::Database(_) => "database",
+            AppError::Corrupt(_) => "database_corrupt",
+            AppError::Io(_) => "io",
+            AppError::TimeParse(_) => "time_parse",
+            AppError::BrowserNotFound(_) => "browser_not_found",
+            AppError::TaskNotFound(_) => "task_not_found",
+            AppError::WorkspaceNotFound(_) => "workspace_not_found",
+            AppError::InvalidWorkspace(_) => "invalid_workspace",
+            AppError::Scheduler(_) => "scheduler",
+            AppError::InvalidTask(_) => "invalid_task",
+            AppError::InvalidSettings(_) => "invalid_settings",
+            AppError::Conflict(_) => "conflict",
+            AppError::TaskBusy(_) => "task_busy",
+            AppError::Keyring(_) => "keyring",
+            AppError::Notifier(_) => "notifier",
+            AppError::InvalidPin(_) => "invalid_pin",
+            AppError::PermissionDenied(_) => "permission_denied",
+            AppError::AlreadyRunning => "already_running",
+            AppError::NotRunning => "not_running",
+        }
+    }
+}
+
 pub type Result<T> = std::result::Result<T, AppError>;
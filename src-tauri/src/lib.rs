@@ -4,19 +4,28 @@ use tauri::Manager;
 mod commands;
 mod core;
 mod db;
+// URLs only reach the app through macOS `Opened` events
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+mod deep_link;
 mod error;
+mod notifications;
+mod report;
 mod utils;
 mod tray;
 
-use commands::{browser_commands, scheduler_commands, task_commands, settings_commands, window_commands};
+use commands::{browser_commands, dashboard_commands, health_commands, lock_commands, notification_commands, report_commands, scheduler_commands, task_commands, settings_commands, window_commands, workspace_commands};
+use core::access::AppLock;
+use core::history::EditHistory;
 use core::TaskScheduler;
-use db::Database;
+use db::{Database, NotificationKind};
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_autostart::init(tauri_plugin_autostart::MacosLauncher::LaunchAgent, Some(vec![])))
+        .manage(AppLock::default())
+        .manage(EditHistory::default())
         .setup(|app| {
             let app_handle = app.handle().clone();
 
@@ -34,12 +43,39 @@ pub fn run() {
                     .await
                     .expect("Failed to load settings");
 
+                if let Some(recovery) = db.recovery() {
+                    let body = format!(
+                        "The database was damaged and has been restored from the backup {}. The damaged copy was kept at {}.",
+                        recovery.backup.display(),
+                        recovery.moved_aside.display()
+                    );
+                    let _ = notifications::record(
+                        &app_handle,
+                        &db,
+                        NotificationKind::Failure,
+                        None,
+                        "Database restored from backup",
+                        &body,
+                    )
+                    .await;
+                    notifications::notify(&app_handle, "Database restored from backup", &body, "alert");
+                }
+
+                if let Err(e) = db.purge_expired_deleted_tasks().await {
+                    eprintln!("Failed to purge expired deleted tasks: {}", e);
+                }
+
                 // Create system tray and store it to prevent destruction
                 let tray = tray::create_tray(&app_handle).expect("Failed to create system tray");
 
                 // Initialize scheduler with AppHandle
                 let scheduler = Arc::new(TaskScheduler::new(Arc::clone(&db), app_handle.clone()));
 
+                core::updates::spawn_auto_check(app_handle.clone(), Arc::clone(&db));
+                notifications::email::spawn_daily_summary(Arc::clone(&db));
+                core::home_assistant::spawn_server(app_handle.clone(), Arc::clone(&db));
+                core::power_monitor::spawn(Arc::clone(&scheduler));
+
                 // Store in app state
                 app.manage(db);
                 app.manage(scheduler.clone());
@@ -63,6 +99,14 @@ pub fn run() {
             Ok(())
         })
         .on_window_event(|window, event| {
+            // Shortcuts dragged from a browser or the desktop start a new task for their link
+            if let tauri::WindowEvent::DragDrop(tauri::DragDropEvent::Drop { paths, .. }) = event {
+                let links = utils::links::links_from_paths(paths);
+                if !links.is_empty() {
+                    core::events::emit_quick_add(window.app_handle(), &links);
+                }
+            }
+
             if let tauri::WindowEvent::CloseRequested { api, .. } = event {
                 let app_handle = window.app_handle();
                 let db = app_handle.state::<Arc<Database>>();
@@ -84,20 +128,100 @@ pub fn run() {
         })
         .invoke_handler(tauri::generate_handler![
             task_commands::get_all_tasks,
+            task_commands::search_tasks,
+            task_commands::share_task,
+            task_commands::read_task_link,
             task_commands::get_task,
             task_commands::create_task,
+            task_commands::validate_task,
             task_commands::update_task,
             task_commands::delete_task,
+            task_commands::pause_task,
+            task_commands::resume_task,
+            task_commands::bulk_update_status,
+            task_commands::bulk_delete,
+            task_commands::bulk_shift_time,
+            task_commands::get_tags,
+            task_commands::get_tasks_by_tag,
+            task_commands::set_tag_status,
+            task_commands::get_deleted_tasks,
+            task_commands::restore_deleted_task,
+            task_commands::purge_deleted,
+            task_commands::get_audit_log,
+            task_commands::get_task_executions,
+            task_commands::get_recent_executions,
+            task_commands::get_task_revisions,
+            task_commands::restore_task_revision,
+            task_commands::undo_last_change,
+            task_commands::redo,
+            dashboard_commands::get_dashboard,
+            dashboard_commands::get_usage_stats,
+            report_commands::generate_weekly_report,
+            report_commands::export_ical,
+            report_commands::import_ical,
             scheduler_commands::start_scheduler,
             scheduler_commands::stop_scheduler,
             scheduler_commands::get_scheduler_status,
+            scheduler_commands::pause_scheduler_until,
+            scheduler_commands::resume_scheduler,
+            scheduler_commands::run_task_now,
+            scheduler_commands::snooze_task,
+            scheduler_commands::skip_next,
+            scheduler_commands::close_all_scheduled,
+            scheduler_commands::simulate_schedule,
+            scheduler_commands::get_backfill_report,
+            health_commands::health_check,
+            health_commands::check_for_updates,
             browser_commands::get_installed_browsers,
+            browser_commands::get_browser_profiles,
             browser_commands::get_default_browser,
+            browser_commands::test_browser_launch,
+            browser_commands::get_displays,
+            browser_commands::get_open_tabs,
+            browser_commands::get_session_snapshots,
+            browser_commands::delete_session_snapshot,
             settings_commands::get_settings,
             settings_commands::update_settings,
+            settings_commands::set_quiet_hours,
+            settings_commands::get_data_dir,
+            settings_commands::move_database,
+            settings_commands::get_locale_config,
+            settings_commands::humanize_duration,
+            settings_commands::humanize_until,
+            settings_commands::describe_schedule,
+            settings_commands::generate_home_assistant_token,
+            settings_commands::is_read_only,
+            workspace_commands::get_workspaces,
+            workspace_commands::create_workspace,
+            workspace_commands::delete_workspace,
+            workspace_commands::get_active_workspace,
+            workspace_commands::switch_workspace,
+            lock_commands::get_lock_status,
+            lock_commands::unlock,
+            lock_commands::lock,
+            lock_commands::set_pin,
             window_commands::toggle_window_visibility,
             window_commands::apply_auto_start,
+            window_commands::quit_app,
+            window_commands::paste_links,
+            window_commands::extend_task_close,
+            window_commands::close_task_now,
+            window_commands::dismiss_close_countdown,
+            notification_commands::get_notifications,
+            notification_commands::mark_notification_read,
+            notification_commands::mark_all_notifications_read,
+            notification_commands::clear_notifications,
+            notification_commands::set_smtp_password,
+            notification_commands::set_telegram_token,
+            notification_commands::send_test_notification,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while running tauri application")
+        .run(|_app, _event| {
+            // do-in-time:// URLs from Shortcuts and other apps
+            #[cfg(target_os = "macos")]
+            if let tauri::RunEvent::Opened { urls } = _event {
+                deep_link::handle_urls(_app, urls);
+            }
+        });
 }
@@ -0,0 +1,125 @@
+//! Buttons on upcoming-task warnings. Each platform shows them its own way; the chosen action is
+//! routed back here and runs through the same scheduler calls as the matching commands.
+
+use std::sync::Arc;
+use chrono::Duration;
+use tauri::{AppHandle, Manager};
+use crate::core::TaskScheduler;
+use crate::error::{AppError, Result};
+
+/// Minutes the Snooze button pushes an upcoming open back
+pub const SNOOZE_MINUTES: i64 = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NotificationAction {
+    Snooze,
+    Skip,
+    OpenNow,
+}
+
+impl NotificationAction {
+    pub const ALL: [NotificationAction; 3] = [Self::Snooze, Self::Skip, Self::OpenNow];
+
+    /// Identifier passed through the platform's notification system
+    pub fn key(&self) -> &'static str {
+        match self {
+            Self::Snooze => "snooze",
+            Self::Skip => "skip",
+            Self::OpenNow => "open",
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            Self::Snooze => format!("Snooze {} min", SNOOZE_MINUTES),
+            Self::Skip => "Skip".to_string(),
+            Self::OpenNow => "Open now".to_string(),
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|action| action.key() == key.trim())
+    }
+}
+
+/// Runs an action chosen on a notification about `task_id`.
+pub async fn perform(app: &AppHandle, task_id: i64, action: NotificationAction) -> Result<()> {
+    let scheduler = app
+        .try_state::<Arc<TaskScheduler>>()
+        .map(|scheduler| Arc::clone(&scheduler))
+        .ok_or_else(|| AppError::Scheduler("Scheduler was not initialized".to_string()))?;
+
+    match action {
+        NotificationAction::Snooze => scheduler.snooze_task(task_id, SNOOZE_MINUTES).await.map(|_| ()),
+        NotificationAction::Skip => scheduler.skip_next(task_id).await.map(|_| ()),
+        NotificationAction::OpenNow => scheduler.run_task_now(task_id).await,
+    }
+}
+
+/// Response the platform reports when the notification itself, not a button, was clicked
+pub const CLICKED: &str = "clicked";
+
+/// Handles what the platform reported back: an action key, [`CLICKED`], or nothing when the
+/// notification was dismissed.
+#[cfg_attr(target_os = "macos", allow(dead_code))]
+pub(super) async fn handle_response(app: &AppHandle, task_id: i64, response: &str) {
+    match NotificationAction::from_key(response) {
+        Some(action) => {
+            if let Err(e) = perform(app, task_id, action).await {
+                eprintln!("Failed to {} task {} from a notification: {}", action.key(), task_id, e);
+            }
+        }
+        None if response.trim() == CLICKED => super::navigate_to_task(app, task_id),
+        None => {}
+    }
+}
+
+/// Shows a warning about a task that opens in `remaining`, with snooze, skip and open-now
+/// buttons where the platform supports them.
+pub fn notify_upcoming(app: &AppHandle, task_id: i64, title: &str, body: &str, sound: &str, remaining: Duration) {
+    show(app, task_id, title, body, sound, remaining, &NotificationAction::ALL);
+}
+
+/// Asks whether to open a task now, with only an open-now button, shown for `wait`.
+pub fn notify_confirm_open(app: &AppHandle, task_id: i64, title: &str, body: &str, sound: &str, wait: Duration) {
+    show(app, task_id, title, body, sound, wait, &[NotificationAction::OpenNow]);
+}
+
+/// Shows a notification about `task_id` with `buttons`, routing a click on it or a button back
+/// where the platform reports them.
+pub(super) fn show(
+    app: &AppHandle,
+    task_id: i64,
+    title: &str,
+    body: &str,
+    sound: &str,
+    remaining: Duration,
+    buttons: &[NotificationAction],
+) {
+    #[cfg(target_os = "windows")]
+    {
+        super::windows_toast::show_with_actions(app, task_id, title, body, sound, remaining, buttons);
+    }
+
+    #[cfg(target_os = "linux")]
+    super::linux_notify::show_with_actions(app, task_id, title, body, sound, remaining, buttons);
+
+    #[cfg(target_os = "macos")]
+    {
+        let _ = (task_id, remaining, buttons);
+        super::notify(app, title, body, sound);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_action_keys_round_trip() {
+        for action in NotificationAction::ALL {
+            assert_eq!(NotificationAction::from_key(action.key()), Some(action));
+        }
+        assert_eq!(NotificationAction::from_key("delete"), None);
+    }
+}
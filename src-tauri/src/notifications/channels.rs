@@ -0,0 +1,171 @@
+//! Places notifications are forwarded to besides the desktop and the in-app notification center.
+
+use std::sync::Arc;
+use serde_json::json;
+use tauri::{AppHandle, Manager};
+use crate::db::{AppNotification, AppSettings, Database, NotificationKind};
+use crate::error::{AppError, Result};
+use crate::notifications::email::EmailChannel;
+use crate::utils::{curl, keyring};
+
+/// Keyring entry holding the Telegram bot token
+pub const TELEGRAM_TOKEN_KEY: &str = "telegram-bot-token";
+
+/// An external destination for notifications, such as email or a team chat.
+pub trait NotificationChannel: Send + Sync {
+    /// Short name used in logs
+    fn name(&self) -> &'static str;
+
+    /// Whether notifications of `kind` go to this channel
+    fn accepts(&self, kind: &NotificationKind) -> bool;
+
+    /// Delivers a notification; blocks until the channel has taken it.
+    fn send(&self, notification: &AppNotification) -> Result<()>;
+}
+
+/// Posts task failures to a Slack incoming webhook.
+pub struct SlackChannel {
+    webhook_url: String,
+}
+
+impl NotificationChannel for SlackChannel {
+    fn name(&self) -> &'static str {
+        "slack"
+    }
+
+    fn accepts(&self, kind: &NotificationKind) -> bool {
+        *kind == NotificationKind::Failure
+    }
+
+    fn send(&self, notification: &AppNotification) -> Result<()> {
+        let text = format!("*{}*\n{}", notification.title, notification.body);
+        curl::post_json(&self.webhook_url, &json!({ "text": text })).map(|_| ())
+    }
+}
+
+/// Posts task failures to a Discord webhook.
+pub struct DiscordChannel {
+    webhook_url: String,
+}
+
+impl NotificationChannel for DiscordChannel {
+    fn name(&self) -> &'static str {
+        "discord"
+    }
+
+    fn accepts(&self, kind: &NotificationKind) -> bool {
+        *kind == NotificationKind::Failure
+    }
+
+    fn send(&self, notification: &AppNotification) -> Result<()> {
+        let content = format!("**{}**\n{}", notification.title, notification.body);
+        curl::post_json(&self.webhook_url, &json!({ "content": content })).map(|_| ())
+    }
+}
+
+/// Sends reminders and task failures to a Telegram chat through the user's bot.
+pub struct TelegramChannel {
+    chat_id: String,
+}
+
+impl NotificationChannel for TelegramChannel {
+    fn name(&self) -> &'static str {
+        "telegram"
+    }
+
+    fn accepts(&self, kind: &NotificationKind) -> bool {
+        matches!(kind, NotificationKind::Failure | NotificationKind::Reminder)
+    }
+
+    fn send(&self, notification: &AppNotification) -> Result<()> {
+        let token = keyring::get_secret(TELEGRAM_TOKEN_KEY)?
+            .ok_or_else(|| AppError::Notifier("No Telegram bot token is saved".to_string()))?;
+        let url = format!("https://api.telegram.org/bot{}/sendMessage", token);
+        let text = format!("{}\n{}", notification.title, notification.body);
+        curl::post_json(&url, &json!({ "chat_id": self.chat_id, "text": text })).map(|_| ())
+    }
+}
+
+/// Channels the user has set up.
+pub fn configured(settings: &AppSettings) -> Vec<Box<dyn NotificationChannel>> {
+    let mut channels: Vec<Box<dyn NotificationChannel>> = Vec::new();
+
+    if settings.smtp_enabled {
+        channels.push(Box::new(EmailChannel::new(settings.clone())));
+    }
+    if !settings.slack_webhook_url.trim().is_empty() {
+        channels.push(Box::new(SlackChannel { webhook_url: settings.slack_webhook_url.trim().to_string() }));
+    }
+    if !settings.discord_webhook_url.trim().is_empty() {
+        channels.push(Box::new(DiscordChannel { webhook_url: settings.discord_webhook_url.trim().to_string() }));
+    }
+    if !settings.telegram_chat_id.trim().is_empty() {
+        channels.push(Box::new(TelegramChannel { chat_id: settings.telegram_chat_id.trim().to_string() }));
+    }
+
+    channels
+}
+
+/// Sends a notification to every configured channel that accepts it, in the background.
+pub fn forward(app: &AppHandle, notification: &AppNotification) {
+    let Some(db) = app.try_state::<Arc<Database>>().map(|db| Arc::clone(&db)) else { return };
+
+    let notification = notification.clone();
+    tauri::async_runtime::spawn(async move {
+        let Ok(settings) = db.get_settings().await else { return };
+        let channels: Vec<_> = configured(&settings)
+            .into_iter()
+            .filter(|channel| channel.accepts(&notification.kind))
+            .collect();
+        if channels.is_empty() {
+            return;
+        }
+
+        let _ = tokio::task::spawn_blocking(move || {
+            for channel in channels {
+                if let Err(e) = channel.send(&notification) {
+                    eprintln!("Failed to send notification to {}: {}", channel.name(), e);
+                }
+            }
+        })
+        .await;
+    });
+}
+
+/// Sends a test notification to one configured channel by name.
+pub async fn send_test(settings: &AppSettings, name: &str) -> Result<()> {
+    let channel = configured(settings)
+        .into_iter()
+        .find(|channel| channel.name() == name)
+        .ok_or_else(|| AppError::Notifier(format!("Channel '{}' is not set up", name)))?;
+
+    let notification = AppNotification {
+        id: 0,
+        kind: NotificationKind::Info,
+        task_id: None,
+        title: "Test notification from Do In Time".to_string(),
+        body: format!("The {} channel is set up correctly.", name),
+        read: false,
+        created_at: chrono::Utc::now(),
+    };
+
+    tokio::task::spawn_blocking(move || channel.send(&notification))
+        .await
+        .map_err(|e| AppError::Notifier(e.to_string()))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_configured_channels() {
+        let mut settings = AppSettings::default();
+        assert!(configured(&settings).is_empty());
+
+        settings.slack_webhook_url = "https://hooks.slack.com/services/T/B/X".to_string();
+        settings.discord_webhook_url = "  ".to_string();
+        let names: Vec<_> = configured(&settings).iter().map(|c| c.name()).collect();
+        assert_eq!(names, vec!["slack"]);
+    }
+}
@@ -0,0 +1,228 @@
+//! Failure notifications and a daily execution summary by email, sent through the user's SMTP
+//! server with the system curl. The password stays in the OS keyring and reaches curl on stdin.
+
+use std::future::Future;
+use std::sync::Arc;
+use chrono::{DateTime, Duration, Local, Utc};
+use crate::db::{AppNotification, AppSettings, Database, LocaleConfig, NotificationKind, TaskExecutionSummary};
+use crate::error::{AppError, Result};
+use crate::notifications::channels::NotificationChannel;
+use crate::utils::{curl, keyring, locale};
+
+/// Keyring entry holding the SMTP password
+pub const PASSWORD_KEY: &str = "smtp-password";
+
+/// Port of SMTP over implicit TLS; other ports are upgraded with STARTTLS
+const SMTPS_PORT: u16 = 465;
+
+/// How often the background task wakes up to see whether the daily summary is due
+const SUMMARY_POLL: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+
+fn recipients(settings: &AppSettings) -> Vec<String> {
+    settings
+        .smtp_to
+        .split(',')
+        .map(|r| r.trim().to_string())
+        .filter(|r| !r.is_empty())
+        .collect()
+}
+
+fn smtp_url(host: &str, port: u16) -> String {
+    let scheme = if port == SMTPS_PORT { "smtps" } else { "smtp" };
+    format!("{}://{}:{}", scheme, host, port)
+}
+
+/// Strips line breaks so a value cannot add headers of its own
+fn header_value(value: &str) -> String {
+    value.replace(['\r', '\n'], " ")
+}
+
+/// The message in RFC 5322 format, as uploaded to the server
+fn format_message(from: &str, to: &[String], subject: &str, body: &str, date: DateTime<Utc>) -> String {
+    format!(
+        "From: {}\r\nTo: {}\r\nSubject: {}\r\nDate: {}\r\nMIME-Version: 1.0\r\nContent-Type: text/plain; charset=utf-8\r\n\r\n{}\r\n",
+        header_value(from),
+        header_value(&to.join(", ")),
+        header_value(subject),
+        date.to_rfc2822(),
+        body.replace("\r\n", "\n").replace('\n', "\r\n"),
+    )
+}
+
+/// Sends an email with the configured server; blocks until curl is done.
+fn send_blocking(settings: &AppSettings, subject: &str, body: &str) -> Result<()> {
+    let to = recipients(settings);
+    if settings.smtp_host.trim().is_empty() || settings.smtp_from.trim().is_empty() || to.is_empty() {
+        return Err(AppError::Notifier(
+            "Email needs an SMTP host, a sender and at least one recipient".to_string(),
+        ));
+    }
+
+    let path = std::env::temp_dir().join(format!("do-in-time-mail-{:016x}.eml", rand::random::<u64>()));
+    std::fs::write(&path, format_message(&settings.smtp_from, &to, subject, body, Utc::now()))?;
+
+    let mut command = curl::command();
+    command
+        .args(["--silent", "--show-error", "--max-time", "30"])
+        .args(["--url", &smtp_url(settings.smtp_host.trim(), settings.smtp_port)])
+        .args(["--mail-from", settings.smtp_from.trim()]);
+    for recipient in &to {
+        command.args(["--mail-rcpt", recipient]);
+    }
+    if settings.smtp_port != SMTPS_PORT {
+        command.arg("--ssl-reqd");
+    }
+    command.arg("--upload-file").arg(&path);
+
+    // Credentials go through the config on stdin so they stay out of the process list
+    let config = match settings.smtp_username.trim() {
+        "" => String::new(),
+        username => {
+            let password = keyring::get_secret(PASSWORD_KEY)?.unwrap_or_default();
+            format!("user = {}\n", curl::config_quote(&format!("{}:{}", username, password)))
+        }
+    };
+
+    let result = curl::run_with_config(command, &config).map(|_| ());
+    let _ = std::fs::remove_file(&path);
+    result
+}
+
+/// Sends an email with the configured server.
+pub async fn send(settings: AppSettings, subject: String, body: String) -> Result<()> {
+    tokio::task::spawn_blocking(move || send_blocking(&settings, &subject, &body))
+        .await
+        .map_err(|e| AppError::Notifier(e.to_string()))?
+}
+
+/// Emails failure notifications while `smtp_enabled` is set.
+pub struct EmailChannel {
+    settings: AppSettings,
+}
+
+impl EmailChannel {
+    pub fn new(settings: AppSettings) -> Self {
+        Self { settings }
+    }
+}
+
+impl NotificationChannel for EmailChannel {
+    fn name(&self) -> &'static str {
+        "email"
+    }
+
+    fn accepts(&self, kind: &NotificationKind) -> bool {
+        *kind == NotificationKind::Failure
+    }
+
+    fn send(&self, notification: &AppNotification) -> Result<()> {
+        send_blocking(&self.settings, &notification.title, &notification.body)
+    }
+}
+
+/// Plain-text summary of a day's executions
+fn summary_text(tasks: &[TaskExecutionSummary]) -> String {
+    if tasks.is_empty() {
+        return "No tasks ran in the last 24 hours.".to_string();
+    }
+
+    let mut text = String::new();
+    for task in tasks {
+        text.push_str(&format!(
+            "{}: {} open(s), {} close(s), {} failure(s), open for {}\n",
+            task.task_name,
+            task.opens,
+            task.closes,
+            task.failures,
+            locale::humanize_duration(Duration::seconds(task.total_open_secs)),
+        ));
+    }
+    text
+}
+
+/// Emails a summary of the last 24 hours once a day while `smtp_daily_summary` is enabled.
+pub fn spawn_daily_summary(db: Arc<Database>) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            if let Err(e) = send_summary_if_due(&db, send).await {
+                eprintln!("Failed to email daily summary: {}", e);
+            }
+            tokio::time::sleep(SUMMARY_POLL).await;
+        }
+    });
+}
+
+/// Sends the summary through `send` unless one already went out today.
+async fn send_summary_if_due<F, Fut>(db: &Database, send: F) -> Result<()>
+where
+    F: FnOnce(AppSettings, String, String) -> Fut,
+    Fut: Future<Output = Result<()>>,
+{
+    let settings = db.get_settings().await?;
+    if !settings.smtp_enabled || !settings.smtp_daily_summary {
+        return Ok(());
+    }
+
+    let today = Local::now().date_naive().to_string();
+    if db.get_setting_value("last_daily_summary").await?.as_deref() == Some(today.as_str()) {
+        return Ok(());
+    }
+
+    let now = Utc::now();
+    let tasks = db.get_execution_summary(now - Duration::days(1), now).await?;
+    let subject = format!(
+        "Daily summary for {}",
+        locale::format_date(now, &LocaleConfig::from(&settings))
+    );
+    send(settings, subject, summary_text(&tasks)).await?;
+
+    db.update_setting_value("last_daily_summary", &today).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn test_format_message_strips_header_injection() {
+        let date = Utc::now();
+        let message = format_message(
+            "app@example.com",
+            &["me@example.com".to_string()],
+            "Task failed\r\nBcc: other@example.com",
+            "line one\nline two",
+            date,
+        );
+
+        assert!(message.contains("Subject: Task failed  Bcc: other@example.com\r\n"));
+        assert!(message.ends_with("\r\n\r\nline one\r\nline two\r\n"));
+    }
+
+    #[test]
+    fn test_smtp_url() {
+        assert_eq!(smtp_url("mail.example.com", 465), "smtps://mail.example.com:465");
+        assert_eq!(smtp_url("mail.example.com", 587), "smtp://mail.example.com:587");
+    }
+
+    #[tokio::test]
+    async fn test_daily_summary_sends_once_a_day() {
+        let db = Database::in_memory().await.unwrap();
+        db.update_setting("smtp_enabled", true).await.unwrap();
+        db.update_setting("smtp_daily_summary", true).await.unwrap();
+
+        let sent = AtomicUsize::new(0);
+        for _ in 0..2 {
+            let sent = &sent;
+            send_summary_if_due(&db, move |_, _, _| async move {
+                sent.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            })
+            .await
+            .unwrap();
+        }
+
+        assert_eq!(sent.into_inner(), 1);
+    }
+}
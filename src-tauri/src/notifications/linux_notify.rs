@@ -0,0 +1,77 @@
+//! Clickable notifications and notifications with action buttons on Linux, through
+//! `notify-send` from libnotify 0.7.9 or later. With actions, notify-send waits for the
+//! notification to close and prints the name of the chosen action, which is routed to the
+//! action handlers.
+
+use std::process::Command;
+use chrono::Duration;
+use tauri::AppHandle;
+use super::actions::{self, NotificationAction, CLICKED};
+
+/// Action name the freedesktop spec uses for a click on the notification body
+const DEFAULT_ACTION: &str = "default";
+
+fn args(title: &str, body: &str, sound: &str, remaining: Duration, buttons: &[NotificationAction]) -> Vec<String> {
+    let mut args = vec![
+        "--app-name=Do In Time".to_string(),
+        "--urgency=normal".to_string(),
+        format!("--expire-time={}", remaining.num_milliseconds().max(60_000)),
+        format!("--action={}=Open task", DEFAULT_ACTION),
+    ];
+    if let Some(sound) = super::resolve_sound(sound) {
+        args.push(format!("--hint=string:sound-name:{}", sound));
+    }
+    args.extend(
+        buttons
+            .iter()
+            .map(|action| format!("--action={}={}", action.key(), action.label())),
+    );
+    // `--` keeps a title starting with a dash from being read as an option
+    args.extend(["--".to_string(), title.to_string(), body.to_string()]);
+    args
+}
+
+/// Shows a notification with the given buttons, if any, falling back to a plain notification
+/// when notify-send is missing or too old for actions.
+pub fn show_with_actions(
+    app: &AppHandle,
+    task_id: i64,
+    title: &str,
+    body: &str,
+    sound: &str,
+    remaining: Duration,
+    buttons: &[NotificationAction],
+) {
+    let app = app.clone();
+    let args = args(title, body, sound, remaining, buttons);
+    let (title, body, sound) = (title.to_string(), body.to_string(), sound.to_string());
+
+    tauri::async_runtime::spawn(async move {
+        let output = tokio::task::spawn_blocking(move || Command::new("notify-send").args(args).output()).await;
+
+        match output {
+            Ok(Ok(output)) if output.status.success() => {
+                let response = String::from_utf8_lossy(&output.stdout).trim().to_string();
+                let response = if response == DEFAULT_ACTION { CLICKED } else { response.as_str() };
+                actions::handle_response(&app, task_id, response).await;
+            }
+            _ => super::notify(&app, &title, &body, &sound),
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_args_list_every_action() {
+        let args = args("-rf", "Opens in 5 min", "none", Duration::minutes(5), &NotificationAction::ALL);
+
+        assert!(args.contains(&"--expire-time=300000".to_string()));
+        assert!(args.contains(&"--action=snooze=Snooze 10 min".to_string()));
+        assert!(args.contains(&"--action=open=Open now".to_string()));
+        assert!(!args.iter().any(|arg| arg.starts_with("--hint")));
+        assert_eq!(args[args.len() - 3..], ["--", "-rf", "Opens in 5 min"]);
+    }
+}
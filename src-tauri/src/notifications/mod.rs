@@ -0,0 +1,105 @@
+use chrono::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_notification::NotificationExt;
+use crate::db::{AppNotification, Database, NotificationKind};
+use crate::error::Result;
+
+pub mod actions;
+pub mod channels;
+pub mod email;
+pub mod template;
+#[cfg(target_os = "linux")]
+mod linux_notify;
+#[cfg(target_os = "windows")]
+mod windows_toast;
+
+/// Minutes a task notification can still be clicked to open the task
+const CLICK_WAIT_MINUTES: i64 = 10;
+
+/// Platform sound played for the `default` sound setting
+#[cfg(target_os = "macos")]
+const DEFAULT_SOUND: &str = "Default";
+#[cfg(target_os = "windows")]
+const DEFAULT_SOUND: &str = "Default";
+#[cfg(target_os = "linux")]
+const DEFAULT_SOUND: &str = "message-new-instant";
+
+/// Platform sound played for the `alert` sound setting, meant to stand out from the default
+#[cfg(target_os = "macos")]
+const ALERT_SOUND: &str = "Basso";
+#[cfg(target_os = "windows")]
+const ALERT_SOUND: &str = "Alarm";
+#[cfg(target_os = "linux")]
+const ALERT_SOUND: &str = "dialog-warning";
+
+/// Sound to play for a sound setting; `None` keeps the notification silent.
+pub(crate) fn resolve_sound(setting: &str) -> Option<&str> {
+    match setting.trim() {
+        "" | "none" => None,
+        "default" => Some(DEFAULT_SOUND),
+        "alert" => Some(ALERT_SOUND),
+        name => Some(name),
+    }
+}
+
+/// Shows a notification about a task. Clicking it opens the task, on Windows and Linux where
+/// the notification reports its activation back; macOS notifications are not clickable.
+///
+/// `sound` is one of the sound settings, e.g. `AppSettings::failure_sound`.
+pub fn notify_task(app: &AppHandle, task_id: i64, title: &str, body: &str, sound: &str) {
+    actions::show(app, task_id, title, body, sound, Duration::minutes(CLICK_WAIT_MINUTES), &[]);
+}
+
+/// Shows a notification that is not about a particular task.
+pub fn notify(app: &AppHandle, title: &str, body: &str, sound: &str) {
+    let mut builder = app.notification().builder().title(title).body(body);
+    if let Some(sound) = resolve_sound(sound) {
+        builder = builder.sound(sound);
+    }
+    let _ = builder.show();
+}
+
+/// Brings the main window up and asks the frontend to show the given task.
+pub fn navigate_to_task(app: &AppHandle, task_id: i64) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+
+    let _ = app.emit("navigate-to-task", task_id);
+}
+
+/// Stores an entry in the notification center so it survives after the toast is gone.
+pub async fn record(
+    app: &AppHandle,
+    db: &Database,
+    kind: NotificationKind,
+    task_id: Option<i64>,
+    title: &str,
+    body: &str,
+) -> Result<AppNotification> {
+    let notification = db.create_notification(kind, task_id, title, body).await?;
+    publish(app, &notification);
+    Ok(notification)
+}
+
+/// Tells the frontend about a stored notification and forwards it to the channels.
+pub fn publish(app: &AppHandle, notification: &AppNotification) {
+    let _ = app.emit("notification-created", notification.clone());
+    channels::forward(app, notification);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_sound() {
+        assert_eq!(resolve_sound("none"), None);
+        assert_eq!(resolve_sound(""), None);
+        assert_eq!(resolve_sound("default"), Some(DEFAULT_SOUND));
+        assert_eq!(resolve_sound("alert"), Some(ALERT_SOUND));
+        assert_eq!(resolve_sound("Glass"), Some("Glass"));
+    }
+}
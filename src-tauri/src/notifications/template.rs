@@ -0,0 +1,69 @@
+//! User-defined notification wording with `{task}`, `{url}`, `{time}` and `{error}` placeholders.
+
+/// Values substituted into a template; missing values render as empty text.
+#[derive(Debug, Default)]
+pub struct TemplateValues<'a> {
+    pub task: &'a str,
+    pub url: &'a str,
+    pub time: &'a str,
+    pub error: &'a str,
+}
+
+/// Renders `template`, or returns `fallback` when the template is empty.
+///
+/// Unknown placeholders are kept as written so a typo shows up in the notification.
+pub fn render(template: &str, values: &TemplateValues, fallback: String) -> String {
+    if template.trim().is_empty() {
+        return fallback;
+    }
+
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        rendered.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let Some(end) = rest.find('}') else { break };
+
+        let value = match &rest[1..end] {
+            "task" => Some(values.task),
+            "url" => Some(values.url),
+            "time" => Some(values.time),
+            "error" => Some(values.error),
+            _ => None,
+        };
+        match value {
+            Some(value) => rendered.push_str(value),
+            None => rendered.push_str(&rest[..=end]),
+        }
+        rest = &rest[end + 1..];
+    }
+    rendered.push_str(rest);
+
+    rendered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_placeholders() {
+        let values = TemplateValues {
+            task: "Standup",
+            url: "https://meet.example.com/{task}",
+            time: "09:00",
+            error: "",
+        };
+
+        assert_eq!(
+            render("[{time}] {task} opened {url}", &values, String::new()),
+            "[09:00] Standup opened https://meet.example.com/{task}"
+        );
+        assert_eq!(render("{unknown} {task", &values, String::new()), "{unknown} {task");
+    }
+
+    #[test]
+    fn test_render_empty_template_uses_fallback() {
+        assert_eq!(render("  ", &TemplateValues::default(), "Built-in".to_string()), "Built-in");
+    }
+}
@@ -0,0 +1,114 @@
+//! Interactive Windows toasts, shown through PowerShell and the WinRT toast API since the
+//! notification plugin has no buttons and does not report clicks. PowerShell stays alive until
+//! the toast is acted on and prints the chosen action, which is routed to the action handlers.
+
+use std::os::windows::process::CommandExt;
+use std::process::Command;
+use chrono::Duration;
+use tauri::{AppHandle, Manager};
+use super::actions::{self, NotificationAction, CLICKED};
+
+/// Keeps PowerShell from flashing a console window
+const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+/// Title, body, sound and button markup come in through environment variables, so nothing
+/// from a task name reaches the script text.
+const TOAST_SCRIPT: &str = r#"
+[Windows.UI.Notifications.ToastNotificationManager, Windows.UI.Notifications, ContentType = WindowsRuntime] | Out-Null
+[Windows.Data.Xml.Dom.XmlDocument, Windows.Data.Xml.Dom.XmlDocument, ContentType = WindowsRuntime] | Out-Null
+$title = [System.Security.SecurityElement]::Escape($env:DIT_TOAST_TITLE)
+$body = [System.Security.SecurityElement]::Escape($env:DIT_TOAST_BODY)
+$xml = New-Object Windows.Data.Xml.Dom.XmlDocument
+$xml.LoadXml("<toast scenario='$env:DIT_TOAST_SCENARIO' launch='$env:DIT_TOAST_LAUNCH'><visual><binding template='ToastGeneric'><text>$title</text><text>$body</text></binding></visual>$env:DIT_TOAST_AUDIO$env:DIT_TOAST_ACTIONS</toast>")
+$toast = New-Object Windows.UI.Notifications.ToastNotification $xml
+Register-ObjectEvent -InputObject $toast -EventName Activated -SourceIdentifier Activated | Out-Null
+Register-ObjectEvent -InputObject $toast -EventName Dismissed -SourceIdentifier Dismissed | Out-Null
+[Windows.UI.Notifications.ToastNotificationManager]::CreateToastNotifier($env:DIT_TOAST_APP_ID).Show($toast)
+$event = Wait-Event -Timeout ([int]$env:DIT_TOAST_TIMEOUT)
+if ($event -and $event.SourceIdentifier -eq 'Activated') {
+    [Console]::Out.Write(([Windows.UI.Notifications.ToastActivatedEventArgs]$event.SourceArgs[1]).Arguments)
+}
+"#;
+
+/// Windows notification sounds that play once; the others only exist as looping sounds
+const SINGLE_SOUNDS: [&str; 5] = ["Default", "IM", "Mail", "Reminder", "SMS"];
+
+fn actions_xml(buttons: &[NotificationAction]) -> String {
+    if buttons.is_empty() {
+        return String::new();
+    }
+    let actions: String = buttons
+        .iter()
+        .map(|action| {
+            format!(
+                "<action content='{}' arguments='{}' activationType='foreground'/>",
+                action.label(),
+                action.key()
+            )
+        })
+        .collect();
+    format!("<actions>{}</actions>", actions)
+}
+
+/// The toast's audio element for a sound setting. Unknown sound names, e.g. macOS ones, fall
+/// back to the default sound.
+fn audio_xml(sound: &str) -> String {
+    let Some(name) = super::resolve_sound(sound) else {
+        return "<audio silent='true'/>".to_string();
+    };
+    let event = match SINGLE_SOUNDS.iter().find(|single| single.eq_ignore_ascii_case(name)) {
+        Some(single) => format!("Notification.{}", single),
+        None if name.eq_ignore_ascii_case("Alarm") || name.eq_ignore_ascii_case("Call") => {
+            format!("Notification.Looping.{}", name)
+        }
+        None => "Notification.Default".to_string(),
+    };
+    format!("<audio src='ms-winsoundevent:{}'/>", event)
+}
+
+/// Shows a toast with the given buttons, if any, waiting up to `remaining` for a click or a
+/// choice. Toasts with buttons stay on screen until acted on.
+pub fn show_with_actions(
+    app: &AppHandle,
+    task_id: i64,
+    title: &str,
+    body: &str,
+    sound: &str,
+    remaining: Duration,
+    buttons: &[NotificationAction],
+) {
+    let app = app.clone();
+    let scenario = if buttons.is_empty() { "default" } else { "reminder" };
+    let audio_xml = audio_xml(sound);
+    let actions_xml = actions_xml(buttons);
+    let app_id = app.config().identifier.clone();
+    let timeout = remaining.num_seconds().max(60).to_string();
+    let (title, body) = (title.to_string(), body.to_string());
+
+    tauri::async_runtime::spawn(async move {
+        let output = tokio::task::spawn_blocking(move || {
+            Command::new("powershell")
+                .args(["-NoProfile", "-NonInteractive", "-Command", TOAST_SCRIPT])
+                .env("DIT_TOAST_TITLE", title)
+                .env("DIT_TOAST_BODY", body)
+                .env("DIT_TOAST_SCENARIO", scenario)
+                .env("DIT_TOAST_LAUNCH", CLICKED)
+                .env("DIT_TOAST_AUDIO", audio_xml)
+                .env("DIT_TOAST_ACTIONS", actions_xml)
+                .env("DIT_TOAST_APP_ID", app_id)
+                .env("DIT_TOAST_TIMEOUT", timeout)
+                .creation_flags(CREATE_NO_WINDOW)
+                .output()
+        })
+        .await;
+
+        match output {
+            Ok(Ok(output)) => {
+                let response = String::from_utf8_lossy(&output.stdout).to_string();
+                actions::handle_response(&app, task_id, &response).await;
+            }
+            Ok(Err(e)) => eprintln!("Failed to show toast: {}", e),
+            Err(e) => eprintln!("Failed to show toast: {}", e),
+        }
+    });
+}
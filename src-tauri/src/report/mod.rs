@@ -0,0 +1,170 @@
+use std::path::PathBuf;
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use crate::db::{Database, LocaleConfig, TaskExecutionSummary};
+use crate::error::Result;
+use crate::utils::locale;
+
+/// Summary of executions, open time and failures per task over one week.
+#[derive(Debug, Clone, Serialize)]
+pub struct WeeklyReport {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub generated_at: DateTime<Utc>,
+    pub tasks: Vec<TaskExecutionSummary>,
+}
+
+impl WeeklyReport {
+    pub fn total_failures(&self) -> i64 {
+        self.tasks.iter().map(|t| t.failures).sum()
+    }
+
+    pub fn total_open_secs(&self) -> i64 {
+        self.tasks.iter().map(|t| t.total_open_secs).sum()
+    }
+}
+
+/// Builds the report for the seven days ending at `to`.
+pub async fn build_weekly_report(db: &Database, to: DateTime<Utc>) -> Result<WeeklyReport> {
+    let from = to - Duration::weeks(1);
+    let tasks = db.get_execution_summary(from, to).await?;
+
+    Ok(WeeklyReport {
+        from,
+        to,
+        generated_at: Utc::now(),
+        tasks,
+    })
+}
+
+/// Writes the HTML rendering into the `reports` folder of the data directory.
+pub fn save_report(report: &WeeklyReport, locale: &LocaleConfig) -> Result<PathBuf> {
+    let dir = Database::data_dir()?.join("reports");
+    std::fs::create_dir_all(&dir)?;
+
+    let path = dir.join(format!("weekly-{}.html", report.to.format("%Y-%m-%d")));
+    std::fs::write(&path, render_html(report, locale))?;
+
+    Ok(path)
+}
+
+/// Dates are written in the user's configured format.
+pub fn render_html(report: &WeeklyReport, locale: &LocaleConfig) -> String {
+    let mut rows = String::new();
+    for task in &report.tasks {
+        rows.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+            escape_html(&task.task_name),
+            task.opens,
+            task.closes,
+            task.failures,
+            format_duration(task.total_open_secs),
+        ));
+    }
+
+    if rows.is_empty() {
+        rows.push_str("<tr><td colspan=\"5\">No executions this week</td></tr>\n");
+    }
+
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>Weekly report {from} to {to}</title>
+<style>
+body {{ font-family: sans-serif; margin: 2rem; }}
+table {{ border-collapse: collapse; }}
+th, td {{ border: 1px solid #ccc; padding: 0.4rem 0.8rem; text-align: left; }}
+</style>
+</head>
+<body>
+<h1>Weekly report</h1>
+<p>{from} to {to}</p>
+<p>Total open time: {total_open} &middot; Failures: {failures}</p>
+<table>
+<tr><th>Task</th><th>Opens</th><th>Closes</th><th>Failures</th><th>Open time</th></tr>
+{rows}</table>
+</body>
+</html>
+"#,
+        from = locale::format_date(report.from, locale),
+        to = locale::format_date(report.to, locale),
+        total_open = format_duration(report.total_open_secs()),
+        failures = report.total_failures(),
+        rows = rows,
+    )
+}
+
+fn format_duration(secs: i64) -> String {
+    let hours = secs / 3600;
+    let minutes = (secs % 3600) / 60;
+    format!("{}h {:02}m", hours, minutes)
+}
+
+fn escape_html(input: &str) -> String {
+    let mut escaped = String::with_capacity(input.len());
+    for c in input.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(name: &str, failures: i64, total_open_secs: i64) -> TaskExecutionSummary {
+        TaskExecutionSummary {
+            task_id: 1,
+            task_name: name.to_string(),
+            opens: 5,
+            closes: 5,
+            failures,
+            total_open_secs,
+        }
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(0), "0h 00m");
+        assert_eq!(format_duration(21_600), "6h 00m");
+        assert_eq!(format_duration(5_430), "1h 30m");
+    }
+
+    #[test]
+    fn test_render_html_escapes_task_names() {
+        let to = Utc::now();
+        let report = WeeklyReport {
+            from: to - Duration::weeks(1),
+            to,
+            generated_at: to,
+            tasks: vec![summary("<script>alert('x')</script>", 1, 3600)],
+        };
+
+        let html = render_html(&report, &LocaleConfig::default());
+        assert!(!html.contains("<script>"));
+        assert!(html.contains("&lt;script&gt;"));
+        assert!(html.contains("Failures: 1"));
+    }
+
+    #[test]
+    fn test_render_html_empty_week() {
+        let to = Utc::now();
+        let report = WeeklyReport {
+            from: to - Duration::weeks(1),
+            to,
+            generated_at: to,
+            tasks: Vec::new(),
+        };
+
+        assert!(render_html(&report, &LocaleConfig::default()).contains("No executions this week"));
+    }
+}
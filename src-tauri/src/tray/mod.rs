@@ -1,9 +1,17 @@
+use std::sync::Arc;
+use chrono::Utc;
 use tauri::{
     AppHandle, Manager, Emitter,
     menu::{Menu, MenuItem},
     tray::{TrayIconBuilder, TrayIconEvent, MouseButton, MouseButtonState, TrayIcon},
     image::Image,
 };
+use crate::core::{quiet_hours, TaskScheduler};
+use crate::db::{Database, ExecutionAction};
+use crate::utils::{i18n, locale};
+
+/// Tooltip shown when nothing is scheduled
+const DEFAULT_TOOLTIP: &str = "Browser Scheduler";
 
 // Helper function to create menu
 fn create_menu(app: &AppHandle) -> Result<Menu<tauri::Wry>, String> {
@@ -11,10 +19,12 @@ fn create_menu(app: &AppHandle) -> Result<Menu<tauri::Wry>, String> {
         .map_err(|e| e.to_string())?;
     let settings = MenuItem::with_id(app, "settings", "Settings", true, None::<&str>)
         .map_err(|e| e.to_string())?;
+    let close_all = MenuItem::with_id(app, "close_all", "Close Everything Now", true, None::<&str>)
+        .map_err(|e| e.to_string())?;
     let quit = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)
         .map_err(|e| e.to_string())?;
 
-    Menu::with_items(app, &[&toggle_window, &settings, &quit])
+    Menu::with_items(app, &[&toggle_window, &settings, &close_all, &quit])
         .map_err(|e| e.to_string())
 }
 
@@ -58,7 +68,7 @@ pub fn create_tray(app: &AppHandle) -> Result<TrayIcon<tauri::Wry>, String> {
     // Create the tray icon with tooltip
     let tray = TrayIconBuilder::with_id("main_tray")
         .icon(icon)
-        .tooltip("Browser Scheduler")
+        .tooltip(DEFAULT_TOOLTIP)
         .menu(&menu)
         .show_menu_on_left_click(false)  // Only show menu on right-click
         .on_menu_event(move |app, event| {
@@ -89,8 +99,40 @@ pub fn create_tray(app: &AppHandle) -> Result<TrayIcon<tauri::Wry>, String> {
                         let _ = window.unminimize();
                     }
                 }
+                "close_all" => {
+                    if let Some(scheduler) = app.try_state::<Arc<TaskScheduler>>() {
+                        let scheduler = Arc::clone(&scheduler);
+                        tauri::async_runtime::spawn(async move {
+                            let closed = scheduler.close_all_opened().await;
+                            println!("Panic button closed {} scheduler-opened browser(s)", closed);
+                        });
+                    }
+                }
                 "quit" => {
-                    app.exit(0);
+                    let close_opened = app
+                        .try_state::<Arc<Database>>()
+                        .map(|db| {
+                            tauri::async_runtime::block_on(async {
+                                db.get_settings().await.map(|s| s.close_opened_on_quit).unwrap_or(false)
+                            })
+                        })
+                        .unwrap_or(false);
+                    let opened = app
+                        .try_state::<Arc<TaskScheduler>>()
+                        .map(|scheduler| scheduler.opened_count())
+                        .unwrap_or(0);
+
+                    if close_opened && opened > 0 {
+                        // The frontend confirms and then calls quit_app
+                        let _ = app.emit("confirm-quit", opened);
+                        if let Some(window) = app.get_webview_window("main") {
+                            let _ = window.show();
+                            let _ = window.set_focus();
+                            let _ = window.unminimize();
+                        }
+                    } else {
+                        app.exit(0);
+                    }
                 }
                 _ => {}
             }
@@ -125,3 +167,46 @@ pub fn create_tray(app: &AppHandle) -> Result<TrayIcon<tauri::Wry>, String> {
 
     Ok(tray)
 }
+
+/// Shows the next scheduled action in the tray tooltip, e.g. "Next: open Standup in 15 min",
+/// and when a pause of the scheduler or quiet hours end.
+pub async fn refresh_tooltip(app: &AppHandle, db: &Database) {
+    let Some(tray) = app.tray_by_id("main_tray") else { return };
+
+    let next = db.get_next_action().await.ok().flatten().and_then(|(task, action)| {
+        let at = match action {
+            ExecutionAction::Open => task.next_open_execution,
+            ExecutionAction::Close => task.next_close_execution,
+        }?;
+        Some((task, action, at))
+    });
+
+    let locale_config = db.get_locale_config().await.unwrap_or_default();
+    let paused_until = db.get_paused_until().await.ok().flatten().filter(|until| *until > Utc::now());
+    let quiet_until = db.get_settings().await.ok().and_then(|s| quiet_hours::active_now(&s));
+    let title = match (paused_until, quiet_until) {
+        (Some(until), _) => format!("{} (paused until {})", DEFAULT_TOOLTIP, locale::format_datetime(until, &locale_config)),
+        (None, Some(until)) => format!("{} (quiet until {})", DEFAULT_TOOLTIP, locale::format_datetime(until, &locale_config)),
+        (None, None) => DEFAULT_TOOLTIP.to_string(),
+    };
+
+    let tooltip = match next {
+        Some((task, action, at)) => {
+            let verb = match action {
+                ExecutionAction::Open => "open",
+                ExecutionAction::Close => "close",
+            };
+            format!(
+                "{}\nNext: {} {} {}\n{}",
+                title,
+                verb,
+                task.name,
+                locale::humanize_until(at, &locale_config),
+                i18n::describe_schedule(&task, &locale_config)
+            )
+        }
+        None => title,
+    };
+
+    let _ = tray.set_tooltip(Some(tooltip));
+}
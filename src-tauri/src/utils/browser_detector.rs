@@ -52,6 +52,24 @@ pub fn get_installed_browsers() -> Vec<BrowserType> {
         }
     }
 
+    if !browsers.contains(&BrowserType::Vivaldi) {
+        if check_vivaldi_installed() {
+            browsers.push(BrowserType::Vivaldi);
+        }
+    }
+
+    if !browsers.contains(&BrowserType::Chromium) {
+        if check_chromium_installed() {
+            browsers.push(BrowserType::Chromium);
+        }
+    }
+
+    if !browsers.contains(&BrowserType::Zen) {
+        if check_zen_installed() {
+            browsers.push(BrowserType::Zen);
+        }
+    }
+
     browsers.dedup();
     browsers
 }
@@ -86,6 +104,15 @@ fn check_registry_browsers() -> Vec<BrowserType> {
         if stdout.contains("opera") {
             browsers.push(BrowserType::Opera);
         }
+        if stdout.contains("vivaldi") {
+            browsers.push(BrowserType::Vivaldi);
+        }
+        if stdout.contains("chromium") {
+            browsers.push(BrowserType::Chromium);
+        }
+        if stdout.contains("zen browser") || stdout.contains("\\zen") {
+            browsers.push(BrowserType::Zen);
+        }
     }
 
     browsers
@@ -127,6 +154,28 @@ fn check_opera_installed() -> bool {
         || check_app_path("opera.exe")
 }
 
+#[cfg(target_os = "windows")]
+fn check_vivaldi_installed() -> bool {
+    std::path::Path::new("C:\\Program Files\\Vivaldi\\Application\\vivaldi.exe").exists()
+        || std::path::Path::new("C:\\Program Files (x86)\\Vivaldi\\Application\\vivaldi.exe").exists()
+        || check_app_path("vivaldi.exe")
+}
+
+#[cfg(target_os = "windows")]
+fn check_chromium_installed() -> bool {
+    // No App Paths check: Chromium's chrome.exe entry cannot be told apart from Google Chrome's
+    let local_app_data = std::env::var("LOCALAPPDATA").unwrap_or_default();
+    std::path::Path::new(&format!("{}\\Chromium\\Application\\chrome.exe", local_app_data)).exists()
+        || std::path::Path::new("C:\\Program Files\\Chromium\\Application\\chrome.exe").exists()
+}
+
+#[cfg(target_os = "windows")]
+fn check_zen_installed() -> bool {
+    std::path::Path::new("C:\\Program Files\\Zen Browser\\zen.exe").exists()
+        || std::path::Path::new("C:\\Program Files (x86)\\Zen Browser\\zen.exe").exists()
+        || check_app_path("zen.exe")
+}
+
 #[cfg(target_os = "windows")]
 fn check_app_path(exe_name: &str) -> bool {
     // Check registry App Paths for custom installations
@@ -177,6 +226,12 @@ pub fn get_default_browser() -> Option<BrowserType> {
             return Some(BrowserType::Brave);
         } else if stdout.contains("OperaStable") {
             return Some(BrowserType::Opera);
+        } else if stdout.contains("VivaldiHTM") {
+            return Some(BrowserType::Vivaldi);
+        } else if stdout.contains("ChromiumHTM") {
+            return Some(BrowserType::Chromium);
+        } else if stdout.contains("ZenHTML") || stdout.contains("ZenURL") {
+            return Some(BrowserType::Zen);
         }
     }
 
@@ -210,6 +265,19 @@ pub fn get_installed_browsers() -> Vec<BrowserType> {
         if stdout.contains("opera.app") {
             browsers.push(BrowserType::Opera);
         }
+        if stdout.contains("vivaldi.app") {
+            browsers.push(BrowserType::Vivaldi);
+        }
+        if stdout.contains("chromium.app") {
+            browsers.push(BrowserType::Chromium);
+        }
+        // With the slash, so that e.g. "Research.app" does not count
+        if stdout.contains("/arc.app") {
+            browsers.push(BrowserType::Arc);
+        }
+        if stdout.contains("/zen.app") || stdout.contains("/zen browser.app") {
+            browsers.push(BrowserType::Zen);
+        }
     }
 
     // Method 2: Fallback to standard paths
@@ -233,6 +301,25 @@ pub fn get_installed_browsers() -> Vec<BrowserType> {
         browsers.push(BrowserType::Opera);
     }
 
+    if !browsers.contains(&BrowserType::Vivaldi) && std::path::Path::new("/Applications/Vivaldi.app").exists() {
+        browsers.push(BrowserType::Vivaldi);
+    }
+
+    if !browsers.contains(&BrowserType::Chromium) && std::path::Path::new("/Applications/Chromium.app").exists() {
+        browsers.push(BrowserType::Chromium);
+    }
+
+    if !browsers.contains(&BrowserType::Arc) && std::path::Path::new("/Applications/Arc.app").exists() {
+        browsers.push(BrowserType::Arc);
+    }
+
+    if !browsers.contains(&BrowserType::Zen)
+        && (std::path::Path::new("/Applications/Zen.app").exists()
+            || std::path::Path::new("/Applications/Zen Browser.app").exists())
+    {
+        browsers.push(BrowserType::Zen);
+    }
+
     // Method 3: Check user Applications folder
     if let Ok(home) = std::env::var("HOME") {
         let user_apps = format!("{}/Applications", home);
@@ -264,6 +351,34 @@ pub fn get_installed_browsers() -> Vec<BrowserType> {
                 browsers.push(BrowserType::Opera);
             }
         }
+
+        if !browsers.contains(&BrowserType::Vivaldi) {
+            let vivaldi_path = format!("{}/Vivaldi.app", user_apps);
+            if std::path::Path::new(&vivaldi_path).exists() {
+                browsers.push(BrowserType::Vivaldi);
+            }
+        }
+
+        if !browsers.contains(&BrowserType::Chromium) {
+            let chromium_path = format!("{}/Chromium.app", user_apps);
+            if std::path::Path::new(&chromium_path).exists() {
+                browsers.push(BrowserType::Chromium);
+            }
+        }
+
+        if !browsers.contains(&BrowserType::Arc) {
+            let arc_path = format!("{}/Arc.app", user_apps);
+            if std::path::Path::new(&arc_path).exists() {
+                browsers.push(BrowserType::Arc);
+            }
+        }
+
+        if !browsers.contains(&BrowserType::Zen) {
+            let zen_path = format!("{}/Zen.app", user_apps);
+            if std::path::Path::new(&zen_path).exists() {
+                browsers.push(BrowserType::Zen);
+            }
+        }
     }
 
     browsers.dedup();
@@ -289,6 +404,14 @@ pub fn get_default_browser() -> Option<BrowserType> {
             return Some(BrowserType::Brave);
         } else if stdout.contains("opera") {
             return Some(BrowserType::Opera);
+        } else if stdout.contains("vivaldi") {
+            return Some(BrowserType::Vivaldi);
+        } else if stdout.contains("org.chromium.chromium") {
+            return Some(BrowserType::Chromium);
+        } else if stdout.contains("company.thebrowser.browser") {
+            return Some(BrowserType::Arc);
+        } else if stdout.contains("app.zen-browser.zen") {
+            return Some(BrowserType::Zen);
         }
     }
 
@@ -331,6 +454,20 @@ pub fn get_installed_browsers() -> Vec<BrowserType> {
                     if !browsers.contains(&BrowserType::Opera) && filename_lower.contains("opera") {
                         browsers.push(BrowserType::Opera);
                     }
+
+                    if !browsers.contains(&BrowserType::Vivaldi) && filename_lower.contains("vivaldi") {
+                        browsers.push(BrowserType::Vivaldi);
+                    }
+
+                    if !browsers.contains(&BrowserType::Chromium) && filename_lower.contains("chromium") {
+                        browsers.push(BrowserType::Chromium);
+                    }
+
+                    if !browsers.contains(&BrowserType::Zen)
+                        && (filename_lower.starts_with("zen") || filename_lower.contains("zen_browser"))
+                    {
+                        browsers.push(BrowserType::Zen);
+                    }
                 }
             }
         }
@@ -396,6 +533,49 @@ pub fn get_installed_browsers() -> Vec<BrowserType> {
         }
     }
 
+    if !browsers.contains(&BrowserType::Vivaldi) {
+        if Command::new("which")
+            .arg("vivaldi")
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false)
+            || Command::new("which")
+                .arg("vivaldi-stable")
+                .output()
+                .map(|o| o.status.success())
+                .unwrap_or(false)
+        {
+            browsers.push(BrowserType::Vivaldi);
+        }
+    }
+
+    if !browsers.contains(&BrowserType::Chromium) {
+        if Command::new("which")
+            .arg("chromium")
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false)
+            || Command::new("which")
+                .arg("chromium-browser")
+                .output()
+                .map(|o| o.status.success())
+                .unwrap_or(false)
+        {
+            browsers.push(BrowserType::Chromium);
+        }
+    }
+
+    if !browsers.contains(&BrowserType::Zen) {
+        if Command::new("which")
+            .arg("zen-browser")
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false)
+        {
+            browsers.push(BrowserType::Zen);
+        }
+    }
+
     browsers.dedup();
     browsers
 }
@@ -417,6 +597,12 @@ pub fn get_default_browser() -> Option<BrowserType> {
             return Some(BrowserType::Brave);
         } else if stdout.contains("opera") {
             return Some(BrowserType::Opera);
+        } else if stdout.contains("vivaldi") {
+            return Some(BrowserType::Vivaldi);
+        } else if stdout.contains("chromium") {
+            return Some(BrowserType::Chromium);
+        } else if stdout.starts_with("zen") || stdout.contains("zen_browser") {
+            return Some(BrowserType::Zen);
         }
     }
 
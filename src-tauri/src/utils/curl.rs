@@ -0,0 +1,69 @@
+//! The system curl, used for HTTP and SMTP so the app does not bundle a network stack.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+use crate::error::{AppError, Result};
+
+/// Command for the system curl; Windows 10 and later ship it in System32.
+pub fn command() -> Command {
+    #[cfg(target_os = "windows")]
+    let curl = std::env::var_os("SystemRoot")
+        .map(|root| std::path::PathBuf::from(root).join("System32").join("curl.exe"))
+        .unwrap_or_else(|| "curl.exe".into());
+    #[cfg(not(target_os = "windows"))]
+    let curl = std::path::PathBuf::from("curl");
+
+    Command::new(curl)
+}
+
+/// Quotes `value` for a curl config file, as read with `--config -`.
+pub fn config_quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Runs curl with `config` fed on stdin as `--config -`, returning its stdout.
+///
+/// Anything secret, like credentials or webhook URLs, belongs in the config so it stays out of
+/// the process list. Failures are reported as notification delivery errors.
+pub fn run_with_config(mut command: Command, config: &str) -> Result<String> {
+    let mut child = command
+        .args(["--config", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| AppError::Notifier(format!("Failed to start curl: {}", e)))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(config.as_bytes())?;
+    }
+
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(AppError::Notifier(String::from_utf8_lossy(&output.stderr).trim().to_string()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// POSTs `body` as JSON to `url` and returns the response body.
+pub fn post_json(url: &str, body: &serde_json::Value) -> Result<String> {
+    let mut command = command();
+    command.args(["--fail", "--silent", "--show-error", "--max-time", "15"]);
+
+    let config = format!(
+        "url = {}\nheader = \"Content-Type: application/json\"\ndata-binary = {}\n",
+        config_quote(url),
+        config_quote(&body.to_string())
+    );
+    run_with_config(command, &config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_quote() {
+        assert_eq!(config_quote("user:pa\"ss\\"), "\"user:pa\\\"ss\\\\\"");
+    }
+}
@@ -0,0 +1,297 @@
+//! Translations of backend-generated text: schedule descriptions and error messages shown in the
+//! tray, notifications and reports, in the language chosen in the settings.
+//!
+//! Free-form details inside errors (paths, browser output) are passed through untranslated.
+
+use chrono::{Datelike, Weekday};
+use chrono_tz::Tz;
+use crate::db::{Language, LocaleConfig, RepeatInterval, Task, LAST_WEEK_OF_MONTH};
+use crate::error::AppError;
+use crate::utils::locale;
+
+fn weekday_name(day: Weekday, language: Language) -> &'static str {
+    const EN: [&str; 7] = ["Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday", "Sunday"];
+    const DE: [&str; 7] = ["Montag", "Dienstag", "Mittwoch", "Donnerstag", "Freitag", "Samstag", "Sonntag"];
+    // French and Spanish name recurring days in the plural ("tous les lundis")
+    const FR: [&str; 7] = ["lundis", "mardis", "mercredis", "jeudis", "vendredis", "samedis", "dimanches"];
+    const ES: [&str; 7] = ["lunes", "martes", "miércoles", "jueves", "viernes", "sábados", "domingos"];
+
+    let names = match language {
+        Language::En => &EN,
+        Language::De => &DE,
+        Language::Fr => &FR,
+        Language::Es => &ES,
+    };
+    names[day.num_days_from_monday() as usize]
+}
+
+/// A single weekday, e.g. in "the second Tuesday of every month"
+fn single_weekday_name(day: Weekday, language: Language) -> &'static str {
+    const FR: [&str; 7] = ["lundi", "mardi", "mercredi", "jeudi", "vendredi", "samedi", "dimanche"];
+    const ES: [&str; 7] = ["lunes", "martes", "miércoles", "jueves", "viernes", "sábado", "domingo"];
+
+    match language {
+        Language::En | Language::De => weekday_name(day, language),
+        Language::Fr => FR[day.num_days_from_monday() as usize],
+        Language::Es => ES[day.num_days_from_monday() as usize],
+    }
+}
+
+/// Ordinal of a week of the month, e.g. "second", or "last" for `LAST_WEEK_OF_MONTH`
+fn week_ordinal(week: u8, language: Language) -> &'static str {
+    const EN: [&str; 5] = ["first", "second", "third", "fourth", "last"];
+    const DE: [&str; 5] = ["ersten", "zweiten", "dritten", "vierten", "letzten"];
+    const FR: [&str; 5] = ["premier", "deuxième", "troisième", "quatrième", "dernier"];
+    const ES: [&str; 5] = ["primer", "segundo", "tercer", "cuarto", "último"];
+
+    let names = match language {
+        Language::En => &EN,
+        Language::De => &DE,
+        Language::Fr => &FR,
+        Language::Es => &ES,
+    };
+    names[(week.clamp(1, LAST_WEEK_OF_MONTH) - 1) as usize]
+}
+
+/// A task's schedule in words, e.g. "Every Monday at 09:00, 5 times", in the task's time zone.
+pub fn describe_schedule(task: &Task, locale: &LocaleConfig) -> String {
+    let tz: Tz = task.timezone.parse().unwrap_or(Tz::UTC);
+    let start = task.start_time.with_timezone(&tz);
+    let time = start.format(locale::time_pattern(&locale.clock_format)).to_string();
+    let date = start.format(locale::date_pattern(&locale.date_format)).to_string();
+    let language = locale.language;
+
+    let Some(repeat) = &task.repeat_config else {
+        return match language {
+            Language::En => format!("Once on {} at {}", date, time),
+            Language::De => format!("Einmalig am {} um {}", date, time),
+            Language::Fr => format!("Une fois le {} à {}", date, time),
+            Language::Es => format!("Una vez el {} a las {}", date, time),
+        };
+    };
+
+    let mut text = match (&repeat.interval, language) {
+        (RepeatInterval::Hourly, Language::En) => format!("Every hour from {}", time),
+        (RepeatInterval::Hourly, Language::De) => format!("Stündlich ab {}", time),
+        (RepeatInterval::Hourly, Language::Fr) => format!("Toutes les heures à partir de {}", time),
+        (RepeatInterval::Hourly, Language::Es) => format!("Cada hora desde las {}", time),
+        (RepeatInterval::EveryNMinutes(n), Language::En) => format!("Every {} minutes from {}", n, time),
+        (RepeatInterval::EveryNMinutes(n), Language::De) => format!("Alle {} Minuten ab {}", n, time),
+        (RepeatInterval::EveryNMinutes(n), Language::Fr) => format!("Toutes les {} minutes à partir de {}", n, time),
+        (RepeatInterval::EveryNMinutes(n), Language::Es) => format!("Cada {} minutos desde las {}", n, time),
+        (RepeatInterval::EveryNDays(n), Language::En) => format!("Every {} days at {}", n, time),
+        (RepeatInterval::EveryNDays(n), Language::De) => format!("Alle {} Tage um {}", n, time),
+        (RepeatInterval::EveryNDays(n), Language::Fr) => format!("Tous les {} jours à {}", n, time),
+        (RepeatInterval::EveryNDays(n), Language::Es) => format!("Cada {} días a las {}", n, time),
+        (RepeatInterval::Daily, Language::En) => format!("Every day at {}", time),
+        (RepeatInterval::Daily, Language::De) => format!("Täglich um {}", time),
+        (RepeatInterval::Daily, Language::Fr) => format!("Tous les jours à {}", time),
+        (RepeatInterval::Daily, Language::Es) => format!("Todos los días a las {}", time),
+        (RepeatInterval::Weekly, _) => {
+            let day = weekday_name(start.weekday(), language);
+            match language {
+                Language::En => format!("Every {} at {}", day, time),
+                Language::De => format!("Jeden {} um {}", day, time),
+                Language::Fr => format!("Tous les {} à {}", day, time),
+                Language::Es => format!("Todos los {} a las {}", day, time),
+            }
+        }
+        (RepeatInterval::Monthly, _) => {
+            let day = start.day();
+            match language {
+                Language::En => format!("Monthly on day {} at {}", day, time),
+                Language::De => format!("Monatlich am {}. um {}", day, time),
+                Language::Fr => format!("Tous les mois le {} à {}", day, time),
+                Language::Es => format!("Cada mes el día {} a las {}", day, time),
+            }
+        }
+        (RepeatInterval::MonthlyByWeekday { week, weekday }, _) => {
+            let ordinal = week_ordinal(*week, language);
+            let day = single_weekday_name(*weekday, language);
+            match language {
+                Language::En => format!("Every {} {} of the month at {}", ordinal, day, time),
+                Language::De => format!("Jeden {} {} im Monat um {}", ordinal, day, time),
+                Language::Fr => format!("Le {} {} de chaque mois à {}", ordinal, day, time),
+                Language::Es => format!("El {} {} de cada mes a las {}", ordinal, day, time),
+            }
+        }
+    };
+
+    if let Some(count) = repeat.end_after {
+        text.push_str(&match language {
+            Language::En => format!(", {} times", count),
+            Language::De => format!(", {} Mal", count),
+            Language::Fr => format!(", {} fois", count),
+            Language::Es => format!(", {} veces", count),
+        });
+    } else if let Some(end) = repeat.end_date {
+        let end = end.with_timezone(&tz).format(locale::date_pattern(&locale.date_format)).to_string();
+        text.push_str(&match language {
+            Language::En => format!(", until {}", end),
+            Language::De => format!(", bis {}", end),
+            Language::Fr => format!(", jusqu'au {}", end),
+            Language::Es => format!(", hasta el {}", end),
+        });
+    }
+
+    text
+}
+
+/// Translated lead-in for errors of the given code; the error's own detail follows it
+fn error_prefix(code: &str, language: Language) -> Option<&'static str> {
+    let prefix = match (code, language) {
+        (_, Language::En) => return None,
+        ("database_corrupt", Language::De) => "Datenbank beschädigt",
+        ("database_corrupt", Language::Fr) => "Base de données corrompue",
+        ("database_corrupt", Language::Es) => "Base de datos dañada",
+        ("database" | "database_locked", Language::De) => "Datenbankfehler",
+        ("database" | "database_locked", Language::Fr) => "Erreur de base de données",
+        ("database" | "database_locked", Language::Es) => "Error de base de datos",
+        ("io", Language::De) => "E/A-Fehler",
+        ("io", Language::Fr) => "Erreur d'entrée/sortie",
+        ("io", Language::Es) => "Error de E/S",
+        ("time_parse", Language::De) => "Ungültige Zeitangabe",
+        ("time_parse", Language::Fr) => "Heure invalide",
+        ("time_parse", Language::Es) => "Hora no válida",
+        ("browser_not_found", Language::De) => "Browser nicht gefunden",
+        ("browser_not_found", Language::Fr) => "Navigateur introuvable",
+        ("browser_not_found", Language::Es) => "Navegador no encontrado",
+        ("task_not_found", Language::De) => "Aufgabe nicht gefunden",
+        ("task_not_found", Language::Fr) => "Tâche introuvable",
+        ("task_not_found", Language::Es) => "Tarea no encontrada",
+        ("workspace_not_found", Language::De) => "Arbeitsbereich nicht gefunden",
+        ("workspace_not_found", Language::Fr) => "Espace de travail introuvable",
+        ("workspace_not_found", Language::Es) => "Espacio de trabajo no encontrado",
+        ("invalid_workspace", Language::De) => "Ungültiger Arbeitsbereich",
+        ("invalid_workspace", Language::Fr) => "Espace de travail invalide",
+        ("invalid_workspace", Language::Es) => "Espacio de trabajo no válido",
+        ("scheduler", Language::De) => "Planerfehler",
+        ("scheduler", Language::Fr) => "Erreur du planificateur",
+        ("scheduler", Language::Es) => "Error del planificador",
+        ("invalid_task", Language::De) => "Ungültige Aufgabe",
+        ("invalid_task", Language::Fr) => "Tâche invalide",
+        ("invalid_task", Language::Es) => "Tarea no válida",
+        ("invalid_settings", Language::De) => "Ungültige Einstellungen",
+        ("invalid_settings", Language::Fr) => "Paramètres invalides",
+        ("invalid_settings", Language::Es) => "Configuración no válida",
+        ("keyring", Language::De) => "Schlüsselbundfehler",
+        ("keyring", Language::Fr) => "Erreur du trousseau",
+        ("keyring", Language::Es) => "Error del llavero",
+        ("notifier", Language::De) => "Benachrichtigung fehlgeschlagen",
+        ("notifier", Language::Fr) => "Échec de l'envoi de la notification",
+        ("notifier", Language::Es) => "Error al enviar la notificación",
+        ("invalid_pin", Language::De) => "Ungültige PIN",
+        ("invalid_pin", Language::Fr) => "Code PIN invalide",
+        ("invalid_pin", Language::Es) => "PIN no válido",
+        ("permission_denied", Language::De) => "Zugriff verweigert",
+        ("permission_denied", Language::Fr) => "Accès refusé",
+        ("permission_denied", Language::Es) => "Permiso denegado",
+        _ => return None,
+    };
+    Some(prefix)
+}
+
+/// The user-facing message for `error` in `language`, falling back to English.
+pub fn error_message(error: &AppError, language: Language) -> String {
+    let detail = match error {
+        AppError::Database(e) => e.to_string(),
+        AppError::Io(e) => e.to_string(),
+        AppError::TaskNotFound(id) | AppError::WorkspaceNotFound(id) => id.to_string(),
+        AppError::Corrupt(detail)
+        | AppError::TimeParse(detail)
+        | AppError::BrowserNotFound(detail)
+        | AppError::InvalidWorkspace(detail)
+        | AppError::Scheduler(detail)
+        | AppError::InvalidTask(detail)
+        | AppError::InvalidSettings(detail)
+        | AppError::Keyring(detail)
+        | AppError::Notifier(detail)
+        | AppError::InvalidPin(detail)
+        | AppError::PermissionDenied(detail) => detail.clone(),
+        AppError::Conflict(id) => {
+            return match language {
+                Language::En => error.to_string(),
+                Language::De => format!("Aufgabe {} wurde anderswo geändert; neu laden oder Änderung erzwingen", id),
+                Language::Fr => format!("La tâche {} a été modifiée ailleurs ; rechargez-la ou forcez la mise à jour", id),
+                Language::Es => format!("La tarea {} se modificó en otro lugar; recárguela o fuerce la actualización", id),
+            }
+        }
+        AppError::TaskBusy(id) => {
+            return match language {
+                Language::En => error.to_string(),
+                Language::De => format!("Aufgabe {} wird bereits ausgeführt", id),
+                Language::Fr => format!("La tâche {} est déjà en cours d'exécution", id),
+                Language::Es => format!("La tarea {} ya se está ejecutando", id),
+            }
+        }
+        AppError::AlreadyRunning => {
+            return match language {
+                Language::En => error.to_string(),
+                Language::De => "Läuft bereits".to_string(),
+                Language::Fr => "Déjà en cours d'exécution".to_string(),
+                Language::Es => "Ya se está ejecutando".to_string(),
+            }
+        }
+        AppError::NotRunning => {
+            return match language {
+                Language::En => error.to_string(),
+                Language::De => "Läuft nicht".to_string(),
+                Language::Fr => "N'est pas en cours d'exécution".to_string(),
+                Language::Es => "No se está ejecutando".to_string(),
+            }
+        }
+    };
+
+    match error_prefix(error.code(), language) {
+        Some(prefix) => format!("{}: {}", prefix, detail),
+        None => error.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use crate::db::{BrowserType, RepeatConfig};
+
+    fn task(repeat: Option<RepeatConfig>) -> Task {
+        // Monday 4 March 2024, 09:30 in Berlin
+        let start = Utc.with_ymd_and_hms(2024, 3, 4, 8, 30, 0).unwrap();
+        let mut task = Task::new("Standup".to_string(), BrowserType::Chrome, start, "Europe/Berlin".to_string());
+        task.repeat_config = repeat;
+        task
+    }
+
+    fn locale(language: Language) -> LocaleConfig {
+        LocaleConfig { language, ..Default::default() }
+    }
+
+    #[test]
+    fn test_describe_schedule() {
+        assert_eq!(describe_schedule(&task(None), &locale(Language::En)), "Once on 2024-03-04 at 09:30");
+
+        let weekly = task(Some(RepeatConfig { interval: RepeatInterval::Weekly, end_after: Some(5), end_date: None }));
+        assert_eq!(describe_schedule(&weekly, &locale(Language::En)), "Every Monday at 09:30, 5 times");
+        assert_eq!(describe_schedule(&weekly, &locale(Language::De)), "Jeden Montag um 09:30, 5 Mal");
+        assert_eq!(describe_schedule(&weekly, &locale(Language::Fr)), "Tous les lundis à 09:30, 5 fois");
+
+        let monthly = task(Some(RepeatConfig { interval: RepeatInterval::Monthly, end_after: None, end_date: None }));
+        assert_eq!(describe_schedule(&monthly, &locale(Language::Es)), "Cada mes el día 4 a las 09:30");
+
+        let second_tuesday = RepeatInterval::MonthlyByWeekday { week: 2, weekday: Weekday::Tue };
+        let by_weekday = task(Some(RepeatConfig { interval: second_tuesday, end_after: None, end_date: None }));
+        assert_eq!(describe_schedule(&by_weekday, &locale(Language::En)), "Every second Tuesday of the month at 09:30");
+        assert_eq!(describe_schedule(&by_weekday, &locale(Language::Fr)), "Le deuxième mardi de chaque mois à 09:30");
+
+        let every_two_hours = task(Some(RepeatConfig { interval: RepeatInterval::EveryNMinutes(120), end_after: Some(4), end_date: None }));
+        assert_eq!(describe_schedule(&every_two_hours, &locale(Language::En)), "Every 120 minutes from 09:30, 4 times");
+    }
+
+    #[test]
+    fn test_error_message() {
+        let error = AppError::TaskNotFound(7);
+        assert_eq!(error_message(&error, Language::En), "Task not found: 7");
+        assert_eq!(error_message(&error, Language::De), "Aufgabe nicht gefunden: 7");
+        assert_eq!(error_message(&AppError::TaskBusy(3), Language::Fr), "La tâche 3 est déjà en cours d'exécution");
+    }
+}
@@ -0,0 +1,745 @@
+//! iCalendar (RFC 5545) export of the schedule, so it can be subscribed to from a calendar app,
+//! and import of calendar events as tasks.
+//!
+//! Each active task becomes a VEVENT starting at its start time in its own time zone. Repeats map
+//! to an RRULE, and occurrences that were skipped or fall on excluded dates become EXDATEs. A
+//! task without a close time becomes an event with no duration.
+//!
+//! Importing goes the other way: an event's start and end become the task's open and close, and
+//! its RRULE becomes the repeat when the scheduler has a matching interval. All-day events,
+//! rules the scheduler cannot follow and single changed occurrences are skipped with a reason;
+//! the days of EXDATEs become excluded dates of the task.
+
+use std::collections::HashMap;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
+use crate::db::{BrowserType, DateExclusion, RepeatConfig, RepeatInterval, SkippedEvent, Task, TaskStatus, LAST_WEEK_OF_MONTH};
+use crate::error::{AppError, Result};
+use crate::utils::curl;
+use crate::utils::links::extract_links;
+use crate::utils::validation::validate_url;
+
+const PRODID: &str = "-//do-in-time//Browser Scheduler//EN";
+
+/// Longest content line in octets before it is folded
+const MAX_LINE_OCTETS: usize = 75;
+
+/// A calendar of the active tasks in `tasks`. `exceptions` holds each task's skipped
+/// occurrences; `now` stamps the events.
+pub fn render_calendar(
+    tasks: &[Task],
+    exceptions: &HashMap<i64, Vec<DateTime<Utc>>>,
+    now: DateTime<Utc>,
+) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        format!("PRODID:{}", PRODID),
+        "CALSCALE:GREGORIAN".to_string(),
+        "X-WR-CALNAME:Browser Scheduler".to_string(),
+    ];
+
+    for task in tasks.iter().filter(|task| task.status == TaskStatus::Active) {
+        let Some(task_id) = task.id else { continue };
+        let tz: Tz = task.timezone.parse().unwrap_or(Tz::UTC);
+
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:task-{}@do-in-time", task_id));
+        lines.push(format!("DTSTAMP:{}", utc_stamp(now)));
+        lines.push(format!("DTSTART;TZID={}:{}", tz.name(), local_stamp(task.start_time, tz)));
+        if let Some(length) = task.open_length() {
+            lines.push(format!("DTEND;TZID={}:{}", tz.name(), local_stamp(task.start_time + length, tz)));
+        }
+        lines.push(format!("SUMMARY:{}", escape_text(&task.name)));
+
+        let description = [task.description.as_deref(), task.url.as_deref()]
+            .into_iter()
+            .flatten()
+            .filter(|part| !part.trim().is_empty())
+            .collect::<Vec<_>>()
+            .join("\n");
+        if !description.is_empty() {
+            lines.push(format!("DESCRIPTION:{}", escape_text(&description)));
+        }
+        if let Some(ref url) = task.url {
+            lines.push(format!("URL:{}", url));
+        }
+
+        if let Some(ref repeat) = task.repeat_config {
+            lines.push(format!("RRULE:{}", rrule(repeat, task, tz)));
+
+            if let Some(skipped) = exceptions.get(&task_id).filter(|skipped| !skipped.is_empty()) {
+                let dates: Vec<String> = skipped.iter().map(|at| local_stamp(*at, tz)).collect();
+                lines.push(format!("EXDATE;TZID={}:{}", tz.name(), dates.join(",")));
+            }
+        }
+
+        lines.push("END:VEVENT".to_string());
+    }
+
+    lines.push("END:VCALENDAR".to_string());
+
+    let mut calendar = String::new();
+    for line in lines {
+        calendar.push_str(&fold(&line));
+        calendar.push_str("\r\n");
+    }
+    calendar
+}
+
+/// The RRULE value of a repeat, e.g. `FREQ=WEEKLY;COUNT=5`.
+///
+/// Monthly repeats from the 29th or later fall on the last day of shorter months, as the
+/// scheduler does, rather than skipping them as a plain BYMONTHDAY would.
+fn rrule(repeat: &RepeatConfig, task: &Task, tz: Tz) -> String {
+    let mut rule = match repeat.interval {
+        RepeatInterval::Hourly => "FREQ=HOURLY".to_string(),
+        RepeatInterval::Daily => "FREQ=DAILY".to_string(),
+        RepeatInterval::Weekly => "FREQ=WEEKLY".to_string(),
+        RepeatInterval::Monthly => {
+            let day = task.start_time.with_timezone(&tz).day();
+            if day > 28 {
+                format!("FREQ=MONTHLY;BYMONTHDAY={},-1;BYSETPOS=1", day)
+            } else {
+                "FREQ=MONTHLY".to_string()
+            }
+        }
+        RepeatInterval::EveryNMinutes(n) => format!("FREQ=MINUTELY;INTERVAL={}", n),
+        RepeatInterval::EveryNDays(n) => format!("FREQ=DAILY;INTERVAL={}", n),
+        RepeatInterval::MonthlyByWeekday { week, weekday } => {
+            let code = WEEKDAY_CODES[weekday.num_days_from_monday() as usize];
+            if week == LAST_WEEK_OF_MONTH {
+                format!("FREQ=MONTHLY;BYDAY=-1{}", code)
+            } else {
+                format!("FREQ=MONTHLY;BYDAY={}{}", week, code)
+            }
+        }
+    };
+
+    // The scheduler's end count is the total number of runs, which is what COUNT means too
+    if let Some(count) = repeat.end_after {
+        rule.push_str(&format!(";COUNT={}", count.max(1)));
+    } else if let Some(end_date) = repeat.end_date {
+        rule.push_str(&format!(";UNTIL={}", utc_stamp(end_date)));
+    }
+
+    rule
+}
+
+fn utc_stamp(at: DateTime<Utc>) -> String {
+    at.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+fn local_stamp(at: DateTime<Utc>, tz: Tz) -> String {
+    at.with_timezone(&tz).format("%Y%m%dT%H%M%S").to_string()
+}
+
+/// Escapes a TEXT value: backslashes, semicolons, commas and line breaks
+fn escape_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Splits a content line into lines of at most 75 octets, continued with a leading space,
+/// without breaking a UTF-8 character.
+fn fold(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len() + line.len() / MAX_LINE_OCTETS * 3);
+    let mut octets = 0;
+    for c in line.chars() {
+        if octets + c.len_utf8() > MAX_LINE_OCTETS {
+            folded.push_str("\r\n ");
+            // The leading space counts towards the continuation line
+            octets = 1;
+        }
+        folded.push(c);
+        octets += c.len_utf8();
+    }
+    folded
+}
+
+/// Name given to imported events without a summary
+const UNNAMED_EVENT: &str = "Calendar event";
+
+/// RRULE weekday codes, from Monday
+const WEEKDAY_CODES: [&str; 7] = ["MO", "TU", "WE", "TH", "FR", "SA", "SU"];
+
+/// The calendar at `source`: an http(s) or webcal URL, or a file path.
+pub fn read_calendar(source: &str) -> Result<String> {
+    let source = source.trim();
+    let url = match source.split_once("://") {
+        Some((scheme, rest)) if scheme.eq_ignore_ascii_case("webcal") => format!("https://{}", rest),
+        Some((scheme, _)) if scheme.eq_ignore_ascii_case("http") || scheme.eq_ignore_ascii_case("https") => {
+            source.to_string()
+        }
+        _ => return Ok(std::fs::read_to_string(source)?),
+    };
+
+    let output = curl::command()
+        .args(["--fail", "--silent", "--show-error", "--location", "--max-time", "15", url.as_str()])
+        .output()?;
+
+    if !output.status.success() {
+        return Err(AppError::Io(std::io::Error::other(format!(
+            "Fetching the calendar failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Unsaved tasks opening `browser` for the events in `ics`, and the events left out.
+///
+/// Times without a known time zone are read in `default_tz`, which imported tasks also repeat
+/// in when the event gave its times in UTC. One-off events that are over by `now` are skipped.
+pub fn parse_calendar(
+    ics: &str,
+    browser: &BrowserType,
+    default_tz: Tz,
+    now: DateTime<Utc>,
+) -> Result<(Vec<Task>, Vec<SkippedEvent>)> {
+    let lines: Vec<Property> = unfold(ics).iter().filter_map(|line| parse_property(line)).collect();
+    if !lines
+        .first()
+        .is_some_and(|first| first.name == "BEGIN" && first.value.eq_ignore_ascii_case("VCALENDAR"))
+    {
+        return Err(AppError::InvalidTask("Not an iCalendar file".to_string()));
+    }
+
+    let mut tasks = Vec::new();
+    let mut skipped = Vec::new();
+    for event in events(lines) {
+        match event_task(&event, browser, default_tz, now) {
+            Ok(task) => tasks.push(task),
+            Err(reason) => skipped.push(SkippedEvent {
+                summary: find(&event, "SUMMARY")
+                    .map(|summary| unescape_text(&summary.value))
+                    .unwrap_or_else(|| UNNAMED_EVENT.to_string()),
+                reason,
+            }),
+        }
+    }
+    Ok((tasks, skipped))
+}
+
+/// A content line: its name, parameters and value
+struct Property {
+    name: String,
+    params: Vec<(String, String)>,
+    value: String,
+}
+
+impl Property {
+    fn param(&self, name: &str) -> Option<&str> {
+        self.params.iter().find(|(key, _)| key == name).map(|(_, value)| value.as_str())
+    }
+}
+
+/// Joins folded lines back together
+fn unfold(ics: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in ics.split('\n') {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        match (line.strip_prefix(&[' ', '\t'][..]), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ if !line.is_empty() => lines.push(line.to_string()),
+            _ => {}
+        }
+    }
+    lines
+}
+
+fn parse_property(line: &str) -> Option<Property> {
+    // Parameter values may be quoted and contain a colon
+    let mut quoted = false;
+    let (colon, _) = line.char_indices().find(|&(_, c)| {
+        if c == '"' {
+            quoted = !quoted;
+        }
+        c == ':' && !quoted
+    })?;
+
+    let mut head = line[..colon].split(';');
+    let name = head.next()?.trim().to_ascii_uppercase();
+    let params = head
+        .filter_map(|param| param.split_once('='))
+        .map(|(key, value)| (key.trim().to_ascii_uppercase(), value.trim_matches('"').to_string()))
+        .collect();
+    Some(Property { name, params, value: line[colon + 1..].to_string() })
+}
+
+/// The properties of each VEVENT, without those of components inside it like VALARM
+fn events(lines: Vec<Property>) -> Vec<Vec<Property>> {
+    let mut events = Vec::new();
+    let mut current: Option<Vec<Property>> = None;
+    let mut nested = 0;
+
+    for property in lines {
+        let Some(event) = current.as_mut() else {
+            if property.name == "BEGIN" && property.value.eq_ignore_ascii_case("VEVENT") {
+                current = Some(Vec::new());
+            }
+            continue;
+        };
+        match property.name.as_str() {
+            "BEGIN" => nested += 1,
+            "END" if nested > 0 => nested -= 1,
+            "END" => events.extend(current.take()),
+            _ if nested == 0 => event.push(property),
+            _ => {}
+        }
+    }
+    events
+}
+
+fn find<'a>(event: &'a [Property], name: &str) -> Option<&'a Property> {
+    event.iter().find(|property| property.name == name)
+}
+
+/// The task for one event, or why there is none
+fn event_task(
+    event: &[Property],
+    browser: &BrowserType,
+    default_tz: Tz,
+    now: DateTime<Utc>,
+) -> std::result::Result<Task, String> {
+    if find(event, "RECURRENCE-ID").is_some() {
+        return Err("Changes a single occurrence of a repeating event".to_string());
+    }
+    if find(event, "STATUS").is_some_and(|status| status.value.eq_ignore_ascii_case("CANCELLED")) {
+        return Err("Cancelled".to_string());
+    }
+
+    let dtstart = find(event, "DTSTART").ok_or("Has no start time")?;
+    if dtstart.param("VALUE") == Some("DATE") || dtstart.value.len() == 8 {
+        return Err("All-day event".to_string());
+    }
+    let (start, tz) = parse_date_time(&dtstart.value, dtstart.param("TZID"), default_tz)
+        .ok_or_else(|| format!("Invalid start time {}", dtstart.value))?;
+
+    let end = match (find(event, "DTEND"), find(event, "DURATION")) {
+        (Some(dtend), _) => Some(
+            parse_date_time(&dtend.value, dtend.param("TZID"), default_tz)
+                .ok_or_else(|| format!("Invalid end time {}", dtend.value))?
+                .0,
+        ),
+        (None, Some(duration)) => Some(
+            start + parse_duration(&duration.value).ok_or_else(|| format!("Invalid duration {}", duration.value))?,
+        ),
+        (None, None) => None,
+    };
+
+    let repeat_config = find(event, "RRULE").map(|rule| parse_rrule(&rule.value, start, tz)).transpose()?;
+    let over = match repeat_config {
+        Some(ref repeat) => repeat.end_date.is_some_and(|end_date| end_date <= now),
+        None => end.unwrap_or(start) <= now,
+    };
+    if over {
+        return Err("Already over".to_string());
+    }
+
+    let name = find(event, "SUMMARY")
+        .map(|summary| unescape_text(&summary.value))
+        .filter(|name| !name.trim().is_empty())
+        .unwrap_or_else(|| UNNAMED_EVENT.to_string());
+
+    let mut task = Task::new(name, browser.clone(), start, tz.name().to_string());
+    task.close_time = end.filter(|end| *end > start);
+    task.repeat_config = repeat_config;
+    if task.repeat_config.is_some() {
+        task.exclusions = exdates(event, tz);
+    }
+    task.description = find(event, "DESCRIPTION")
+        .map(|description| unescape_text(&description.value))
+        .filter(|description| !description.trim().is_empty());
+
+    // Meeting invites usually put the link in the location or description rather than URL
+    task.url = find(event, "URL")
+        .map(|url| url.value.trim().to_string())
+        .filter(|url| validate_url(url).is_ok())
+        .or_else(|| {
+            ["LOCATION", "DESCRIPTION"]
+                .iter()
+                .filter_map(|name| find(event, name))
+                .find_map(|property| extract_links(&unescape_text(&property.value)).into_iter().next())
+                .map(|link| link.url)
+        });
+
+    Ok(task)
+}
+
+/// Days of an event's removed occurrences in `tz`, as single-day exclusions
+fn exdates(event: &[Property], tz: Tz) -> Vec<DateExclusion> {
+    let mut days: Vec<NaiveDate> = event
+        .iter()
+        .filter(|property| property.name == "EXDATE")
+        .flat_map(|property| {
+            property.value.split(',').filter_map(move |value| {
+                if value.len() == 8 {
+                    NaiveDate::parse_from_str(value, "%Y%m%d").ok()
+                } else {
+                    parse_date_time(value, property.param("TZID"), tz).map(|(at, _)| at.with_timezone(&tz).date_naive())
+                }
+            })
+        })
+        .collect();
+    days.sort();
+    days.dedup();
+
+    days.into_iter().map(|day| DateExclusion { start: day, end: None }).collect()
+}
+
+/// A DATE-TIME in UTC and the zone the task should repeat in. UTC and floating times, and time
+/// zones that are not IANA names like those Outlook writes, use `default_tz`.
+fn parse_date_time(value: &str, tzid: Option<&str>, default_tz: Tz) -> Option<(DateTime<Utc>, Tz)> {
+    if let Some(utc) = value.strip_suffix('Z') {
+        let naive = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+        return Some((Utc.from_utc_datetime(&naive), default_tz));
+    }
+
+    let naive = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?;
+    let tz = tzid
+        .and_then(|id| id.trim_start_matches('/').parse::<Tz>().ok())
+        .unwrap_or(default_tz);
+    let at = tz.from_local_datetime(&naive).earliest()?;
+    Some((at.with_timezone(&Utc), tz))
+}
+
+/// A DURATION such as `PT1H30M` or `P1D`; negative durations are not valid for an event
+fn parse_duration(value: &str) -> Option<Duration> {
+    let rest = value.strip_prefix('+').unwrap_or(value).strip_prefix('P')?;
+    let mut total = Duration::zero();
+    let mut number = String::new();
+
+    for c in rest.chars() {
+        match c {
+            '0'..='9' => number.push(c),
+            'T' => {}
+            unit => {
+                let n: i64 = number.parse().ok()?;
+                number.clear();
+                total += match unit {
+                    'W' => Duration::weeks(n),
+                    'D' => Duration::days(n),
+                    'H' => Duration::hours(n),
+                    'M' => Duration::minutes(n),
+                    'S' => Duration::seconds(n),
+                    _ => return None,
+                };
+            }
+        }
+    }
+    number.is_empty().then_some(total)
+}
+
+/// The repeat for an RRULE starting at `start` in `tz`.
+///
+/// BYDAY and BYMONTHDAY are only accepted when they name the start's own day, as calendar apps
+/// write them for plain weekly and monthly events, or for monthly events one weekday of the
+/// month such as `2TU` or `-1FR`.
+fn parse_rrule(value: &str, start: DateTime<Utc>, tz: Tz) -> std::result::Result<RepeatConfig, String> {
+    let parts: HashMap<String, &str> = value
+        .split(';')
+        .filter_map(|part| part.split_once('='))
+        .map(|(key, value)| (key.trim().to_ascii_uppercase(), value.trim()))
+        .collect();
+
+    let freq = parts.get("FREQ").map(|freq| freq.to_ascii_uppercase()).unwrap_or_default();
+    let local = start.with_timezone(&tz);
+    let day = local.day().to_string();
+
+    for (key, value) in &parts {
+        let supported = match key.as_str() {
+            "FREQ" | "INTERVAL" | "COUNT" | "UNTIL" | "WKST" => true,
+            "BYDAY" => {
+                (freq == "WEEKLY"
+                    && value.eq_ignore_ascii_case(WEEKDAY_CODES[local.weekday().num_days_from_monday() as usize]))
+                    || (freq == "MONTHLY" && weekday_of_month(value).is_some())
+            }
+            "BYMONTHDAY" => freq == "MONTHLY" && (*value == day || *value == format!("{},-1", day)),
+            // Written with a BYMONTHDAY of the start day or the month's last, as the export does
+            "BYSETPOS" => *value == "1" && parts.contains_key("BYMONTHDAY"),
+            _ => false,
+        };
+        if !supported {
+            return Err(format!("Repeat rule {}={} is not supported", key, value));
+        }
+    }
+
+    let every: u32 = match parts.get("INTERVAL") {
+        Some(every) => every.parse().map_err(|_| format!("Invalid repeat interval {}", every))?,
+        None => 1,
+    };
+    if every == 0 {
+        return Err("Repeat interval must be at least 1".to_string());
+    }
+
+    let interval = match (freq.as_str(), every) {
+        ("MINUTELY", n) => RepeatInterval::EveryNMinutes(n),
+        ("HOURLY", 1) => RepeatInterval::Hourly,
+        ("HOURLY", n) => RepeatInterval::EveryNMinutes(n.checked_mul(60).ok_or("Repeat interval is too long")?),
+        ("DAILY", 1) => RepeatInterval::Daily,
+        ("DAILY", n) => RepeatInterval::EveryNDays(n),
+        ("WEEKLY", 1) => RepeatInterval::Weekly,
+        ("WEEKLY", n) => RepeatInterval::EveryNDays(n.checked_mul(7).ok_or("Repeat interval is too long")?),
+        ("MONTHLY", 1) => match parts.get("BYDAY").and_then(|value| weekday_of_month(value)) {
+            Some((week, weekday)) => RepeatInterval::MonthlyByWeekday { week, weekday },
+            None => RepeatInterval::Monthly,
+        },
+        ("MONTHLY", n) => return Err(format!("Repeats every {} months, which is not supported", n)),
+        ("", _) => return Err("Repeat rule has no frequency".to_string()),
+        (freq, _) => return Err(format!("{} repeats are not supported", freq.to_lowercase())),
+    };
+
+    let end_after = match parts.get("COUNT") {
+        Some(count) => Some(count.parse().map_err(|_| format!("Invalid repeat count {}", count))?),
+        None => None,
+    };
+    let end_date = match parts.get("UNTIL") {
+        Some(until) => Some(parse_until(until, tz).ok_or_else(|| format!("Invalid repeat end {}", until))?),
+        None => None,
+    };
+
+    Ok(RepeatConfig { interval, end_after, end_date })
+}
+
+/// The week and weekday of a monthly BYDAY like `2TU`, `+1MO` or `-1FR`; the fifth is left out as
+/// most months have none
+fn weekday_of_month(value: &str) -> Option<(u8, Weekday)> {
+    let split = value.len().checked_sub(2)?;
+    let (ordinal, code) = (value.get(..split)?, value.get(split..)?);
+    let day = WEEKDAY_CODES.iter().position(|c| c.eq_ignore_ascii_case(code))?;
+    let weekday = (0..day).fold(Weekday::Mon, |weekday, _| weekday.succ());
+    let week = match ordinal.trim_start_matches('+') {
+        "-1" => LAST_WEEK_OF_MONTH,
+        ordinal => ordinal.parse::<u8>().ok().filter(|week| (1..LAST_WEEK_OF_MONTH).contains(week))?,
+    };
+    Some((week, weekday))
+}
+
+/// The end date for an UNTIL. UNTIL includes its own time, and a date includes the whole day,
+/// while the scheduler stops before its end date.
+fn parse_until(value: &str, tz: Tz) -> Option<DateTime<Utc>> {
+    if value.len() == 8 {
+        let next_day = NaiveDate::parse_from_str(value, "%Y%m%d").ok()?.succ_opt()?;
+        let midnight = tz.from_local_datetime(&next_day.and_hms_opt(0, 0, 0)?).earliest()?;
+        return Some(midnight.with_timezone(&Utc));
+    }
+    parse_date_time(value, None, tz).map(|(until, _)| until + Duration::seconds(1))
+}
+
+/// Reverses `escape_text`
+fn unescape_text(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => unescaped.push('\n'),
+            Some(c) => unescaped.push(c),
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use crate::db::BrowserType;
+
+    fn task(repeat: Option<RepeatConfig>) -> Task {
+        // Monday 4 March 2024, 09:30 in Berlin
+        let start = Utc.with_ymd_and_hms(2024, 3, 4, 8, 30, 0).unwrap();
+        let mut task = Task::new("Standup, daily".to_string(), BrowserType::Chrome, start, "Europe/Berlin".to_string());
+        task.id = Some(7);
+        task.url = Some("https://meet.example.com/standup".to_string());
+        task.close_time = Some(start + chrono::Duration::minutes(15));
+        task.repeat_config = repeat;
+        task
+    }
+
+    #[test]
+    fn test_render_calendar() {
+        let weekly = task(Some(RepeatConfig { interval: RepeatInterval::Weekly, end_after: Some(5), end_date: None }));
+        let skipped = Utc.with_ymd_and_hms(2024, 3, 11, 8, 30, 0).unwrap();
+        let exceptions = HashMap::from([(7, vec![skipped])]);
+        let now = Utc.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap();
+
+        let calendar = render_calendar(&[weekly], &exceptions, now);
+        let lines: Vec<&str> = calendar.split("\r\n").collect();
+
+        assert_eq!(lines[0], "BEGIN:VCALENDAR");
+        assert!(lines.contains(&"UID:task-7@do-in-time"));
+        assert!(lines.contains(&"DTSTAMP:20240301T120000Z"));
+        assert!(lines.contains(&"DTSTART;TZID=Europe/Berlin:20240304T093000"));
+        assert!(lines.contains(&"DTEND;TZID=Europe/Berlin:20240304T094500"));
+        assert!(lines.contains(&"SUMMARY:Standup\\, daily"));
+        assert!(lines.contains(&"RRULE:FREQ=WEEKLY;COUNT=5"));
+        assert!(lines.contains(&"EXDATE;TZID=Europe/Berlin:20240311T093000"));
+        assert!(calendar.ends_with("END:VCALENDAR\r\n"));
+    }
+
+    #[test]
+    fn test_render_calendar_skips_inactive_tasks() {
+        let mut paused = task(None);
+        paused.status = TaskStatus::Paused;
+
+        let calendar = render_calendar(&[paused], &HashMap::new(), Utc::now());
+        assert!(!calendar.contains("BEGIN:VEVENT"));
+    }
+
+    #[test]
+    fn test_rrule() {
+        let tz: Tz = "Europe/Berlin".parse().unwrap();
+        let repeat = |interval| RepeatConfig { interval, end_after: None, end_date: None };
+
+        assert_eq!(rrule(&repeat(RepeatInterval::EveryNMinutes(120)), &task(None), tz), "FREQ=MINUTELY;INTERVAL=120");
+        assert_eq!(rrule(&repeat(RepeatInterval::EveryNDays(2)), &task(None), tz), "FREQ=DAILY;INTERVAL=2");
+
+        let mut end_of_month = task(None);
+        end_of_month.start_time = Utc.with_ymd_and_hms(2024, 1, 31, 8, 0, 0).unwrap();
+        assert_eq!(
+            rrule(&repeat(RepeatInterval::Monthly), &end_of_month, tz),
+            "FREQ=MONTHLY;BYMONTHDAY=31,-1;BYSETPOS=1"
+        );
+        assert_eq!(
+            rrule(&repeat(RepeatInterval::MonthlyByWeekday { week: 2, weekday: Weekday::Tue }), &task(None), tz),
+            "FREQ=MONTHLY;BYDAY=2TU"
+        );
+        assert_eq!(
+            rrule(
+                &repeat(RepeatInterval::MonthlyByWeekday { week: LAST_WEEK_OF_MONTH, weekday: Weekday::Fri }),
+                &task(None),
+                tz
+            ),
+            "FREQ=MONTHLY;BYDAY=-1FR"
+        );
+
+        let until = RepeatConfig {
+            interval: RepeatInterval::Daily,
+            end_after: None,
+            end_date: Some(Utc.with_ymd_and_hms(2024, 6, 1, 0, 0, 0).unwrap()),
+        };
+        assert_eq!(rrule(&until, &task(None), tz), "FREQ=DAILY;UNTIL=20240601T000000Z");
+    }
+
+    #[test]
+    fn test_parse_calendar() {
+        let ics = "BEGIN:VCALENDAR\r\n\
+VERSION:2.0\r\n\
+BEGIN:VEVENT\r\n\
+UID:1@example.com\r\n\
+DTSTART;TZID=Europe/Berlin:20240304T093000\r\n\
+DTEND;TZID=Europe/Berlin:20240304T094500\r\n\
+RRULE:FREQ=WEEKLY;BYDAY=MO;COUNT=10\r\n\
+EXDATE;TZID=Europe/Berlin:20240318T093000,20240325T093000\r\n\
+SUMMARY:Standup\\, daily\r\n\
+LOCATION:https://meet.example.com/\r\n standup\r\n\
+BEGIN:VALARM\r\n\
+ACTION:DISPLAY\r\n\
+DESCRIPTION:Reminder\r\n\
+END:VALARM\r\n\
+END:VEVENT\r\n\
+BEGIN:VEVENT\r\n\
+DTSTART;VALUE=DATE:20240305\r\n\
+SUMMARY:Holiday\r\n\
+END:VEVENT\r\n\
+BEGIN:VEVENT\r\n\
+DTSTART:20240306T140000Z\r\n\
+DURATION:PT1H\r\n\
+RRULE:FREQ=WEEKLY;BYDAY=MO,WE\r\n\
+SUMMARY:Gym\r\n\
+END:VEVENT\r\n\
+END:VCALENDAR\r\n";
+        let now = Utc.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap();
+
+        let (tasks, skipped) = parse_calendar(ics, &BrowserType::Firefox, Tz::UTC, now).unwrap();
+
+        assert_eq!(tasks.len(), 1);
+        let standup = &tasks[0];
+        assert_eq!(standup.name, "Standup, daily");
+        assert_eq!(standup.browser, BrowserType::Firefox);
+        assert_eq!(standup.timezone, "Europe/Berlin");
+        assert_eq!(standup.start_time, Utc.with_ymd_and_hms(2024, 3, 4, 8, 30, 0).unwrap());
+        assert_eq!(standup.close_time, Some(Utc.with_ymd_and_hms(2024, 3, 4, 8, 45, 0).unwrap()));
+        assert_eq!(standup.url.as_deref(), Some("https://meet.example.com/standup"));
+        assert_eq!(standup.description, None);
+        assert_eq!(
+            standup.repeat_config,
+            Some(RepeatConfig { interval: RepeatInterval::Weekly, end_after: Some(10), end_date: None })
+        );
+        let excluded: Vec<_> = standup.exclusions.iter().map(|e| e.start.to_string()).collect();
+        assert_eq!(excluded, vec!["2024-03-18", "2024-03-25"]);
+
+        let reasons: Vec<_> = skipped.iter().map(|s| (s.summary.as_str(), s.reason.as_str())).collect();
+        assert_eq!(
+            reasons,
+            vec![("Holiday", "All-day event"), ("Gym", "Repeat rule BYDAY=MO,WE is not supported")]
+        );
+
+        assert!(parse_calendar("not a calendar", &BrowserType::Chrome, Tz::UTC, now).is_err());
+    }
+
+    #[test]
+    fn test_parse_calendar_round_trip() {
+        let repeat = RepeatConfig { interval: RepeatInterval::EveryNDays(3), end_after: None, end_date: None };
+        let now = Utc.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap();
+        let calendar = render_calendar(&[task(Some(repeat.clone()))], &HashMap::new(), now);
+
+        let (tasks, skipped) = parse_calendar(&calendar, &BrowserType::Chrome, Tz::UTC, now).unwrap();
+        assert!(skipped.is_empty());
+        assert_eq!(tasks[0].name, "Standup, daily");
+        assert_eq!(tasks[0].start_time, task(None).start_time);
+        assert_eq!(tasks[0].close_time, task(None).close_time);
+        assert_eq!(tasks[0].url, task(None).url);
+        assert_eq!(tasks[0].repeat_config, Some(repeat));
+    }
+
+    #[test]
+    fn test_parse_rrule() {
+        let tz: Tz = "Europe/Berlin".parse().unwrap();
+        let start = Utc.with_ymd_and_hms(2024, 1, 31, 8, 0, 0).unwrap();
+
+        let interval = |rule: &str| parse_rrule(rule, start, tz).map(|repeat| repeat.interval);
+        assert_eq!(interval("FREQ=HOURLY;INTERVAL=2"), Ok(RepeatInterval::EveryNMinutes(120)));
+        assert_eq!(interval("FREQ=WEEKLY;INTERVAL=2;BYDAY=WE"), Ok(RepeatInterval::EveryNDays(14)));
+        assert_eq!(interval("FREQ=MONTHLY;BYMONTHDAY=31,-1;BYSETPOS=1"), Ok(RepeatInterval::Monthly));
+        assert_eq!(
+            interval("FREQ=MONTHLY;BYDAY=2TU"),
+            Ok(RepeatInterval::MonthlyByWeekday { week: 2, weekday: Weekday::Tue })
+        );
+        assert_eq!(
+            interval("FREQ=MONTHLY;BYDAY=-1FR"),
+            Ok(RepeatInterval::MonthlyByWeekday { week: LAST_WEEK_OF_MONTH, weekday: Weekday::Fri })
+        );
+        assert!(interval("FREQ=MONTHLY;BYDAY=5TU").is_err());
+        assert!(interval("FREQ=MONTHLY;BYDAY=TU").is_err());
+        assert!(interval("FREQ=YEARLY").is_err());
+        assert!(interval("FREQ=DAILY;INTERVAL=0").is_err());
+
+        let until = parse_rrule("FREQ=DAILY;UNTIL=20240601", start, tz).unwrap();
+        assert_eq!(until.end_date, Some(Utc.with_ymd_and_hms(2024, 6, 1, 22, 0, 0).unwrap()));
+    }
+
+    #[test]
+    fn test_fold() {
+        let line = format!("SUMMARY:{}", "ü".repeat(50));
+        let folded = fold(&line);
+        assert!(folded.split("\r\n").all(|part| part.len() <= MAX_LINE_OCTETS));
+        assert_eq!(folded.replace("\r\n ", ""), line);
+    }
+}
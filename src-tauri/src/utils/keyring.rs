@@ -0,0 +1,156 @@
+//! Secrets stored in the OS keyring: the macOS Keychain, the Secret Service on Linux and the
+//! Windows Credential Locker. Secrets are passed to the platform tools on stdin where the tool
+//! allows it, so they do not show up in the process list.
+
+use crate::error::{AppError, Result};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Service name all of the app's keyring entries are stored under
+const SERVICE: &str = "do-in-time";
+
+/// Keys are interpolated into the Windows PowerShell script, so only plain identifiers are allowed
+fn check_key(key: &str) -> Result<()> {
+    if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        return Err(AppError::Keyring(format!("Invalid keyring key '{}'", key)));
+    }
+    Ok(())
+}
+
+/// Runs a platform keyring tool, feeding `input` on stdin, and returns its trimmed stdout.
+fn run(command: &mut Command, input: Option<&str>) -> Result<String> {
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| AppError::Keyring(format!("Failed to start keyring tool: {}", e)))?;
+
+    if let Some(input) = input {
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(input.as_bytes())?;
+        }
+    }
+
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(AppError::Keyring(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim_end_matches(['\r', '\n']).to_string())
+}
+
+#[cfg(target_os = "macos")]
+pub fn set_secret(key: &str, secret: &str) -> Result<()> {
+    check_key(key)?;
+    // `security` has no stdin mode for the password; -U replaces an existing entry
+    run(
+        Command::new("security").args(["add-generic-password", "-U", "-s", SERVICE, "-a", key, "-w", secret]),
+        None,
+    )
+    .map(|_| ())
+}
+
+#[cfg(target_os = "macos")]
+pub fn get_secret(key: &str) -> Result<Option<String>> {
+    check_key(key)?;
+    match run(Command::new("security").args(["find-generic-password", "-s", SERVICE, "-a", key, "-w"]), None) {
+        Ok(secret) => Ok(Some(secret)),
+        // Exit status 44: the item could not be found
+        Err(AppError::Keyring(message)) if message.contains("could not be found") => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(target_os = "macos")]
+pub fn delete_secret(key: &str) -> Result<()> {
+    check_key(key)?;
+    match run(Command::new("security").args(["delete-generic-password", "-s", SERVICE, "-a", key]), None) {
+        Err(AppError::Keyring(message)) if message.contains("could not be found") => Ok(()),
+        result => result.map(|_| ()),
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub fn set_secret(key: &str, secret: &str) -> Result<()> {
+    check_key(key)?;
+    run(
+        Command::new("secret-tool").args(["store", "--label", &format!("{} {}", SERVICE, key), "service", SERVICE, "key", key]),
+        Some(secret),
+    )
+    .map(|_| ())
+}
+
+#[cfg(target_os = "linux")]
+pub fn get_secret(key: &str) -> Result<Option<String>> {
+    check_key(key)?;
+    // secret-tool exits with status 1 and no output when nothing matches
+    match run(Command::new("secret-tool").args(["lookup", "service", SERVICE, "key", key]), None) {
+        Ok(secret) => Ok(Some(secret)),
+        Err(AppError::Keyring(message)) if message.is_empty() => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub fn delete_secret(key: &str) -> Result<()> {
+    check_key(key)?;
+    run(Command::new("secret-tool").args(["clear", "service", SERVICE, "key", key]), None).map(|_| ())
+}
+
+/// PowerShell prelude loading the WinRT credential locker
+#[cfg(target_os = "windows")]
+const VAULT: &str = "[void][Windows.Security.Credentials.PasswordVault,Windows.Security.Credentials,ContentType=WindowsRuntime]; \
+                     $vault = New-Object Windows.Security.Credentials.PasswordVault";
+
+#[cfg(target_os = "windows")]
+fn powershell(script: &str, input: Option<&str>) -> Result<String> {
+    run(
+        Command::new("powershell").args(["-NoProfile", "-NonInteractive", "-Command", script]),
+        input,
+    )
+}
+
+#[cfg(target_os = "windows")]
+pub fn set_secret(key: &str, secret: &str) -> Result<()> {
+    check_key(key)?;
+    powershell(
+        &format!(
+            "{}; $secret = [Console]::In.ReadToEnd(); \
+             $vault.Add((New-Object Windows.Security.Credentials.PasswordCredential('{}', '{}', $secret)))",
+            VAULT, SERVICE, key
+        ),
+        Some(secret),
+    )
+    .map(|_| ())
+}
+
+#[cfg(target_os = "windows")]
+pub fn get_secret(key: &str) -> Result<Option<String>> {
+    check_key(key)?;
+    let output = powershell(
+        &format!(
+            "{}; try {{ $c = $vault.Retrieve('{}', '{}'); $c.RetrievePassword(); \
+             [Console]::Out.Write('1' + $c.Password) }} catch {{ [Console]::Out.Write('0') }}",
+            VAULT, SERVICE, key
+        ),
+        None,
+    )?;
+
+    Ok(output.strip_prefix('1').map(str::to_string))
+}
+
+#[cfg(target_os = "windows")]
+pub fn delete_secret(key: &str) -> Result<()> {
+    check_key(key)?;
+    powershell(
+        &format!(
+            "{}; try {{ $vault.Remove($vault.Retrieve('{}', '{}')) }} catch {{ }}",
+            VAULT, SERVICE, key
+        ),
+        None,
+    )
+    .map(|_| ())
+}
@@ -0,0 +1,163 @@
+//! Pulls web links out of pasted text and dropped files, for creating a task from a link.
+//!
+//! Dropped files are usually shortcuts dragged from a browser's address bar or the desktop:
+//! `.url` on Windows, `.webloc` on macOS and `.desktop` links on Linux. Any other small text
+//! file is scanned for links.
+
+use std::path::Path;
+use std::process::Command;
+use crate::db::LinkDraft;
+use crate::error::{AppError, Result};
+use crate::utils::validation::{url_host, validate_url};
+
+/// Largest dropped file read when looking for links
+const MAX_FILE_BYTES: u64 = 64 * 1024;
+
+/// Punctuation that ends a sentence or encloses a link rather than belonging to it
+const TRAILING: &[char] = &['.', ',', ';', ':', '!', '?', ')', ']', '}', '>', '"', '\''];
+
+fn draft(url: &str, name: Option<String>) -> Option<LinkDraft> {
+    validate_url(url).ok()?;
+    let name = name
+        .filter(|name| !name.trim().is_empty())
+        .or_else(|| url_host(url).map(|host| host.trim_start_matches("www.").to_string()))?;
+    Some(LinkDraft { url: url.to_string(), name })
+}
+
+/// Where an http(s) link starts in `word`, ignoring the scheme's case
+fn link_start(word: &str) -> Option<usize> {
+    word.char_indices().map(|(i, _)| i).find(|&i| {
+        let rest = word[i..].as_bytes();
+        ["http://", "https://"]
+            .iter()
+            .any(|scheme| rest.get(..scheme.len()).is_some_and(|head| head.eq_ignore_ascii_case(scheme.as_bytes())))
+    })
+}
+
+/// The http(s) links in `text`, in order and without duplicates.
+pub fn extract_links(text: &str) -> Vec<LinkDraft> {
+    let mut links: Vec<LinkDraft> = Vec::new();
+    for word in text.split(|c: char| c.is_whitespace() || matches!(c, '<' | '"' | '\'' | '(')) {
+        let Some(start) = link_start(word) else { continue };
+        let url = word[start..].trim_end_matches(TRAILING);
+        if let Some(link) = draft(url, None) {
+            if !links.iter().any(|l| l.url == link.url) {
+                links.push(link);
+            }
+        }
+    }
+    links
+}
+
+/// The value of `key=` in an INI-style shortcut such as `.url` or `.desktop`
+fn ini_value<'a>(text: &'a str, key: &str) -> Option<&'a str> {
+    text.lines()
+        .filter_map(|line| line.trim().split_once('='))
+        .find(|(k, _)| k.trim().eq_ignore_ascii_case(key))
+        .map(|(_, value)| value.trim())
+}
+
+/// The link in the dropped file at `path`, named after the file for shortcuts.
+pub fn link_from_file(path: &Path) -> Option<LinkDraft> {
+    if std::fs::metadata(path).ok()?.len() > MAX_FILE_BYTES {
+        return None;
+    }
+    let text = std::fs::read_to_string(path).ok()?;
+    let stem = path.file_stem().map(|s| s.to_string_lossy().to_string());
+    let extension = path.extension().map(|e| e.to_string_lossy().to_lowercase()).unwrap_or_default();
+
+    let url = match extension.as_str() {
+        "url" | "desktop" => ini_value(&text, "URL"),
+        // A property list whose only string is the URL
+        "webloc" => text.split("<string>").nth(1).and_then(|rest| rest.split("</string>").next()),
+        _ => return extract_links(&text).into_iter().next(),
+    };
+    let name = match extension.as_str() {
+        "desktop" => ini_value(&text, "Name").map(str::to_string).or(stem),
+        _ => stem,
+    };
+    draft(url?.trim(), name)
+}
+
+/// The links in the files dropped on the window.
+pub fn links_from_paths(paths: &[impl AsRef<Path>]) -> Vec<LinkDraft> {
+    let mut links: Vec<LinkDraft> = Vec::new();
+    for link in paths.iter().filter_map(|path| link_from_file(path.as_ref())) {
+        if !links.iter().any(|l| l.url == link.url) {
+            links.push(link);
+        }
+    }
+    links
+}
+
+/// The text on the system clipboard, read through the platform's clipboard tool.
+pub fn clipboard_text() -> Result<String> {
+    #[cfg(target_os = "macos")]
+    let output = Command::new("pbpaste").output();
+
+    #[cfg(target_os = "windows")]
+    let output = {
+        use std::os::windows::process::CommandExt;
+        // Keeps PowerShell from flashing a console window
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        Command::new("powershell")
+            .args(["-NoProfile", "-NonInteractive", "-Command", "Get-Clipboard -Raw"])
+            .creation_flags(CREATE_NO_WINDOW)
+            .output()
+    };
+
+    // Wayland sessions have wl-paste; X11 ones usually xclip
+    #[cfg(target_os = "linux")]
+    let output = Command::new("wl-paste")
+        .arg("--no-newline")
+        .output()
+        .or_else(|_| Command::new("xclip").args(["-selection", "clipboard", "-o"]).output());
+
+    let output = output
+        .map_err(|e| AppError::Io(std::io::Error::new(e.kind(), format!("Cannot read the clipboard: {}", e))))?;
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_links() {
+        let links = extract_links("See https://www.example.com/report?id=3, or (http://intranet.local/x). https://www.example.com/report?id=3");
+        assert_eq!(
+            links,
+            vec![
+                LinkDraft { url: "https://www.example.com/report?id=3".to_string(), name: "example.com".to_string() },
+                LinkDraft { url: "http://intranet.local/x".to_string(), name: "intranet.local".to_string() },
+            ]
+        );
+        assert!(extract_links("javascript:alert(1) and no links").is_empty());
+    }
+
+    #[test]
+    fn test_link_from_shortcut_files() {
+        let dir = std::env::temp_dir().join(format!("do-in-time-links-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let url_file = dir.join("Team Standup.url");
+        std::fs::write(&url_file, "[InternetShortcut]\r\nURL=https://meet.example.com/standup\r\n").unwrap();
+        let webloc = dir.join("Docs.webloc");
+        std::fs::write(
+            &webloc,
+            "<plist version=\"1.0\"><dict><key>URL</key><string>https://docs.example.com/</string></dict></plist>",
+        )
+        .unwrap();
+
+        let links = links_from_paths(&[url_file, webloc]);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            links,
+            vec![
+                LinkDraft { url: "https://meet.example.com/standup".to_string(), name: "Team Standup".to_string() },
+                LinkDraft { url: "https://docs.example.com/".to_string(), name: "Docs".to_string() },
+            ]
+        );
+    }
+}
@@ -0,0 +1,145 @@
+//! Dates and times in backend-generated text, written the way the user configured.
+
+use chrono::{DateTime, Duration, Local, Utc};
+use crate::db::{ClockFormat, DateFormat, LocaleConfig};
+
+pub(crate) fn date_pattern(format: &DateFormat) -> &'static str {
+    match format {
+        DateFormat::Iso => "%Y-%m-%d",
+        DateFormat::Us => "%m/%d/%Y",
+        DateFormat::Dmy => "%d/%m/%Y",
+        DateFormat::Dotted => "%d.%m.%Y",
+    }
+}
+
+pub(crate) fn time_pattern(format: &ClockFormat) -> &'static str {
+    match format {
+        ClockFormat::H24 => "%H:%M",
+        ClockFormat::H12 => "%-I:%M %p",
+    }
+}
+
+/// Date of `at` in the system's local time zone
+pub fn format_date(at: DateTime<Utc>, locale: &LocaleConfig) -> String {
+    at.with_timezone(&Local).format(date_pattern(&locale.date_format)).to_string()
+}
+
+/// Time of day of `at` in the system's local time zone
+pub fn format_time(at: DateTime<Utc>, locale: &LocaleConfig) -> String {
+    at.with_timezone(&Local).format(time_pattern(&locale.clock_format)).to_string()
+}
+
+/// Date and time of `at` in the system's local time zone
+pub fn format_datetime(at: DateTime<Utc>, locale: &LocaleConfig) -> String {
+    let pattern = format!("{} {}", date_pattern(&locale.date_format), time_pattern(&locale.clock_format));
+    at.with_timezone(&Local).format(&pattern).to_string()
+}
+
+/// IANA name of the system time zone, e.g. "Europe/Berlin", falling back to UTC.
+///
+/// Read from `TZ` or the target of the /etc/localtime link on macOS and Linux.
+pub fn system_timezone() -> String {
+    let from_env = std::env::var("TZ").ok().map(|tz| tz.trim_start_matches(':').to_string());
+    let from_link = || {
+        std::fs::read_link("/etc/localtime").ok().and_then(|target| {
+            let target = target.to_string_lossy().to_string();
+            target.split_once("zoneinfo/").map(|(_, name)| name.to_string())
+        })
+    };
+
+    from_env
+        .or_else(from_link)
+        .filter(|name| name.parse::<chrono_tz::Tz>().is_ok())
+        .unwrap_or_else(|| "UTC".to_string())
+}
+
+/// Relative times further away than this are written as a date instead
+const RELATIVE_LIMIT_DAYS: i64 = 7;
+
+/// Length of `duration` for people, e.g. "2 h 15 min" or "3 days"
+pub fn humanize_duration(duration: Duration) -> String {
+    let minutes = duration.num_minutes().abs();
+    if minutes < 1 {
+        return "less than a minute".to_string();
+    }
+
+    let (days, hours, minutes) = (minutes / 1440, minutes % 1440 / 60, minutes % 60);
+    match (days, hours, minutes) {
+        (0, 0, m) => format!("{} min", m),
+        (0, h, 0) => format!("{} h", h),
+        (0, h, m) => format!("{} h {} min", h, m),
+        (1, 0, _) => "1 day".to_string(),
+        (1, h, _) => format!("1 day {} h", h),
+        (d, _, _) => format!("{} days", d),
+    }
+}
+
+/// `at` relative to `now`, e.g. "in 2 h 15 min" or "3 days ago"; times more than a week away
+/// are written as a date in the user's format.
+pub fn humanize_relative(at: DateTime<Utc>, now: DateTime<Utc>, locale: &LocaleConfig) -> String {
+    let delta = at - now;
+    if delta.num_days().abs() > RELATIVE_LIMIT_DAYS {
+        return format!("on {}", format_date(at, locale));
+    }
+    if delta.num_minutes() == 0 {
+        return "now".to_string();
+    }
+
+    if delta > Duration::zero() {
+        format!("in {}", humanize_duration(delta))
+    } else {
+        format!("{} ago", humanize_duration(delta))
+    }
+}
+
+/// `at` relative to the current time; see [`humanize_relative`]
+pub fn humanize_until(at: DateTime<Utc>, locale: &LocaleConfig) -> String {
+    humanize_relative(at, Utc::now(), locale)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveDate, TimeZone};
+
+    #[test]
+    fn test_format_datetime() {
+        let local = Local
+            .from_local_datetime(&NaiveDate::from_ymd_opt(2024, 3, 9).unwrap().and_hms_opt(14, 5, 0).unwrap())
+            .unwrap();
+        let at = local.with_timezone(&Utc);
+
+        assert_eq!(format_datetime(at, &LocaleConfig::default()), "2024-03-09 14:05");
+
+        let locale = LocaleConfig {
+            clock_format: ClockFormat::H12,
+            date_format: DateFormat::Us,
+            ..Default::default()
+        };
+        assert_eq!(format_datetime(at, &locale), "03/09/2024 2:05 PM");
+        assert_eq!(format_date(at, &LocaleConfig { date_format: DateFormat::Dotted, ..Default::default() }), "09.03.2024");
+    }
+
+    #[test]
+    fn test_humanize_duration() {
+        assert_eq!(humanize_duration(Duration::seconds(30)), "less than a minute");
+        assert_eq!(humanize_duration(Duration::minutes(45)), "45 min");
+        assert_eq!(humanize_duration(Duration::minutes(120)), "2 h");
+        assert_eq!(humanize_duration(Duration::minutes(135)), "2 h 15 min");
+        assert_eq!(humanize_duration(Duration::hours(28)), "1 day 4 h");
+        assert_eq!(humanize_duration(-Duration::days(3)), "3 days");
+    }
+
+    #[test]
+    fn test_humanize_relative() {
+        let now = Utc::now();
+        let locale = LocaleConfig::default();
+
+        assert_eq!(humanize_relative(now + Duration::minutes(135), now, &locale), "in 2 h 15 min");
+        assert_eq!(humanize_relative(now - Duration::days(3), now, &locale), "3 days ago");
+        assert_eq!(humanize_relative(now + Duration::seconds(20), now, &locale), "now");
+
+        let far = now + Duration::days(30);
+        assert_eq!(humanize_relative(far, now, &locale), format!("on {}", format_date(far, &locale)));
+    }
+}
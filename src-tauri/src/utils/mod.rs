@@ -1,2 +1,14 @@
 pub mod browser_detector;
+pub mod curl;
+pub mod i18n;
+pub mod ical;
+pub mod keyring;
+pub mod links;
+pub mod locale;
+pub mod profile_detector;
+pub mod secret_url;
+pub mod session_lock;
+pub mod share;
+pub mod system_load;
+pub mod task_validation;
 pub mod validation;
@@ -0,0 +1,232 @@
+//! Lists the profiles a browser has, so a task's profile can be picked instead of typed.
+//!
+//! Chromium browsers keep their profiles in the `profile.info_cache` of the `Local State` file
+//! in their user data directory; Firefox and Zen list them in `profiles.ini`. Opera, Arc and
+//! Safari have no profiles a task can select and always return an empty list.
+
+use std::path::{Path, PathBuf};
+use crate::db::{BrowserProfile, BrowserType};
+use crate::error::{AppError, Result};
+use crate::utils::validation::validate_browser_profile;
+
+/// Profiles of `browser` on this machine, or an empty list when it has never been run.
+/// Profiles a task could not select, like Firefox names with punctuation, are left out.
+pub fn get_browser_profiles(browser: &BrowserType) -> Result<Vec<BrowserProfile>> {
+    let profiles = match browser {
+        BrowserType::Chrome
+        | BrowserType::Edge
+        | BrowserType::Brave
+        | BrowserType::Vivaldi
+        | BrowserType::Chromium => {
+            let Some(path) = chromium_user_data_dir(browser).map(|dir| dir.join("Local State")) else {
+                return Ok(Vec::new());
+            };
+            match read_if_exists(&path)? {
+                Some(contents) => parse_local_state(&contents)?,
+                None => Vec::new(),
+            }
+        }
+        BrowserType::Firefox | BrowserType::Zen => {
+            let Some(path) = firefox_dir(browser).map(|dir| dir.join("profiles.ini")) else {
+                return Ok(Vec::new());
+            };
+            match read_if_exists(&path)? {
+                Some(contents) => parse_profiles_ini(&contents),
+                None => Vec::new(),
+            }
+        }
+        // Arc keeps its spaces in a single profile
+        BrowserType::Opera | BrowserType::Arc | BrowserType::Safari => Vec::new(),
+    };
+
+    Ok(profiles
+        .into_iter()
+        .filter(|profile| validate_browser_profile(&profile.browser_profile).is_ok())
+        .collect())
+}
+
+fn read_if_exists(path: &Path) -> Result<Option<String>> {
+    match std::fs::read_to_string(path) {
+        Ok(contents) => Ok(Some(contents)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// User data directory of a Chromium browser, holding `Local State` and one directory per profile
+fn chromium_user_data_dir(browser: &BrowserType) -> Option<PathBuf> {
+    #[cfg(target_os = "windows")]
+    let (base, parts): (Option<PathBuf>, &[&str]) = (
+        dirs::data_local_dir(),
+        match browser {
+            BrowserType::Chrome => &["Google", "Chrome", "User Data"],
+            BrowserType::Edge => &["Microsoft", "Edge", "User Data"],
+            BrowserType::Brave => &["BraveSoftware", "Brave-Browser", "User Data"],
+            BrowserType::Vivaldi => &["Vivaldi", "User Data"],
+            BrowserType::Chromium => &["Chromium", "User Data"],
+            _ => return None,
+        },
+    );
+
+    #[cfg(target_os = "macos")]
+    let (base, parts): (Option<PathBuf>, &[&str]) = (
+        dirs::home_dir().map(|home| home.join("Library").join("Application Support")),
+        match browser {
+            BrowserType::Chrome => &["Google", "Chrome"],
+            BrowserType::Edge => &["Microsoft Edge"],
+            BrowserType::Brave => &["BraveSoftware", "Brave-Browser"],
+            BrowserType::Vivaldi => &["Vivaldi"],
+            BrowserType::Chromium => &["Chromium"],
+            _ => return None,
+        },
+    );
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let (base, parts): (Option<PathBuf>, &[&str]) = (
+        dirs::config_dir(),
+        match browser {
+            BrowserType::Chrome => &["google-chrome"],
+            BrowserType::Edge => &["microsoft-edge"],
+            BrowserType::Brave => &["BraveSoftware", "Brave-Browser"],
+            BrowserType::Vivaldi => &["vivaldi"],
+            BrowserType::Chromium => &["chromium"],
+            _ => return None,
+        },
+    );
+
+    base.map(|base| parts.iter().fold(base, |dir, part| dir.join(part)))
+}
+
+/// Directory holding the `profiles.ini` of Firefox or Zen
+fn firefox_dir(browser: &BrowserType) -> Option<PathBuf> {
+    let zen = *browser == BrowserType::Zen;
+    if cfg!(target_os = "windows") {
+        dirs::config_dir().map(|dir| if zen { dir.join("zen") } else { dir.join("Mozilla").join("Firefox") })
+    } else if cfg!(target_os = "macos") {
+        dirs::home_dir()
+            .map(|home| home.join("Library").join("Application Support").join(if zen { "zen" } else { "Firefox" }))
+    } else {
+        dirs::home_dir().map(|home| if zen { home.join(".zen") } else { home.join(".mozilla").join("firefox") })
+    }
+}
+
+/// Profiles in a Chromium `Local State` file, by directory. A task selects them by directory.
+fn parse_local_state(contents: &str) -> Result<Vec<BrowserProfile>> {
+    let state: serde_json::Value = serde_json::from_str(contents)
+        .map_err(|e| AppError::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, e)))?;
+
+    let mut profiles: Vec<BrowserProfile> = state
+        .pointer("/profile/info_cache")
+        .and_then(|cache| cache.as_object())
+        .map(|cache| {
+            cache
+                .iter()
+                .map(|(directory, info)| BrowserProfile {
+                    name: info
+                        .get("name")
+                        .and_then(|name| name.as_str())
+                        .filter(|name| !name.is_empty())
+                        .unwrap_or(directory)
+                        .to_string(),
+                    directory: directory.clone(),
+                    browser_profile: directory.clone(),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    profiles.sort_by(|a, b| a.directory.cmp(&b.directory));
+    Ok(profiles)
+}
+
+/// Profiles in Firefox's `profiles.ini`, in file order. A task selects them by name.
+fn parse_profiles_ini(contents: &str) -> Vec<BrowserProfile> {
+    let mut profiles = Vec::new();
+    let mut in_profile = false;
+    let mut name: Option<String> = None;
+    let mut path: Option<String> = None;
+
+    let mut flush = |name: &mut Option<String>, path: &mut Option<String>| {
+        if let (Some(name), Some(path)) = (name.take(), path.take()) {
+            profiles.push(BrowserProfile { browser_profile: name.clone(), name, directory: path });
+        }
+    };
+
+    for line in contents.lines().map(str::trim) {
+        if line.starts_with('[') && line.ends_with(']') {
+            flush(&mut name, &mut path);
+            in_profile = line[1..line.len() - 1].starts_with("Profile");
+            continue;
+        }
+        if !in_profile {
+            continue;
+        }
+        match line.split_once('=') {
+            Some(("Name", value)) => name = Some(value.trim().to_string()),
+            Some(("Path", value)) => path = Some(value.trim().to_string()),
+            _ => {}
+        }
+    }
+    flush(&mut name, &mut path);
+
+    profiles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_local_state() {
+        let contents = r#"{
+            "profile": {
+                "info_cache": {
+                    "Profile 1": { "name": "Work" },
+                    "Default": { "name": "Person 1" },
+                    "Profile 3": {}
+                }
+            }
+        }"#;
+
+        let profiles = parse_local_state(contents).unwrap();
+        let entries: Vec<_> = profiles.iter().map(|p| (p.name.as_str(), p.browser_profile.as_str())).collect();
+        assert_eq!(entries, vec![("Person 1", "Default"), ("Work", "Profile 1"), ("Profile 3", "Profile 3")]);
+
+        assert!(parse_local_state("{}").unwrap().is_empty());
+        assert!(parse_local_state("not json").is_err());
+    }
+
+    #[test]
+    fn test_parse_profiles_ini() {
+        let contents = "\
+[Install4F96D1932A9F858E]
+Default=Profiles/abcd1234.default-release
+Locked=1
+
+[Profile1]
+Name=default
+IsRelative=1
+Path=Profiles/wxyz5678.default
+
+[Profile0]
+Name=default-release
+IsRelative=1
+Path=Profiles/abcd1234.default-release
+Default=1
+
+[General]
+StartWithLastProfile=1
+Version=2
+";
+
+        let profiles = parse_profiles_ini(contents);
+        let entries: Vec<_> = profiles.iter().map(|p| (p.browser_profile.as_str(), p.directory.as_str())).collect();
+        assert_eq!(
+            entries,
+            vec![
+                ("default", "Profiles/wxyz5678.default"),
+                ("default-release", "Profiles/abcd1234.default-release"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,83 @@
+//! URLs whose query string holds a credential. The query string is kept in the OS keyring and
+//! only a reference to it is stored with the task, so the database can be copied or exported
+//! without leaking it.
+
+use crate::db::Task;
+use crate::error::Result;
+use crate::utils::keyring;
+
+/// Splits a URL into the part stored in the database and the query string kept secret.
+/// Returns `None` when there is no query string. A fragment stays with the stored part.
+pub fn split(url: &str) -> Option<(String, String)> {
+    let (before_fragment, fragment) = match url.split_once('#') {
+        Some((before, fragment)) => (before, Some(fragment)),
+        None => (url, None),
+    };
+    let (base, query) = before_fragment.split_once('?')?;
+    if query.is_empty() {
+        return None;
+    }
+
+    let stored = match fragment {
+        Some(fragment) => format!("{}#{}", base, fragment),
+        None => base.to_string(),
+    };
+    Some((stored, query.to_string()))
+}
+
+/// Puts a secret query string back into a stored URL, before any fragment.
+pub fn join(stored: &str, query: &str) -> String {
+    match stored.split_once('#') {
+        Some((base, fragment)) => format!("{}?{}#{}", base, query, fragment),
+        None => format!("{}?{}", stored, query),
+    }
+}
+
+/// New keyring key for a task's secret query string.
+pub fn new_ref() -> String {
+    format!("task-url-{}", hex::encode(rand::random::<[u8; 8]>()))
+}
+
+/// The URL to actually open for a task, with its secret query string restored.
+pub fn resolve(task: &Task) -> Result<Option<String>> {
+    let (Some(url), Some(secret_ref)) = (task.url.as_deref(), task.url_secret_ref.as_deref()) else {
+        return Ok(task.url.clone());
+    };
+
+    Ok(Some(match keyring::get_secret(secret_ref)? {
+        Some(query) => join(url, &query),
+        None => url.to_string(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_and_join_round_trip() {
+        for url in [
+            "https://example.com/feed?token=abc",
+            "https://example.com/app?key=1&user=me#inbox",
+        ] {
+            let (stored, query) = split(url).unwrap();
+            assert!(!stored.contains('?'));
+            assert_eq!(join(&stored, &query), url);
+        }
+    }
+
+    #[test]
+    fn test_split_keeps_fragment_in_stored_part() {
+        assert_eq!(
+            split("https://example.com/?token=abc#top"),
+            Some(("https://example.com/#top".to_string(), "token=abc".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_split_ignores_urls_without_query() {
+        assert_eq!(split("https://example.com/page"), None);
+        assert_eq!(split("https://example.com/page?"), None);
+        assert_eq!(split("https://example.com/#a?b"), None);
+    }
+}
@@ -0,0 +1,37 @@
+//! Whether the user's desktop session is locked, polled through the platform's own tools.
+
+use std::process::Command;
+
+/// Whether the screen of the current session is locked. Anything that cannot be checked counts
+/// as unlocked, so the scheduler keeps running.
+#[cfg(target_os = "macos")]
+pub fn is_locked() -> bool {
+    // The console user's session dictionary carries the lock flag
+    Command::new("/usr/sbin/ioreg")
+        .args(["-n", "Root", "-d1"])
+        .output()
+        .map(|o| String::from_utf8_lossy(&o.stdout).contains("\"CGSSessionScreenIsLocked\"=Yes"))
+        .unwrap_or(false)
+}
+
+#[cfg(target_os = "linux")]
+pub fn is_locked() -> bool {
+    // logind's hint is set by most screen lockers; without a session id, ask for the caller's
+    let session = std::env::var("XDG_SESSION_ID").unwrap_or_else(|_| "self".to_string());
+    Command::new("loginctl")
+        .args(["show-session", &session, "-p", "LockedHint", "--value"])
+        .output()
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim() == "yes")
+        .unwrap_or(false)
+}
+
+#[cfg(target_os = "windows")]
+pub fn is_locked() -> bool {
+    // The lock screen runs as LogonUI.exe for as long as the workstation is locked
+    let system_root = std::env::var_os("SystemRoot").unwrap_or_else(|| "C:\\Windows".into());
+    Command::new(std::path::PathBuf::from(system_root).join("System32").join("tasklist.exe"))
+        .args(["/FI", "IMAGENAME eq LogonUI.exe", "/NH"])
+        .output()
+        .map(|o| String::from_utf8_lossy(&o.stdout).to_lowercase().contains("logonui.exe"))
+        .unwrap_or(false)
+}
@@ -0,0 +1,180 @@
+//! Compact, shareable encoding of a task's schedule: URL-safe base64 of a small JSON object,
+//! carried in a `do-in-time://import-task?data=...` link. Only what a teammate needs to set up
+//! the same schedule is included; profiles, secrets and run state stay on this machine.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use crate::db::{BrowserType, RepeatConfig, Task};
+use crate::error::{AppError, Result};
+use crate::utils::browser_detector;
+
+/// Bumped when the encoding changes incompatibly
+const SHARE_VERSION: u32 = 1;
+
+/// Longest code accepted, to keep a pasted link from allocating without bound
+const MAX_CODE_LEN: usize = 8 * 1024;
+
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// The shared part of a task.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SharedTask {
+    pub v: u32,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    pub browser: BrowserType,
+    pub start: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub close: Option<DateTime<Utc>>,
+    pub tz: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repeat: Option<RepeatConfig>,
+}
+
+impl SharedTask {
+    pub fn from_task(task: &Task) -> Self {
+        Self {
+            v: SHARE_VERSION,
+            name: task.name.clone(),
+            description: task.description.clone().filter(|d| !d.is_empty()),
+            // The stored URL never holds the keyring part of a secret URL
+            url: task.url.clone(),
+            browser: task.browser.clone(),
+            start: task.start_time,
+            close: task.close_time,
+            tz: task.timezone.clone(),
+            repeat: task.repeat_config.clone(),
+        }
+    }
+
+    /// A new, unsaved task with this schedule.
+    pub fn into_task(self) -> Task {
+        let mut task = Task::new(self.name, self.browser, self.start, self.tz);
+        task.description = self.description;
+        task.url = self.url;
+        task.close_time = self.close;
+        task.repeat_config = self.repeat;
+        task
+    }
+}
+
+/// URL-safe base64 without padding
+fn base64_encode(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0u32, |n, (i, b)| n | (*b as u32) << (16 - 8 * i));
+        for i in 0..=chunk.len() {
+            out.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+        }
+    }
+    out
+}
+
+fn base64_decode(text: &str) -> Option<Vec<u8>> {
+    // Standard base64 and padding are accepted too, for codes that passed through other tools
+    let digits: Vec<u32> = text
+        .trim()
+        .trim_end_matches('=')
+        .bytes()
+        .map(|c| match c {
+            b'+' => Some(62),
+            b'/' => Some(63),
+            _ => ALPHABET.iter().position(|a| *a == c).map(|p| p as u32),
+        })
+        .collect::<Option<_>>()?;
+
+    let mut out = Vec::with_capacity(digits.len() * 3 / 4);
+    for chunk in digits.chunks(4) {
+        if chunk.len() == 1 {
+            return None;
+        }
+        let n = chunk.iter().enumerate().fold(0u32, |n, (i, d)| n | d << (18 - 6 * i));
+        for i in 0..chunk.len() - 1 {
+            out.push((n >> (16 - 8 * i)) as u8);
+        }
+    }
+    Some(out)
+}
+
+/// The share code of `task`.
+pub fn encode(task: &Task) -> Result<String> {
+    let json = serde_json::to_vec(&SharedTask::from_task(task))
+        .map_err(|e| AppError::InvalidTask(format!("Cannot encode the task: {}", e)))?;
+    Ok(base64_encode(&json))
+}
+
+/// The shared task in a share code.
+pub fn decode(code: &str) -> Result<SharedTask> {
+    let invalid = || AppError::InvalidTask("Not a valid task link".to_string());
+    if code.len() > MAX_CODE_LEN {
+        return Err(invalid());
+    }
+    let json = base64_decode(code).ok_or_else(invalid)?;
+    let shared: SharedTask = serde_json::from_slice(&json).map_err(|_| invalid())?;
+    if shared.v > SHARE_VERSION {
+        return Err(AppError::InvalidTask(
+            "This task link was made by a newer version of the app".to_string(),
+        ));
+    }
+    Ok(shared)
+}
+
+/// The unsaved task in a share code. A browser that is not installed here is swapped for the
+/// first one that is.
+pub fn import(code: &str) -> Result<Task> {
+    let mut task = decode(code)?.into_task();
+    let installed = browser_detector::get_installed_browsers();
+    if !installed.contains(&task.browser) {
+        if let Some(browser) = installed.into_iter().next() {
+            task.browser = browser;
+        }
+    }
+    Ok(task)
+}
+
+/// The deep link that imports `task` when opened.
+pub fn share_link(task: &Task) -> Result<String> {
+    Ok(format!("{}://import-task?data={}", crate::deep_link::SCHEME, encode(task)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use crate::db::RepeatInterval;
+
+    #[test]
+    fn test_base64_round_trip() {
+        for input in [&b""[..], b"f", b"fo", b"foo", b"foob", b"\xff\xfe\x00"] {
+            assert_eq!(base64_decode(&base64_encode(input)).unwrap(), input);
+        }
+        assert_eq!(base64_encode(b"foob"), "Zm9vYg");
+        assert_eq!(base64_decode("Zm9vYg==").unwrap(), b"foob");
+        assert!(base64_decode("Z").is_none());
+        assert!(base64_decode("Zm9v!").is_none());
+    }
+
+    #[test]
+    fn test_share_code_round_trip() {
+        let start = Utc.with_ymd_and_hms(2024, 3, 4, 9, 0, 0).unwrap();
+        let mut task = Task::new("Dashboard".to_string(), BrowserType::Firefox, start, "Europe/Berlin".to_string());
+        task.id = Some(12);
+        task.url = Some("https://grafana.example.com/d/ops".to_string());
+        task.browser_profile = Some("Work".to_string());
+        task.repeat_config = Some(RepeatConfig { interval: RepeatInterval::Weekly, end_after: None, end_date: None });
+
+        let imported = decode(&encode(&task).unwrap()).unwrap().into_task();
+        assert_eq!(imported.id, None);
+        assert_eq!(imported.name, "Dashboard");
+        assert_eq!(imported.url, task.url);
+        assert_eq!(imported.start_time, start);
+        assert_eq!(imported.timezone, "Europe/Berlin");
+        assert_eq!(imported.browser_profile, None);
+        assert!(matches!(imported.repeat_config, Some(RepeatConfig { interval: RepeatInterval::Weekly, .. })));
+
+        assert!(decode("not a code").is_err());
+    }
+}
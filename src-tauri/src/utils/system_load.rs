@@ -0,0 +1,156 @@
+//! CPU and memory usage of the whole system, read through the platform's own tools so the
+//! scheduler can hold back launches while the machine is busy.
+
+use crate::error::{AppError, Result};
+#[cfg(not(target_os = "linux"))]
+use std::process::Command;
+
+/// System-wide usage, both in percent
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SystemLoad {
+    pub cpu_percent: f64,
+    pub memory_percent: f64,
+}
+
+#[cfg(not(target_os = "linux"))]
+fn command_output(command: &mut Command) -> Result<String> {
+    let output = command
+        .output()
+        .map_err(|e| AppError::Scheduler(format!("Failed to read system load: {}", e)))?;
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Busy and total jiffies from the aggregate `cpu` line of /proc/stat
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_proc_stat(stat: &str) -> Option<(u64, u64)> {
+    let values: Vec<u64> = stat
+        .lines()
+        .find(|line| line.starts_with("cpu "))?
+        .split_whitespace()
+        .skip(1)
+        .filter_map(|v| v.parse().ok())
+        .collect();
+
+    let total: u64 = values.iter().sum();
+    // idle and iowait
+    let idle = values.get(3).copied().unwrap_or(0) + values.get(4).copied().unwrap_or(0);
+    Some((total - idle, total))
+}
+
+/// Used memory in percent from /proc/meminfo
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_meminfo(meminfo: &str) -> Option<f64> {
+    let field = |name: &str| {
+        meminfo
+            .lines()
+            .find(|line| line.starts_with(name))?
+            .split_whitespace()
+            .nth(1)?
+            .parse::<f64>()
+            .ok()
+    };
+
+    let total = field("MemTotal:")?;
+    let available = field("MemAvailable:")?;
+    (total > 0.0).then(|| (total - available) / total * 100.0)
+}
+
+#[cfg(target_os = "linux")]
+pub async fn current() -> Result<SystemLoad> {
+    let read_stat = || {
+        std::fs::read_to_string("/proc/stat")
+            .ok()
+            .and_then(|stat| parse_proc_stat(&stat))
+            .ok_or_else(|| AppError::Scheduler("Failed to read /proc/stat".to_string()))
+    };
+
+    // CPU usage is the share of busy time between two samples
+    let (busy_before, total_before) = read_stat()?;
+    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+    let (busy_after, total_after) = read_stat()?;
+    let total = total_after.saturating_sub(total_before);
+    let cpu_percent = if total == 0 {
+        0.0
+    } else {
+        busy_after.saturating_sub(busy_before) as f64 / total as f64 * 100.0
+    };
+
+    let memory_percent = std::fs::read_to_string("/proc/meminfo")
+        .ok()
+        .and_then(|meminfo| parse_meminfo(&meminfo))
+        .ok_or_else(|| AppError::Scheduler("Failed to read /proc/meminfo".to_string()))?;
+
+    Ok(SystemLoad { cpu_percent, memory_percent })
+}
+
+#[cfg(target_os = "macos")]
+pub async fn current() -> Result<SystemLoad> {
+    let sysctl = |name: &str| -> Result<f64> {
+        command_output(Command::new("/usr/sbin/sysctl").args(["-n", name]))?
+            .trim()
+            .parse()
+            .map_err(|_| AppError::Scheduler(format!("Failed to read {}", name)))
+    };
+
+    // Summed per-process CPU is relative to one core
+    let cpus = sysctl("hw.logicalcpu")?.max(1.0);
+    let cpu_total: f64 = command_output(Command::new("/bin/ps").args(["-A", "-o", "%cpu="]))?
+        .lines()
+        .filter_map(|line| line.trim().parse::<f64>().ok())
+        .sum();
+    let cpu_percent = (cpu_total / cpus).min(100.0);
+
+    // vm_stat reports pages; free, inactive and speculative pages are available
+    let vm_stat = command_output(&mut Command::new("/usr/bin/vm_stat"))?;
+    let pages = |name: &str| {
+        vm_stat
+            .lines()
+            .find(|line| line.starts_with(name))
+            .and_then(|line| line.rsplit(':').next())
+            .and_then(|v| v.trim().trim_end_matches('.').parse::<f64>().ok())
+            .unwrap_or(0.0)
+    };
+    let available = (pages("Pages free") + pages("Pages inactive") + pages("Pages speculative"))
+        * sysctl("hw.pagesize")?;
+    let total = sysctl("hw.memsize")?;
+    let memory_percent = if total > 0.0 { (total - available) / total * 100.0 } else { 0.0 };
+
+    Ok(SystemLoad { cpu_percent, memory_percent })
+}
+
+#[cfg(target_os = "windows")]
+pub async fn current() -> Result<SystemLoad> {
+    let output = command_output(Command::new("powershell").args([
+        "-NoProfile",
+        "-NonInteractive",
+        "-Command",
+        "$cpu = (Get-CimInstance Win32_Processor | Measure-Object LoadPercentage -Average).Average; \
+         $os = Get-CimInstance Win32_OperatingSystem; \
+         [Console]::Out.Write(\"$cpu $($os.FreePhysicalMemory) $($os.TotalVisibleMemorySize)\")",
+    ]))?;
+
+    let values: Vec<f64> = output.split_whitespace().filter_map(|v| v.parse().ok()).collect();
+    let [cpu_percent, free, total] = values[..] else {
+        return Err(AppError::Scheduler(format!("Unexpected system load output: {}", output)));
+    };
+    let memory_percent = if total > 0.0 { (total - free) / total * 100.0 } else { 0.0 };
+
+    Ok(SystemLoad { cpu_percent, memory_percent })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_proc_stat() {
+        let stat = "cpu  100 0 50 800 50 0 0 0 0 0\ncpu0 50 0 25 400 25 0 0 0 0 0\n";
+        assert_eq!(parse_proc_stat(stat), Some((150, 1000)));
+    }
+
+    #[test]
+    fn test_parse_meminfo() {
+        let meminfo = "MemTotal:       16000000 kB\nMemFree:         1000000 kB\nMemAvailable:    4000000 kB\n";
+        assert_eq!(parse_meminfo(meminfo), Some(75.0));
+    }
+}
@@ -0,0 +1,615 @@
+use chrono::Utc;
+use chrono_tz::Tz;
+use crate::db::{BrowserType, IssueSeverity, RepeatInterval, StepAction, Task, TaskKind, ValidationIssue, WindowState, LAST_WEEK_OF_MONTH};
+use crate::error::{AppError, Result};
+use crate::utils::validation::{validate_browser_profile, validate_domain, validate_locale, validate_tag, validate_url_allowing, validate_user_agent};
+
+/// Zoom range Chromium accepts for its scale factor
+const ZOOM_RANGE: std::ops::RangeInclusive<f64> = 0.25..=5.0;
+
+/// Most tries of one occurrence a task may ask for
+const MAX_RETRY_ATTEMPTS: u32 = 10;
+/// Longest first retry backoff in seconds: one day
+const MAX_RETRY_BACKOFF_SECS: u32 = 86_400;
+
+/// Most excluded dates or ranges a task may have
+const MAX_EXCLUSIONS: usize = 200;
+
+/// Runs every check applied to a task before it is saved.
+///
+/// Errors block saving; warnings point at settings that are valid but probably not what the
+/// user meant. URLs may use http(s) or one of `extra_schemes` from the settings. Checks that
+/// need the host system, like browser availability, are left to the caller.
+pub fn check_task(task: &Task, extra_schemes: &[&str]) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+
+    if task.name.trim().is_empty() {
+        issues.push(ValidationIssue::error("name", "Task name cannot be empty"));
+    }
+
+    if let Some(ref url) = task.url {
+        push_if_invalid(&mut issues, "url", validate_url_allowing(url, extra_schemes));
+    }
+    if let Some(ref profile) = task.browser_profile {
+        push_if_invalid(&mut issues, "browser_profile", validate_browser_profile(profile));
+    }
+    for tag in &task.tags {
+        push_if_invalid(&mut issues, "tags", validate_tag(tag));
+    }
+
+    if let Some(user_agent) = task.user_agent.as_deref().filter(|ua| !ua.trim().is_empty()) {
+        push_if_invalid(&mut issues, "user_agent", validate_user_agent(user_agent));
+        if !task.browser.is_chromium() {
+            issues.push(ValidationIssue::warning(
+                "user_agent",
+                "Only Chromium-based browsers can override the user agent",
+            ));
+        }
+    }
+
+    if let Some(lang) = task.lang.as_deref().filter(|lang| !lang.is_empty()) {
+        push_if_invalid(&mut issues, "lang", validate_locale(lang));
+        if !task.browser.is_chromium() {
+            issues.push(ValidationIssue::warning("lang", "Only Chromium-based browsers can be opened in another locale"));
+        }
+    }
+
+    if task.fresh_session && task.managed_profile {
+        issues.push(ValidationIssue::error(
+            "managed_profile",
+            "A task opens either on a fresh session or on its managed profile, not both",
+        ));
+    }
+    if task.managed_profile && !task.browser.is_chromium() {
+        issues.push(ValidationIssue::warning(
+            "managed_profile",
+            "Only Chromium-based browsers can open on a managed profile; this browser uses its normal profile",
+        ));
+    }
+    if task.fresh_session && !task.browser.is_chromium() {
+        issues.push(ValidationIssue::warning(
+            "fresh_session",
+            "Only Chromium-based browsers can open a fresh session; this browser uses its normal profile",
+        ));
+    }
+    if (task.fresh_session || task.managed_profile)
+        && task.browser.is_chromium()
+        && task.browser_profile.as_deref().is_some_and(|p| !p.trim().is_empty())
+    {
+        issues.push(ValidationIssue::warning(
+            "browser_profile",
+            "The task opens on a profile of its own, so the browser profile is ignored",
+        ));
+    }
+
+    if task.prelaunch_minutes.is_some_and(|m| m > 0) && task.kind != TaskKind::Browser {
+        issues.push(ValidationIssue::warning(
+            "prelaunch_minutes",
+            "Only browser tasks are warmed up; this task does not launch a browser",
+        ));
+    }
+
+    if let Some(attempts) = task.retry_max_attempts {
+        if attempts == 0 || attempts > MAX_RETRY_ATTEMPTS {
+            issues.push(ValidationIssue::error(
+                "retry_max_attempts",
+                format!("Attempts must be between 1 and {}", MAX_RETRY_ATTEMPTS),
+            ));
+        }
+    }
+
+    if let Some(backoff) = task.retry_backoff_secs {
+        if backoff == 0 || backoff > MAX_RETRY_BACKOFF_SECS {
+            issues.push(ValidationIssue::error(
+                "retry_backoff_secs",
+                format!("Backoff must be between 1 and {} seconds", MAX_RETRY_BACKOFF_SECS),
+            ));
+        }
+    }
+
+    if task.timezone.parse::<Tz>().is_err() {
+        issues.push(ValidationIssue::error(
+            "timezone",
+            format!("Invalid timezone: {}", task.timezone),
+        ));
+    }
+
+    check_times(task, &mut issues);
+    check_exclusions(task, &mut issues);
+    check_pomodoro(task, &mut issues);
+    check_steps(task, extra_schemes, &mut issues);
+
+    if task.kind == TaskKind::Reminder && task.minimize_browsers && task.open_length().is_none() {
+        issues.push(ValidationIssue::error(
+            "close_time",
+            "Minimizing browsers during a break needs a close time or duration to end the break",
+        ));
+    }
+
+    if matches!(task.kind, TaskKind::SaveSession | TaskKind::RestoreSession)
+        && task.session_name.as_deref().map_or(true, |name| name.trim().is_empty())
+    {
+        issues.push(ValidationIssue::error(
+            "session_name",
+            "A session task needs the name of the session to save or restore",
+        ));
+    }
+    if task.kind == TaskKind::SaveSession && task.open_length().is_some() {
+        issues.push(ValidationIssue::error(
+            "close_time",
+            "Saving a session is a single action and cannot have a close time",
+        ));
+    }
+
+    if task.target_display.is_some() && !task.browser.is_chromium() {
+        issues.push(ValidationIssue::warning(
+            "target_display",
+            "Only Chromium-based browsers can be placed on a display; this browser opens where it last was",
+        ));
+    }
+
+    if matches!(task.window_state, WindowState::Maximized | WindowState::Fullscreen)
+        && !task.browser.is_chromium()
+    {
+        issues.push(ValidationIssue::warning(
+            "window_state",
+            "Only Chromium-based browsers can be opened maximized or fullscreen",
+        ));
+    }
+
+    if task.window_position.is_some() && !task.browser.is_chromium() {
+        issues.push(ValidationIssue::warning(
+            "window_position",
+            "Only Chromium-based browsers can be told where to open their window",
+        ));
+    }
+    if let Some(size) = task.window_size {
+        if size.width == 0 || size.height == 0 {
+            issues.push(ValidationIssue::error("window_size", "Window width and height must be at least 1"));
+        } else if !task.browser.is_chromium() && !task.browser.is_firefox() {
+            issues.push(ValidationIssue::warning(
+                "window_size",
+                "Only Chromium- and Firefox-based browsers can be told how large to open their window",
+            ));
+        } else if matches!(task.window_state, WindowState::Maximized | WindowState::Fullscreen) {
+            issues.push(ValidationIssue::warning(
+                "window_size",
+                "A maximized or fullscreen window ignores the window size",
+            ));
+        }
+    }
+
+    if task.kiosk && !task.browser.is_chromium() && !task.browser.is_firefox() {
+        issues.push(ValidationIssue::warning(
+            "kiosk",
+            "Only Chromium- and Firefox-based browsers have a kiosk mode",
+        ));
+    }
+
+    if task.app_mode {
+        if !task.browser.is_chromium() {
+            issues.push(ValidationIssue::warning(
+                "app_mode",
+                "Only Chromium-based browsers can open a URL as an app",
+            ));
+        } else if task.url.is_none() {
+            issues.push(ValidationIssue::warning("app_mode", "App mode needs a URL to open"));
+        }
+    }
+
+    if let Some(zoom) = task.zoom {
+        if !ZOOM_RANGE.contains(&zoom) {
+            issues.push(ValidationIssue::error(
+                "zoom",
+                format!("Zoom must be between {} and {}", ZOOM_RANGE.start(), ZOOM_RANGE.end()),
+            ));
+        } else if !task.browser.is_chromium() {
+            issues.push(ValidationIssue::warning("zoom", "Only Chromium-based browsers can be zoomed"));
+        }
+    }
+
+    if task.kind == TaskKind::Block {
+        if task.open_length().is_none() {
+            issues.push(ValidationIssue::error(
+                "close_time",
+                "A block task needs a close time or duration to end its blocking window",
+            ));
+        }
+        if task.blocked_domains.is_empty() {
+            issues.push(ValidationIssue::error(
+                "blocked_domains",
+                "A block task needs at least one domain to block",
+            ));
+        }
+        for domain in &task.blocked_domains {
+            push_if_invalid(&mut issues, "blocked_domains", validate_domain(domain));
+        }
+    }
+
+    issues
+}
+
+/// Returns the first blocking issue of a task as an error.
+pub fn ensure_valid(task: &Task, extra_schemes: &[&str]) -> Result<()> {
+    match check_task(task, extra_schemes)
+        .into_iter()
+        .find(|issue| issue.severity == IssueSeverity::Error)
+    {
+        Some(issue) => Err(AppError::InvalidTask(issue.message)),
+        None => Ok(()),
+    }
+}
+
+fn push_if_invalid(issues: &mut Vec<ValidationIssue>, field: &str, result: Result<()>) {
+    match result {
+        Ok(()) => {}
+        Err(AppError::InvalidTask(message)) => issues.push(ValidationIssue::error(field, message)),
+        Err(e) => issues.push(ValidationIssue::error(field, e.to_string())),
+    }
+}
+
+fn check_times(task: &Task, issues: &mut Vec<ValidationIssue>) {
+    if let Some(close_time) = task.close_time {
+        if close_time <= task.start_time {
+            issues.push(ValidationIssue::error("close_time", "Close time must be after the start time"));
+        }
+    }
+    match task.duration_minutes {
+        Some(0) => issues.push(ValidationIssue::error("duration_minutes", "Duration must be at least 1 minute")),
+        Some(_) if task.close_time.is_some() => issues.push(ValidationIssue::error(
+            "duration_minutes",
+            "A task closes either at a close time or after a duration, not both",
+        )),
+        _ => {}
+    }
+
+    match &task.repeat_config {
+        Some(repeat_config) => {
+            if repeat_config.end_after.is_some_and(|count| count <= 0) {
+                issues.push(ValidationIssue::warning(
+                    "repeat_config",
+                    "Repeat count is not positive; the task will only run once",
+                ));
+            }
+            if repeat_config.end_date.is_some_and(|end_date| end_date <= task.start_time) {
+                issues.push(ValidationIssue::warning(
+                    "repeat_config",
+                    "Repeat end date is before the start time; the task will only run once",
+                ));
+            }
+            if repeat_config.interval.min_gap() <= chrono::Duration::zero() {
+                issues.push(ValidationIssue::error("repeat_config", "Repeat interval must be at least 1"));
+            } else if matches!(
+                repeat_config.interval,
+                RepeatInterval::MonthlyByWeekday { week, .. } if !(1..=LAST_WEEK_OF_MONTH).contains(&week)
+            ) {
+                issues.push(ValidationIssue::error(
+                    "repeat_config",
+                    "The week of the month must be between 1 and 4, or 5 for the last",
+                ));
+            } else if task.jitter_window() * 2 >= repeat_config.interval.min_gap() {
+                issues.push(ValidationIssue::error(
+                    "jitter_minutes",
+                    "The jitter window must be less than half the time between occurrences",
+                ));
+            }
+        }
+        None => {
+            if task.id.is_none() && task.start_time < Utc::now() {
+                issues.push(ValidationIssue::warning(
+                    "start_time",
+                    "Start time is in the past; the task will run as soon as it is saved",
+                ));
+            }
+        }
+    }
+}
+
+fn check_exclusions(task: &Task, issues: &mut Vec<ValidationIssue>) {
+    if task.exclusions.is_empty() {
+        return;
+    }
+
+    if task.exclusions.len() > MAX_EXCLUSIONS {
+        issues.push(ValidationIssue::error(
+            "exclusions",
+            format!("A task can have at most {} excluded dates", MAX_EXCLUSIONS),
+        ));
+    }
+    if task.exclusions.iter().any(|e| e.end.is_some_and(|end| end < e.start)) {
+        issues.push(ValidationIssue::error("exclusions", "An excluded range must end on or after its first day"));
+    }
+    if task.repeat_config.is_none() {
+        issues.push(ValidationIssue::warning(
+            "exclusions",
+            "Only repeating tasks skip excluded dates; this task runs once",
+        ));
+    }
+}
+
+fn check_pomodoro(task: &Task, issues: &mut Vec<ValidationIssue>) {
+    let Some(ref pomodoro) = task.pomodoro else { return };
+
+    if pomodoro.work_minutes <= 0 || pomodoro.break_minutes < 0 || pomodoro.cycles <= 0 {
+        issues.push(ValidationIssue::error(
+            "pomodoro",
+            "Pomodoro work minutes and cycles must be positive",
+        ));
+    }
+    if task.kind != TaskKind::Browser {
+        issues.push(ValidationIssue::error(
+            "pomodoro",
+            "Pomodoro mode is only available for browser tasks",
+        ));
+    }
+    if task.open_length().is_some() {
+        issues.push(ValidationIssue::error(
+            "close_time",
+            "Pomodoro tasks close on their own cadence and cannot have a close time",
+        ));
+    }
+    if task.url.is_none() && !task.allow_close_all {
+        issues.push(ValidationIssue::error(
+            "url",
+            "Pomodoro tasks need a URL to close between cycles",
+        ));
+    }
+}
+
+fn check_steps(task: &Task, extra_schemes: &[&str], issues: &mut Vec<ValidationIssue>) {
+    if task.steps.is_empty() {
+        return;
+    }
+
+    if task.kind != TaskKind::Browser || task.pomodoro.is_some() {
+        issues.push(ValidationIssue::error(
+            "steps",
+            "Steps are only available for browser tasks without pomodoro mode",
+        ));
+    }
+    if task.open_length().is_some() {
+        issues.push(ValidationIssue::error(
+            "close_time",
+            "Multi-step tasks close pages with close steps and cannot have a close time",
+        ));
+    }
+
+    for step in &task.steps {
+        match step.action {
+            StepAction::Open | StepAction::Close => match step.url.as_deref() {
+                Some(url) => push_if_invalid(issues, "steps", validate_url_allowing(url, extra_schemes)),
+                None => issues.push(ValidationIssue::error(
+                    "steps",
+                    format!("A {} step needs a URL", step.action),
+                )),
+            },
+            StepAction::Wait => {
+                if !step.wait_minutes.is_some_and(|m| m > 0) {
+                    issues.push(ValidationIssue::error(
+                        "steps",
+                        "A wait step needs a positive number of minutes",
+                    ));
+                }
+            }
+        }
+    }
+
+    if task.steps.last().is_some_and(|step| step.action == StepAction::Wait) {
+        issues.push(ValidationIssue::warning(
+            "steps",
+            "The last step is a wait; it only delays the end of the sequence",
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Weekday;
+    use crate::db::{DateExclusion, RepeatConfig, WindowPosition, WindowSize};
+    use chrono::Duration;
+
+    fn task() -> Task {
+        let mut task = Task::new(
+            "Morning".to_string(),
+            BrowserType::Chrome,
+            Utc::now() + Duration::hours(1),
+            "Europe/Berlin".to_string(),
+        );
+        task.url = Some("https://example.com".to_string());
+        task
+    }
+
+    #[test]
+    fn test_check_task_valid() {
+        assert!(check_task(&task(), &[]).is_empty());
+        assert!(ensure_valid(&task(), &[]).is_ok());
+    }
+
+    #[test]
+    fn test_check_task_collects_all_errors() {
+        let mut task = task();
+        task.url = Some("javascript:alert(1)".to_string());
+        task.timezone = "Mars/Olympus".to_string();
+        task.close_time = Some(task.start_time - Duration::minutes(5));
+
+        let fields: Vec<_> = check_task(&task, &[]).into_iter().map(|i| i.field).collect();
+        assert_eq!(fields, vec!["url", "timezone", "close_time"]);
+        assert!(ensure_valid(&task, &[]).is_err());
+    }
+
+    #[test]
+    fn test_check_task_warnings_do_not_block() {
+        let mut task = task();
+        task.start_time = Utc::now() - Duration::hours(1);
+
+        let issues = check_task(&task, &[]);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].severity, IssueSeverity::Warning);
+        assert!(ensure_valid(&task, &[]).is_ok());
+    }
+
+    #[test]
+    fn test_check_task_session_needs_name() {
+        let mut task = task();
+        task.kind = TaskKind::RestoreSession;
+        assert_eq!(check_task(&task, &[])[0].field, "session_name");
+
+        task.session_name = Some("Morning tabs".to_string());
+        assert!(check_task(&task, &[]).is_empty());
+    }
+
+    #[test]
+    fn test_check_task_jitter_window() {
+        let mut task = task();
+        task.repeat_config = Some(RepeatConfig {
+            interval: RepeatInterval::Daily,
+            end_after: None,
+            end_date: None,
+        });
+
+        task.jitter_minutes = Some(15);
+        assert!(check_task(&task, &[]).is_empty());
+
+        task.jitter_minutes = Some(720);
+        let fields: Vec<_> = check_task(&task, &[]).into_iter().map(|i| i.field).collect();
+        assert_eq!(fields, vec!["jitter_minutes"]);
+    }
+
+    #[test]
+    fn test_check_task_retry_attempts() {
+        let mut task = task();
+        task.retry_max_attempts = Some(3);
+        assert!(check_task(&task, &[]).is_empty());
+
+        task.retry_max_attempts = Some(0);
+        assert_eq!(check_task(&task, &[])[0].field, "retry_max_attempts");
+    }
+
+    #[test]
+    fn test_check_task_retry_backoff() {
+        let mut task = task();
+        task.retry_backoff_secs = Some(MAX_RETRY_BACKOFF_SECS);
+        assert!(check_task(&task, &[]).is_empty());
+
+        for backoff in [0, MAX_RETRY_BACKOFF_SECS + 1, u32::MAX] {
+            task.retry_backoff_secs = Some(backoff);
+            assert_eq!(check_task(&task, &[])[0].field, "retry_backoff_secs");
+        }
+    }
+
+    #[test]
+    fn test_check_task_exclusions() {
+        let mut task = task();
+        task.repeat_config = Some(RepeatConfig { interval: RepeatInterval::Daily, end_after: None, end_date: None });
+        let day = |d: u32| chrono::NaiveDate::from_ymd_opt(2024, 12, d).unwrap();
+        task.exclusions = vec![
+            DateExclusion { start: day(25), end: None },
+            DateExclusion { start: day(27), end: Some(day(31)) },
+        ];
+        assert!(check_task(&task, &[]).is_empty());
+
+        task.exclusions.push(DateExclusion { start: day(20), end: Some(day(18)) });
+        assert_eq!(check_task(&task, &[])[0].field, "exclusions");
+
+        task.exclusions.pop();
+        task.repeat_config = None;
+        let issues = check_task(&task, &[]);
+        assert_eq!(issues[0].field, "exclusions");
+        assert_eq!(issues[0].severity, IssueSeverity::Warning);
+    }
+
+    #[test]
+    fn test_check_task_window_placement() {
+        let mut task = task();
+        task.window_position = Some(WindowPosition { x: 0, y: 0 });
+        task.window_size = Some(WindowSize { width: 1920, height: 1080 });
+        assert!(check_task(&task, &[]).is_empty());
+
+        task.browser = BrowserType::Firefox;
+        let fields: Vec<_> = check_task(&task, &[]).into_iter().map(|i| i.field).collect();
+        assert_eq!(fields, vec!["window_position"]);
+
+        task.browser = BrowserType::Chrome;
+        task.window_size = Some(WindowSize { width: 0, height: 1080 });
+        let issues = check_task(&task, &[]);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].severity, IssueSeverity::Error);
+
+        task.window_size = Some(WindowSize { width: 1920, height: 1080 });
+        task.window_state = WindowState::Maximized;
+        let issues = check_task(&task, &[]);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].severity, IssueSeverity::Warning);
+    }
+
+    #[test]
+    fn test_check_task_kiosk() {
+        let mut task = task();
+        task.kiosk = true;
+        assert!(check_task(&task, &[]).is_empty());
+        task.browser = BrowserType::Safari;
+        let fields: Vec<_> = check_task(&task, &[]).into_iter().map(|i| i.field).collect();
+        assert_eq!(fields, vec!["kiosk"]);
+    }
+
+    #[test]
+    fn test_check_task_app_mode() {
+        let mut task = task();
+        task.app_mode = true;
+        assert!(check_task(&task, &[]).is_empty());
+        task.browser = BrowserType::Firefox;
+        let fields: Vec<_> = check_task(&task, &[]).into_iter().map(|i| i.field).collect();
+        assert_eq!(fields, vec!["app_mode"]);
+    }
+
+    #[test]
+    fn test_check_task_duration() {
+        let mut task = task();
+        task.duration_minutes = Some(45);
+        assert!(check_task(&task, &[]).is_empty());
+
+        task.kind = TaskKind::Block;
+        task.blocked_domains = vec!["example.com".to_string()];
+        assert!(check_task(&task, &[]).is_empty());
+
+        task.duration_minutes = Some(0);
+        let fields: Vec<_> = check_task(&task, &[]).into_iter().map(|i| i.field).collect();
+        assert_eq!(fields, vec!["duration_minutes"]);
+
+        task.duration_minutes = Some(45);
+        task.close_time = Some(task.start_time + Duration::minutes(30));
+        let fields: Vec<_> = check_task(&task, &[]).into_iter().map(|i| i.field).collect();
+        assert_eq!(fields, vec!["duration_minutes"]);
+    }
+
+    #[test]
+    fn test_check_task_custom_interval() {
+        let mut task = task();
+        task.repeat_config = Some(RepeatConfig {
+            interval: RepeatInterval::EveryNMinutes(120),
+            end_after: None,
+            end_date: None,
+        });
+        assert!(check_task(&task, &[]).is_empty());
+
+        task.repeat_config.as_mut().unwrap().interval = RepeatInterval::EveryNDays(0);
+        let fields: Vec<_> = check_task(&task, &[]).into_iter().map(|i| i.field).collect();
+        assert_eq!(fields, vec!["repeat_config"]);
+
+        task.repeat_config.as_mut().unwrap().interval = RepeatInterval::MonthlyByWeekday { week: 5, weekday: Weekday::Fri };
+        assert!(check_task(&task, &[]).is_empty());
+
+        task.repeat_config.as_mut().unwrap().interval = RepeatInterval::MonthlyByWeekday { week: 6, weekday: Weekday::Fri };
+        let fields: Vec<_> = check_task(&task, &[]).into_iter().map(|i| i.field).collect();
+        assert_eq!(fields, vec!["repeat_config"]);
+    }
+
+    #[test]
+    fn test_check_task_extra_schemes() {
+        let mut task = task();
+        task.url = Some("zoommtg://zoom.us/join?confno=123".to_string());
+        assert_eq!(check_task(&task, &[])[0].field, "url");
+        assert!(check_task(&task, &["zoommtg"]).is_empty());
+    }
+}
@@ -1,7 +1,11 @@
+use chrono::NaiveTime;
 use crate::error::{AppError, Result};
 #[cfg(target_os = "macos")]
 use std::borrow::Cow;
 
+/// Schemes that are never allowed, not even through the settings' allowlist
+const DANGEROUS_SCHEMES: &[&str] = &["javascript", "data", "vbscript", "file", "about"];
+
 /// Validates and sanitizes a URL string
 ///
 /// # Security
@@ -9,6 +13,12 @@ use std::borrow::Cow;
 /// - Prevents javascript:, data:, and other potentially dangerous schemes
 /// - Validates basic URL structure
 pub fn validate_url(url: &str) -> Result<()> {
+    validate_url_allowing(url, &[])
+}
+
+/// Validates a URL like `validate_url`, but also accepts links with one of `extra_schemes`,
+/// e.g. `zoommtg://` or `slack://`, as allowed in the settings
+pub fn validate_url_allowing(url: &str, extra_schemes: &[&str]) -> Result<()> {
     let url_trimmed = url.trim();
 
     if url_trimmed.is_empty() {
@@ -16,27 +26,31 @@ pub fn validate_url(url: &str) -> Result<()> {
     }
 
     // Check for dangerous URL schemes
-    let dangerous_schemes = [
-        "javascript:",
-        "data:",
-        "vbscript:",
-        "file:",
-        "about:",
-    ];
-
     let url_lower = url_trimmed.to_lowercase();
-    for scheme in &dangerous_schemes {
-        if url_lower.starts_with(scheme) {
+    for scheme in DANGEROUS_SCHEMES {
+        if url_lower.starts_with(&format!("{}:", scheme)) {
             return Err(AppError::InvalidTask(
-                format!("Dangerous URL scheme not allowed: {}", scheme)
+                format!("Dangerous URL scheme not allowed: {}:", scheme)
             ));
         }
     }
 
+    if let Some(scheme) = url_scheme(url_trimmed) {
+        if extra_schemes.iter().any(|extra| extra.eq_ignore_ascii_case(&scheme)) {
+            let rest = &url_trimmed[scheme.len() + 1..];
+            if rest.trim_start_matches('/').is_empty() {
+                return Err(AppError::InvalidTask(
+                    format!("{}: link has nothing after the scheme", scheme)
+                ));
+            }
+            return Ok(());
+        }
+    }
+
     // Ensure URL starts with http:// or https://
     if !url_lower.starts_with("http://") && !url_lower.starts_with("https://") {
         return Err(AppError::InvalidTask(
-            "URL must start with http:// or https://".to_string()
+            "URL must start with http:// or https://, or a scheme allowed in the settings".to_string()
         ));
     }
 
@@ -50,6 +64,39 @@ pub fn validate_url(url: &str) -> Result<()> {
     Ok(())
 }
 
+/// The lowercase scheme of a URL, e.g. "https" or "zoommtg"
+pub fn url_scheme(url: &str) -> Option<String> {
+    let (scheme, _) = url.trim().split_once(':')?;
+    let mut chars = scheme.chars();
+    let valid = chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+
+    valid.then(|| scheme.to_lowercase())
+}
+
+/// Validates a scheme added to the settings' allowlist of extra URL schemes
+///
+/// # Security
+/// - Only plain scheme names, as defined by RFC 3986
+/// - Schemes that run code or read local files can never be allowed
+pub fn validate_url_scheme(scheme: &str) -> Result<()> {
+    let scheme = scheme.trim();
+
+    if scheme.is_empty() || scheme.len() > 32 || url_scheme(&format!("{}:", scheme)).is_none() {
+        return Err(AppError::InvalidTask(format!("Invalid URL scheme: '{}'", scheme)));
+    }
+
+    let lower = scheme.to_lowercase();
+    if DANGEROUS_SCHEMES.contains(&lower.as_str()) {
+        return Err(AppError::InvalidTask(format!("URL scheme cannot be allowed: {}", lower)));
+    }
+    if lower == "http" || lower == "https" {
+        return Err(AppError::InvalidTask(format!("{} links are always allowed", lower)));
+    }
+
+    Ok(())
+}
+
 /// Validates a browser profile name
 ///
 /// # Security
@@ -89,6 +136,119 @@ pub fn validate_browser_profile(profile: &str) -> Result<()> {
     Ok(())
 }
 
+/// Validates a domain listed in a focus-mode block task
+pub fn validate_domain(domain: &str) -> Result<()> {
+    let domain = domain.trim();
+
+    if domain.is_empty() || domain.len() > 253 || !domain.contains('.') {
+        return Err(AppError::InvalidTask(format!("Invalid domain: '{}'", domain)));
+    }
+
+    for c in domain.chars() {
+        if !c.is_ascii_alphanumeric() && c != '-' && c != '.' {
+            return Err(AppError::InvalidTask(
+                format!("Domain contains invalid character: '{}'", c)
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Validates a user-agent override, which is passed to the browser as a command-line switch
+pub fn validate_user_agent(user_agent: &str) -> Result<()> {
+    let user_agent = user_agent.trim();
+
+    if user_agent.is_empty() || user_agent.len() > 512 {
+        return Err(AppError::InvalidTask(
+            "User agent must be between 1 and 512 characters".to_string()
+        ));
+    }
+
+    // Printable ASCII only: no control characters or quotes that could break out of the switch
+    for c in user_agent.chars() {
+        if !(' '..='~').contains(&c) || c == '"' || c == '\'' || c == '`' || c == '\\' {
+            return Err(AppError::InvalidTask(
+                format!("User agent contains invalid character: {:?}", c)
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Validates a browser locale such as "de" or "pt-BR"
+pub fn validate_locale(locale: &str) -> Result<()> {
+    let mut parts = locale.split('-');
+    let language = parts.next().unwrap_or_default();
+
+    let valid = (2..=3).contains(&language.len())
+        && language.chars().all(|c| c.is_ascii_alphabetic())
+        && parts.all(|part| (1..=8).contains(&part.len()) && part.chars().all(|c| c.is_ascii_alphanumeric()));
+
+    if !valid {
+        return Err(AppError::InvalidTask(format!("Invalid locale: '{}'", locale)));
+    }
+
+    Ok(())
+}
+
+/// Longest tag, in characters
+const MAX_TAG_CHARS: usize = 32;
+
+/// A tag as it is stored: trimmed and lowercased, so "Work" and "work " are the same tag
+pub fn normalize_tag(tag: &str) -> String {
+    tag.trim().to_lowercase()
+}
+
+/// Validates a task tag: letters, digits, '-' and '_', up to 32 characters
+pub fn validate_tag(tag: &str) -> Result<()> {
+    let tag = tag.trim();
+
+    if tag.is_empty() || tag.chars().count() > MAX_TAG_CHARS {
+        return Err(AppError::InvalidTask(format!(
+            "Tag must be between 1 and {} characters",
+            MAX_TAG_CHARS
+        )));
+    }
+
+    for c in tag.chars() {
+        if !c.is_alphanumeric() && c != '-' && c != '_' {
+            return Err(AppError::InvalidTask(format!("Tag contains invalid character: '{}'", c)));
+        }
+    }
+
+    Ok(())
+}
+
+/// Parses a time of day written as HH:MM, e.g. the start of quiet hours
+pub fn parse_time_of_day(value: &str) -> Result<NaiveTime> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M")
+        .map_err(|_| AppError::TimeParse(format!("Invalid time of day, expected HH:MM: '{}'", value)))
+}
+
+/// Extracts the lowercase host from an http(s) URL
+pub fn url_host(url: &str) -> Option<String> {
+    let rest = url.trim().split_once("://")?.1;
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let host_port = authority.rsplit('@').next()?;
+    let host = host_port.split(':').next()?;
+
+    if host.is_empty() {
+        None
+    } else {
+        Some(host.to_lowercase())
+    }
+}
+
+/// Whether `host` is `domain` itself or one of its subdomains
+pub fn host_matches_domain(host: &str, domain: &str) -> bool {
+    let host = host.to_lowercase();
+    let domain = domain.trim().trim_start_matches('.').to_lowercase();
+
+    host == domain || host.ends_with(&format!(".{}", domain))
+}
+
 /// Escapes a string for safe use in AppleScript
 ///
 /// # Security
@@ -136,6 +296,35 @@ mod tests {
         assert!(validate_url("www.example.com").is_err());
     }
 
+    #[test]
+    fn test_validate_url_allowing_extra_schemes() {
+        assert!(validate_url_allowing("zoommtg://zoom.us/join?confno=123", &["zoommtg"]).is_ok());
+        assert!(validate_url_allowing("Slack://channel?team=T1", &["slack"]).is_ok());
+        assert!(validate_url_allowing("https://example.com", &["slack"]).is_ok());
+        assert!(validate_url_allowing("zoommtg://zoom.us/join", &["slack"]).is_err());
+        assert!(validate_url_allowing("slack://", &["slack"]).is_err());
+        assert!(validate_url_allowing("javascript:alert(1)", &["javascript"]).is_err());
+    }
+
+    #[test]
+    fn test_url_scheme() {
+        assert_eq!(url_scheme("HTTPS://example.com"), Some("https".to_string()));
+        assert_eq!(url_scheme("ms-teams:/l/meetup-join"), Some("ms-teams".to_string()));
+        assert_eq!(url_scheme("example.com"), None);
+        assert_eq!(url_scheme("1x://host"), None);
+    }
+
+    #[test]
+    fn test_validate_url_scheme() {
+        assert!(validate_url_scheme("zoommtg").is_ok());
+        assert!(validate_url_scheme("ms-teams").is_ok());
+        assert!(validate_url_scheme("file").is_err());
+        assert!(validate_url_scheme("JavaScript").is_err());
+        assert!(validate_url_scheme("https").is_err());
+        assert!(validate_url_scheme("zoom://").is_err());
+        assert!(validate_url_scheme("").is_err());
+    }
+
     #[test]
     fn test_validate_browser_profile_valid() {
         assert!(validate_browser_profile("Default").is_ok());
@@ -159,6 +348,70 @@ mod tests {
         assert!(validate_browser_profile("profile`cmd`").is_err());
     }
 
+    #[test]
+    fn test_validate_tag() {
+        assert!(validate_tag("work").is_ok());
+        assert!(validate_tag(" stand-up_2 ").is_ok());
+        assert!(validate_tag("").is_err());
+        assert!(validate_tag("two words").is_err());
+        assert!(validate_tag(&"a".repeat(33)).is_err());
+        assert_eq!(normalize_tag(" Work "), "work");
+    }
+
+    #[test]
+    fn test_parse_time_of_day() {
+        assert_eq!(parse_time_of_day("07:30").unwrap(), NaiveTime::from_hms_opt(7, 30, 0).unwrap());
+        assert_eq!(parse_time_of_day(" 22:00 ").unwrap(), NaiveTime::from_hms_opt(22, 0, 0).unwrap());
+        assert!(parse_time_of_day("24:00").is_err());
+        assert!(parse_time_of_day("7pm").is_err());
+        assert!(parse_time_of_day("").is_err());
+    }
+
+    #[test]
+    fn test_validate_domain() {
+        assert!(validate_domain("youtube.com").is_ok());
+        assert!(validate_domain("news.ycombinator.com").is_ok());
+        assert!(validate_domain("localhost").is_err());
+        assert!(validate_domain("evil.com/path").is_err());
+        assert!(validate_domain("").is_err());
+    }
+
+    #[test]
+    fn test_validate_user_agent() {
+        assert!(validate_user_agent(
+            "Mozilla/5.0 (iPhone; CPU iPhone OS 17_0 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Mobile/15E148"
+        ).is_ok());
+        assert!(validate_user_agent("").is_err());
+        assert!(validate_user_agent("agent\n--disable-web-security").is_err());
+        assert!(validate_user_agent("agent\" --flag").is_err());
+    }
+
+    #[test]
+    fn test_validate_locale() {
+        assert!(validate_locale("de").is_ok());
+        assert!(validate_locale("pt-BR").is_ok());
+        assert!(validate_locale("zh-Hant-TW").is_ok());
+        assert!(validate_locale("").is_err());
+        assert!(validate_locale("de --flag").is_err());
+        assert!(validate_locale("de-").is_err());
+    }
+
+    #[test]
+    fn test_url_host() {
+        assert_eq!(url_host("https://www.YouTube.com/watch?v=1"), Some("www.youtube.com".to_string()));
+        assert_eq!(url_host("http://user:pw@example.org:8080/x"), Some("example.org".to_string()));
+        assert_eq!(url_host("https://example.com#frag"), Some("example.com".to_string()));
+        assert_eq!(url_host("example.com"), None);
+    }
+
+    #[test]
+    fn test_host_matches_domain() {
+        assert!(host_matches_domain("youtube.com", "youtube.com"));
+        assert!(host_matches_domain("www.youtube.com", "youtube.com"));
+        assert!(!host_matches_domain("notyoutube.com", "youtube.com"));
+        assert!(!host_matches_domain("youtube.com.evil.org", "youtube.com"));
+    }
+
     #[test]
     #[cfg(target_os = "macos")]
     fn test_escape_applescript() {
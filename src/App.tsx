@@ -6,14 +6,54 @@ import { TaskForm } from './components/TaskForm';
 import { TaskList } from './components/TaskList';
 import { SchedulerStatus } from './components/SchedulerStatus';
 import { SettingsModal } from './components/SettingsModal';
-import { Task } from './types/task';
+import { TauriTaskService } from './services/tauri-api';
+import { Task, LinkDraft } from './types/task';
 
 function App() {
   const { tasks, loading, error, createTask, updateTask, deleteTask } = useTasks();
-  const { running, toggleScheduler } = useScheduler();
+  const { running, errorCount, lastError, toggleScheduler } = useScheduler();
   const [showForm, setShowForm] = useState(false);
   const [editingTask, setEditingTask] = useState<Task | null>(null);
   const [showSettings, setShowSettings] = useState(false);
+  const [draft, setDraft] = useState<LinkDraft | null>(null);
+
+  // Opens the new-task form pre-filled with the first link
+  const startQuickAdd = (links: LinkDraft[]) => {
+    if (links.length === 0) {
+      return false;
+    }
+    setEditingTask(null);
+    setDraft(links[0]);
+    setShowForm(true);
+    return true;
+  };
+
+  // Shows a shared task in the form, ready to save
+  const startImport = (task: Task) => {
+    setDraft(null);
+    setEditingTask(task);
+    setShowForm(true);
+  };
+
+  const handleImportLink = async () => {
+    const link = prompt('Paste a shared task link');
+    if (!link) return;
+    try {
+      startImport(await TauriTaskService.readTaskLink(link));
+    } catch (err) {
+      alert(err instanceof Error ? err.message : 'Failed to read the task link');
+    }
+  };
+
+  const handlePasteLink = async () => {
+    try {
+      if (!startQuickAdd(await TauriTaskService.pasteLinks())) {
+        alert('No link found on the clipboard');
+      }
+    } catch (err) {
+      alert(err instanceof Error ? err.message : 'Failed to read the clipboard');
+    }
+  };
 
   const handleCreateOrUpdate = async (task: Task) => {
     try {
@@ -24,12 +64,14 @@ function App() {
       }
       setShowForm(false);
       setEditingTask(null);
+      setDraft(null);
     } catch (err) {
       alert(err instanceof Error ? err.message : 'Failed to save task');
     }
   };
 
   const handleEdit = (task: Task) => {
+    setDraft(null);
     setEditingTask(task);
     setShowForm(true);
   };
@@ -47,6 +89,7 @@ function App() {
   const handleCancel = () => {
     setShowForm(false);
     setEditingTask(null);
+    setDraft(null);
   };
 
   // Listen for 'open-settings' event from system tray
@@ -60,6 +103,57 @@ function App() {
     };
   }, []);
 
+  // Listen for 'confirm-quit' when quitting from the tray with browsers still open
+  useEffect(() => {
+    const unlisten = listen<number>('confirm-quit', (event) => {
+      const closeOpened = confirm(
+        `Close the ${event.payload} browser window(s) the scheduler opened before quitting?`
+      );
+      TauriTaskService.quitApp(closeOpened);
+    });
+
+    return () => {
+      unlisten.then(fn => fn());
+    };
+  }, []);
+
+  // Listen for 'quick-add' when link shortcuts are dropped on the window
+  useEffect(() => {
+    const unlisten = listen<LinkDraft[]>('quick-add', (event) => {
+      startQuickAdd(event.payload);
+    });
+
+    return () => {
+      unlisten.then(fn => fn());
+    };
+  }, []);
+
+  // Listen for 'import-task' when a shared task link is opened
+  useEffect(() => {
+    const unlisten = listen<Task>('import-task', (event) => {
+      startImport(event.payload);
+    });
+
+    return () => {
+      unlisten.then(fn => fn());
+    };
+  }, []);
+
+  // Listen for 'navigate-to-task' when a task notification is clicked
+  useEffect(() => {
+    const unlisten = listen<number>('navigate-to-task', (event) => {
+      const task = tasks.find((t) => t.id === event.payload);
+      if (task) {
+        setEditingTask(task);
+        setShowForm(true);
+      }
+    });
+
+    return () => {
+      unlisten.then(fn => fn());
+    };
+  }, [tasks]);
+
   return (
     <div className="min-h-screen bg-gray-100 dark:bg-gray-900">
       <div className="container mx-auto px-4 py-8">
@@ -84,25 +178,47 @@ function App() {
                   <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={2} d="M15 12a3 3 0 11-6 0 3 3 0 016 0z" />
                 </svg>
               </button>
-              <SchedulerStatus running={running} onToggle={toggleScheduler} />
+              <SchedulerStatus
+                running={running}
+                errorCount={errorCount}
+                lastError={lastError}
+                onToggle={toggleScheduler}
+              />
             </div>
           </div>
         </header>
 
         <main>
           {!showForm && (
-            <button
-              onClick={() => setShowForm(true)}
-              className="mb-6 px-4 py-2 bg-blue-600 text-white rounded-lg hover:bg-blue-700 transition-colors"
-            >
-              + New Task
-            </button>
+            <div className="mb-6 flex gap-3">
+              <button
+                onClick={() => setShowForm(true)}
+                className="px-4 py-2 bg-blue-600 text-white rounded-lg hover:bg-blue-700 transition-colors"
+              >
+                + New Task
+              </button>
+              <button
+                onClick={handlePasteLink}
+                className="px-4 py-2 bg-gray-200 dark:bg-gray-700 text-gray-800 dark:text-gray-200 rounded-lg hover:bg-gray-300 dark:hover:bg-gray-600 transition-colors"
+                title="Create a task from a link on the clipboard, or drop a link shortcut on the window"
+              >
+                Paste Link
+              </button>
+              <button
+                onClick={handleImportLink}
+                className="px-4 py-2 bg-gray-200 dark:bg-gray-700 text-gray-800 dark:text-gray-200 rounded-lg hover:bg-gray-300 dark:hover:bg-gray-600 transition-colors"
+                title="Import a task someone shared with you"
+              >
+                Import Link
+              </button>
+            </div>
           )}
 
           {showForm && (
             <div className="mb-6 bg-white dark:bg-gray-800 rounded-lg shadow-lg p-6">
               <TaskForm
                 initialTask={editingTask}
+                draft={draft}
                 onSubmit={handleCreateOrUpdate}
                 onCancel={handleCancel}
               />
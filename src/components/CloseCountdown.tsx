@@ -0,0 +1,93 @@
+import { useEffect, useState } from 'react';
+import { listen } from '@tauri-apps/api/event';
+import { TauriTaskService } from '../services/tauri-api';
+import { Task, TaskUpdatedEvent } from '../types/task';
+
+/** Minutes the Extend button keeps the task open for */
+const EXTEND_MINUTES = 10;
+
+interface CloseCountdownProps {
+  taskId: number;
+}
+
+function formatRemaining(ms: number): string {
+  const totalSecs = Math.max(0, Math.ceil(ms / 1000));
+  const mins = Math.floor(totalSecs / 60);
+  const secs = totalSecs % 60;
+  return `${mins}:${secs.toString().padStart(2, '0')}`;
+}
+
+/** Always-on-top window counting down to a task's scheduled close. */
+export function CloseCountdown({ taskId }: CloseCountdownProps) {
+  const [task, setTask] = useState<Task | null>(null);
+  const [now, setNow] = useState(Date.now());
+  const [busy, setBusy] = useState(false);
+
+  useEffect(() => {
+    TauriTaskService.getTask(taskId).then(setTask).catch(() => TauriTaskService.dismissCloseCountdown(taskId));
+  }, [taskId]);
+
+  useEffect(() => {
+    const timer = setInterval(() => setNow(Date.now()), 1000);
+    return () => clearInterval(timer);
+  }, []);
+
+  // The close may move or run from elsewhere, e.g. the main window or a snooze
+  useEffect(() => {
+    const unlisten = listen<TaskUpdatedEvent>('task-updated', (event) => {
+      if (event.payload.task.id === taskId) {
+        setTask(event.payload.task);
+      }
+    });
+
+    return () => {
+      unlisten.then(fn => fn());
+    };
+  }, [taskId]);
+
+  const closeAt = task?.next_close_execution ? new Date(task.next_close_execution).getTime() : null;
+
+  useEffect(() => {
+    if (task && (closeAt === null || closeAt <= now)) {
+      TauriTaskService.dismissCloseCountdown(taskId);
+    }
+  }, [task, closeAt, now, taskId]);
+
+  const run = async (action: () => Promise<unknown>) => {
+    setBusy(true);
+    try {
+      await action();
+    } catch (err) {
+      console.error('Countdown action failed:', err);
+      setBusy(false);
+    }
+  };
+
+  if (!task || closeAt === null) {
+    return null;
+  }
+
+  return (
+    <div className="h-screen flex flex-col justify-between p-3 bg-white dark:bg-gray-800 select-none">
+      <p className="text-sm font-medium text-gray-900 dark:text-white truncate">
+        Closing {task.name} in <span className="tabular-nums">{formatRemaining(closeAt - now)}</span>
+      </p>
+      <div className="flex gap-2 justify-end">
+        <button
+          disabled={busy}
+          onClick={() => run(() => TauriTaskService.extendTaskClose(taskId, EXTEND_MINUTES))}
+          className="px-3 py-1 text-sm rounded-lg bg-gray-200 hover:bg-gray-300 dark:bg-gray-700 dark:hover:bg-gray-600 text-gray-900 dark:text-white disabled:opacity-50"
+        >
+          Extend {EXTEND_MINUTES} min
+        </button>
+        <button
+          disabled={busy}
+          onClick={() => run(() => TauriTaskService.closeTaskNow(taskId))}
+          className="px-3 py-1 text-sm rounded-lg bg-red-600 hover:bg-red-700 text-white disabled:opacity-50"
+        >
+          Close now
+        </button>
+      </div>
+    </div>
+  );
+}
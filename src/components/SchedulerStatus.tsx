@@ -1,19 +1,28 @@
+import { SchedulerErrorEvent } from '../types/task';
+
 interface SchedulerStatusProps {
   running: boolean;
+  errorCount?: number;
+  lastError?: SchedulerErrorEvent | null;
   onToggle: () => Promise<void>;
 }
 
-export function SchedulerStatus({ running, onToggle }: SchedulerStatusProps) {
+export function SchedulerStatus({ running, errorCount = 0, lastError, onToggle }: SchedulerStatusProps) {
+  const degraded = running && errorCount > 0;
+
   return (
     <div className="flex items-center gap-3">
       <div className="flex items-center gap-2">
         <div
           className={`w-3 h-3 rounded-full ${
-            running ? 'bg-green-500 animate-pulse' : 'bg-gray-400'
+            degraded ? 'bg-yellow-500' : running ? 'bg-green-500 animate-pulse' : 'bg-gray-400'
           }`}
         />
-        <span className="text-sm font-medium text-gray-700 dark:text-gray-300">
-          Scheduler {running ? 'Running' : 'Stopped'}
+        <span
+          className="text-sm font-medium text-gray-700 dark:text-gray-300"
+          title={lastError?.message}
+        >
+          Scheduler {degraded ? `degraded: ${lastError?.code.replace(/_/g, ' ') ?? 'errors'}` : running ? 'Running' : 'Stopped'}
         </span>
       </div>
       <button
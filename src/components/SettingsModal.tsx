@@ -1,4 +1,5 @@
 import { useSettings } from '../hooks/useSettings';
+import { BooleanSettingKey } from '../types/task';
 
 interface SettingsModalProps {
   isOpen: boolean;
@@ -43,7 +44,7 @@ function ToggleSwitch({ enabled, onChange, label, description }: ToggleSwitchPro
 export function SettingsModal({ isOpen, onClose }: SettingsModalProps) {
   const { settings, loading, error, toggleSetting } = useSettings();
 
-  const handleToggle = async (key: keyof typeof settings) => {
+  const handleToggle = async (key: BooleanSettingKey) => {
     try {
       await toggleSetting(key);
     } catch (err) {
@@ -116,6 +117,48 @@ export function SettingsModal({ isOpen, onClose }: SettingsModalProps) {
               label="Auto-Start"
               description="Launch the application automatically when the system starts"
             />
+            <ToggleSwitch
+              enabled={settings.dry_run}
+              onChange={() => handleToggle('dry_run')}
+              label="Dry Run"
+              description="Simulate scheduled runs without opening or closing browsers"
+            />
+            <ToggleSwitch
+              enabled={settings.auto_check_updates}
+              onChange={() => handleToggle('auto_check_updates')}
+              label="Check for Updates"
+              description="Look for a new version once a week and notify in the notification center"
+            />
+            <ToggleSwitch
+              enabled={settings.close_opened_on_quit}
+              onChange={() => handleToggle('close_opened_on_quit')}
+              label="Close opened browsers on quit"
+              description="When quitting from the tray, close the browser windows the scheduler opened"
+            />
+            <ToggleSwitch
+              enabled={settings.defer_while_locked}
+              onChange={() => handleToggle('defer_while_locked')}
+              label="Pause while the screen is locked"
+              description="Run nothing on a locked screen; on unlock, handle what came due per the missed policy"
+            />
+            <ToggleSwitch
+              enabled={settings.quiet_hours_enabled}
+              onChange={() => handleToggle('quiet_hours_enabled')}
+              label="Quiet hours"
+              description={`Run nothing from ${settings.quiet_hours_start} to ${settings.quiet_hours_end}; afterwards, what came due is handled per the missed policy`}
+            />
+            <ToggleSwitch
+              enabled={settings.windows_ui_automation}
+              onChange={() => handleToggle('windows_ui_automation')}
+              label="Close tabs via UI Automation (Windows)"
+              description="Best effort: finds the tab showing the task's URL in Chromium browsers and closes it. Off by default."
+            />
+            <ToggleSwitch
+              enabled={settings.url_actions_enabled}
+              onChange={() => handleToggle('url_actions_enabled')}
+              label="Allow actions from links"
+              description="Let do-in-time:// links (e.g. from Shortcuts) run tasks and pause or resume the scheduler. New tasks always open in the form first."
+            />
           </div>
         )}
 
@@ -1,11 +1,13 @@
 import { useState, useEffect } from 'react';
 import { invoke } from '@tauri-apps/api/core';
-import { Task, BrowserType, TaskStatus, RepeatInterval } from '../types/task';
+import { Task, BrowserType, BrowserProfile, TaskStatus, RepeatInterval, CustomRepeatInterval, LinkDraft, DateExclusion } from '../types/task';
 import { utcToLocalDatetimeString, localDatetimeStringToUtc } from '../utils/datetime';
 import * as chrono from 'chrono-node';
 
 interface TaskFormProps {
   initialTask: Task | null;
+  /** Pre-fills a new task from a dropped or pasted link */
+  draft?: LinkDraft | null;
   onSubmit: (task: Task) => Promise<void>;
   onCancel: () => void;
 }
@@ -30,24 +32,97 @@ const InfoTooltip = ({ text }: { text: string }) => (
   </div>
 );
 
-export function TaskForm({ initialTask, onSubmit, onCancel }: TaskFormProps) {
+/** Repeat interval picked in the form; custom intervals take their count from a separate field */
+type RepeatChoice = RepeatInterval | 'every_n_minutes' | 'every_n_days' | 'monthly_by_weekday';
+
+interface RepeatFields {
+  repeatInterval: RepeatChoice;
+  repeatEvery: string;
+  repeatWeek: string;
+  repeatWeekday: string;
+}
+
+const WEEKDAYS = ['Mon', 'Tue', 'Wed', 'Thu', 'Fri', 'Sat', 'Sun'];
+
+function repeatChoiceOf(interval?: RepeatInterval | CustomRepeatInterval): RepeatFields {
+  const fields: RepeatFields = { repeatInterval: RepeatInterval.Daily, repeatEvery: '', repeatWeek: '1', repeatWeekday: 'Mon' };
+  if (!interval) {
+    return fields;
+  }
+  if (typeof interval === 'string') {
+    return { ...fields, repeatInterval: interval };
+  }
+  if ('monthly_by_weekday' in interval) {
+    return {
+      ...fields,
+      repeatInterval: 'monthly_by_weekday',
+      repeatWeek: interval.monthly_by_weekday.week.toString(),
+      repeatWeekday: interval.monthly_by_weekday.weekday,
+    };
+  }
+  if ('every_n_minutes' in interval) {
+    return { ...fields, repeatInterval: 'every_n_minutes', repeatEvery: interval.every_n_minutes.toString() };
+  }
+  return { ...fields, repeatInterval: 'every_n_days', repeatEvery: interval.every_n_days.toString() };
+}
+
+/** Excluded dates as typed in the form, e.g. "2024-12-25, 2024-12-27..2024-12-31" */
+function exclusionsToText(exclusions?: DateExclusion[]): string {
+  return (exclusions || [])
+    .map((exclusion) => (exclusion.end && exclusion.end !== exclusion.start ? `${exclusion.start}..${exclusion.end}` : exclusion.start))
+    .join(', ');
+}
+
+function exclusionsOf(text: string): DateExclusion[] {
+  return text
+    .split(',')
+    .map((part) => part.trim())
+    .filter(Boolean)
+    .map((part) => {
+      const [start, end] = part.split('..').map((day) => day.trim());
+      return { start, end: end || null };
+    });
+}
+
+function repeatIntervalOf({ repeatInterval: choice, repeatEvery: every, repeatWeek, repeatWeekday }: RepeatFields): RepeatInterval | CustomRepeatInterval {
+  switch (choice) {
+    case 'monthly_by_weekday':
+      return { monthly_by_weekday: { week: parseInt(repeatWeek), weekday: repeatWeekday } };
+    case 'every_n_minutes':
+      return { every_n_minutes: parseInt(every) };
+    case 'every_n_days':
+      return { every_n_days: parseInt(every) };
+    default:
+      return choice;
+  }
+}
+
+export function TaskForm({ initialTask, draft, onSubmit, onCancel }: TaskFormProps) {
   const [submitting, setSubmitting] = useState(false);
   const [installedBrowsers, setInstalledBrowsers] = useState<BrowserType[]>([]);
   const [defaultBrowser, setDefaultBrowser] = useState<BrowserType | null>(null);
+  const [browserProfiles, setBrowserProfiles] = useState<BrowserProfile[]>([]);
   const [naturalLanguageTime, setNaturalLanguageTime] = useState('');
   const [formData, setFormData] = useState({
     name: '',
+    description: '',
+    tags: '',
     browser: BrowserType.Chrome,
     url: '',
     allowCloseAll: false,
     browserProfile: '',
     startTime: '',
     closeTime: '',
+    durationMinutes: '',
     timezone: Intl.DateTimeFormat().resolvedOptions().timeZone,
     repeatEnabled: false,
-    repeatInterval: RepeatInterval.Daily,
+    repeatInterval: RepeatInterval.Daily as RepeatChoice,
+    repeatEvery: '',
+    repeatWeek: '1',
+    repeatWeekday: 'Mon',
     repeatEndAfter: '',
     repeatEndDate: '',
+    repeatExclusions: '',
   });
 
   // Detect installed browsers on mount
@@ -77,24 +152,40 @@ export function TaskForm({ initialTask, onSubmit, onCancel }: TaskFormProps) {
     detectBrowsers();
   }, []);
 
+  // List the selected browser's profiles so one can be picked instead of typed
+  useEffect(() => {
+    invoke<BrowserProfile[]>('get_browser_profiles', { browser: formData.browser })
+      .then(setBrowserProfiles)
+      .catch((error) => {
+        console.error('Failed to list browser profiles:', error);
+        setBrowserProfiles([]);
+      });
+  }, [formData.browser]);
+
   useEffect(() => {
     if (initialTask) {
       setFormData({
         name: initialTask.name,
+        description: initialTask.description || '',
+        tags: initialTask.tags?.join(', ') || '',
         browser: initialTask.browser,
         url: initialTask.url || '',
         allowCloseAll: initialTask.allow_close_all || false,
         browserProfile: initialTask.browser_profile || '',
         startTime: initialTask.start_time ? utcToLocalDatetimeString(initialTask.start_time) : '',
         closeTime: initialTask.close_time ? utcToLocalDatetimeString(initialTask.close_time) : '',
+        durationMinutes: initialTask.duration_minutes?.toString() || '',
         timezone: initialTask.timezone,
         repeatEnabled: !!initialTask.repeat_config,
-        repeatInterval: initialTask.repeat_config?.interval || RepeatInterval.Daily,
+        ...repeatChoiceOf(initialTask.repeat_config?.interval),
         repeatEndAfter: initialTask.repeat_config?.end_after?.toString() || '',
         repeatEndDate: initialTask.repeat_config?.end_date ? utcToLocalDatetimeString(initialTask.repeat_config.end_date) : '',
+        repeatExclusions: exclusionsToText(initialTask.exclusions),
       });
+    } else if (draft) {
+      setFormData((prev) => ({ ...prev, name: draft.name, url: draft.url }));
     }
-  }, [initialTask]);
+  }, [initialTask, draft]);
 
   const handleNaturalLanguageInput = (input: string) => {
     setNaturalLanguageTime(input);
@@ -118,7 +209,7 @@ export function TaskForm({ initialTask, onSubmit, onCancel }: TaskFormProps) {
       if (parsed.end) {
         const endDate = parsed.end.date();
         const endTimeStr = utcToLocalDatetimeString(endDate.toISOString());
-        setFormData(prev => ({ ...prev, closeTime: endTimeStr }));
+        setFormData(prev => ({ ...prev, closeTime: endTimeStr, durationMinutes: '' }));
       }
     }
   };
@@ -140,7 +231,7 @@ export function TaskForm({ initialTask, onSubmit, onCancel }: TaskFormProps) {
         timezone: formData.timezone,
         repeat_config: formData.repeatEnabled
           ? {
-              interval: formData.repeatInterval,
+              interval: repeatIntervalOf(formData),
               end_after: formData.repeatEndAfter ? parseInt(formData.repeatEndAfter) : null,
               end_date: formData.repeatEndDate ? localDatetimeStringToUtc(formData.repeatEndDate) : null,
             }
@@ -149,6 +240,42 @@ export function TaskForm({ initialTask, onSubmit, onCancel }: TaskFormProps) {
         status: initialTask?.status || TaskStatus.Active,
         next_open_execution: initialTask?.next_open_execution,
         next_close_execution: initialTask?.next_close_execution,
+        consecutive_failures: initialTask?.consecutive_failures ?? 0,
+        kind: initialTask?.kind,
+        pomodoro: initialTask?.pomodoro,
+        minimize_browsers: initialTask?.minimize_browsers,
+        steps: initialTask?.steps,
+        tags: formData.tags.split(',').map((tag) => tag.trim()).filter(Boolean),
+        exclusions: formData.repeatEnabled ? exclusionsOf(formData.repeatExclusions) : [],
+        current_step: initialTask?.current_step,
+        workspace_id: initialTask?.workspace_id,
+        secret_url_params: initialTask?.secret_url_params,
+        session_name: initialTask?.session_name,
+        target_display: initialTask?.target_display,
+        window_state: initialTask?.window_state,
+        window_position: initialTask?.window_position,
+        window_size: initialTask?.window_size,
+        kiosk: initialTask?.kiosk,
+        app_mode: initialTask?.app_mode,
+        launcher: initialTask?.launcher,
+        zoom: initialTask?.zoom,
+        user_agent: initialTask?.user_agent,
+        lang: initialTask?.lang,
+        fresh_session: initialTask?.fresh_session,
+        managed_profile: initialTask?.managed_profile,
+        prelaunch_minutes: initialTask?.prelaunch_minutes,
+        jitter_minutes: initialTask?.jitter_minutes,
+        missed_policy: initialTask?.missed_policy,
+        retry_max_attempts: initialTask?.retry_max_attempts,
+        retry_backoff_secs: initialTask?.retry_backoff_secs,
+        retry_attempt: initialTask?.retry_attempt,
+        pending_occurrence: initialTask?.pending_occurrence,
+        duration_minutes: formData.durationMinutes ? parseInt(formData.durationMinutes) : null,
+        description: formData.description.trim() || null,
+        pomodoro_cycle: initialTask?.pomodoro_cycle,
+        blocked_domains: initialTask?.blocked_domains,
+        last_success_at: initialTask?.last_success_at,
+        updated_at: initialTask?.updated_at,
       };
 
       await onSubmit(task);
@@ -182,6 +309,34 @@ export function TaskForm({ initialTask, onSubmit, onCancel }: TaskFormProps) {
         />
       </div>
 
+      <div>
+        <label className="flex items-center text-sm font-medium text-gray-700 dark:text-gray-300 mb-2">
+          Description
+          <InfoTooltip text="Optional notes about the task. Searching tasks also looks through the description." />
+        </label>
+        <textarea
+          rows={2}
+          value={formData.description}
+          onChange={(e) => setFormData({ ...formData, description: e.target.value })}
+          className="w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-lg bg-white dark:bg-gray-700 text-gray-900 dark:text-white focus:ring-2 focus:ring-blue-500"
+          placeholder="e.g., Quarterly report dashboard for the finance review"
+        />
+      </div>
+
+      <div>
+        <label className="flex items-center text-sm font-medium text-gray-700 dark:text-gray-300 mb-2">
+          Tags
+          <InfoTooltip text="Comma-separated labels for grouping tasks. All tasks with a tag can be paused or resumed together." />
+        </label>
+        <input
+          type="text"
+          value={formData.tags}
+          onChange={(e) => setFormData({ ...formData, tags: e.target.value })}
+          className="w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-lg bg-white dark:bg-gray-700 text-gray-900 dark:text-white focus:ring-2 focus:ring-blue-500"
+          placeholder="e.g., work, standup"
+        />
+      </div>
+
       <div className="grid grid-cols-1 gap-4">
         <div>
           <label className="flex items-center text-sm font-medium text-gray-700 dark:text-gray-300 mb-2">
@@ -233,7 +388,15 @@ export function TaskForm({ initialTask, onSubmit, onCancel }: TaskFormProps) {
           onChange={(e) => setFormData({ ...formData, browserProfile: e.target.value })}
           className="w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-lg bg-white dark:bg-gray-700 text-gray-900 dark:text-white focus:ring-2 focus:ring-blue-500"
           placeholder="e.g., Profile 1"
+          list="browser-profiles"
         />
+        <datalist id="browser-profiles">
+          {browserProfiles.map((profile) => (
+            <option key={profile.directory} value={profile.browser_profile}>
+              {profile.name}
+            </option>
+          ))}
+        </datalist>
       </div>
 
       <div className="border-2 border-blue-200 dark:border-blue-800 rounded-lg p-4 bg-blue-50 dark:bg-blue-900/20">
@@ -275,8 +438,23 @@ export function TaskForm({ initialTask, onSubmit, onCancel }: TaskFormProps) {
         <input
           type="datetime-local"
           value={formData.closeTime}
-          onChange={(e) => setFormData({ ...formData, closeTime: e.target.value })}
+          onChange={(e) => setFormData({ ...formData, closeTime: e.target.value, durationMinutes: '' })}
+          className="w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-lg bg-white dark:bg-gray-700 text-gray-900 dark:text-white focus:ring-2 focus:ring-blue-500"
+        />
+      </div>
+
+      <div>
+        <label className="flex items-center text-sm font-medium text-gray-700 dark:text-gray-300 mb-2">
+          Close after (minutes, optional)
+          <InfoTooltip text="Instead of a close time: close the browser this many minutes after it opens. Counted from each open, so snoozed or late opens still stay open this long." />
+        </label>
+        <input
+          type="number"
+          min="1"
+          value={formData.durationMinutes}
+          onChange={(e) => setFormData({ ...formData, durationMinutes: e.target.value, closeTime: '' })}
           className="w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-lg bg-white dark:bg-gray-700 text-gray-900 dark:text-white focus:ring-2 focus:ring-blue-500"
+          placeholder="e.g., 45"
         />
       </div>
 
@@ -315,19 +493,77 @@ export function TaskForm({ initialTask, onSubmit, onCancel }: TaskFormProps) {
           <div>
             <label className="flex items-center text-sm font-medium text-gray-700 dark:text-gray-300 mb-2">
               Repeat Interval
-              <InfoTooltip text="How often the task should repeat: Daily (every 24 hours), Weekly (every 7 days), or Monthly (same day each month)." />
+              <InfoTooltip text="How often the task should repeat: Daily (every 24 hours), Weekly (every 7 days), Monthly (same day each month), or a weekday of the month such as its second Tuesday." />
             </label>
             <select
               value={formData.repeatInterval}
-              onChange={(e) => setFormData({ ...formData, repeatInterval: e.target.value as RepeatInterval })}
+              onChange={(e) => setFormData({ ...formData, repeatInterval: e.target.value as RepeatChoice })}
               className="w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-lg bg-white dark:bg-gray-700 text-gray-900 dark:text-white focus:ring-2 focus:ring-blue-500"
             >
+              <option value={RepeatInterval.Hourly}>Hourly</option>
               <option value={RepeatInterval.Daily}>Daily</option>
               <option value={RepeatInterval.Weekly}>Weekly</option>
               <option value={RepeatInterval.Monthly}>Monthly</option>
+              <option value="every_n_minutes">Every N minutes</option>
+              <option value="every_n_days">Every N days</option>
+              <option value="monthly_by_weekday">Weekday of the month</option>
             </select>
           </div>
 
+          {formData.repeatInterval === 'monthly_by_weekday' && (
+            <div className="grid grid-cols-2 gap-4">
+              <div>
+                <label className="flex items-center text-sm font-medium text-gray-700 dark:text-gray-300 mb-2">
+                  Week
+                </label>
+                <select
+                  value={formData.repeatWeek}
+                  onChange={(e) => setFormData({ ...formData, repeatWeek: e.target.value })}
+                  className="w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-lg bg-white dark:bg-gray-700 text-gray-900 dark:text-white focus:ring-2 focus:ring-blue-500"
+                >
+                  <option value="1">First</option>
+                  <option value="2">Second</option>
+                  <option value="3">Third</option>
+                  <option value="4">Fourth</option>
+                  <option value="5">Last</option>
+                </select>
+              </div>
+              <div>
+                <label className="flex items-center text-sm font-medium text-gray-700 dark:text-gray-300 mb-2">
+                  Weekday
+                </label>
+                <select
+                  value={formData.repeatWeekday}
+                  onChange={(e) => setFormData({ ...formData, repeatWeekday: e.target.value })}
+                  className="w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-lg bg-white dark:bg-gray-700 text-gray-900 dark:text-white focus:ring-2 focus:ring-blue-500"
+                >
+                  {WEEKDAYS.map((weekday) => (
+                    <option key={weekday} value={weekday}>
+                      {weekday}
+                    </option>
+                  ))}
+                </select>
+              </div>
+            </div>
+          )}
+
+          {(formData.repeatInterval === 'every_n_minutes' || formData.repeatInterval === 'every_n_days') && (
+            <div>
+              <label className="flex items-center text-sm font-medium text-gray-700 dark:text-gray-300 mb-2">
+                {formData.repeatInterval === 'every_n_minutes' ? 'Every (minutes)' : 'Every (days)'}
+              </label>
+              <input
+                type="number"
+                min="1"
+                required
+                value={formData.repeatEvery}
+                onChange={(e) => setFormData({ ...formData, repeatEvery: e.target.value })}
+                className="w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-lg bg-white dark:bg-gray-700 text-gray-900 dark:text-white focus:ring-2 focus:ring-blue-500"
+                placeholder={formData.repeatInterval === 'every_n_minutes' ? 'e.g., 120' : 'e.g., 2'}
+              />
+            </div>
+          )}
+
           <div>
             <label className="flex items-center text-sm font-medium text-gray-700 dark:text-gray-300 mb-2">
               End after (occurrences, optional)
@@ -355,6 +591,20 @@ export function TaskForm({ initialTask, onSubmit, onCancel }: TaskFormProps) {
               className="w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-lg bg-white dark:bg-gray-700 text-gray-900 dark:text-white focus:ring-2 focus:ring-blue-500"
             />
           </div>
+
+          <div>
+            <label className="flex items-center text-sm font-medium text-gray-700 dark:text-gray-300 mb-2">
+              Skip dates (optional)
+              <InfoTooltip text="Comma-separated days the task does not run on, like holidays. Write a range such as a vacation as first..last day." />
+            </label>
+            <input
+              type="text"
+              value={formData.repeatExclusions}
+              onChange={(e) => setFormData({ ...formData, repeatExclusions: e.target.value })}
+              className="w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-lg bg-white dark:bg-gray-700 text-gray-900 dark:text-white focus:ring-2 focus:ring-blue-500"
+              placeholder="e.g., 2024-12-25, 2024-12-27..2024-12-31"
+            />
+          </div>
         </div>
       )}
 
@@ -1,5 +1,21 @@
-import { Task, TaskStatus } from '../types/task';
+import { Task, TaskStatus, RepeatInterval, CustomRepeatInterval } from '../types/task';
 import { format } from 'date-fns';
+import { TauriTaskService } from '../services/tauri-api';
+
+const WEEK_ORDINALS = ['first', 'second', 'third', 'fourth', 'last'];
+
+function describeInterval(interval: RepeatInterval | CustomRepeatInterval): string {
+  if (typeof interval === 'string') {
+    return interval;
+  }
+  if ('monthly_by_weekday' in interval) {
+    const { week, weekday } = interval.monthly_by_weekday;
+    return `every ${WEEK_ORDINALS[week - 1] ?? week} ${weekday} of the month`;
+  }
+  return 'every_n_minutes' in interval
+    ? `every ${interval.every_n_minutes} minutes`
+    : `every ${interval.every_n_days} days`;
+}
 
 interface TaskItemProps {
   task: Task;
@@ -8,10 +24,23 @@ interface TaskItemProps {
 }
 
 export function TaskItem({ task, onEdit, onDelete }: TaskItemProps) {
+  const handleShare = async () => {
+    if (!task.id) return;
+    try {
+      const link = await TauriTaskService.shareTask(task.id);
+      await navigator.clipboard.writeText(link);
+      alert('Task link copied to the clipboard');
+    } catch (err) {
+      alert(err instanceof Error ? err.message : 'Failed to share task');
+    }
+  };
+
   const statusColors = {
     [TaskStatus.Active]: 'bg-blue-100 text-blue-800 dark:bg-blue-900 dark:text-blue-300',
+    [TaskStatus.Paused]: 'bg-gray-100 text-gray-800 dark:bg-gray-700 dark:text-gray-300',
     [TaskStatus.Completed]: 'bg-green-100 text-green-800 dark:bg-green-900 dark:text-green-300',
     [TaskStatus.Failed]: 'bg-red-100 text-red-800 dark:bg-red-900 dark:text-red-300',
+    [TaskStatus.Disabled]: 'bg-orange-100 text-orange-800 dark:bg-orange-900 dark:text-orange-300',
   };
 
   const formatDate = (dateStr: string) => {
@@ -77,7 +106,7 @@ export function TaskItem({ task, onEdit, onDelete }: TaskItemProps) {
             {task.repeat_config && (
               <div className="flex items-center gap-2">
                 <span className="font-medium">Repeat:</span>
-                <span className="capitalize">{task.repeat_config.interval}</span>
+                <span className="capitalize">{describeInterval(task.repeat_config.interval)}</span>
               </div>
             )}
           </div>
@@ -90,6 +119,13 @@ export function TaskItem({ task, onEdit, onDelete }: TaskItemProps) {
           >
             Edit
           </button>
+          <button
+            onClick={handleShare}
+            className="px-3 py-1 text-sm bg-gray-100 dark:bg-gray-700 text-gray-700 dark:text-gray-300 rounded hover:bg-gray-200 dark:hover:bg-gray-600 transition-colors"
+            title="Copy a link that imports this schedule"
+          >
+            Share
+          </button>
           <button
             onClick={() => task.id && onDelete(task.id)}
             className="px-3 py-1 text-sm bg-red-100 dark:bg-red-900 text-red-700 dark:text-red-300 rounded hover:bg-red-200 dark:hover:bg-red-800 transition-colors"
@@ -1,14 +1,20 @@
 import { useState, useEffect, useCallback } from 'react';
+import { listen } from '@tauri-apps/api/event';
 import { TauriTaskService } from '../services/tauri-api';
+import { SchedulerErrorEvent } from '../types/task';
 
 export function useScheduler() {
   const [running, setRunning] = useState(false);
   const [loading, setLoading] = useState(true);
+  const [errorCount, setErrorCount] = useState(0);
+  const [lastError, setLastError] = useState<SchedulerErrorEvent | null>(null);
 
   const checkStatus = useCallback(async () => {
     try {
       const status = await TauriTaskService.getSchedulerStatus();
       setRunning(status.running);
+      setErrorCount(status.recent_error_count);
+      setLastError(status.last_error ?? null);
     } catch (err) {
       console.error('Failed to get scheduler status:', err);
     } finally {
@@ -51,9 +57,23 @@ export function useScheduler() {
     return () => clearInterval(interval);
   }, [checkStatus]);
 
+  // Surface loop errors as soon as they happen instead of waiting for the next poll
+  useEffect(() => {
+    const unlisten = listen<SchedulerErrorEvent>('scheduler-error', (event) => {
+      setErrorCount((count) => count + 1);
+      setLastError(event.payload);
+    });
+
+    return () => {
+      unlisten.then(fn => fn());
+    };
+  }, []);
+
   return {
     running,
     loading,
+    errorCount,
+    lastError,
     startScheduler,
     stopScheduler,
     toggleScheduler,
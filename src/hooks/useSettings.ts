@@ -1,5 +1,14 @@
 import { useState, useEffect, useCallback } from 'react';
-import { AppSettings } from '../types/task';
+import {
+  AppSettings,
+  BooleanSettingKey,
+  ClockFormat,
+  ConcurrentOpenPolicy,
+  DateFormat,
+  FirstDayOfWeek,
+  Language,
+  MissedPolicy,
+} from '../types/task';
 import { TauriTaskService } from '../services/tauri-api';
 
 export function useSettings() {
@@ -8,6 +17,53 @@ export function useSettings() {
     start_minimized: false,
     show_notifications: false,
     auto_start: false,
+    max_consecutive_failures: 3,
+    dry_run: false,
+    missed_policy: MissedPolicy.RunLatest,
+    auto_check_updates: false,
+    deleted_retention_days: 30,
+    db_max_connections: 5,
+    devtools_port: 0,
+    launch_stagger_secs: 0,
+    max_concurrent_opens: 0,
+    concurrent_open_policy: ConcurrentOpenPolicy.Defer,
+    max_cpu_percent: 0,
+    max_memory_percent: 0,
+    close_opened_on_quit: false,
+    defer_while_locked: false,
+    quiet_hours_enabled: false,
+    quiet_hours_start: '22:00',
+    quiet_hours_end: '07:00',
+    first_day_of_week: FirstDayOfWeek.Monday,
+    clock_format: ClockFormat.H24,
+    date_format: DateFormat.Iso,
+    action_sound: 'default',
+    reminder_sound: 'default',
+    failure_sound: 'alert',
+    notify_on_failure: true,
+    action_notification_title: '',
+    action_notification_body: '',
+    failure_notification_title: '',
+    failure_notification_body: '',
+    smtp_enabled: false,
+    smtp_host: '',
+    smtp_port: 587,
+    smtp_username: '',
+    smtp_from: '',
+    smtp_to: '',
+    smtp_daily_summary: false,
+    slack_webhook_url: '',
+    discord_webhook_url: '',
+    telegram_chat_id: '',
+    home_assistant_enabled: false,
+    home_assistant_port: 8765,
+    home_assistant_listen_all: false,
+    warn_before_minutes: 0,
+    close_countdown_minutes: 0,
+    language: Language.En,
+    windows_ui_automation: false,
+    url_actions_enabled: false,
+    allowed_url_schemes: [],
   });
   const [loading, setLoading] = useState(true);
   const [error, setError] = useState<string | null>(null);
@@ -63,7 +119,7 @@ export function useSettings() {
     }
   }, [settings]);
 
-  const toggleSetting = useCallback(async (key: keyof AppSettings) => {
+  const toggleSetting = useCallback(async (key: BooleanSettingKey) => {
     const newSettings = {
       ...settings,
       [key]: !settings[key],
@@ -1,30 +1,34 @@
 import { useState, useEffect, useCallback } from 'react';
-import { Task } from '../types/task';
+import { Task, TaskListQuery, TaskUpdatedEvent } from '../types/task';
 import { TauriTaskService } from '../services/tauri-api';
 import { listen } from '@tauri-apps/api/event';
 
-export function useTasks() {
+export function useTasks(query: TaskListQuery = {}) {
+  const { limit, offset, sort, descending } = query;
   const [tasks, setTasks] = useState<Task[]>([]);
+  const [total, setTotal] = useState(0);
   const [loading, setLoading] = useState(true);
   const [error, setError] = useState<string | null>(null);
 
   const loadTasks = useCallback(async () => {
     try {
       setLoading(true);
-      const data = await TauriTaskService.getAllTasks();
-      setTasks(data);
+      const page = await TauriTaskService.getAllTasks({ limit, offset, sort, descending });
+      setTasks(page.items);
+      setTotal(page.total);
       setError(null);
     } catch (err) {
       setError(err instanceof Error ? err.message : 'Failed to load tasks');
     } finally {
       setLoading(false);
     }
-  }, []);
+  }, [limit, offset, sort, descending]);
 
   const createTask = useCallback(async (task: Task): Promise<Task> => {
     try {
       const newTask = await TauriTaskService.createTask(task);
       setTasks((prev) => [...prev, newTask]);
+      setTotal((prev) => prev + 1);
       return newTask;
     } catch (err) {
       const message = err instanceof Error ? err.message : 'Failed to create task';
@@ -47,6 +51,7 @@ export function useTasks() {
     try {
       await TauriTaskService.deleteTask(id);
       setTasks((prev) => prev.filter((t) => t.id !== id));
+      setTotal((prev) => prev - 1);
     } catch (err) {
       const message = err instanceof Error ? err.message : 'Failed to delete task';
       throw new Error(message);
@@ -60,9 +65,14 @@ export function useTasks() {
 
   // Listen for task-updated events from backend
   useEffect(() => {
-    const unlisten = listen<number>('task-updated', () => {
-      // Refresh all tasks when any task is updated by the scheduler
-      loadTasks();
+    const unlisten = listen<TaskUpdatedEvent>('task-updated', (event) => {
+      // Replace just the affected row with the task carried in the event
+      const updated = event.payload.task;
+      setTasks((prev) =>
+        prev.some((t) => t.id === updated.id)
+          ? prev.map((t) => (t.id === updated.id ? updated : t))
+          : [...prev, updated]
+      );
     });
 
     return () => {
@@ -72,6 +82,7 @@ export function useTasks() {
 
   return {
     tasks,
+    total,
     loading,
     error,
     createTask,
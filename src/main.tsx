@@ -1,10 +1,15 @@
 import React from "react";
 import ReactDOM from "react-dom/client";
 import App from "./App";
+import { CloseCountdown } from "./components/CloseCountdown";
 import "./App.css";
 
+// Countdown windows before a scheduled close load the same page with ?countdown=<task id>
+const countdownParam = new URLSearchParams(window.location.search).get("countdown");
+const countdownTaskId = countdownParam !== null ? Number(countdownParam) : null;
+
 ReactDOM.createRoot(document.getElementById("root") as HTMLElement).render(
   <React.StrictMode>
-    <App />
+    {countdownTaskId !== null ? <CloseCountdown taskId={countdownTaskId} /> : <App />}
   </React.StrictMode>,
 );
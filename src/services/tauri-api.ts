@@ -1,27 +1,166 @@
 import { invoke } from '@tauri-apps/api/core';
-import { Task, SchedulerStatus, AppSettings } from '../types/task';
+import { Task, TaskStatus, TaskListQuery, TaskPage, TaskExecution, ExecutionPage, TaskRevision, SchedulerStatus, AppSettings, AppNotification, Dashboard, TaskUsage, WeeklyReportResult, IcalExport, IcalImport, TagCount, ValidationIssue, BrowserLaunchTest, BrowserType, SimulatedAction, BackfillReport, HealthReport, UpdateInfo, Workspace, LockStatus, AuditEntry, DeletedTask, OpenTab, SessionSnapshot, DisplayInfo, LocaleConfig, LinkDraft } from '../types/task';
 
 export class TauriTaskService {
-  static async getAllTasks(): Promise<Task[]> {
-    return invoke<Task[]>('get_all_tasks');
+  static async getAllTasks(query: TaskListQuery = {}): Promise<TaskPage> {
+    return invoke<TaskPage>('get_all_tasks', { ...query });
+  }
+
+  static async searchTasks(query: string): Promise<Task[]> {
+    return invoke<Task[]>('search_tasks', { query });
   }
 
   static async getTask(id: number): Promise<Task> {
     return invoke<Task>('get_task', { id });
   }
 
+  /** A do-in-time:// link that gives a teammate the same schedule. */
+  static async shareTask(id: number): Promise<string> {
+    return invoke<string>('share_task', { id });
+  }
+
+  /** The unsaved task in a shared link or share code. */
+  static async readTaskLink(link: string): Promise<Task> {
+    return invoke<Task>('read_task_link', { link });
+  }
+
   static async createTask(task: Task): Promise<Task> {
     return invoke<Task>('create_task', { task });
   }
 
-  static async updateTask(id: number, task: Task): Promise<Task> {
-    return invoke<Task>('update_task', { id, task });
+  static async validateTask(task: Task): Promise<ValidationIssue[]> {
+    return invoke<ValidationIssue[]>('validate_task', { task });
+  }
+
+  static async updateTask(id: number, task: Task, force = false): Promise<Task> {
+    return invoke<Task>('update_task', { id, task, force });
   }
 
   static async deleteTask(id: number): Promise<void> {
     return invoke<void>('delete_task', { id });
   }
 
+  static async pauseTask(id: number): Promise<Task> {
+    return invoke<Task>('pause_task', { id });
+  }
+
+  static async resumeTask(id: number): Promise<Task> {
+    return invoke<Task>('resume_task', { id });
+  }
+
+  static async bulkUpdateStatus(ids: number[], status: TaskStatus): Promise<Task[]> {
+    return invoke<Task[]>('bulk_update_status', { ids, status });
+  }
+
+  static async bulkDelete(ids: number[]): Promise<number[]> {
+    return invoke<number[]>('bulk_delete', { ids });
+  }
+
+  static async bulkShiftTime(ids: number[], minutes: number): Promise<Task[]> {
+    return invoke<Task[]>('bulk_shift_time', { ids, minutes });
+  }
+
+  static async getTags(): Promise<TagCount[]> {
+    return invoke<TagCount[]>('get_tags');
+  }
+
+  static async getTasksByTag(tag: string): Promise<Task[]> {
+    return invoke<Task[]>('get_tasks_by_tag', { tag });
+  }
+
+  static async setTagStatus(tag: string, status: TaskStatus): Promise<Task[]> {
+    return invoke<Task[]>('set_tag_status', { tag, status });
+  }
+
+  static async getDeletedTasks(): Promise<DeletedTask[]> {
+    return invoke<DeletedTask[]>('get_deleted_tasks');
+  }
+
+  static async restoreDeletedTask(id: number): Promise<Task> {
+    return invoke<Task>('restore_deleted_task', { id });
+  }
+
+  static async purgeDeleted(): Promise<number> {
+    return invoke<number>('purge_deleted');
+  }
+
+  /** Returns the ids of the affected tasks; empty when there was nothing to undo. */
+  static async undoLastChange(): Promise<number[]> {
+    return invoke<number[]>('undo_last_change');
+  }
+
+  /** Returns the ids of the affected tasks; empty when there was nothing to redo. */
+  static async redo(): Promise<number[]> {
+    return invoke<number[]>('redo');
+  }
+
+  static async getAuditLog(taskId?: number, limit?: number): Promise<AuditEntry[]> {
+    return invoke<AuditEntry[]>('get_audit_log', { taskId, limit });
+  }
+
+  static async getTaskExecutions(taskId: number, limit?: number, offset?: number): Promise<ExecutionPage> {
+    return invoke<ExecutionPage>('get_task_executions', { taskId, limit, offset });
+  }
+
+  static async getRecentExecutions(limit?: number): Promise<TaskExecution[]> {
+    return invoke<TaskExecution[]>('get_recent_executions', { limit });
+  }
+
+  static async getTaskRevisions(id: number): Promise<TaskRevision[]> {
+    return invoke<TaskRevision[]>('get_task_revisions', { id });
+  }
+
+  static async restoreTaskRevision(id: number, rev: number): Promise<Task> {
+    return invoke<Task>('restore_task_revision', { id, rev });
+  }
+
+  static async getDashboard(): Promise<Dashboard> {
+    return invoke<Dashboard>('get_dashboard');
+  }
+
+  static async getUsageStats(from: string, to: string): Promise<TaskUsage[]> {
+    return invoke<TaskUsage[]>('get_usage_stats', { from, to });
+  }
+
+  static async generateWeeklyReport(save = false): Promise<WeeklyReportResult> {
+    return invoke<WeeklyReportResult>('generate_weekly_report', { save });
+  }
+
+  /** The schedule as an .ics calendar; `save` also writes it where a calendar app can subscribe to it. */
+  static async exportIcal(save = false): Promise<IcalExport> {
+    return invoke<IcalExport>('export_ical', { save });
+  }
+
+  static async importIcal(pathOrUrl: string, defaultBrowser: BrowserType): Promise<IcalImport> {
+    return invoke<IcalImport>('import_ical', { pathOrUrl, defaultBrowser });
+  }
+
+  static async testBrowserLaunch(
+    browser: BrowserType,
+    profile?: string | null,
+    url?: string | null,
+    closeAfterSecs?: number | null,
+  ): Promise<BrowserLaunchTest> {
+    return invoke<BrowserLaunchTest>('test_browser_launch', { browser, profile, url, closeAfterSecs });
+  }
+
+  static async getDisplays(): Promise<DisplayInfo[]> {
+    return invoke<DisplayInfo[]>('get_displays');
+  }
+
+  /** Omit the browser to read every browser whose tabs can be read. */
+  static async getOpenTabs(browser?: BrowserType | null): Promise<OpenTab[]> {
+    return invoke<OpenTab[]>('get_open_tabs', { browser });
+  }
+
+  static async getSessionSnapshots(): Promise<SessionSnapshot[]> {
+    return invoke<SessionSnapshot[]>('get_session_snapshots');
+  }
+
+  static async deleteSessionSnapshot(name: string): Promise<void> {
+    return invoke<void>('delete_session_snapshot', { name });
+  }
+
   static async startScheduler(): Promise<void> {
     return invoke<void>('start_scheduler');
   }
@@ -34,6 +173,62 @@ export class TauriTaskService {
     return invoke<SchedulerStatus>('get_scheduler_status');
   }
 
+  /** Skips scheduled opens until `until` (ISO 8601), then resumes on its own. */
+  static async pauseSchedulerUntil(until: string): Promise<void> {
+    return invoke<void>('pause_scheduler_until', { until });
+  }
+
+  static async resumeScheduler(): Promise<void> {
+    return invoke<void>('resume_scheduler');
+  }
+
+  /** Closes everything the scheduler opened; returns how many tasks were closed. */
+  static async closeAllScheduled(): Promise<number> {
+    return invoke<number>('close_all_scheduled');
+  }
+
+  static async runTaskNow(id: number): Promise<void> {
+    return invoke<void>('run_task_now', { id });
+  }
+
+  static async snoozeTask(id: number, minutes: number): Promise<Task> {
+    return invoke<Task>('snooze_task', { id, minutes });
+  }
+
+  /** Extend button of the close countdown: keeps the task open `minutes` longer. */
+  static async extendTaskClose(id: number, minutes: number): Promise<Task> {
+    return invoke<Task>('extend_task_close', { id, minutes });
+  }
+
+  /** Close-now button of the close countdown. */
+  static async closeTaskNow(id: number): Promise<void> {
+    return invoke<void>('close_task_now', { id });
+  }
+
+  static async dismissCloseCountdown(id: number): Promise<void> {
+    return invoke<void>('dismiss_close_countdown', { id });
+  }
+
+  static async skipNext(id: number): Promise<Task> {
+    return invoke<Task>('skip_next', { id });
+  }
+
+  static async healthCheck(): Promise<HealthReport> {
+    return invoke<HealthReport>('health_check');
+  }
+
+  static async checkForUpdates(): Promise<UpdateInfo> {
+    return invoke<UpdateInfo>('check_for_updates');
+  }
+
+  static async getBackfillReport(): Promise<BackfillReport | null> {
+    return invoke<BackfillReport | null>('get_backfill_report');
+  }
+
+  static async simulateSchedule(from: string, to: string): Promise<SimulatedAction[]> {
+    return invoke<SimulatedAction[]>('simulate_schedule', { from, to });
+  }
+
   static async getSettings(): Promise<AppSettings> {
     return invoke<AppSettings>('get_settings');
   }
@@ -42,11 +237,123 @@ export class TauriTaskService {
     return invoke<AppSettings>('update_settings', { settings });
   }
 
+  /** Turns quiet hours on or off; `start` and `end` are local times as HH:MM. */
+  static async setQuietHours(enabled: boolean, start: string, end: string): Promise<AppSettings> {
+    return invoke<AppSettings>('set_quiet_hours', { enabled, start, end });
+  }
+
+  static async getLocaleConfig(): Promise<LocaleConfig> {
+    return invoke<LocaleConfig>('get_locale_config');
+  }
+
+  static async humanizeDuration(secs: number): Promise<string> {
+    return invoke<string>('humanize_duration', { secs });
+  }
+
+  static async humanizeUntil(at: string): Promise<string> {
+    return invoke<string>('humanize_until', { at });
+  }
+
+  static async describeSchedule(id: number): Promise<string> {
+    return invoke<string>('describe_schedule', { id });
+  }
+
+  static async generateHomeAssistantToken(): Promise<string> {
+    return invoke<string>('generate_home_assistant_token');
+  }
+
+  static async isReadOnly(): Promise<boolean> {
+    return invoke<boolean>('is_read_only');
+  }
+
+  static async getLockStatus(): Promise<LockStatus> {
+    return invoke<LockStatus>('get_lock_status');
+  }
+
+  static async unlock(pin: string): Promise<void> {
+    return invoke<void>('unlock', { pin });
+  }
+
+  static async lock(): Promise<void> {
+    return invoke<void>('lock');
+  }
+
+  /** Pass a null newPin to remove the PIN. */
+  static async setPin(currentPin: string | null, newPin: string | null): Promise<void> {
+    return invoke<void>('set_pin', { currentPin, newPin });
+  }
+
+  static async getDataDir(): Promise<string> {
+    return invoke<string>('get_data_dir');
+  }
+
+  static async moveDatabase(newPath: string): Promise<string> {
+    return invoke<string>('move_database', { newPath });
+  }
+
+  static async getWorkspaces(): Promise<Workspace[]> {
+    return invoke<Workspace[]>('get_workspaces');
+  }
+
+  static async createWorkspace(name: string): Promise<Workspace> {
+    return invoke<Workspace>('create_workspace', { name });
+  }
+
+  static async deleteWorkspace(id: number): Promise<void> {
+    return invoke<void>('delete_workspace', { id });
+  }
+
+  static async getActiveWorkspace(): Promise<number | null> {
+    return invoke<number | null>('get_active_workspace');
+  }
+
+  /** Pass null to run the tasks of every workspace. */
+  static async switchWorkspace(workspaceId: number | null): Promise<void> {
+    return invoke<void>('switch_workspace', { workspaceId });
+  }
+
   static async applyAutoStart(enabled: boolean): Promise<void> {
     return invoke<void>('apply_auto_start', { enabled });
   }
 
+  static async quitApp(closeOpened: boolean): Promise<void> {
+    return invoke<void>('quit_app', { closeOpened });
+  }
+
+  /** Links found in the text on the clipboard. */
+  static async pasteLinks(): Promise<LinkDraft[]> {
+    return invoke<LinkDraft[]>('paste_links');
+  }
+
   static async toggleWindowVisibility(): Promise<void> {
     return invoke<void>('toggle_window_visibility');
   }
+
+  static async getNotifications(unreadOnly = false): Promise<AppNotification[]> {
+    return invoke<AppNotification[]>('get_notifications', { unreadOnly });
+  }
+
+  static async markNotificationRead(id: number): Promise<void> {
+    return invoke<void>('mark_notification_read', { id });
+  }
+
+  static async markAllNotificationsRead(): Promise<void> {
+    return invoke<void>('mark_all_notifications_read');
+  }
+
+  static async clearNotifications(): Promise<void> {
+    return invoke<void>('clear_notifications');
+  }
+
+  static async setSmtpPassword(password: string | null): Promise<void> {
+    return invoke<void>('set_smtp_password', { password });
+  }
+
+  static async setTelegramToken(token: string | null): Promise<void> {
+    return invoke<void>('set_telegram_token', { token });
+  }
+
+  static async sendTestNotification(channel: 'email' | 'slack' | 'discord' | 'telegram'): Promise<void> {
+    return invoke<void>('send_test_notification', { channel });
+  }
 }
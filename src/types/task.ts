@@ -5,26 +5,86 @@ export enum BrowserType {
   Safari = 'safari',
   Brave = 'brave',
   Opera = 'opera',
+  Vivaldi = 'vivaldi',
+  Chromium = 'chromium',
+  /** macOS only */
+  Arc = 'arc',
+  Zen = 'zen',
+}
+
+export enum TaskKind {
+  Browser = 'browser',
+  Block = 'block',
+  Reminder = 'reminder',
+  SaveSession = 'save_session',
+  RestoreSession = 'restore_session',
+}
+
+export enum WindowState {
+  Normal = 'normal',
+  Maximized = 'maximized',
+  Fullscreen = 'fullscreen',
+  Minimized = 'minimized',
+}
+
+/** Physical pixels from the corner of the target display, or of the primary one */
+export interface WindowPosition {
+  x: number;
+  y: number;
+}
+
+export interface WindowSize {
+  width: number;
+  height: number;
+}
+
+export enum LauncherKind {
+  Native = 'native',
+  Devtools = 'devtools',
 }
 
 export enum TaskStatus {
   Active = 'active',
+  Paused = 'paused',
   Completed = 'completed',
   Failed = 'failed',
+  Disabled = 'disabled',
 }
 
 export enum RepeatInterval {
+  Hourly = 'hourly',
   Daily = 'daily',
   Weekly = 'weekly',
   Monthly = 'monthly',
 }
 
+/** Repeat every N minutes or every N days */
+export type CustomRepeatInterval =
+  | { every_n_minutes: number }
+  | { every_n_days: number }
+  /** The `week`th weekday of every month, e.g. `{ week: 2, weekday: 'Tue' }`; week 5 is the last */
+  | { monthly_by_weekday: { week: number; weekday: string } };
+
 export interface RepeatConfig {
-  interval: RepeatInterval;
+  interval: RepeatInterval | CustomRepeatInterval;
   end_after?: number | null;
   end_date?: string | null;
 }
 
+export interface PomodoroConfig {
+  work_minutes: number;
+  break_minutes: number;
+  cycles: number;
+}
+
+export type StepAction = 'open' | 'close' | 'wait';
+
+export interface TaskStep {
+  action: StepAction;
+  url?: string | null;
+  wait_minutes?: number | null;
+}
+
 export interface Task {
   id?: number | null;
   name: string;
@@ -36,14 +96,412 @@ export interface Task {
   close_time?: string | null;
   timezone: string;
   repeat_config?: RepeatConfig | null;
+  pomodoro?: PomodoroConfig | null;
+  pomodoro_cycle?: number;
   execution_count: number;
   status: TaskStatus;
   next_open_execution?: string | null;
   next_close_execution?: string | null;
+  consecutive_failures?: number;
+  kind?: TaskKind;
+  blocked_domains?: string[];
+  minimize_browsers?: boolean;
+  steps?: TaskStep[];
+  tags?: string[];
+  /** Dates a repeating task skips, like holidays or a vacation */
+  exclusions?: DateExclusion[];
+  current_step?: number;
+  workspace_id?: number | null;
+  secret_url_params?: boolean;
+  session_name?: string | null;
+  target_display?: number | null;
+  window_state?: WindowState;
+  window_position?: WindowPosition | null;
+  window_size?: WindowSize | null;
+  /** Fullscreen without tabs or toolbars, for dashboards on a wall screen */
+  kiosk?: boolean;
+  /** Dedicated window without tabs or toolbars; Chromium-based browsers only */
+  app_mode?: boolean;
+  launcher?: LauncherKind;
+  /** Scale factor, e.g. 1.5 for 150% */
+  zoom?: number | null;
+  user_agent?: string | null;
+  lang?: string | null;
+  fresh_session?: boolean;
+  managed_profile?: boolean;
+  prelaunch_minutes?: number | null;
+  jitter_minutes?: number | null;
+  /** Overrides the missed_policy setting for this task */
+  missed_policy?: MissedPolicy | null;
+  /** Tries of a scheduled open or close, including the first */
+  retry_max_attempts?: number | null;
+  retry_backoff_secs?: number | null;
+  retry_attempt?: number;
+  /** Scheduled time of an open being deferred or retried */
+  pending_occurrence?: string | null;
+  /** Close this many minutes after each open, instead of at close_time */
+  duration_minutes?: number | null;
+  description?: string | null;
+  last_success_at?: string | null;
+  updated_at?: string | null;
+}
+
+export enum ConcurrentOpenPolicy {
+  Defer = 'defer',
+  Skip = 'skip',
+  Ask = 'ask',
+}
+
+export enum FirstDayOfWeek {
+  Monday = 'monday',
+  Saturday = 'saturday',
+  Sunday = 'sunday',
+}
+
+export enum ClockFormat {
+  H24 = '24h',
+  H12 = '12h',
+}
+
+export enum DateFormat {
+  Iso = 'iso',
+  Us = 'us',
+  Dmy = 'dmy',
+  Dotted = 'dotted',
+}
+
+export enum Language {
+  En = 'en',
+  De = 'de',
+  Fr = 'fr',
+  Es = 'es',
+}
+
+export interface LocaleConfig {
+  first_day_of_week: FirstDayOfWeek;
+  clock_format: ClockFormat;
+  date_format: DateFormat;
+  language: Language;
+}
+
+export enum MissedPolicy {
+  RunLatest = 'run_latest',
+  Skip = 'skip',
+}
+
+export interface MissedTask {
+  task_id: number;
+  task_name: string;
+  missed: string[];
+  missed_count: number;
+  runs_latest: boolean;
+  asked?: boolean;
+}
+
+export interface BackfillReport {
+  generated_at: string;
+  policy: MissedPolicy;
+  tasks: MissedTask[];
+}
+
+export interface SimulatedAction {
+  task_id: number;
+  task_name: string;
+  kind: TaskKind;
+  action: ExecutionAction;
+  at: string;
+  url?: string | null;
+  conflict?: string | null;
+}
+
+export interface BrowserLaunchTest {
+  browser: BrowserType;
+  launched: boolean;
+  pid?: number | null;
+  closed: boolean;
+  message: string;
+}
+
+export type IssueSeverity = 'error' | 'warning';
+
+export interface ValidationIssue {
+  field: string;
+  severity: IssueSeverity;
+  message: string;
+}
+
+export type TaskUpdateCause = 'executed' | 'edited' | 'failed';
+
+export interface TaskUpdatedEvent {
+  task: Task;
+  cause: TaskUpdateCause;
+}
+
+export type ExecutionAction = 'open' | 'close';
+
+export type ExecutionStatus = 'running' | 'success' | 'failed' | 'skipped' | 'simulated';
+
+export interface TaskExecution {
+  id: number;
+  task_id: number;
+  action: ExecutionAction;
+  occurrence?: string | null;
+  status: ExecutionStatus;
+  error?: string | null;
+  executed_at: string;
+  duration_secs?: number | null;
+  pid?: number | null;
+  exit_code?: number | null;
+}
+
+export interface ExecutionPage {
+  items: TaskExecution[];
+  total: number;
+}
+
+export interface TaskUsage {
+  task_id: number;
+  task_name: string;
+  open_count: number;
+  total_open_secs: number;
+}
+
+export type NotificationKind = 'failure' | 'missed' | 'upcoming' | 'reminder' | 'info';
+
+export interface AppNotification {
+  id: number;
+  kind: NotificationKind;
+  task_id?: number | null;
+  title: string;
+  body: string;
+  read: boolean;
+  created_at: string;
+}
+
+export interface UpcomingExecution {
+  task_id: number;
+  task_name: string;
+  action: ExecutionAction;
+  scheduled_at: string;
+}
+
+export interface Dashboard {
+  active_count: number;
+  paused_count: number;
+  failed_count: number;
+  completed_today: TaskExecution[];
+  upcoming_today: UpcomingExecution[];
+  next_action?: UpcomingExecution | null;
+}
+
+export interface TaskExecutionSummary {
+  task_id: number;
+  task_name: string;
+  opens: number;
+  closes: number;
+  failures: number;
+  total_open_secs: number;
+}
+
+export interface WeeklyReport {
+  from: string;
+  to: string;
+  generated_at: string;
+  tasks: TaskExecutionSummary[];
+}
+
+export interface WeeklyReportResult {
+  report: WeeklyReport;
+  html: string;
+  saved_path?: string | null;
+}
+
+export interface IcalExport {
+  ics: string;
+  saved_path?: string | null;
+}
+
+/** A day, or an inclusive range of days (YYYY-MM-DD), a repeating task skips. */
+export interface DateExclusion {
+  start: string;
+  /** Last excluded day; unset excludes `start` only */
+  end?: string | null;
+}
+
+export interface TagCount {
+  tag: string;
+  task_count: number;
+}
+
+export interface SkippedEvent {
+  summary: string;
+  reason: string;
+}
+
+export interface IcalImport {
+  created: Task[];
+  skipped: SkippedEvent[];
+}
+
+export interface SchedulerErrorEvent {
+  code: string;
+  message: string;
+  task_id?: number | null;
+  occurred_at: string;
+}
+
+/** Payload of the `task-failed` event, emitted for every failed open or close. */
+export interface TaskFailedEvent {
+  task_id: number;
+  task_name: string;
+  action: ExecutionAction;
+  code: string;
+  message: string;
+  /** Failed attempt of this occurrence, counting from 1 */
+  attempt: number;
+  /** When the action is tried again, if it has attempts left */
+  retry_at?: string | null;
+  failed_at: string;
+}
+
+export interface UpdateInfo {
+  current_version: string;
+  latest_version: string;
+  update_available: boolean;
+  release_url: string;
+  checked_at: string;
+}
+
+export type HealthStatus = 'ok' | 'warning' | 'error';
+
+export interface ComponentHealth {
+  component: string;
+  status: HealthStatus;
+  detail: string;
+}
+
+export interface HealthReport {
+  checked_at: string;
+  healthy: boolean;
+  components: ComponentHealth[];
+}
+
+export interface Workspace {
+  id: number;
+  name: string;
+  created_at: string;
+}
+
+export interface LockStatus {
+  enabled: boolean;
+  unlocked: boolean;
+}
+
+export interface DisplayInfo {
+  index: number;
+  name?: string | null;
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+  primary: boolean;
+}
+
+export interface OpenTab {
+  browser: BrowserType;
+  title: string;
+  url: string;
+}
+
+/** A profile of an installed browser, to pick a task's profile from. */
+export interface BrowserProfile {
+  name: string;
+  directory: string;
+  /** Value for the task's browser_profile: the directory for Chromium browsers, the name for Firefox */
+  browser_profile: string;
+}
+
+/** A link dropped on or pasted into the app, to pre-fill a new task. */
+export interface LinkDraft {
+  url: string;
+  name: string;
+}
+
+export interface SessionSnapshot {
+  name: string;
+  browser: BrowserType;
+  browser_profile?: string | null;
+  tabs: OpenTab[];
+  saved_at: string;
+}
+
+export type TaskSort = 'start_time' | 'name' | 'next_action' | 'status' | 'updated_at';
+
+export interface TaskListQuery {
+  limit?: number;
+  offset?: number;
+  sort?: TaskSort;
+  descending?: boolean;
+}
+
+export interface TaskPage {
+  items: Task[];
+  total: number;
+}
+
+export interface DeletedTask {
+  task_id: number;
+  name: string;
+  task: Task;
+  deleted_at: string;
+}
+
+export interface TaskRevision {
+  task_id: number;
+  revision: number;
+  source: AuditSource;
+  task: Task;
+  created_at: string;
+}
+
+export type AuditAction = 'create' | 'update' | 'delete' | 'status_change';
+
+export type AuditSource = 'ui' | 'scheduler' | 'integration';
+
+export interface AuditEntry {
+  id: number;
+  task_id: number;
+  action: AuditAction;
+  source: AuditSource;
+  before?: Task | null;
+  after?: Task | null;
+  created_at: string;
+}
+
+export interface SchedulerState {
+  started_at?: string | null;
+  last_tick_at?: string | null;
+  executions_since_start: number;
+  last_error?: string | null;
+  last_error_at?: string | null;
 }
 
 export interface SchedulerStatus {
   running: boolean;
+  recent_error_count: number;
+  last_error?: SchedulerErrorEvent | null;
+  state: SchedulerState;
+  /** End of a timed pause; scheduled opens are skipped until then */
+  paused_until?: string | null;
+  /** End of the quiet hours in effect; scheduled actions wait until then */
+  quiet_until?: string | null;
+}
+
+/** A URL scheme besides http(s) that tasks may open, e.g. `zoommtg`. */
+export interface AllowedUrlScheme {
+  scheme: string;
+  /** Ask with a notification before each scheduled open */
+  confirm: boolean;
 }
 
 export interface AppSettings {
@@ -51,4 +509,55 @@ export interface AppSettings {
   start_minimized: boolean;
   show_notifications: boolean;
   auto_start: boolean;
+  max_consecutive_failures: number;
+  dry_run: boolean;
+  missed_policy: MissedPolicy;
+  auto_check_updates: boolean;
+  deleted_retention_days: number;
+  db_max_connections: number;
+  devtools_port: number;
+  launch_stagger_secs: number;
+  max_concurrent_opens: number;
+  concurrent_open_policy: ConcurrentOpenPolicy;
+  max_cpu_percent: number;
+  max_memory_percent: number;
+  close_opened_on_quit: boolean;
+  defer_while_locked: boolean;
+  quiet_hours_enabled: boolean;
+  quiet_hours_start: string;
+  quiet_hours_end: string;
+  first_day_of_week: FirstDayOfWeek;
+  clock_format: ClockFormat;
+  date_format: DateFormat;
+  action_sound: string;
+  reminder_sound: string;
+  failure_sound: string;
+  notify_on_failure: boolean;
+  action_notification_title: string;
+  action_notification_body: string;
+  failure_notification_title: string;
+  failure_notification_body: string;
+  smtp_enabled: boolean;
+  smtp_host: string;
+  smtp_port: number;
+  smtp_username: string;
+  smtp_from: string;
+  smtp_to: string;
+  smtp_daily_summary: boolean;
+  slack_webhook_url: string;
+  discord_webhook_url: string;
+  telegram_chat_id: string;
+  home_assistant_enabled: boolean;
+  home_assistant_port: number;
+  home_assistant_listen_all: boolean;
+  warn_before_minutes: number;
+  close_countdown_minutes: number;
+  language: Language;
+  windows_ui_automation: boolean;
+  url_actions_enabled: boolean;
+  allowed_url_schemes: AllowedUrlScheme[];
 }
+
+export type BooleanSettingKey = {
+  [K in keyof AppSettings]: AppSettings[K] extends boolean ? K : never;
+}[keyof AppSettings];